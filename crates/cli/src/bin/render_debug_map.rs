@@ -0,0 +1,25 @@
+//! Deprecated, use `civorum render` and `civorum export`. Kept for one release:
+//! translates the old positional arguments (`[size] [seed|none] [map_type] [cell_px] [out_path]`) and runs them.
+
+use std::{env, process};
+
+use civorum_cli::{
+    cli::{legacy_render_args, parse_args},
+    commands,
+};
+
+fn main() {
+    eprintln!(
+        "render_debug_map is deprecated and will be removed in the next release, use `civorum render` instead."
+    );
+    let args: Vec<String> = env::args().skip(1).collect();
+    for args in legacy_render_args(&args) {
+        let result = parse_args(&args)
+            .map_err(|err| err.into())
+            .and_then(|command| commands::run(&command));
+        if let Err(err) = result {
+            eprintln!("Error: {err}");
+            process::exit(1);
+        }
+    }
+}
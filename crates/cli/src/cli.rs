@@ -0,0 +1,997 @@
+//! Hand-rolled argument parser: `civorum <subcommand> [options]`.
+//! Every subcommand takes the shared map options, the option tables below drive both the parser and `--help`.
+
+use std::{fmt, ops::Range, path::PathBuf};
+
+use civorum_core::{DebugLayer, GraphFormat, GraphSubset, LayerOptions};
+use civorum_mapgen::pipeline::{
+    helpers::{ConstraintsConfig, LandPreset, WorldAge, land_profile_preset},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+    recipe::Recipe,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Subcommands of the `civorum` binary
+pub enum Subcommand {
+    View,
+    Render,
+    Analyze,
+    Export,
+}
+
+impl Subcommand {
+    pub const ALL: [Subcommand; 4] = [
+        Subcommand::View,
+        Subcommand::Render,
+        Subcommand::Analyze,
+        Subcommand::Export,
+    ];
+
+    /// Name on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Subcommand::View => "view",
+            Subcommand::Render => "render",
+            Subcommand::Analyze => "analyze",
+            Subcommand::Export => "export",
+        }
+    }
+
+    /// One-line description for `--help`
+    pub fn summary(self) -> &'static str {
+        match self {
+            Subcommand::View => "generate a map and print a terrain overview with sanity warnings",
+            Subcommand::Render => "render a map (or an authored text map) to a debug PNG",
+            Subcommand::Analyze => "print map statistics, or stress test a range of seeds",
+            Subcommand::Export => {
+                "write the map as JSON, its adjacency graph, or the effective config"
+            }
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Subcommand> {
+        Subcommand::ALL
+            .into_iter()
+            .find(|subcommand| subcommand.name() == name)
+    }
+
+    /// Options of the subcommand on top of the shared map options
+    fn options(self) -> &'static [CliOption] {
+        match self {
+            Subcommand::View => VIEW_OPTIONS,
+            Subcommand::Render => RENDER_OPTIONS,
+            Subcommand::Analyze => ANALYZE_OPTIONS,
+            Subcommand::Export => EXPORT_OPTIONS,
+        }
+    }
+
+    fn find_option(self, flag: &str) -> Option<&'static CliOption> {
+        MAP_OPTIONS
+            .iter()
+            .chain(self.options())
+            .find(|option| option.flag == flag)
+    }
+}
+
+impl fmt::Display for Subcommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A flag with its value placeholder (None for switches) and help text
+struct CliOption {
+    flag: &'static str,
+    value: Option<&'static str>,
+    help: &'static str,
+}
+
+const fn option(flag: &'static str, value: Option<&'static str>, help: &'static str) -> CliOption {
+    CliOption { flag, value, help }
+}
+
+const MAP_OPTIONS: &[CliOption] = &[
+    option("--size", Some("size"), "map size (default standard)"),
+    option(
+        "--seed",
+        Some("seed|none"),
+        "seed, none for a random map (default 12)",
+    ),
+    option("--type", Some("map_type"), "map type (default continents)"),
+    option(
+        "--preset",
+        Some("name"),
+        "replace landmasses.yml with a built-in land preset",
+    ),
+    option(
+        "--min-land",
+        Some("ratio"),
+        "lowest land ratio the map is repaired into",
+    ),
+    option(
+        "--max-land",
+        Some("ratio"),
+        "highest land ratio the map is repaired into",
+    ),
+    option(
+        "--land-profile",
+        Some("flat|earthlike"),
+        "land weight per latitude",
+    ),
+    option(
+        "--scale-invariant",
+        None,
+        "give a seed a similar world at every map size",
+    ),
+    option(
+        "--age",
+        Some("young|standard|old"),
+        "world age, how rugged the terrain is (default from biomes.yaml)",
+    ),
+    option(
+        "--recipe",
+        Some("recipe"),
+        "map options of a civorum:v1 recipe, map options given as flags win",
+    ),
+];
+
+const VIEW_OPTIONS: &[CliOption] = &[option("--quiet", None, "do not print sanity warnings")];
+
+const RENDER_OPTIONS: &[CliOption] = &[
+    option(
+        "--out",
+        Some("path"),
+        "output PNG (default out/debug_map_<size>_seed<seed>.png)",
+    ),
+    option(
+        "--cell-px",
+        Some("px"),
+        "size of a tile in pixels, at least 10 (default 16)",
+    ),
+    option(
+        "--layer",
+        Some("layer"),
+        "terrain, temperature_range, appeal, geological_age, lowland, wind or canals",
+    ),
+    option(
+        "--layers",
+        Some("overlays"),
+        "comma-separated terrain, hills, rivers, resources, coast_distance",
+    ),
+    option("--watch", None, "re-render whenever a config file changes"),
+    option(
+        "--evolve",
+        Some("<columns>x<rows>"),
+        "contact sheet of the seed with perturbed settings",
+    ),
+    option(
+        "--debug-steps",
+        Some("dir"),
+        "landmask of every repair pass (needs the debug-render feature)",
+    ),
+    option(
+        "--from-text",
+        Some("path"),
+        "render an authored text map instead of generating one",
+    ),
+];
+
+const ANALYZE_OPTIONS: &[CliOption] = &[
+    option(
+        "--seeds",
+        Some("<start>..<end>"),
+        "stress test the seeds and list the ones that failed",
+    ),
+    option(
+        "--adjacency-csv",
+        Some("path"),
+        "write the terrain adjacency matrix as CSV, summed over all seeds with --seeds",
+    ),
+    option(
+        "--batch-csv",
+        Some("path"),
+        "with --seeds, write one CSV row per seed including its satisfied constraint count",
+    ),
+];
+
+const EXPORT_OPTIONS: &[CliOption] = &[
+    option("--out", Some("path"), "output file (required)"),
+    option(
+        "--format",
+        Some("format"),
+        "json, ndjson, csv, dot, graphml or yaml (default from the extension)",
+    ),
+    option(
+        "--graph-subset",
+        Some("subset"),
+        "all, land or water tiles of the graph (default all)",
+    ),
+];
+
+/// Render flags that select a mode, at most one of them may be given
+const RENDER_MODES: [&str; 4] = ["--watch", "--evolve", "--debug-steps", "--from-text"];
+
+#[derive(Debug, Clone, PartialEq)]
+/// Map options shared by every subcommand
+pub struct MapOptions {
+    pub size: MapSizes,
+    pub seed: Option<u64>,
+    pub map_type: MapTypes,
+    pub preset: Option<LandPreset>,
+    /// Constraint overrides, zero fields keep the configured value (see `ConstraintsConfig::merge_override`)
+    pub extra_constraints: ConstraintsConfig,
+    pub land_profile: Option<Vec<f32>>,
+    /// Turn on `LandGlobalConfig::scale_invariant`
+    pub scale_invariant: bool,
+    /// Replace `BiomesConfig::world_age`, None keeps the configured age
+    pub world_age: Option<WorldAge>,
+    /// Config hash of the `--recipe`, checked against the installed config before generating
+    pub recipe_config_hash: Option<u64>,
+    /// Flags given both explicitly and in the `--recipe`, the explicit value is used
+    pub recipe_overrides: Vec<&'static str>,
+}
+
+impl Default for MapOptions {
+    fn default() -> Self {
+        MapOptions {
+            size: MapSizes::Standard,
+            seed: Some(12),
+            map_type: MapTypes::Continents,
+            preset: None,
+            extra_constraints: ConstraintsConfig::default(),
+            land_profile: None,
+            scale_invariant: false,
+            world_age: None,
+            recipe_config_hash: None,
+            recipe_overrides: Vec::new(),
+        }
+    }
+}
+
+impl MapOptions {
+    /// Recipe of the options without config hash, `--recipe` with it gives the same options.
+    /// A map without seed is generated with seed 12, so that is the seed of its recipe
+    pub fn to_recipe(&self) -> Recipe {
+        let mut recipe = Recipe::new(self.seed.unwrap_or(12), self.size, self.map_type);
+        if let Some(preset) = self.preset {
+            recipe = recipe.with_option("preset", preset.name());
+        }
+        if self.extra_constraints.min_land_ratio > 0.0 {
+            recipe = recipe.with_option("min_land", &self.extra_constraints.min_land_ratio.to_string());
+        }
+        if self.extra_constraints.max_land_ratio > 0.0 {
+            recipe = recipe.with_option("max_land", &self.extra_constraints.max_land_ratio.to_string());
+        }
+        if let Some(profile) = &self.land_profile
+            && let Some(name) = ["flat", "earthlike"]
+                .into_iter()
+                .find(|name| land_profile_preset(name).as_ref() == Some(profile))
+        {
+            recipe = recipe.with_option("land_profile", name);
+        }
+        if self.scale_invariant {
+            recipe = recipe.with_option("scale_invariant", "true");
+        }
+        if let Some(world_age) = self.world_age {
+            recipe = recipe.with_option("age", world_age.name());
+        }
+        recipe
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewArgs {
+    pub map: MapOptions,
+    pub quiet: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// What `civorum render` draws
+pub enum RenderMode {
+    /// A single PNG of the generated map
+    Single,
+    /// Render once and then again whenever a config file changes
+    Watch,
+    /// Contact sheet of the seed with perturbed settings, `(columns, rows)`
+    Evolve((usize, usize)),
+    /// Landmask of every repair pass into the directory
+    DebugSteps(PathBuf),
+    /// An authored text map instead of a generated one
+    FromText(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderArgs {
+    pub map: MapOptions,
+    pub mode: RenderMode,
+    /// Output path, None picks a default from the mode, size and seed
+    pub out: Option<PathBuf>,
+    pub cell_px: u32,
+    pub layer: DebugLayer,
+    pub layers: LayerOptions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeArgs {
+    pub map: MapOptions,
+    /// Stress test these seeds instead of analyzing the single map
+    pub seeds: Option<Range<u64>>,
+    /// Write the terrain adjacency matrix of the map (or the sum over the seeds) to this CSV file
+    pub adjacency_csv: Option<PathBuf>,
+    /// Write one row per seed of the stress test to this CSV file
+    pub batch_csv: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What `civorum export` writes
+pub enum ExportFormat {
+    /// The map with every tile as JSON
+    Json,
+    /// The map as NDJSON, one tile per line, written while streaming
+    Ndjson,
+    /// The hex adjacency graph
+    Graph(GraphFormat),
+    /// The effective config as YAML
+    Config,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportArgs {
+    pub map: MapOptions,
+    pub out: PathBuf,
+    pub format: ExportFormat,
+    pub graph_subset: GraphSubset,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A parsed command line
+pub enum Command {
+    /// Print the usage of a subcommand, or the overview without one
+    Help(Option<Subcommand>),
+    View(ViewArgs),
+    Render(RenderArgs),
+    Analyze(AnalyzeArgs),
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Errors of `parse_args`
+pub enum CliError {
+    UnknownSubcommand(String),
+    /// The flag exists for none of the subcommands
+    UnknownFlag {
+        subcommand: Subcommand,
+        flag: String,
+    },
+    /// The flag belongs to other subcommands, e.g. `--quiet` under `render`
+    WrongSubcommand {
+        subcommand: Subcommand,
+        flag: String,
+        belongs_to: Vec<Subcommand>,
+    },
+    MissingValue {
+        flag: String,
+    },
+    InvalidValue {
+        flag: String,
+        message: String,
+    },
+    MissingFlag {
+        subcommand: Subcommand,
+        flag: &'static str,
+    },
+    /// Two flags that cannot be combined
+    Conflict {
+        first: String,
+        second: String,
+    },
+    DuplicateFlag {
+        flag: String,
+    },
+    UnexpectedArgument(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSubcommand(name) => {
+                let names: Vec<&str> = Subcommand::ALL
+                    .iter()
+                    .map(|subcommand| subcommand.name())
+                    .collect();
+                write!(
+                    f,
+                    "unknown subcommand '{name}'. Use one of: {}",
+                    names.join(", ")
+                )
+            }
+            Self::UnknownFlag { subcommand, flag } => {
+                write!(f, "unknown flag '{flag}' for '{subcommand}'")
+            }
+            Self::WrongSubcommand {
+                subcommand,
+                flag,
+                belongs_to,
+            } => {
+                let names: Vec<&str> = belongs_to
+                    .iter()
+                    .map(|subcommand| subcommand.name())
+                    .collect();
+                write!(
+                    f,
+                    "'{flag}' is not an option of '{subcommand}', only of: {}",
+                    names.join(", ")
+                )
+            }
+            Self::MissingValue { flag } => write!(f, "missing value for '{flag}'"),
+            Self::InvalidValue { flag, message } => write!(f, "{flag}: {message}"),
+            Self::MissingFlag { subcommand, flag } => write!(f, "'{subcommand}' needs '{flag}'"),
+            Self::Conflict { first, second } => {
+                write!(f, "'{first}' cannot be combined with '{second}'")
+            }
+            Self::DuplicateFlag { flag } => write!(f, "'{flag}' is given more than once"),
+            Self::UnexpectedArgument(arg) => write!(f, "unexpected argument '{arg}'"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Parse the arguments after the program name.
+/// Without a subcommand `view` runs, `--help` before or after the subcommand asks for its usage.
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let (subcommand, rest) = match args.first().map(String::as_str) {
+        None => (Subcommand::View, args),
+        Some("--help" | "-h" | "help") => {
+            let subcommand = args.get(1).and_then(|name| Subcommand::from_name(name));
+            return Ok(Command::Help(subcommand));
+        }
+        Some(first) if first.starts_with('-') => (Subcommand::View, args),
+        Some(first) => {
+            let subcommand = Subcommand::from_name(first)
+                .ok_or_else(|| CliError::UnknownSubcommand(first.to_string()))?;
+            (subcommand, &args[1..])
+        }
+    };
+
+    if rest.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(Command::Help(Some(subcommand)));
+    }
+    let flags = Flags::parse(subcommand, rest)?;
+    let map = flags.map_options()?;
+    match subcommand {
+        Subcommand::View => Ok(Command::View(ViewArgs {
+            map,
+            quiet: flags.switch("--quiet"),
+        })),
+        Subcommand::Render => flags.render(map).map(Command::Render),
+        Subcommand::Analyze => flags.analyze(map).map(Command::Analyze),
+        Subcommand::Export => flags.export(map).map(Command::Export),
+    }
+}
+
+/// Usage of a subcommand, or the list of subcommands without one
+pub fn usage(subcommand: Option<Subcommand>) -> String {
+    let Some(subcommand) = subcommand else {
+        let mut text =
+            String::from("Usage:\n  civorum [subcommand] [options]\nSubcommands (default view):\n");
+        for subcommand in Subcommand::ALL {
+            text += &format!("  {:<8} {}\n", subcommand.name(), subcommand.summary());
+        }
+        text += "Run civorum <subcommand> --help for its options.\n";
+        return text;
+    };
+
+    let mut text = format!(
+        "Usage:\n  civorum {subcommand} [options]\n{}.\n",
+        capitalize(subcommand.summary())
+    );
+    text += &options_usage("Options", subcommand.options());
+    text += &options_usage("Map options", MAP_OPTIONS);
+    let sizes: Vec<&str> = MapSizes::ALL.iter().map(MapSizes::short_label).collect();
+    let map_types: Vec<String> = MapTypes::ALL.iter().map(MapTypes::to_string).collect();
+    let presets: Vec<&str> = LandPreset::ALL.iter().map(|preset| preset.name()).collect();
+    text += &format!("Sizes: {}\n", sizes.join(" "));
+    text += &format!("Map types: {}\n", map_types.join(" "));
+    text += &format!("Presets: {}\n", presets.join(" "));
+    text
+}
+
+fn options_usage(title: &str, options: &[CliOption]) -> String {
+    let mut text = format!("{title}:\n");
+    for option in options {
+        let flag = match option.value {
+            Some(value) => format!("{} <{}>", option.flag, value.trim_matches(['<', '>'])),
+            None => option.flag.to_string(),
+        };
+        text += &format!("  {flag:<32} {}\n", option.help);
+    }
+    text
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_ascii_uppercase().to_string() + chars.as_str()
+    })
+}
+
+/// Flags of one subcommand in command line order, with their values
+struct Flags {
+    entries: Vec<(&'static str, Option<String>)>,
+}
+
+impl Flags {
+    fn parse(subcommand: Subcommand, args: &[String]) -> Result<Self, CliError> {
+        let mut entries: Vec<(&'static str, Option<String>)> = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                return Err(CliError::UnexpectedArgument(arg.clone()));
+            }
+            let Some(option) = subcommand.find_option(arg) else {
+                let belongs_to: Vec<Subcommand> = Subcommand::ALL
+                    .into_iter()
+                    .filter(|other| other.options().iter().any(|option| option.flag == arg))
+                    .collect();
+                return Err(if belongs_to.is_empty() {
+                    CliError::UnknownFlag {
+                        subcommand,
+                        flag: arg.clone(),
+                    }
+                } else {
+                    CliError::WrongSubcommand {
+                        subcommand,
+                        flag: arg.clone(),
+                        belongs_to,
+                    }
+                });
+            };
+            if entries.iter().any(|(flag, _)| *flag == option.flag) {
+                return Err(CliError::DuplicateFlag { flag: arg.clone() });
+            }
+
+            let value = match option.value {
+                Some(_) => Some(
+                    iter.next()
+                        .cloned()
+                        .ok_or_else(|| CliError::MissingValue { flag: arg.clone() })?,
+                ),
+                None => None,
+            };
+            entries.push((option.flag, value));
+        }
+        Ok(Flags { entries })
+    }
+
+    fn switch(&self, flag: &str) -> bool {
+        self.entries.iter().any(|(name, _)| *name == flag)
+    }
+
+    /// Parse the value of a flag, None if the flag is not given
+    fn value<T>(
+        &self,
+        flag: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, CliError> {
+        self.entries
+            .iter()
+            .find(|(name, _)| *name == flag)
+            .and_then(|(_, value)| value.as_deref())
+            .map(|value| {
+                parse(value).map_err(|message| CliError::InvalidValue {
+                    flag: flag.to_string(),
+                    message,
+                })
+            })
+            .transpose()
+    }
+
+    /// Map options of the explicit flags, with the `--recipe` filling in the flags that are not given
+    fn map_options(&self) -> Result<MapOptions, CliError> {
+        let Some(recipe) = self.value("--recipe", |value| Recipe::parse(value).map_err(|err| err.to_string()))? else {
+            return self.explicit_map_options();
+        };
+
+        let mut merged = Flags {
+            entries: self.entries.clone(),
+        };
+        let mut recipe_overrides = Vec::new();
+        for (flag, value) in recipe_flags(&recipe) {
+            if self.switch(flag) {
+                recipe_overrides.push(flag);
+            } else {
+                merged.entries.push((flag, value));
+            }
+        }
+        Ok(MapOptions {
+            recipe_config_hash: recipe.config_hash,
+            recipe_overrides,
+            ..merged.explicit_map_options()?
+        })
+    }
+
+    fn explicit_map_options(&self) -> Result<MapOptions, CliError> {
+        let defaults = MapOptions::default();
+        let mut extra_constraints = ConstraintsConfig::default();
+        if let Some(ratio) = self.value("--min-land", parse_land_ratio)? {
+            extra_constraints.min_land_ratio = ratio;
+        }
+        if let Some(ratio) = self.value("--max-land", parse_land_ratio)? {
+            extra_constraints.max_land_ratio = ratio;
+        }
+        let (min_land, max_land) = (extra_constraints.min_land_ratio, extra_constraints.max_land_ratio);
+        if max_land > 0.0 && min_land > max_land {
+            return Err(CliError::InvalidValue {
+                flag: "--min-land".to_string(),
+                message: format!("{min_land} is above --max-land {max_land}"),
+            });
+        }
+        Ok(MapOptions {
+            size: self
+                .value("--size", |value| value.parse::<MapSizes>().map_err(|err| err.to_string()))?
+                .unwrap_or(defaults.size),
+            seed: self.value("--seed", parse_seed)?.unwrap_or(defaults.seed),
+            map_type: self
+                .value("--type", |value| {
+                    value.parse::<MapTypes>().map_err(|err| err.to_string())
+                })?
+                .unwrap_or(defaults.map_type),
+            preset: self.value("--preset", parse_preset)?,
+            extra_constraints,
+            land_profile: self.value("--land-profile", parse_land_profile)?,
+            scale_invariant: self.switch("--scale-invariant"),
+            world_age: self.value("--age", parse_world_age)?,
+            ..defaults
+        })
+    }
+
+    fn render(&self, map: MapOptions) -> Result<RenderArgs, CliError> {
+        let modes: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|(flag, _)| *flag)
+            .filter(|flag| RENDER_MODES.contains(flag))
+            .collect();
+        if let [first, second, ..] = modes[..] {
+            return Err(CliError::Conflict {
+                first: first.to_string(),
+                second: second.to_string(),
+            });
+        }
+
+        let mode = if self.switch("--watch") {
+            RenderMode::Watch
+        } else if let Some(grid) = self.value("--evolve", parse_evolve_grid)? {
+            RenderMode::Evolve(grid)
+        } else if let Some(dir) = self.value("--debug-steps", |value| Ok(PathBuf::from(value)))? {
+            RenderMode::DebugSteps(dir)
+        } else if let Some(path) = self.value("--from-text", |value| Ok(PathBuf::from(value)))? {
+            RenderMode::FromText(path)
+        } else {
+            RenderMode::Single
+        };
+        Ok(RenderArgs {
+            map,
+            mode,
+            out: self.value("--out", |value| Ok(PathBuf::from(value)))?,
+            cell_px: self.value("--cell-px", parse_cell_px)?.unwrap_or(16),
+            layer: self
+                .value("--layer", parse_layer)?
+                .unwrap_or(DebugLayer::Terrain),
+            layers: self.value("--layers", parse_layers)?.unwrap_or_default(),
+        })
+    }
+
+    fn analyze(&self, map: MapOptions) -> Result<AnalyzeArgs, CliError> {
+        let seeds = self.value("--seeds", parse_seed_range)?;
+        let batch_csv = self.value("--batch-csv", |value| Ok(PathBuf::from(value)))?;
+        if batch_csv.is_some() && seeds.is_none() {
+            return Err(CliError::MissingFlag {
+                subcommand: Subcommand::Analyze,
+                flag: "--seeds",
+            });
+        }
+        Ok(AnalyzeArgs {
+            map,
+            seeds,
+            adjacency_csv: self.value("--adjacency-csv", |value| Ok(PathBuf::from(value)))?,
+            batch_csv,
+        })
+    }
+
+    fn export(&self, map: MapOptions) -> Result<ExportArgs, CliError> {
+        let out = self
+            .value("--out", |value| Ok(PathBuf::from(value)))?
+            .ok_or(CliError::MissingFlag {
+                subcommand: Subcommand::Export,
+                flag: "--out",
+            })?;
+        let format = match self.value("--format", parse_export_format)? {
+            Some(format) => format,
+            None => export_format_from_path(&out).ok_or_else(|| CliError::InvalidValue {
+                flag: "--out".to_string(),
+                message: format!(
+                    "cannot tell the format of '{}'. Use a .json, .ndjson, .csv, .dot, .graphml or .yaml extension or --format",
+                    out.display()
+                ),
+            })?,
+        };
+        let graph_subset = self.value("--graph-subset", parse_graph_subset)?;
+        if graph_subset.is_some() && !matches!(format, ExportFormat::Graph(_)) {
+            return Err(CliError::Conflict {
+                first: "--graph-subset".to_string(),
+                second: format!("{format:?} export"),
+            });
+        }
+        Ok(ExportArgs {
+            map,
+            out,
+            format,
+            graph_subset: graph_subset.unwrap_or_default(),
+        })
+    }
+}
+
+/// Translate the positional arguments of the deprecated `render_debug_map` binary into `civorum` command lines.
+/// `--dump-config` becomes an export of the config, `--export-graph` an export after the render.
+pub fn legacy_render_args(args: &[String]) -> Vec<Vec<String>> {
+    let owned = |values: &[&str]| {
+        values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+    };
+    match args.first().map(String::as_str) {
+        Some("--help" | "-h") => return vec![owned(&["render", "--help"])],
+        Some("--dump-config") => {
+            let mut export = owned(&["export", "--format", "yaml", "--out"]);
+            export.extend(args.get(1).cloned());
+            return vec![export];
+        }
+        _ => {}
+    }
+
+    let mut map_flags = Vec::new();
+    let mut render_flags = Vec::new();
+    let mut graph_flags = Vec::new();
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let target = match arg.as_str() {
+            "--watch" => {
+                render_flags.push(arg.clone());
+                continue;
+            }
+            "--min-land" | "--max-land" | "--land-profile" | "--preset" => &mut map_flags,
+            "--export-graph" | "--graph-subset" => &mut graph_flags,
+            flag if flag.starts_with("--") => &mut render_flags,
+            _ => {
+                positional.push(arg.clone());
+                continue;
+            }
+        };
+        let flag = if arg == "--export-graph" {
+            "--out".to_string()
+        } else {
+            arg.clone()
+        };
+        target.push(flag);
+        target.extend(iter.next().cloned());
+    }
+
+    let from_text = render_flags.iter().any(|flag| flag == "--from-text");
+    let names: &[&str] = if from_text {
+        &["--cell-px", "--out"]
+    } else {
+        &["--size", "--seed", "--type", "--cell-px", "--out"]
+    };
+    for (name, value) in names.iter().zip(positional) {
+        let target = if matches!(*name, "--size" | "--seed" | "--type") {
+            &mut map_flags
+        } else {
+            &mut render_flags
+        };
+        target.push(name.to_string());
+        target.push(value);
+    }
+
+    let mut render = owned(&["render"]);
+    render.extend(map_flags.iter().cloned());
+    render.extend(render_flags);
+    let mut commands = vec![render];
+    if !graph_flags.is_empty() {
+        let mut export = owned(&["export"]);
+        export.extend(map_flags);
+        export.extend(graph_flags);
+        commands.push(export);
+    }
+    commands
+}
+
+/// Map flags a recipe stands for, with their values. Options this build does not know are left out
+fn recipe_flags(recipe: &Recipe) -> Vec<(&'static str, Option<String>)> {
+    let mut flags = vec![
+        ("--seed", Some(recipe.seed.to_string())),
+        ("--size", Some(recipe.size.short_label().to_string())),
+        ("--type", Some(recipe.map_type.to_string())),
+    ];
+    for (key, value) in &recipe.options {
+        let flag = match key.as_str() {
+            "preset" => "--preset",
+            "min_land" => "--min-land",
+            "max_land" => "--max-land",
+            "land_profile" => "--land-profile",
+            "age" => "--age",
+            "scale_invariant" => {
+                if value == "true" {
+                    flags.push(("--scale-invariant", None));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        flags.push((flag, Some(value.clone())));
+    }
+    flags
+}
+
+fn export_format_from_path(path: &std::path::Path) -> Option<ExportFormat> {
+    if let Some(format) = GraphFormat::from_path(path) {
+        return Some(ExportFormat::Graph(format));
+    }
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "json" => Some(ExportFormat::Json),
+        "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+        "yaml" | "yml" => Some(ExportFormat::Config),
+        _ => None,
+    }
+}
+
+fn parse_export_format(value: &str) -> Result<ExportFormat, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "json" => Ok(ExportFormat::Json),
+        "ndjson" | "jsonl" => Ok(ExportFormat::Ndjson),
+        "csv" => Ok(ExportFormat::Graph(GraphFormat::EdgeListCsv)),
+        "dot" => Ok(ExportFormat::Graph(GraphFormat::Dot)),
+        "graphml" => Ok(ExportFormat::Graph(GraphFormat::GraphMl)),
+        "yaml" | "yml" => Ok(ExportFormat::Config),
+        _ => Err(format!(
+            "invalid format '{value}'. Use one of: json, ndjson, csv, dot, graphml, yaml"
+        )),
+    }
+}
+
+fn parse_graph_subset(value: &str) -> Result<GraphSubset, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "all" => Ok(GraphSubset::All),
+        "land" => Ok(GraphSubset::LandOnly),
+        "water" => Ok(GraphSubset::WaterOnly),
+        _ => Err(format!(
+            "invalid graph subset '{value}'. Use one of: all, land, water"
+        )),
+    }
+}
+
+fn parse_seed(value: &str) -> Result<Option<u64>, String> {
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+
+    value
+        .parse::<u64>()
+        .map(Some)
+        .map_err(|_| format!("invalid seed '{value}'. Use an unsigned integer or 'none'"))
+}
+
+fn parse_seed_range(value: &str) -> Result<Range<u64>, String> {
+    let parsed = value
+        .split_once("..")
+        .and_then(|(start, end)| Some(start.parse::<u64>().ok()?..end.parse::<u64>().ok()?));
+    match parsed {
+        Some(range) if range.start < range.end => Ok(range),
+        _ => Err(format!(
+            "invalid seed range '{value}'. Use <start>..<end> with start < end, e.g. 0..100"
+        )),
+    }
+}
+
+fn parse_preset(value: &str) -> Result<LandPreset, String> {
+    LandPreset::from_name(value).ok_or_else(|| {
+        let names: Vec<&str> = LandPreset::ALL.iter().map(|preset| preset.name()).collect();
+        format!("invalid preset '{value}'. Use one of: {}", names.join(", "))
+    })
+}
+
+fn parse_land_profile(value: &str) -> Result<Vec<f32>, String> {
+    land_profile_preset(value)
+        .ok_or_else(|| format!("invalid land profile '{value}'. Use flat or earthlike"))
+}
+
+fn parse_world_age(value: &str) -> Result<WorldAge, String> {
+    WorldAge::from_name(value)
+        .ok_or_else(|| format!("invalid world age '{value}'. Use young, standard or old"))
+}
+
+fn parse_evolve_grid(value: &str) -> Result<(usize, usize), String> {
+    let parsed = value
+        .to_ascii_lowercase()
+        .split_once('x')
+        .and_then(|(columns, rows)| {
+            Some((columns.parse::<usize>().ok()?, rows.parse::<usize>().ok()?))
+        });
+    match parsed {
+        Some((columns, rows)) if columns > 0 && rows > 0 => Ok((columns, rows)),
+        _ => Err(format!(
+            "invalid evolve grid '{value}'. Use <columns>x<rows>, e.g. 4x3"
+        )),
+    }
+}
+
+fn parse_land_ratio(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(ratio) if ratio > 0.0 && ratio <= 1.0 => Ok(ratio),
+        _ => Err(format!(
+            "invalid land ratio '{value}'. Use a number in (0, 1]"
+        )),
+    }
+}
+
+fn parse_cell_px(value: &str) -> Result<u32, String> {
+    let parsed = value
+        .parse::<u32>()
+        .map_err(|_| format!("invalid cell_px '{value}'. Use an integer >= 10"))?;
+
+    if parsed < 10 {
+        return Err(format!(
+            "invalid cell_px '{value}'. It must be >= 10 for hill visibility"
+        ));
+    }
+
+    Ok(parsed)
+}
+
+fn parse_layer(value: &str) -> Result<DebugLayer, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "terrain" => Ok(DebugLayer::Terrain),
+        "temperature_range" | "temperature-range" => Ok(DebugLayer::TemperatureRange),
+        "appeal" => Ok(DebugLayer::Appeal),
+        "geological_age" | "geological-age" => Ok(DebugLayer::GeologicalAge),
+        "lowland" => Ok(DebugLayer::Lowland),
+        "wind" => Ok(DebugLayer::Wind),
+        "canals" => Ok(DebugLayer::Canals),
+        _ => Err(format!(
+            "invalid layer '{value}'. Use one of: terrain, temperature_range, appeal, geological_age, lowland, wind, canals"
+        )),
+    }
+}
+
+fn parse_layers(value: &str) -> Result<LayerOptions, String> {
+    let mut layers = LayerOptions {
+        show_hills: false,
+        show_rivers: false,
+        show_resources: false,
+        show_coast_distance_heat: false,
+    };
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name.to_ascii_lowercase().as_str() {
+            // terrain is always drawn
+            "terrain" => {}
+            "hills" => layers.show_hills = true,
+            "rivers" => layers.show_rivers = true,
+            "resources" => layers.show_resources = true,
+            "coast_distance" | "coast-distance" => layers.show_coast_distance_heat = true,
+            _ => {
+                return Err(format!(
+                    "invalid overlay '{name}'. Use any of: terrain, hills, rivers, resources, coast_distance"
+                ));
+            }
+        }
+    }
+    Ok(layers)
+}
@@ -0,0 +1,135 @@
+use std::{error::Error, fs, ops::Range, path::Path};
+
+use civorum_mapgen::pipeline::{
+    analysis::{AdjacencyMatrix, StressTestReport, terrain_adjacency_matrix},
+    helpers::biomes_config,
+    map::Map,
+    sanity::check_map,
+    water_bodies::{WaterBodyInfo, WaterBodyKind},
+};
+
+use crate::{
+    cli::{AnalyzeArgs, MapOptions},
+    commands::{apply_map_options, generate, recipe},
+};
+
+/// Print the statistics and sanity warnings of the map, or the stress test report of a seed range
+pub(super) fn run(args: &AnalyzeArgs) -> Result<(), Box<dyn Error>> {
+    apply_map_options(&args.map);
+    match &args.seeds {
+        Some(seeds) => {
+            let report = stress_test(&args.map, seeds.clone());
+            if let Some(path) = &args.batch_csv {
+                write_csv(path, &report.to_csv(), &format!("{} seeds", report.tested))?;
+            }
+            if let Some(path) = &args.adjacency_csv {
+                let matrix = seeds
+                    .clone()
+                    .map(|seed| {
+                        let map = Map::try_new_with_type(Some(seed), args.map.size, args.map.map_type)?;
+                        Ok(adjacency(&map))
+                    })
+                    .sum::<Result<AdjacencyMatrix, Box<dyn Error>>>()?;
+                write_adjacency_csv(path, &matrix)?;
+            }
+        }
+        None => {
+            let map = generate(&args.map)?;
+            let stats = map.statistics();
+            println!("Seed:          {}", map.seed_string());
+            println!("Recipe:        {}", recipe(&args.map));
+            println!("Config hash:   {:016x}", map.config_hash());
+            println!("Land ratio:    {:.3}", stats.land_ratio);
+            println!("Landmasses:    {}", stats.n_landmasses);
+            println!("Largest ratio: {:.3}", stats.largest_ratio);
+            println!("Coastline:     {}", stats.coastline_length);
+            println!("Compactness:   {:.3}", stats.compactness);
+            println!("Water bodies:  {}", water_body_counts(map.water_bodies()));
+            println!("Repair passes: {}", map.repair_iterations().get());
+            println!("World age:     {}", biomes_config().world_age.name());
+            if let Some(report) = map.biome_constraints() {
+                let outcome = if report.is_met() { "met" } else { "unmet" };
+                println!("Biomes:        constraints {outcome} after {} adjustments", report.adjustments);
+            }
+            let outcomes = map.constraint_outcomes();
+            println!("Constraints:   {} of {} satisfied", map.satisfied_constraints(), outcomes.len());
+            for outcome in outcomes {
+                println!("  {outcome}");
+                if let Some(suggestion) = &outcome.suggestion {
+                    println!("    {suggestion}");
+                }
+            }
+            for warning in check_map(&map) {
+                println!("Warning [{:?}]: {}", warning.code, warning.message);
+            }
+            if let Some(path) = &args.adjacency_csv {
+                write_adjacency_csv(path, &adjacency(&map))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Number of water bodies per kind, e.g. "1 ocean, 4 seas, 2 lakes, 0 ponds"
+fn water_body_counts(bodies: &[WaterBodyInfo]) -> String {
+    [
+        (WaterBodyKind::Ocean, "ocean", "oceans"),
+        (WaterBodyKind::Sea, "sea", "seas"),
+        (WaterBodyKind::Lake, "lake", "lakes"),
+        (WaterBodyKind::Pond, "pond", "ponds"),
+    ]
+    .iter()
+    .map(|&(kind, one, many)| {
+        let count = bodies.iter().filter(|body| body.kind == kind).count();
+        format!("{count} {}", if count == 1 { one } else { many })
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn adjacency(map: &Map) -> AdjacencyMatrix {
+    let (width, height) = map.size().dimensions();
+    let terrain: Vec<_> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    terrain_adjacency_matrix(&terrain, width, height)
+}
+
+fn write_adjacency_csv(path: &Path, matrix: &AdjacencyMatrix) -> Result<(), Box<dyn Error>> {
+    write_csv(path, &matrix.to_csv(), &format!("{} neighbor pairs", matrix.pair_count()))
+}
+
+fn write_csv(path: &Path, csv: &str, summary: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, csv)?;
+    println!("Wrote {} ({summary})", path.display());
+    Ok(())
+}
+
+fn stress_test(map: &MapOptions, seeds: Range<u64>) -> StressTestReport {
+    println!(
+        "Generating {:?} maps ({:?}) for seeds {}..{}...",
+        map.map_type, map.size, seeds.start, seeds.end
+    );
+    let report = Map::stress_test(seeds, map.size, map.map_type);
+
+    println!("Tested:    {}", report.tested);
+    println!("Converged: {}", report.converged);
+    println!("Failed:    {}", report.failed_seeds.len());
+    if !report.failed_seeds.is_empty() {
+        let seeds: Vec<String> = report.failed_seeds.iter().map(u64::to_string).collect();
+        println!("Failed seeds: {}", seeds.join(", "));
+    }
+
+    println!("Land ratio distribution:");
+    for (bucket, count) in &report.land_ratio_distribution {
+        println!("  {:.2}-{:.2} {:>6}", bucket, bucket + 0.05, count);
+    }
+    println!("Landmass count distribution:");
+    for (bucket, count) in &report.component_count_distribution {
+        println!("  {:>9} {:>6}", bucket, count);
+    }
+    report
+}
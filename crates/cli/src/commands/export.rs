@@ -0,0 +1,53 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::BufWriter,
+};
+
+use civorum_core::export_adjacency;
+use civorum_mapgen::pipeline::helpers::effective_config;
+
+use crate::{
+    cli::{ExportArgs, ExportFormat},
+    commands::{apply_map_options, generate, recipe},
+};
+
+/// Write the map, its adjacency graph or the effective config to the output path
+pub(super) fn run(args: &ExportArgs) -> Result<(), Box<dyn Error>> {
+    apply_map_options(&args.map);
+    if let Some(parent) = args.out.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    match args.format {
+        ExportFormat::Json => {
+            let map = generate(&args.map)?;
+            let mut value = map.to_json_value();
+            value["recipe"] = recipe(&args.map).to_string().into();
+            fs::write(&args.out, format!("{value:#}\n"))?;
+            println!("Wrote {} ({})", args.out.display(), map.seed_string());
+        }
+        ExportFormat::Ndjson => {
+            let map = generate(&args.map)?;
+            map.export_ndjson(BufWriter::new(File::create(&args.out)?))?;
+            println!("Wrote {} ({})", args.out.display(), map.seed_string());
+        }
+        ExportFormat::Graph(format) => {
+            let map = generate(&args.map)?;
+            export_adjacency(&map, &args.out, format, args.graph_subset)?;
+            println!("Wrote {}", args.out.display());
+        }
+        ExportFormat::Config => {
+            let config = effective_config();
+            fs::write(&args.out, config.to_yaml())?;
+            println!(
+                "Wrote {} (config hash {:016x})",
+                args.out.display(),
+                config.hash()
+            );
+        }
+    }
+    Ok(())
+}
@@ -1 +1,86 @@
-//! CLI commands: generate, inspect, step, and save.
+//! CLI commands: view, render, analyze and export.
+
+mod analyze;
+mod export;
+mod render;
+mod view;
+
+pub use render::reload_and_diff;
+
+use std::{error::Error, io};
+
+use civorum_mapgen::pipeline::{
+    error::GenError,
+    helpers::{
+        LandmassesConfig, biomes_config, config_hash, landmasses_config, set_biomes_config, set_landmasses_config,
+    },
+    map::Map,
+    recipe::Recipe,
+};
+
+use crate::cli::{Command, MapOptions, usage};
+
+/// Run a parsed command line
+pub fn run(command: &Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Help(subcommand) => {
+            print!("{}", usage(*subcommand));
+            Ok(())
+        }
+        Command::View(args) => view::run(args),
+        Command::Render(args) => render::run(args),
+        Command::Analyze(args) => analyze::run(args),
+        Command::Export(args) => export::run(args),
+    }
+}
+
+/// Install the land config the map options ask for: the preset (or `landmasses.yml`)
+/// with the constraints of the map type, the land profile and scale invariance given on the command line,
+/// and the world age into the biome config.
+/// Warns about flags that replaced a `--recipe` value and about a recipe made with another config
+fn apply_map_options(map: &MapOptions) {
+    for flag in &map.recipe_overrides {
+        eprintln!("Warning: {flag} replaces the value of the recipe");
+    }
+    let config = map.preset.map_or_else(
+        || landmasses_config().as_ref().clone(),
+        LandmassesConfig::from_presets,
+    );
+    let constraints = config
+        .constraints_for(map.map_type)
+        .merge_override(&map.extra_constraints);
+    let mut config = config.with_constraint_override(map.map_type, constraints);
+    if let Some(profile) = &map.land_profile {
+        config = config.with_latitude_land_profile(profile.clone());
+    }
+    if map.scale_invariant {
+        config = config.with_scale_invariant(true);
+    }
+    set_landmasses_config(config);
+    if let Some(world_age) = map.world_age {
+        set_biomes_config(biomes_config().as_ref().clone().with_world_age(world_age));
+    }
+    if let Some(hash) = map.recipe_config_hash
+        && hash != config_hash()
+    {
+        eprintln!(
+            "Warning: the recipe was made with config {hash:016x}, the current config is {:016x}. The map may differ",
+            config_hash()
+        );
+    }
+}
+
+/// Recipe of the map options with the hash of the installed config, call after `apply_map_options`
+fn recipe(map: &MapOptions) -> Recipe {
+    map.to_recipe().with_config_hash(config_hash())
+}
+
+/// Generate the map of the options, fails if the installed config can not produce it
+/// (e.g. `--min-land` above the configured maximum land ratio)
+fn generate(map: &MapOptions) -> Result<Map, GenError> {
+    Map::try_new_with_type(map.seed, map.size, map.map_type)
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
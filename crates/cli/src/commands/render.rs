@@ -0,0 +1,233 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use civorum_core::{render_debug_layer, render_evolve_sheet, render_map_data};
+use civorum_mapgen::pipeline::{
+    analysis::MapStatistics,
+    helpers::{biomes_config_path, config_hash, landmasses_config_path, reload_configs_from, resources_config_path},
+    text_map::parse_text_map,
+};
+
+use crate::{
+    cli::{MapOptions, RenderArgs, RenderMode},
+    commands::{apply_map_options, generate, invalid_input, recipe},
+};
+
+/// Long side of every thumbnail on the evolve sheet
+const EVOLVE_THUMB_PX: u32 = 240;
+
+/// Render the map in the selected mode
+pub(super) fn run(args: &RenderArgs) -> Result<(), Box<dyn Error>> {
+    let map = &args.map;
+    match &args.mode {
+        RenderMode::FromText(path) => render_text_map(path, args),
+        RenderMode::Evolve(grid) => {
+            apply_map_options(map);
+            let default_path = || {
+                format!(
+                    "out/evolve_{}_seed{}.png",
+                    map.size.short_label(),
+                    map.seed.unwrap_or(12)
+                )
+            };
+            let out_path = args
+                .out
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(default_path()));
+            render_evolve(map, *grid, &out_path)
+        }
+        RenderMode::DebugSteps(dir) => {
+            apply_map_options(map);
+            render_repair_steps(map, args.cell_px, dir)
+        }
+        RenderMode::Single | RenderMode::Watch => {
+            apply_map_options(map);
+            let out_path = args.out.clone().unwrap_or_else(|| default_out_path(map));
+            render_debug_layer(
+                map.seed,
+                map.size,
+                map.map_type,
+                args.layer,
+                args.cell_px,
+                &out_path,
+                &args.layers,
+            )?;
+            let recipe_path = out_path.with_extension("txt");
+            fs::write(&recipe_path, format!("{}\n", recipe(map)))?;
+            println!("Wrote {} and {}", out_path.display(), recipe_path.display());
+            println!("Config hash {:016x}", config_hash());
+            println!("Recipe {}", recipe(map));
+
+            if args.mode == RenderMode::Watch {
+                watch_configs(args, &out_path)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Output path when none is given, e.g. `out/debug_map_standard_seed42.png`
+fn default_out_path(map: &MapOptions) -> PathBuf {
+    let seed = map
+        .seed
+        .map_or_else(|| "none".to_string(), |seed| seed.to_string());
+    PathBuf::from(format!(
+        "out/debug_map_{}_seed{seed}.png",
+        map.size.short_label()
+    ))
+}
+
+/// Render an authored text map, the map options are not used
+fn render_text_map(path: &Path, args: &RenderArgs) -> Result<(), Box<dyn Error>> {
+    let raw = fs::read_to_string(path)?;
+    let data =
+        parse_text_map(&raw).map_err(|err| invalid_input(format!("{}: {err}", path.display())))?;
+
+    let out_path = args
+        .out
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("out/text_map.png"));
+    render_map_data(&data, args.cell_px, &out_path, &args.layers)?;
+    println!(
+        "Wrote {} ({}x{} tiles)",
+        out_path.display(),
+        data.width,
+        data.height
+    );
+    Ok(())
+}
+
+#[cfg(feature = "debug-render")]
+fn render_repair_steps(
+    map: &MapOptions,
+    cell_px: u32,
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let paths = civorum_core::generate_debug_png_for_each_repair_step(
+        map.seed,
+        map.size,
+        map.map_type,
+        cell_px,
+        out_dir,
+    )?;
+    println!(
+        "Wrote {} repair steps to {}",
+        paths.len(),
+        out_dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "debug-render"))]
+fn render_repair_steps(
+    _map: &MapOptions,
+    _cell_px: u32,
+    _out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    Err(invalid_input(
+        "--debug-steps needs the debug-render feature (cargo run --features debug-render ...)"
+            .to_string(),
+    )
+    .into())
+}
+
+/// Render the evolve contact sheet and write the label of every cell next to it (`<out_path>.txt`)
+fn render_evolve(
+    map: &MapOptions,
+    grid: (usize, usize),
+    out_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let cells = render_evolve_sheet(
+        map.seed,
+        map.size,
+        map.map_type,
+        grid,
+        EVOLVE_THUMB_PX,
+        out_path,
+    )?;
+    let legend: String = cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| format!("row {} col {}: {}\n", i / grid.0, i % grid.0, cell.label()))
+        .collect();
+    let legend_path = out_path.with_extension("txt");
+    fs::write(&legend_path, &legend)?;
+
+    print!("{legend}");
+    println!("Wrote {} and {}", out_path.display(), legend_path.display());
+    Ok(())
+}
+
+/// Poll the config files and re-render whenever one of them changes, until interrupted with Ctrl+C.
+fn watch_configs(args: &RenderArgs, out_path: &Path) -> Result<(), Box<dyn Error>> {
+    let map = &args.map;
+    let paths = [landmasses_config_path(), biomes_config_path(), resources_config_path()];
+    let mut mtimes = config_mtimes(&paths);
+    let mut previous = generate(map)?.statistics();
+
+    println!(
+        "Watching {}, {} and {} (Ctrl+C to stop)",
+        paths[0].display(),
+        paths[1].display(),
+        paths[2].display()
+    );
+    loop {
+        thread::sleep(Duration::from_millis(500));
+        let current = config_mtimes(&paths);
+        if current == mtimes {
+            continue;
+        }
+        mtimes = current;
+
+        let (stats, summary) = match reload_and_diff(&paths, map, &previous) {
+            Ok(reloaded) => reloaded,
+            Err(err) => {
+                eprintln!("{err}. Keeping the previous output.");
+                continue;
+            }
+        };
+        if let Err(err) = render_debug_layer(
+            map.seed,
+            map.size,
+            map.map_type,
+            args.layer,
+            args.cell_px,
+            out_path,
+            &args.layers,
+        ) {
+            eprintln!("Failed to render: {err}. Keeping the previous output.");
+            continue;
+        }
+
+        println!("Re-rendered {}: {summary}", out_path.display());
+        previous = stats;
+    }
+}
+
+/// Reload the landmass, biome and resource configs at `paths`, install the map options on top and regenerate the
+/// map. Returns its statistics with a summary of how they changed compared to `previous`.
+/// A config that fails to load replaces none of them, the caller keeps `previous` on any error.
+pub fn reload_and_diff(
+    paths: &[PathBuf; 3],
+    map: &MapOptions,
+    previous: &MapStatistics,
+) -> Result<(MapStatistics, String), Box<dyn Error>> {
+    let [landmasses, biomes, resources] = paths;
+    reload_configs_from(landmasses, biomes, resources)?;
+    apply_map_options(map);
+    let stats = generate(map)?.statistics();
+    let summary = stats.diff_summary(previous);
+    Ok((stats, summary))
+}
+
+fn config_mtimes(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
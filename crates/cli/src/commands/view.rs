@@ -0,0 +1,22 @@
+use std::error::Error;
+
+use civorum_mapgen::pipeline::sanity::check_map;
+
+use crate::{
+    cli::ViewArgs,
+    commands::{apply_map_options, generate},
+};
+
+/// Print the terrain overview of the map and its sanity warnings
+pub(super) fn run(args: &ViewArgs) -> Result<(), Box<dyn Error>> {
+    apply_map_options(&args.map);
+    let map = generate(&args.map)?;
+    map.print_compact_legend();
+
+    if !args.quiet {
+        for warning in check_map(&map) {
+            println!("Warning [{:?}]: {}", warning.code, warning.message);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,5 @@
+//! The `civorum` command line: argument parsing, the subcommands and the terminal UI.
+
+pub mod cli;
+pub mod commands;
+pub mod ui;
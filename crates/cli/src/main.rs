@@ -1,6 +1,19 @@
-mod commands;
-mod ui;
+use std::{env, process};
+
+use civorum_cli::{cli::parse_args, commands};
 
 fn main() {
-    println!("Civorum CLI skeleton. Try: civorum --help");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("Error: {err}. Run civorum --help for usage.");
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = commands::run(&command) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,340 @@
+use std::path::PathBuf;
+
+use civorum_cli::cli::{
+    CliError, Command, ExportFormat, MapOptions, RenderMode, Subcommand, legacy_render_args,
+    parse_args, usage,
+};
+use civorum_core::{DebugLayer, GraphFormat, GraphSubset};
+use civorum_mapgen::pipeline::{
+    helpers::{LandPreset, WorldAge},
+    map_sizes::MapSizes, map_types::MapTypes};
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+fn parse(line: &str) -> Result<Command, CliError> {
+    parse_args(&args(line))
+}
+
+#[test]
+fn view_is_the_default_subcommand() {
+    let Ok(Command::View(view)) = parse("") else {
+        panic!("expected view")
+    };
+    assert_eq!(view.map, MapOptions::default());
+    assert!(!view.quiet);
+
+    let Ok(Command::View(view)) = parse("--quiet --size duel") else {
+        panic!("expected view")
+    };
+    assert!(view.quiet);
+    assert_eq!(view.map.size, MapSizes::Duel);
+    assert_eq!(parse("view --quiet"), parse("--quiet"));
+}
+
+#[test]
+fn map_options_are_shared_by_every_subcommand() {
+    for subcommand in ["view", "render", "analyze", "export --out map.json"] {
+        let line = format!(
+            "{subcommand} --size small --seed none --type small_continents --preset ocean-world --min-land 0.2 --land-profile earthlike --scale-invariant --age old"
+        );
+        let map = match parse(&line) {
+            Ok(Command::View(args)) => args.map,
+            Ok(Command::Render(args)) => args.map,
+            Ok(Command::Analyze(args)) => args.map,
+            Ok(Command::Export(args)) => args.map,
+            other => panic!("{subcommand}: {other:?}"),
+        };
+        assert_eq!(map.size, MapSizes::Small);
+        assert_eq!(map.seed, None);
+        assert_eq!(map.map_type, MapTypes::SmallContinents);
+        assert_eq!(map.preset, Some(LandPreset::OceanWorld));
+        assert_eq!(map.extra_constraints.min_land_ratio, 0.2);
+        assert_eq!(map.extra_constraints.max_land_ratio, 0.0);
+        assert!(map.land_profile.is_some_and(|profile| !profile.is_empty()));
+        assert!(map.scale_invariant);
+        assert_eq!(map.world_age, Some(WorldAge::Old));
+    }
+}
+
+fn view_map(line: &str) -> MapOptions {
+    match parse(line) {
+        Ok(Command::View(args)) => args.map,
+        other => panic!("{line}: {other:?}"),
+    }
+}
+
+#[test]
+fn recipes_round_trip_the_map_options() {
+    let map = view_map("--size small --seed 9 --type terra --preset ocean-world --max-land 0.4 --land-profile flat --scale-invariant --age young");
+    let recipe = map.to_recipe().to_string();
+    assert_eq!(
+        recipe,
+        "civorum:v1;seed=9;size=small;type=terra;preset=ocean-world;max_land=0.4;land_profile=flat;scale_invariant=true;age=young"
+    );
+    assert_eq!(view_map(&format!("--recipe {recipe}")), map);
+    assert_eq!(view_map("--recipe civorum:v1;seed=12;size=standard;type=continents"), MapOptions::default());
+}
+
+#[test]
+fn explicit_flags_win_over_the_recipe() {
+    let map = view_map("--seed 3 --recipe civorum:v1;seed=12;size=duel;type=pangea;age=old;sea=high;cfg=0x00000000000000ff --age young");
+    assert_eq!(map.seed, Some(3));
+    assert_eq!(map.size, MapSizes::Duel);
+    assert_eq!(map.map_type, MapTypes::Pangea);
+    assert_eq!(map.world_age, Some(WorldAge::Young));
+    assert_eq!(map.recipe_overrides, ["--seed", "--age"]);
+    assert_eq!(map.recipe_config_hash, Some(0xff));
+
+    let Err(CliError::InvalidValue { flag, .. }) = parse("--recipe seed=12;size=duel;type=pangea") else {
+        panic!("expected an invalid recipe")
+    };
+    assert_eq!(flag, "--recipe");
+}
+
+#[test]
+fn render_options() {
+    let Ok(Command::Render(render)) =
+        parse("render --layer appeal --cell-px 12 --out x.png --layers rivers")
+    else {
+        panic!("expected render");
+    };
+    assert_eq!(render.mode, RenderMode::Single);
+    assert_eq!(render.layer, DebugLayer::Appeal);
+    assert_eq!(render.cell_px, 12);
+    assert_eq!(render.out, Some(PathBuf::from("x.png")));
+    assert!(render.layers.show_rivers && !render.layers.show_hills);
+
+    let mode = |line: &str| match parse(line) {
+        Ok(Command::Render(render)) => render.mode,
+        other => panic!("{line}: {other:?}"),
+    };
+    assert_eq!(mode("render --watch"), RenderMode::Watch);
+    assert_eq!(mode("render --evolve 4x3"), RenderMode::Evolve((4, 3)));
+    assert_eq!(
+        mode("render --debug-steps steps"),
+        RenderMode::DebugSteps(PathBuf::from("steps"))
+    );
+    assert_eq!(
+        mode("render --from-text map.txt"),
+        RenderMode::FromText(PathBuf::from("map.txt"))
+    );
+}
+
+#[test]
+fn analyze_and_export_options() {
+    let Ok(Command::Analyze(analyze)) = parse("analyze --seeds 5..9") else {
+        panic!("expected analyze")
+    };
+    assert_eq!(analyze.seeds, Some(5..9));
+    assert!(analyze.adjacency_csv.is_none());
+    assert!(matches!(parse("analyze"), Ok(Command::Analyze(analyze)) if analyze.seeds.is_none()));
+    let Ok(Command::Analyze(analyze)) = parse("analyze --seeds 0..4 --adjacency-csv out/adjacency.csv") else {
+        panic!("expected analyze")
+    };
+    assert_eq!(analyze.adjacency_csv, Some(PathBuf::from("out/adjacency.csv")));
+    assert!(analyze.batch_csv.is_none());
+    let Ok(Command::Analyze(analyze)) = parse("analyze --seeds 0..4 --batch-csv out/batch.csv") else {
+        panic!("expected analyze")
+    };
+    assert_eq!(analyze.batch_csv, Some(PathBuf::from("out/batch.csv")));
+    assert!(matches!(
+        parse("analyze --batch-csv out/batch.csv"),
+        Err(CliError::MissingFlag { flag: "--seeds", .. })
+    ));
+
+    let format = |line: &str| match parse(line) {
+        Ok(Command::Export(export)) => export.format,
+        other => panic!("{line}: {other:?}"),
+    };
+    assert_eq!(format("export --out map.json"), ExportFormat::Json);
+    assert_eq!(
+        format("export --out graph.dot"),
+        ExportFormat::Graph(GraphFormat::Dot)
+    );
+    assert_eq!(format("export --out config.yml"), ExportFormat::Config);
+    assert_eq!(format("export --out map.ndjson"), ExportFormat::Ndjson);
+    assert_eq!(
+        format("export --out dump.txt --format graphml"),
+        ExportFormat::Graph(GraphFormat::GraphMl)
+    );
+
+    let Ok(Command::Export(export)) = parse("export --out g.csv --graph-subset land") else {
+        panic!("expected export")
+    };
+    assert_eq!(export.graph_subset, GraphSubset::LandOnly);
+}
+
+#[test]
+fn help_for_every_subcommand() {
+    assert_eq!(parse("--help"), Ok(Command::Help(None)));
+    assert_eq!(
+        parse("help render"),
+        Ok(Command::Help(Some(Subcommand::Render)))
+    );
+    for subcommand in Subcommand::ALL {
+        let line = format!("{subcommand} --size duel -h");
+        assert_eq!(parse(&line), Ok(Command::Help(Some(subcommand))));
+
+        let text = usage(Some(subcommand));
+        assert!(text.starts_with(&format!("Usage:\n  civorum {subcommand} [options]")));
+        assert!(text.contains("--seed <seed|none>"), "{subcommand}");
+    }
+    assert!(
+        usage(None)
+            .lines()
+            .filter(|line| line.starts_with("  "))
+            .count()
+            > Subcommand::ALL.len()
+    );
+}
+
+#[test]
+fn invalid_command_lines() {
+    assert_eq!(
+        parse("draw"),
+        Err(CliError::UnknownSubcommand("draw".to_string()))
+    );
+    assert_eq!(
+        parse("render --frobnicate"),
+        Err(CliError::UnknownFlag {
+            subcommand: Subcommand::Render,
+            flag: "--frobnicate".to_string()
+        })
+    );
+    assert_eq!(
+        parse("render --seed"),
+        Err(CliError::MissingValue {
+            flag: "--seed".to_string()
+        })
+    );
+    assert_eq!(
+        parse("render standard 1"),
+        Err(CliError::UnexpectedArgument("standard".to_string()))
+    );
+    assert_eq!(
+        parse("view --seed 1 --seed 2"),
+        Err(CliError::DuplicateFlag {
+            flag: "--seed".to_string()
+        })
+    );
+    assert_eq!(
+        parse("export"),
+        Err(CliError::MissingFlag {
+            subcommand: Subcommand::Export,
+            flag: "--out"
+        })
+    );
+
+    for line in [
+        "render --size galaxy",
+        "render --seed -3",
+        "view --type archipelago",
+        "view --preset mars",
+        "render --cell-px 4",
+        "render --evolve 4by3",
+        "analyze --seeds 9..5",
+        "export --out map.obj",
+        "view --size duel --min-land 0.7 --max-land 0.3",
+    ] {
+        assert!(
+            matches!(parse(line), Err(CliError::InvalidValue { .. })),
+            "{line}"
+        );
+    }
+}
+
+#[test]
+fn land_ratio_bounds_must_be_ordered() {
+    assert_eq!(
+        parse("view --min-land 0.7 --max-land 0.3"),
+        Err(CliError::InvalidValue {
+            flag: "--min-land".to_string(),
+            message: "0.7 is above --max-land 0.3".to_string(),
+        })
+    );
+    // a single bound and equal bounds are fine
+    assert!(parse("view --min-land 0.7").is_ok());
+    assert!(parse("view --min-land 0.4 --max-land 0.4").is_ok());
+}
+
+#[test]
+fn flags_of_other_subcommands_and_conflicts() {
+    assert_eq!(
+        parse("render --quiet"),
+        Err(CliError::WrongSubcommand {
+            subcommand: Subcommand::Render,
+            flag: "--quiet".to_string(),
+            belongs_to: vec![Subcommand::View],
+        })
+    );
+    assert!(matches!(
+        parse("view --out map.png"),
+        Err(CliError::WrongSubcommand { belongs_to, .. }) if belongs_to == [Subcommand::Render, Subcommand::Export]
+    ));
+    assert!(matches!(
+        parse("export --out a.json --watch"),
+        Err(CliError::WrongSubcommand { .. })
+    ));
+
+    assert_eq!(
+        parse("render --evolve 2x2 --from-text map.txt"),
+        Err(CliError::Conflict {
+            first: "--evolve".to_string(),
+            second: "--from-text".to_string()
+        })
+    );
+    assert!(matches!(
+        parse("render --watch --debug-steps steps"),
+        Err(CliError::Conflict { .. })
+    ));
+    assert!(matches!(
+        parse("export --out map.json --graph-subset land"),
+        Err(CliError::Conflict { .. })
+    ));
+}
+
+#[test]
+fn legacy_positional_arguments_are_translated() {
+    let translate = |line: &str| -> Vec<String> {
+        legacy_render_args(&args(line))
+            .into_iter()
+            .map(|command| command.join(" "))
+            .collect()
+    };
+    assert_eq!(
+        translate("--layer appeal duel 3 pangea 12 out.png"),
+        ["render --size duel --seed 3 --type pangea --layer appeal --cell-px 12 --out out.png"]
+    );
+    assert_eq!(
+        translate("--watch --preset land-world"),
+        ["render --preset land-world --watch"]
+    );
+    assert_eq!(
+        translate("--from-text map.txt 14"),
+        ["render --from-text map.txt --cell-px 14"]
+    );
+    assert_eq!(
+        translate("--dump-config cfg.txt"),
+        ["export --format yaml --out cfg.txt"]
+    );
+    assert_eq!(translate("--help"), ["render --help"]);
+    assert_eq!(
+        translate("--export-graph g.csv --graph-subset water small none"),
+        [
+            "render --size small --seed none",
+            "export --size small --seed none --out g.csv --graph-subset water",
+        ]
+    );
+
+    for line in [
+        "duel 3 pangea 12 out.png",
+        "--evolve 2x2 duel 9",
+        "--export-graph g.dot standard",
+    ] {
+        for command in legacy_render_args(&args(line)) {
+            assert!(parse_args(&command).is_ok(), "{line}: {command:?}");
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fs, path::PathBuf};
+
+use civorum_cli::{cli::MapOptions, commands::reload_and_diff};
+use civorum_mapgen::pipeline::{
+    analysis::MapStatistics,
+    helpers::{biomes_config_path, landmasses_config_path, resources_config_path},
+    map::Map,
+    map_sizes::MapSizes,
+};
+
+fn fields(stats: &MapStatistics) -> (f32, usize, f32, usize) {
+    (stats.land_ratio, stats.n_landmasses, stats.largest_ratio, stats.coastline_length)
+}
+
+/// Copies of the shipped landmass, biome and resource configs in a fresh directory
+fn config_copies() -> [PathBuf; 3] {
+    let dir = std::env::temp_dir().join(format!("civorum_watch_reload_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    [landmasses_config_path(), biomes_config_path(), resources_config_path()].map(|shipped| {
+        let copy = dir.join(shipped.file_name().unwrap());
+        fs::copy(&shipped, &copy).unwrap();
+        copy
+    })
+}
+
+#[test]
+fn reloading_summarizes_changes_and_keeps_the_previous_configs_on_errors() {
+    let paths = config_copies();
+    let [landmasses, biomes, _] = &paths;
+    let map = MapOptions {
+        size: MapSizes::Duel,
+        seed: Some(3),
+        ..MapOptions::default()
+    };
+
+    let (base, _) = reload_and_diff(&paths, &map, &MapStatistics::default()).unwrap();
+    let (same, summary) = reload_and_diff(&paths, &map, &base).unwrap();
+    assert_eq!(fields(&same), fields(&base));
+    assert_eq!(summary, base.diff_summary(&base));
+
+    // a real edit shows up in the summary
+    let shipped = fs::read_to_string(landmasses).unwrap();
+    assert!(shipped.contains("max_repair_iters: 6"));
+    fs::write(landmasses, shipped.replace("max_repair_iters: 6", "max_repair_iters: 0")).unwrap();
+    let (edited, summary) = reload_and_diff(&paths, &map, &base).unwrap();
+    assert_ne!(fields(&edited), fields(&base));
+    assert_eq!(summary, edited.diff_summary(&base));
+
+    // a malformed biome config replaces none of the configs, not even the valid landmass config
+    fs::write(landmasses, &shipped).unwrap();
+    fs::write(biomes, "temperature: [").unwrap();
+    let err = reload_and_diff(&paths, &map, &edited).unwrap_err();
+    assert!(err.to_string().starts_with("Failed to parse biome config"), "{err}");
+    let kept = Map::new_with_type(map.seed, map.size, map.map_type).statistics();
+    assert_eq!(fields(&kept), fields(&edited));
+
+    fs::remove_dir_all(landmasses.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,35 @@
+//! Generate a batch of seeds through the library and write one CSV row per seed, like `analyze --batch-csv`.
+//!
+//! cargo run -p civorum-core --example analyze_seeds -- [seeds, e.g. 0..20]
+
+use std::{env, error::Error, fs, ops::Range, path::Path};
+
+use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes, map_types::MapTypes};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let seeds = match env::args().nth(1) {
+        Some(value) => {
+            let (start, end) = value.split_once("..").ok_or("seeds must look like 0..20")?;
+            start.parse()?..end.parse()?
+        }
+        None => 0..20,
+    };
+    let csv = run(seeds, MapSizes::Duel, MapTypes::Continents);
+    let path = Path::new("out/analyze_seeds.csv");
+    fs::create_dir_all("out")?;
+    fs::write(path, &csv)?;
+    println!("Wrote {} rows to {}", csv.lines().count() - 1, path.display());
+    Ok(())
+}
+
+/// Generate every seed and return the per-seed rows (land ratio, landmasses, repair passes, constraints) as CSV
+pub fn run(seeds: Range<u64>, size: MapSizes, map_type: MapTypes) -> String {
+    let report = Map::stress_test(seeds, size, map_type);
+    println!(
+        "{} of {} seeds converged, average land ratio {:.3}",
+        report.converged,
+        report.tested,
+        report.seeds.iter().map(|row| row.land_ratio).sum::<f32>() / report.seeds.len().max(1) as f32
+    );
+    report.to_csv()
+}
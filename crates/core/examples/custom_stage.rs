@@ -0,0 +1,49 @@
+//! Run a custom stage after generation: grow marshes on wet, flat land along the coast,
+//! then rebuild the map so the tile modifiers, appeal and statistics include the new features.
+//!
+//! cargo run -p civorum-core --example custom_stage -- [seed]
+
+use std::{env, error::Error};
+
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::{
+        helpers::neighbors_odd_r,
+        map::Map,
+        map_builder::{LayerError, MapBuilder},
+        map_sizes::MapSizes,
+    },
+};
+
+/// Rainfall from which flat coastal land becomes a marsh
+const MARSH_RAINFALL: u8 = 130;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let seed = env::args().nth(1).map_or(Ok(6), |value| value.parse())?;
+    let (before, after) = run(seed, MapSizes::Duel)?;
+    let marshes = |map: &Map| map.tiles().iter().filter(|tile| tile.feature() == Some(Feature::Marsh)).count();
+    println!("Marsh tiles: {} generated, {} after the stage", marshes(&before), marshes(&after));
+    Ok(())
+}
+
+/// The generated map of `seed` and the same map after the marsh stage
+pub fn run(seed: u64, size: MapSizes) -> Result<(Map, Map), LayerError> {
+    let map = Map::new(Some(seed), size);
+    let (width, height) = size.dimensions();
+    let tiles = map.tiles();
+
+    let mut features: Vec<Option<Feature>> = tiles.iter().map(|tile| tile.feature()).collect();
+    for (idx, tile) in tiles.iter().enumerate() {
+        let flat_land = matches!(tile.terrain(), Terrain::Grassland | Terrain::Plains) && !tile.is_hill();
+        let coastal = neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .any(|(x, y)| tiles[y * width + x].terrain().is_water());
+        if flat_land && coastal && tile.feature().is_none() && tile.rainfall() >= MARSH_RAINFALL {
+            features[idx] = Some(Feature::Marsh);
+        }
+    }
+
+    // every other layer, the seed and the map type come from the generated map
+    let staged = MapBuilder::from_map(&map).features(features).build()?;
+    Ok((map, staged))
+}
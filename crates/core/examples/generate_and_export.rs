@@ -0,0 +1,33 @@
+//! Generate a map from a seed and export its layers as a JSON map document and a PNG.
+//!
+//! cargo run -p civorum-core --example generate_and_export -- [seed]
+
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use civorum_core::{LayerOptions, render_map_data};
+use civorum_mapgen::pipeline::{document::MapDocument, map::Map, map_sizes::MapSizes, map_types::MapTypes};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let seed = env::args().nth(1).map_or(Ok(1), |value| value.parse())?;
+    let (json, png) = run(seed, MapSizes::Duel, Path::new("out"))?;
+    println!("Wrote {} and {}", json.display(), png.display());
+    Ok(())
+}
+
+/// Generate the map of `seed` and write `map_<seed>.json` and `map_<seed>.png` to `out_dir`
+pub fn run(seed: u64, size: MapSizes, out_dir: &Path) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+    let map = Map::try_new_with_type(Some(seed), size, MapTypes::Continents)?;
+    // terrain, hills and features, the layers a map document and the renderer need
+    let data = map.to_map_data();
+
+    let json = out_dir.join(format!("map_{seed}.json"));
+    MapDocument::new(data.clone()).save(&json)?;
+
+    let png = out_dir.join(format!("map_{seed}.png"));
+    render_map_data(&data, 12, &png, &LayerOptions::default())?;
+    Ok((json, png))
+}
@@ -0,0 +1,41 @@
+//! Generate a map, pick two far apart tiles on the largest landmass and print the cheapest path between them.
+//!
+//! cargo run -p civorum-core --example pathfind -- [seed]
+
+use std::{env, error::Error};
+
+use civorum_mapgen::{
+    map_components::hex_coords::HexCoord,
+    pipeline::{map::Map, map_sizes::MapSizes, pathfinding::Path},
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let seed = env::args().nth(1).map_or(Ok(3), |value| value.parse())?;
+    let Some((from, to, path)) = run(seed, MapSizes::Duel) else {
+        println!("No path on the largest landmass of seed {seed}");
+        return Ok(());
+    };
+    println!("({}, {}) -> ({}, {}) costs {}:", from.x(), from.y(), to.x(), to.y(), path.cost);
+    let hexes: Vec<String> = path.tiles.iter().map(|hex| format!("({}, {})", hex.x(), hex.y())).collect();
+    println!("{}", hexes.join(" "));
+    Ok(())
+}
+
+/// Path between the westmost and eastmost passable tiles of landmass 0, None if the map has no land
+pub fn run(seed: u64, size: MapSizes) -> Option<(HexCoord, HexCoord, Path)> {
+    let map = Map::new(Some(seed), size);
+    // the largest landmass is connected, so the path only fails on impassable tiles like mountains
+    let land: Vec<HexCoord> = map
+        .tiles()
+        .iter()
+        .filter(|tile| tile.landmass_id() == Some(0) && tile.modifiers().is_passable())
+        .map(|tile| tile.hex_coords())
+        .collect();
+    let from = *land.iter().min_by_key(|hex| (hex.x(), hex.y()))?;
+    // try the eastmost tiles first, mountains can wall some of them off
+    let mut targets = land.clone();
+    targets.sort_by_key(|hex| (std::cmp::Reverse(hex.x()), hex.y()));
+    targets
+        .into_iter()
+        .find_map(|to| map.find_path(from, to).map(|path| (from, to, path)))
+}
@@ -0,0 +1,118 @@
+use std::{env, io};
+
+use civorum_mapgen::pipeline::{
+    map::Map,
+    map_sizes::{MapSizes, ParseMapSizesError},
+    map_types::{MapTypes, ParseMapTypesError},
+    recipe::{ParseRecipeError, Recipe},
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut start_seed = 0u64;
+    let mut end_seed = 100u64;
+    let mut size = MapSizes::Standard;
+    let mut map_type = MapTypes::Continents;
+    let mut recipe: Option<Recipe> = None;
+    let mut explicit: Vec<&str> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        if flag == "--help" || flag == "-h" {
+            print_usage();
+            return Ok(());
+        }
+
+        let value = iter
+            .next()
+            .ok_or_else(|| invalid_input(format!("missing value for '{flag}'")))?;
+        match flag.as_str() {
+            "--start-seed" => start_seed = parse_seed(value).map_err(invalid_input)?,
+            "--end-seed" => end_seed = parse_seed(value).map_err(invalid_input)?,
+            "--size" => size = value.parse().map_err(|err: ParseMapSizesError| invalid_input(err.to_string()))?,
+            "--type" => map_type = value.parse().map_err(|err: ParseMapTypesError| invalid_input(err.to_string()))?,
+            "--recipe" => {
+                recipe = Some(Recipe::parse(value).map_err(|err: ParseRecipeError| invalid_input(err.to_string()))?);
+                continue;
+            }
+            _ => return Err(invalid_input(format!("unknown flag '{flag}'")).into()),
+        }
+        explicit.push(flag);
+    }
+
+    // the recipe fills in the flags that are not given, its seed is the first and only seed of the range
+    if let Some(recipe) = &recipe {
+        for flag in explicit.iter().filter(|flag| ["--start-seed", "--end-seed", "--size", "--type"].contains(flag)) {
+            eprintln!("Warning: {flag} replaces the value of the recipe");
+        }
+        if !explicit.contains(&"--start-seed") {
+            start_seed = recipe.seed;
+        }
+        if !explicit.contains(&"--end-seed") {
+            end_seed = start_seed.saturating_add(1);
+        }
+        if !explicit.contains(&"--size") {
+            size = recipe.size;
+        }
+        if !explicit.contains(&"--type") {
+            map_type = recipe.map_type;
+        }
+        if recipe.config_matches() == Some(false) {
+            eprintln!("Warning: the recipe was made with another config, the maps may differ");
+        }
+        if !recipe.options.is_empty() {
+            eprintln!("Warning: the recipe options are not applied, run `civorum analyze --recipe` for them");
+        }
+    }
+
+    if start_seed >= end_seed {
+        return Err(invalid_input("--start-seed must be smaller than --end-seed".to_string()).into());
+    }
+
+    println!("Generating {map_type:?} maps ({size:?}) for seeds {start_seed}..{end_seed}...");
+    let report = Map::stress_test(start_seed..end_seed, size, map_type);
+
+    println!("Tested:    {}", report.tested);
+    println!("Converged: {}", report.converged);
+    println!("Failed:    {}", report.failed_seeds.len());
+    if !report.failed_seeds.is_empty() {
+        let seeds: Vec<String> = report.failed_seeds.iter().map(u64::to_string).collect();
+        println!("Failed seeds: {}", seeds.join(", "));
+    }
+
+    println!("Land ratio distribution:");
+    for (bucket, count) in &report.land_ratio_distribution {
+        println!("  {:.2}-{:.2} {:>6}", bucket, bucket + 0.05, count);
+    }
+    println!("Landmass count distribution:");
+    for (bucket, count) in &report.component_count_distribution {
+        println!("  {:>9} {:>6}", bucket, count);
+    }
+
+    Ok(())
+}
+
+fn print_usage() {
+    println!("Usage:");
+    println!(
+        "  cargo run -p civorum-core --bin civorum-stress-test -- [--start-seed n] [--end-seed n] [--size size] [--type map_type] [--recipe recipe]"
+    );
+    println!("A recipe sets the size, the type and the seed as the only seed, other flags replace its values.");
+    println!("Defaults:");
+    println!("  start-seed=0 end-seed=100 size=standard type=continents");
+    println!("Sizes:");
+    println!("  duel tiny small standard large huge");
+    println!("Map types:");
+    println!("  continents small-continents islands-continents pangea mirror terra");
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| format!("invalid seed '{value}'. Use an unsigned integer"))
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
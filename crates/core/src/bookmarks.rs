@@ -0,0 +1,130 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use civorum_mapgen::pipeline::{
+    helpers::config_hash,
+    map::{Map, SeedString},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::thumbnail::render_thumbnail;
+
+/// Long side of a bookmark thumbnail in pixels
+pub const BOOKMARK_THUMBNAIL_PX: u32 = 128;
+/// File of the bookmark list inside the store directory
+const INDEX_FILE: &str = "bookmarks.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A saved map: enough to regenerate it and check that it is still the same map
+pub struct Bookmark {
+    /// Seed, size and map type, see `SeedString`
+    pub seed_string: String,
+    /// Hash of the config the map was generated with, as 16 hex digits
+    pub config_hash: String,
+    /// `Map::fingerprint` of the map, as 16 hex digits
+    pub fingerprint: String,
+    #[serde(default)]
+    pub note: String,
+    /// File name of the thumbnail inside the store directory
+    pub thumbnail: String,
+}
+
+impl Bookmark {
+    /// Bookmark of a map generated with the current config
+    pub fn new(map: &Map, note: &str) -> Self {
+        let seed_string = map.seed_string().to_string();
+        Bookmark {
+            thumbnail: format!("{seed_string}.png"),
+            seed_string,
+            config_hash: format!("{:016x}", config_hash()),
+            fingerprint: format!("{:016x}", map.fingerprint()),
+            note: note.to_string(),
+        }
+    }
+
+    /// Generate the bookmarked map again with the current config
+    pub fn regenerate(&self) -> Result<RegeneratedBookmark, Box<dyn Error>> {
+        let seed: SeedString = self.seed_string.parse()?;
+        let map = Map::try_new_with_type(Some(seed.seed), seed.size, seed.map_type)?;
+        Ok(RegeneratedBookmark {
+            config_matches: self.config_hash == format!("{:016x}", config_hash()),
+            fingerprint_matches: self.fingerprint == format!("{:016x}", map.fingerprint()),
+            map,
+        })
+    }
+}
+
+/// Result of `Bookmark::regenerate`
+pub struct RegeneratedBookmark {
+    pub map: Map,
+    /// The current config hash is the bookmarked one. If not, the map can differ
+    pub config_matches: bool,
+    /// The regenerated map is the bookmarked map
+    pub fingerprint_matches: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Bookmarks in a directory: a JSON list (`bookmarks.json`) plus one PNG thumbnail per bookmark
+pub struct BookmarkStore {
+    dir: PathBuf,
+}
+
+impl BookmarkStore {
+    /// Store in the given directory, it is created on the first save
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        BookmarkStore { dir: dir.into() }
+    }
+
+    /// `$XDG_DATA_HOME/civorum/bookmarks`, or `~/.local/share/civorum/bookmarks` without it.
+    /// None if neither variable is set.
+    pub fn default_dir() -> Option<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+        Some(data_home.join("civorum").join("bookmarks"))
+    }
+
+    /// Return the directory of the store
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the thumbnail of a bookmark
+    pub fn thumbnail_path(&self, bookmark: &Bookmark) -> PathBuf {
+        self.dir.join(&bookmark.thumbnail)
+    }
+
+    /// Read the bookmarks, oldest first. A store without a list has no bookmarks
+    pub fn load(&self) -> Result<Vec<Bookmark>, Box<dyn Error>> {
+        let path = self.dir.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Replace the bookmark list, e.g. after removing an entry. Thumbnails are left alone
+    pub fn save(&self, bookmarks: &[Bookmark]) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(INDEX_FILE), serde_json::to_string_pretty(bookmarks)?)?;
+        Ok(())
+    }
+
+    /// Bookmark a map: write its thumbnail and add it to the end of the list
+    pub fn append(&self, map: &Map, note: &str) -> Result<Bookmark, Box<dyn Error>> {
+        let bookmark = Bookmark::new(map, note);
+        let (width, height) = map.size().dimensions();
+        let terrain: Vec<_> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+        fs::create_dir_all(&self.dir)?;
+        render_thumbnail(&terrain, width, height, BOOKMARK_THUMBNAIL_PX).save(self.thumbnail_path(&bookmark))?;
+
+        let mut bookmarks = self.load()?;
+        bookmarks.push(bookmark.clone());
+        self.save(&bookmarks)?;
+        Ok(bookmark)
+    }
+}
@@ -1,11 +1,15 @@
 use std::{error::Error, fs::create_dir_all, path::Path};
 
-use civorum_mapgen::map_components::terrain::Terrain;
+use civorum_mapgen::map_components::terrain::{DesertVariant, Terrain};
 use image::{ImageBuffer, Rgb};
 
 const INV_SQRT3: f32 = 0.57735;
 const BG_COLOR: Rgb<u8> = Rgb([20, 20, 20]);
 const BORDER_COLOR: Rgb<u8> = Rgb([0, 0, 0]);
+const RIVER_COLOR: Rgb<u8> = Rgb([40, 110, 220]);
+const ROCKY_DESERT_COLOR: Rgb<u8> = Rgb([190, 150, 105]);
+const HIGHLIGHT_COLOR: Rgb<u8> = Rgb([230, 40, 200]);
+const ARROW_COLOR: Rgb<u8> = Rgb([240, 240, 240]);
 
 pub fn render_map_png(
     terrain: &[Terrain],
@@ -14,6 +18,41 @@ pub fn render_map_png(
     height: i32,
     cell_px: u32,
     out_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let overlays = MapOverlays {
+        hills: Some(hills),
+        ..Default::default()
+    };
+    render_map_png_with_overlays(terrain, &overlays, width, height, cell_px, out_path)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Optional layers drawn on top of the terrain colors.
+pub struct MapOverlays<'a> {
+    /// Hill markers per tile
+    pub hills: Option<&'a [bool]>,
+    /// Rivers as lists of tile indices
+    pub rivers: &'a [Vec<usize>],
+    /// River width tier per tile (1-3), rivers are drawn thicker the wider they are. Without it all rivers are tier 1
+    pub river_widths: Option<&'a [u8]>,
+    /// Per-tile heat [0, 255] blended over land tiles
+    pub heat: Option<&'a [u8]>,
+    /// Desert sub-variants per tile, rocky desert gets its own hue
+    pub desert_variants: Option<&'a [Option<DesertVariant>]>,
+    /// Tiles tinted in a highlight color, e.g. flood-prone lowland
+    pub highlight: Option<&'a [bool]>,
+    /// Arrow angle per tile in degrees, counterclockwise from east, e.g. the prevailing wind. None draws no arrow
+    pub arrows: Option<&'a [Option<f32>]>,
+}
+
+/// Render terrain colors with the given overlays.
+pub fn render_map_png_with_overlays(
+    terrain: &[Terrain],
+    overlays: &MapOverlays,
+    width: i32,
+    height: i32,
+    cell_px: u32,
+    out_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
     if width <= 0 || height <= 0 {
         return Err("width and height must be positive".into());
@@ -28,21 +67,119 @@ pub fn render_map_png(
         .checked_mul(usize::try_from(height_u32)?)
         .ok_or("width * height overflow")?;
 
-    if terrain.len() != expected_len || hills.len() != expected_len {
-        return Err("terrain/hills length must match width * height".into());
+    let layer_lengths_match = overlays.hills.is_none_or(|hills| hills.len() == expected_len)
+        && overlays.heat.is_none_or(|heat| heat.len() == expected_len)
+        && overlays.river_widths.is_none_or(|widths| widths.len() == expected_len)
+        && overlays.desert_variants.is_none_or(|variants| variants.len() == expected_len)
+        && overlays.highlight.is_none_or(|highlight| highlight.len() == expected_len)
+        && overlays.arrows.is_none_or(|arrows| arrows.len() == expected_len);
+    if terrain.len() != expected_len || !layer_lengths_match {
+        return Err("terrain/overlay length must match width * height".into());
+    }
+    if overlays.rivers.iter().flatten().any(|&idx| idx >= expected_len) {
+        return Err("river tile index out of bounds".into());
     }
 
+    let mut img = hex_canvas(width_u32, height_u32, cell_px)?;
     let row_step = (cell_px * 3) / 4;
-    let img_w = width_u32
-        .checked_mul(cell_px)
-        .and_then(|v| v.checked_add(cell_px / 2))
-        .ok_or("image width overflow")?;
-    let img_h = height_u32
-        .checked_mul(row_step)
-        .and_then(|v| v.checked_add(cell_px))
-        .ok_or("image height overflow")?;
+    let origin = |idx: usize| {
+        let (x, y) = (idx as u32 % width_u32, idx as u32 / width_u32);
+        let row_x_offset = if y % 2 == 1 { cell_px / 2 } else { 0 };
+        (x * cell_px + row_x_offset, y * row_step)
+    };
+
+    for idx in 0..expected_len {
+        let (ox, oy) = origin(idx);
+        let tile_terrain = terrain[idx];
+        let mut base = terrain_color(tile_terrain);
+        if overlays
+            .desert_variants
+            .is_some_and(|variants| variants[idx] == Some(DesertVariant::RockyDesert))
+        {
+            base = ROCKY_DESERT_COLOR;
+        }
+        if let Some(heat) = overlays.heat
+            && !tile_terrain.is_water()
+        {
+            base = blend(base, layer_color(heat[idx]), 0.6);
+        }
+        if overlays.highlight.is_some_and(|highlight| highlight[idx]) {
+            base = blend(base, HIGHLIGHT_COLOR, 0.6);
+        }
+
+        fill_hex(&mut img, ox, oy, cell_px, base);
+
+        if let Some(hills) = overlays.hills
+            && hills[idx]
+            && allows_hill_marker(tile_terrain)
+        {
+            draw_hill_marker(&mut img, ox, oy, cell_px, marker_color(base));
+        }
+        if let Some(angle) = overlays.arrows.and_then(|arrows| arrows[idx]) {
+            draw_arrow(&mut img, ox, oy, cell_px, angle);
+        }
+    }
+
+    for &idx in overlays.rivers.iter().flatten() {
+        let (ox, oy) = origin(idx);
+        let tier = overlays.river_widths.map_or(1, |widths| u32::from(widths[idx].clamp(1, 3)));
+        draw_dot(&mut img, ox + cell_px / 2, oy + cell_px / 2, cell_px * (tier + 1) / 10, RIVER_COLOR);
+    }
+
+    save_png(&img, out_path)
+}
+
+/// Render a single per-tile value layer (e.g. temperature range) as a heat map.
+pub fn render_layer_png(
+    values: &[u8],
+    width: i32,
+    height: i32,
+    cell_px: u32,
+    out_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    render_values_png(values.len(), width, height, cell_px, out_path, |idx| layer_color(values[idx]))
+}
+
+/// Render a signed per-tile layer (e.g. appeal) with a diverging palette.
+/// Negative values are red, zero is white and positive values are green, scaled by the largest magnitude.
+pub fn render_diverging_layer_png(
+    values: &[i8],
+    width: i32,
+    height: i32,
+    cell_px: u32,
+    out_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let max = values.iter().map(|v| v.unsigned_abs()).max().unwrap_or(0).max(1) as f32;
+    render_values_png(values.len(), width, height, cell_px, out_path, |idx| {
+        diverging_color(values[idx] as f32 / max)
+    })
+}
+
+/// Fill every hex with the color returned for its index and save the image.
+fn render_values_png(
+    len: usize,
+    width: i32,
+    height: i32,
+    cell_px: u32,
+    out_path: &Path,
+    color: impl Fn(usize) -> Rgb<u8>,
+) -> Result<(), Box<dyn Error>> {
+    if width <= 0 || height <= 0 {
+        return Err("width and height must be positive".into());
+    }
+
+    let width_u32 = u32::try_from(width)?;
+    let height_u32 = u32::try_from(height)?;
+    let expected_len = usize::try_from(width_u32)?
+        .checked_mul(usize::try_from(height_u32)?)
+        .ok_or("width * height overflow")?;
 
-    let mut img = ImageBuffer::from_pixel(img_w, img_h, BG_COLOR);
+    if len != expected_len {
+        return Err("layer length must match width * height".into());
+    }
+
+    let mut img = hex_canvas(width_u32, height_u32, cell_px)?;
+    let row_step = (cell_px * 3) / 4;
 
     for y in 0..height_u32 {
         let row_x_offset = if y % 2 == 1 { cell_px / 2 } else { 0 };
@@ -51,43 +188,15 @@ pub fn render_map_png(
         for x in 0..width_u32 {
             let ox = x * cell_px + row_x_offset;
             let idx = usize::try_from(y * width_u32 + x)?;
-            let tile_terrain = terrain[idx];
-            let base = terrain_color(tile_terrain);
-
-            for py in 0..cell_px {
-                for px in 0..cell_px {
-                    if !inside_hex(px as i32, py as i32, cell_px) {
-                        continue;
-                    }
-
-                    let gx = ox + px;
-                    let gy = oy + py;
-                    if gx >= img_w || gy >= img_h {
-                        continue;
-                    }
-
-                    let border = is_border(px as i32, py as i32, cell_px);
-                    let color = if border { BORDER_COLOR } else { base };
-                    img.put_pixel(gx, gy, color);
-                }
-            }
-
-            if hills[idx] && allows_hill_marker(tile_terrain) {
-                draw_hill_marker(&mut img, ox, oy, cell_px, marker_color(base));
-            }
+            fill_hex(&mut img, ox, oy, cell_px, color(idx));
         }
     }
 
-    if let Some(parent) = out_path.parent() {
-        if !parent.as_os_str().is_empty() {
-            create_dir_all(parent)?;
-        }
-    }
-    img.save(out_path)?;
-    Ok(())
+    save_png(&img, out_path)
 }
 
-fn terrain_color(terrain: Terrain) -> Rgb<u8> {
+/// Shared terrain palette used by all renderers.
+pub fn terrain_color(terrain: Terrain) -> Rgb<u8> {
     match terrain {
         Terrain::Grassland => Rgb([76, 175, 80]),
         Terrain::Plains => Rgb([183, 198, 90]),
@@ -100,6 +209,99 @@ fn terrain_color(terrain: Terrain) -> Rgb<u8> {
     }
 }
 
+/// Blue (low) to yellow to red (high) ramp used for value layers.
+fn layer_color(value: u8) -> Rgb<u8> {
+    const LOW: [f32; 3] = [40.0, 70.0, 170.0];
+    const MID: [f32; 3] = [235.0, 215.0, 90.0];
+    const HIGH: [f32; 3] = [200.0, 40.0, 30.0];
+
+    let t = value as f32 / 255.0;
+    let (from, to, f) = if t < 0.5 { (LOW, MID, t * 2.0) } else { (MID, HIGH, (t - 0.5) * 2.0) };
+    let lerp = |i: usize| (from[i] + (to[i] - from[i]) * f).round() as u8;
+    Rgb([lerp(0), lerp(1), lerp(2)])
+}
+
+/// Red (-1) to white (0) to green (1) ramp used for signed layers.
+fn diverging_color(t: f32) -> Rgb<u8> {
+    const NEGATIVE: Rgb<u8> = Rgb([190, 40, 40]);
+    const NEUTRAL: Rgb<u8> = Rgb([245, 245, 245]);
+    const POSITIVE: Rgb<u8> = Rgb([40, 150, 60]);
+
+    if t < 0.0 {
+        blend(NEUTRAL, NEGATIVE, (-t).min(1.0))
+    } else {
+        blend(NEUTRAL, POSITIVE, t.min(1.0))
+    }
+}
+
+/// Mix `over` into `base`, `amount` 0 keeps `base` and 1 gives `over`.
+fn blend(base: Rgb<u8>, over: Rgb<u8>, amount: f32) -> Rgb<u8> {
+    let mix = |i: usize| (base.0[i] as f32 + (over.0[i] as f32 - base.0[i] as f32) * amount).round() as u8;
+    Rgb([mix(0), mix(1), mix(2)])
+}
+
+/// Draw a filled circle centered at (cx, cy).
+fn draw_dot(img: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, cx: u32, cy: u32, radius: u32, color: Rgb<u8>) {
+    let r = radius as i64;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            if dx * dx + dy * dy > r * r {
+                continue;
+            }
+            let (gx, gy) = (cx as i64 + dx, cy as i64 + dy);
+            if gx >= 0 && gy >= 0 && (gx as u32) < img.width() && (gy as u32) < img.height() {
+                img.put_pixel(gx as u32, gy as u32, color);
+            }
+        }
+    }
+}
+
+/// Create an empty image large enough for a `width` x `height` hex grid.
+fn hex_canvas(width: u32, height: u32, cell_px: u32) -> Result<ImageBuffer<Rgb<u8>, Vec<u8>>, Box<dyn Error>> {
+    let row_step = (cell_px * 3) / 4;
+    let img_w = width
+        .checked_mul(cell_px)
+        .and_then(|v| v.checked_add(cell_px / 2))
+        .ok_or("image width overflow")?;
+    let img_h = height
+        .checked_mul(row_step)
+        .and_then(|v| v.checked_add(cell_px))
+        .ok_or("image height overflow")?;
+
+    Ok(ImageBuffer::from_pixel(img_w, img_h, BG_COLOR))
+}
+
+/// Fill one hex with a border, `ox`/`oy` is the top left corner of its cell.
+fn fill_hex(img: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, ox: u32, oy: u32, cell_px: u32, base: Rgb<u8>) {
+    for py in 0..cell_px {
+        for px in 0..cell_px {
+            if !inside_hex(px as i32, py as i32, cell_px) {
+                continue;
+            }
+
+            let gx = ox + px;
+            let gy = oy + py;
+            if gx >= img.width() || gy >= img.height() {
+                continue;
+            }
+
+            let border = is_border(px as i32, py as i32, cell_px);
+            let color = if border { BORDER_COLOR } else { base };
+            img.put_pixel(gx, gy, color);
+        }
+    }
+}
+
+fn save_png(img: &ImageBuffer<Rgb<u8>, Vec<u8>>, out_path: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = out_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        create_dir_all(parent)?;
+    }
+    img.save(out_path)?;
+    Ok(())
+}
+
 fn allows_hill_marker(terrain: Terrain) -> bool {
     !matches!(
         terrain,
@@ -169,6 +371,44 @@ fn draw_hill_marker(
     }
 }
 
+/// Draw an arrow through the center of a cell pointing at `angle_deg` (counterclockwise from east, north up).
+fn draw_arrow(img: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, ox: u32, oy: u32, cell_px: u32, angle_deg: f32) {
+    let (sin, cos) = angle_deg.to_radians().sin_cos();
+    // image y grows downward
+    let (dx, dy) = (cos, -sin);
+    let (cx, cy) = (ox as f32 + cell_px as f32 / 2.0, oy as f32 + cell_px as f32 / 2.0);
+    let half = cell_px as f32 * 0.3;
+    let to_px = |x: f32, y: f32| (x.round() as i32, y.round() as i32);
+
+    let steps = (2.0 * half).ceil() as i32;
+    for step in 0..=steps {
+        let t = -half + 2.0 * half * step as f32 / steps as f32;
+        let (px, py) = to_px(cx + dx * t, cy + dy * t);
+        put_pixel_checked(img, px, py, ARROW_COLOR);
+    }
+
+    let head = half * 0.6;
+    let tip = to_px(cx + dx * half, cy + dy * half);
+    let (bx, by) = (cx + dx * (half - head), cy + dy * (half - head));
+    let left = to_px(bx - dy * head / 2.0, by + dx * head / 2.0);
+    let right = to_px(bx + dy * head / 2.0, by - dx * head / 2.0);
+    let (min_x, max_x) = (tip.0.min(left.0).min(right.0), tip.0.max(left.0).max(right.0));
+    let (min_y, max_y) = (tip.1.min(left.1).min(right.1), tip.1.max(left.1).max(right.1));
+    for py in min_y..=max_y {
+        for px in min_x..=max_x {
+            if point_in_triangle((px, py), tip, left, right) {
+                put_pixel_checked(img, px, py, ARROW_COLOR);
+            }
+        }
+    }
+}
+
+fn put_pixel_checked(img: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, x: i32, y: i32, color: Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
+
 fn point_in_triangle(p: (i32, i32), a: (i32, i32), b: (i32, i32), c: (i32, i32)) -> bool {
     let pa = edge(a, b, p);
     let pb = edge(b, c, p);
@@ -0,0 +1,106 @@
+use std::{error::Error, fmt::Write as _, fs, path::Path};
+
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{helpers::adjacency_pairs, map::Map},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// File format of an exported adjacency graph.
+pub enum GraphFormat {
+    /// `src_idx,dst_idx,src_terrain,dst_terrain` with a header row
+    EdgeListCsv,
+    /// Graphviz DOT, undirected
+    Dot,
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// Pick the format from the file extension (`csv`, `dot`/`gv`, `graphml`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(GraphFormat::EdgeListCsv),
+            "dot" | "gv" => Some(GraphFormat::Dot),
+            "graphml" => Some(GraphFormat::GraphMl),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Which tiles an exported graph contains.
+pub enum GraphSubset {
+    #[default]
+    All,
+    /// Only land tiles and the edges between them
+    LandOnly,
+    /// Only water tiles and the edges between them
+    WaterOnly,
+}
+
+impl GraphSubset {
+    fn contains(&self, terrain: Terrain) -> bool {
+        match self {
+            GraphSubset::All => true,
+            GraphSubset::LandOnly => !terrain.is_water(),
+            GraphSubset::WaterOnly => terrain.is_water(),
+        }
+    }
+}
+
+/// Write the hex adjacency graph of the map to `path`.
+/// Nodes are row-major tile indices, every edge is listed once.
+pub fn export_adjacency(map: &Map, path: &Path, format: GraphFormat, subset: GraphSubset) -> Result<(), Box<dyn Error>> {
+    let (width, height) = map.size().dimensions();
+    let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    let nodes: Vec<usize> = (0..terrain.len()).filter(|idx| subset.contains(terrain[*idx])).collect();
+    let edges: Vec<(usize, usize)> = adjacency_pairs(width, height)
+        .filter(|(a, b)| subset.contains(terrain[*a]) && subset.contains(terrain[*b]))
+        .collect();
+
+    let mut out = String::new();
+    match format {
+        GraphFormat::EdgeListCsv => {
+            writeln!(out, "src_idx,dst_idx,src_terrain,dst_terrain")?;
+            for (a, b) in edges {
+                writeln!(out, "{a},{b},{:?},{:?}", terrain[a], terrain[b])?;
+            }
+        }
+        GraphFormat::Dot => {
+            writeln!(out, "graph civorum {{")?;
+            for idx in nodes {
+                writeln!(out, "  {idx} [terrain=\"{:?}\"];", terrain[idx])?;
+            }
+            for (a, b) in edges {
+                writeln!(out, "  {a} -- {b};")?;
+            }
+            writeln!(out, "}}")?;
+        }
+        GraphFormat::GraphMl => {
+            writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            writeln!(out, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+            writeln!(out, r#"  <key id="terrain" for="node" attr.name="terrain" attr.type="string"/>"#)?;
+            writeln!(out, r#"  <graph id="civorum" edgedefault="undirected">"#)?;
+            for idx in nodes {
+                writeln!(
+                    out,
+                    r#"    <node id="{idx}"><data key="terrain">{:?}</data></node>"#,
+                    terrain[idx]
+                )?;
+            }
+            for (a, b) in edges {
+                writeln!(out, r#"    <edge source="{a}" target="{b}"/>"#)?;
+            }
+            writeln!(out, "  </graph>")?;
+            writeln!(out, "</graphml>")?;
+        }
+    }
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, out)?;
+    Ok(())
+}
@@ -1,10 +1,74 @@
 use std::path::Path;
 
-use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes, map_types::MapTypes};
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::coast_distance,
+        biomes::try_generate_map_with_type,
+        canals::{MAX_CANAL_LENGTH, find_canal_candidates},
+        evolve::{Perturbation, apply_perturbations, perturbation_schedule},
+        features::river_width_tiers,
+        helpers::{biomes_config, landmasses_config, with_biomes_config, with_landmasses_config},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        text_map::GeneratedMapData,
+    },
+};
 
+pub mod bookmarks;
 pub mod debug_render;
+pub mod graph_export;
+pub mod thumbnail;
 
-pub use debug_render::render_map_png;
+pub use bookmarks::{Bookmark, BookmarkStore, RegeneratedBookmark};
+pub use debug_render::{
+    MapOverlays, render_diverging_layer_png, render_layer_png, render_map_png, render_map_png_with_overlays,
+};
+pub use graph_export::{GraphFormat, GraphSubset, export_adjacency};
+pub use thumbnail::{render_contact_sheet, render_thumbnail, render_thumbnail_into};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Layer drawn by `render_debug_layer`.
+pub enum DebugLayer {
+    /// Terrain colors with hill markers
+    Terrain,
+    /// Seasonal temperature swing as a heat map
+    TemperatureRange,
+    /// Appeal of land tiles, red (negative) to green (positive)
+    Appeal,
+    /// Flavor age of the land as a heat map (young is blue, old is red)
+    GeologicalAge,
+    /// Terrain with the flood-prone lowland tinted, see `Map::lowland`
+    Lowland,
+    /// Terrain with prevailing wind arrows over the water, see `Map::wind`
+    Wind,
+    /// Terrain with the best canal sites tinted, see `find_canal_candidates`
+    Canals,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Overlays drawn on top of the terrain by `render_debug_map`.
+/// The default matches the original output: terrain with hill markers.
+pub struct LayerOptions {
+    pub show_hills: bool,
+    pub show_rivers: bool,
+    /// Resource placement does not exist yet, so this currently draws nothing
+    pub show_resources: bool,
+    /// Blend a heat map of the distance to the nearest water over the land
+    pub show_coast_distance_heat: bool,
+}
+
+impl Default for LayerOptions {
+    fn default() -> Self {
+        LayerOptions {
+            show_hills: true,
+            show_rivers: false,
+            show_resources: false,
+            show_coast_distance_heat: false,
+        }
+    }
+}
 
 pub fn render_debug_map(
     seed: Option<u64>,
@@ -12,22 +76,299 @@ pub fn render_debug_map(
     map_type: MapTypes,
     cell_px: u32,
     out_path: &Path,
+    layers: &LayerOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (width, height) = size.dimensions();
-    let panic_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(|_| {}));
-    let terrain_result = std::panic::catch_unwind(|| Map::debug_terrains(seed, size, map_type));
-    std::panic::set_hook(panic_hook);
-    let (terrain_vec, hill_vec) = terrain_result.map_err(|_| {
-        "map generation panicked while building debug terrain data (check mapgen biome indexing)"
-    })?;
-
-    render_map_png(
-        &terrain_vec,
-        &hill_vec,
+    let (map_layers, rivers) = Map::debug_terrains(seed, size, map_type)?;
+
+    let river_widths = river_width_tiers(&rivers, size.grid_size());
+    let heat = layers
+        .show_coast_distance_heat
+        .then(|| coast_distance_heat(&map_layers.terrain, width, height));
+
+    let overlays = MapOverlays {
+        hills: layers.show_hills.then_some(map_layers.hills.as_slice()),
+        rivers: if layers.show_rivers { &rivers } else { &[] },
+        river_widths: Some(&river_widths),
+        heat: heat.as_deref(),
+        desert_variants: Some(&map_layers.desert_variants),
+        highlight: None,
+        arrows: None,
+    };
+
+    render_map_png_with_overlays(
+        &map_layers.terrain,
+        &overlays,
         i32::try_from(width)?,
         i32::try_from(height)?,
         cell_px,
         out_path,
     )
 }
+
+/// Render a hand-authored map (see `parse_text_map`) with the terrain overlays.
+/// Text maps have no rivers or resources, so those overlays draw nothing.
+pub fn render_map_data(
+    data: &GeneratedMapData,
+    cell_px: u32,
+    out_path: &Path,
+    layers: &LayerOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let heat = layers
+        .show_coast_distance_heat
+        .then(|| coast_distance_heat(&data.terrain, data.width, data.height));
+
+    let overlays = MapOverlays {
+        hills: layers.show_hills.then_some(data.hills.as_slice()),
+        rivers: &[],
+        heat: heat.as_deref(),
+        ..Default::default()
+    };
+
+    render_map_png_with_overlays(
+        &data.terrain,
+        &overlays,
+        i32::try_from(data.width)?,
+        i32::try_from(data.height)?,
+        cell_px,
+        out_path,
+    )
+}
+
+#[cfg(feature = "debug-render")]
+/// Render the landmask before the repair loop, after every repair pass and the finished landmask in two colors
+/// (ocean and land) to `out_dir/step_00_initial.png`, `out_dir/step_01_repair0.png`, ... `step_<n>_final.png`.
+/// Returns the written paths in step order.
+pub fn generate_debug_png_for_each_repair_step(
+    seed: Option<u64>,
+    size: MapSizes,
+    map_type: MapTypes,
+    cell_px: u32,
+    out_dir: &Path,
+) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+    use civorum_mapgen::pipeline::land::{GenerationStepRecorder, try_generate_landmasses};
+
+    let internal_seed = seed.unwrap_or(12);
+    let (result, steps) = GenerationStepRecorder::record(|| try_generate_landmasses(internal_seed, &size, map_type));
+    result?;
+
+    let (width, height) = size.dimensions();
+    let hills = vec![false; size.grid_size()];
+    let mut paths = Vec::with_capacity(steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        let terrain: Vec<Terrain> = step
+            .landmask
+            .iter()
+            .map(|&cell| if cell == 1 { Terrain::Grassland } else { Terrain::Ocean })
+            .collect();
+        let path = out_dir.join(format!("step_{i:02}_{}.png", step.label));
+        render_map_png(&terrain, &hills, i32::try_from(width)?, i32::try_from(height)?, cell_px, &path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[derive(Debug, Clone)]
+/// One variation of the base map, see `generate_evolve_cells`
+pub struct EvolveCell {
+    /// Perturbations applied to the current configs, empty for the base map
+    pub perturbations: Vec<Perturbation>,
+    pub terrain: Vec<Terrain>,
+}
+
+impl EvolveCell {
+    /// Label of the cell, e.g. `base_land_percent +2, center_bias -0.1` or `base`
+    pub fn label(&self) -> String {
+        if self.perturbations.is_empty() {
+            return "base".to_string();
+        }
+        let labels: Vec<String> = self.perturbations.iter().map(Perturbation::to_string).collect();
+        labels.join(", ")
+    }
+}
+
+/// Generate `count` variations of a map with the same seed: cell `i` perturbs the current configs
+/// by `perturbation_schedule(i)`, so cell 0 is the unperturbed base map.
+/// The configs are only replaced on this thread while the cells are generated.
+pub fn generate_evolve_cells(
+    seed: Option<u64>,
+    size: MapSizes,
+    map_type: MapTypes,
+    count: usize,
+) -> Result<Vec<EvolveCell>, Box<dyn std::error::Error>> {
+    let internal_seed = seed.unwrap_or(12);
+    (0..count)
+        .map(|cell| {
+            let mut landmasses = landmasses_config().as_ref().clone();
+            let mut biomes = biomes_config().as_ref().clone();
+            let perturbations =
+                apply_perturbations(&perturbation_schedule(cell), map_type, &mut landmasses, &mut biomes);
+            let layers = with_landmasses_config(landmasses, || {
+                with_biomes_config(biomes, || try_generate_map_with_type(&internal_seed, &size, map_type))
+            })?;
+            Ok(EvolveCell {
+                perturbations,
+                terrain: layers.terrain,
+            })
+        })
+        .collect()
+}
+
+/// Transparent pixels between the cells of an evolve sheet
+pub const EVOLVE_GAP_PX: u32 = 4;
+
+/// Render `columns` x `rows` cells of `generate_evolve_cells` as a contact sheet of thumbnails
+/// (long side `thumb_px`) to `out_path`. Returns the cells in row-major order, for labeling the sheet.
+pub fn render_evolve_sheet(
+    seed: Option<u64>,
+    size: MapSizes,
+    map_type: MapTypes,
+    (columns, rows): (usize, usize),
+    thumb_px: u32,
+    out_path: &Path,
+) -> Result<Vec<EvolveCell>, Box<dyn std::error::Error>> {
+    let (width, height) = size.dimensions();
+    let cells = generate_evolve_cells(seed, size, map_type, columns * rows)?;
+    let thumbnails: Vec<_> = cells
+        .iter()
+        .map(|cell| render_thumbnail(&cell.terrain, width, height, thumb_px))
+        .collect();
+
+    if let Some(parent) = out_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    render_contact_sheet(&thumbnails, columns, EVOLVE_GAP_PX).save(out_path)?;
+    Ok(cells)
+}
+
+/// Distance to the nearest water per tile, scaled to [0, 255] for the heat overlay
+fn coast_distance_heat(terrain: &[Terrain], width: usize, height: usize) -> Vec<u8> {
+    let dist = coast_distance(terrain, width, height);
+    let max = dist.iter().copied().max().unwrap_or(0).max(1) as f32;
+    dist.iter().map(|d| (*d as f32 / max * 255.0).round() as u8).collect()
+}
+
+/// Canal sites tinted by `DebugLayer::Canals`
+pub const CANAL_OVERLAY_COUNT: usize = 5;
+
+/// Same as `render_debug_map` but allows selecting the layer to draw.
+pub fn render_debug_layer(
+    seed: Option<u64>,
+    size: MapSizes,
+    map_type: MapTypes,
+    layer: DebugLayer,
+    cell_px: u32,
+    out_path: &Path,
+    layers: &LayerOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match layer {
+        DebugLayer::Terrain => render_debug_map(seed, size, map_type, cell_px, out_path, layers),
+        DebugLayer::TemperatureRange | DebugLayer::GeologicalAge => {
+            let (width, height) = size.dimensions();
+            let internal_seed = seed.unwrap_or(12);
+            let layers = try_generate_map_with_type(&internal_seed, &size, map_type)?;
+
+            let values = if layer == DebugLayer::TemperatureRange {
+                &layers.temperature_range
+            } else {
+                &layers.geological_age
+            };
+            render_layer_png(
+                values,
+                i32::try_from(width)?,
+                i32::try_from(height)?,
+                cell_px,
+                out_path,
+            )
+        }
+        DebugLayer::Appeal => {
+            let (width, height) = size.dimensions();
+            let map = Map::try_new_with_type(seed, size, map_type)?;
+
+            render_diverging_layer_png(
+                map.appeal(),
+                i32::try_from(width)?,
+                i32::try_from(height)?,
+                cell_px,
+                out_path,
+            )
+        }
+        DebugLayer::Lowland => {
+            let (width, height) = size.dimensions();
+            let map = Map::try_new_with_type(seed, size, map_type)?;
+            let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+            let hills: Vec<bool> = map.tiles().iter().map(|tile| tile.is_hill()).collect();
+            let lowland = map.lowland();
+
+            let overlays = MapOverlays {
+                hills: layers.show_hills.then_some(hills.as_slice()),
+                rivers: if layers.show_rivers { map.rivers() } else { &[] },
+                highlight: Some(&lowland),
+                ..Default::default()
+            };
+            render_map_png_with_overlays(
+                &terrain,
+                &overlays,
+                i32::try_from(width)?,
+                i32::try_from(height)?,
+                cell_px,
+                out_path,
+            )
+        }
+        DebugLayer::Wind => {
+            let (width, height) = size.dimensions();
+            let map = Map::try_new_with_type(seed, size, map_type)?;
+            let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+            let hills: Vec<bool> = map.tiles().iter().map(|tile| tile.is_hill()).collect();
+            let arrows: Vec<Option<f32>> = terrain
+                .iter()
+                .enumerate()
+                .map(|(idx, terrain)| terrain.is_water().then(|| map.wind_at(idx).angle_degrees()))
+                .collect();
+
+            let overlays = MapOverlays {
+                hills: layers.show_hills.then_some(hills.as_slice()),
+                rivers: if layers.show_rivers { map.rivers() } else { &[] },
+                arrows: Some(&arrows),
+                ..Default::default()
+            };
+            render_map_png_with_overlays(
+                &terrain,
+                &overlays,
+                i32::try_from(width)?,
+                i32::try_from(height)?,
+                cell_px,
+                out_path,
+            )
+        }
+        DebugLayer::Canals => {
+            let (width, height) = size.dimensions();
+            let map = Map::try_new_with_type(seed, size, map_type)?;
+            let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+            let hills: Vec<bool> = map.tiles().iter().map(|tile| tile.is_hill()).collect();
+            let mut canals = vec![false; terrain.len()];
+            for candidate in find_canal_candidates(&map.to_map_data(), MAX_CANAL_LENGTH, CANAL_OVERLAY_COUNT) {
+                for idx in candidate.tiles {
+                    canals[idx] = true;
+                }
+            }
+
+            let overlays = MapOverlays {
+                hills: layers.show_hills.then_some(hills.as_slice()),
+                rivers: if layers.show_rivers { map.rivers() } else { &[] },
+                highlight: Some(&canals),
+                ..Default::default()
+            };
+            render_map_png_with_overlays(
+                &terrain,
+                &overlays,
+                i32::try_from(width)?,
+                i32::try_from(height)?,
+                cell_px,
+                out_path,
+            )
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use civorum_mapgen::map_components::terrain::Terrain;
+use image::RgbaImage;
+
+use crate::debug_render::terrain_color;
+
+const BG_COLOR: [u8; 4] = [0, 0, 0, 0];
+
+/// Dimensions (width, height) of a thumbnail for a map of `width` x `height` tiles.
+/// The long side is at most `max_px`, odd rows are shifted by half a tile so the map is `width + 0.5` tiles wide.
+pub fn thumbnail_dimensions(width: usize, height: usize, max_px: u32) -> (u32, u32) {
+    if width == 0 || height == 0 || max_px == 0 {
+        return (0, 0);
+    }
+
+    let world_w = width as f32 + 0.5;
+    let world_h = height as f32;
+    let scale = max_px as f32 / world_w.max(world_h);
+
+    let out_w = ((world_w * scale).round() as u32).clamp(1, max_px);
+    let out_h = ((world_h * scale).round() as u32).clamp(1, max_px);
+    (out_w, out_h)
+}
+
+/// Render a small raster of the map with one block per tile, without the hex rasterization of `render_map_png`.
+/// Pixels outside the map (the half-tile offset) are transparent.
+pub fn render_thumbnail(terrain: &[Terrain], width: usize, height: usize, max_px: u32) -> RgbaImage {
+    let (out_w, out_h) = thumbnail_dimensions(width, height, max_px);
+    let mut img = RgbaImage::new(out_w, out_h);
+    // the buffer is allocated with the exact dimensions, so this cannot fail
+    let _ = render_thumbnail_into(img.as_mut(), terrain, width, height, max_px);
+    img
+}
+
+/// Same as `render_thumbnail` but renders into an existing RGBA buffer to avoid allocations.
+/// The buffer must hold at least `w * h * 4` bytes for the dimensions from `thumbnail_dimensions`.
+/// Returns the dimensions of the rendered thumbnail.
+pub fn render_thumbnail_into(
+    buffer: &mut [u8],
+    terrain: &[Terrain],
+    width: usize,
+    height: usize,
+    max_px: u32,
+) -> Result<(u32, u32), Box<dyn Error>> {
+    let (out_w, out_h) = thumbnail_dimensions(width, height, max_px);
+    let needed = (out_w as usize) * (out_h as usize) * 4;
+    if buffer.len() < needed {
+        return Err(format!("thumbnail buffer too small: need {needed} bytes, got {}", buffer.len()).into());
+    }
+    if out_w == 0 || out_h == 0 {
+        return Ok((out_w, out_h));
+    }
+
+    let world_w = width as f32 + 0.5;
+    let world_h = height as f32;
+    let scale_x = out_w as f32 / world_w;
+    let scale_y = out_h as f32 / world_h;
+
+    for py in 0..out_h {
+        let row = (((py as f32 + 0.5) / scale_y) as usize).min(height - 1);
+        // odd rows are shifted by half a tile
+        let shift = if row % 2 == 1 { 0.5 } else { 0.0 };
+
+        for px in 0..out_w {
+            let col = (px as f32 + 0.5) / scale_x - shift;
+            let color = if col < 0.0 || col as usize >= width {
+                BG_COLOR
+            } else {
+                match terrain.get(row * width + col as usize) {
+                    Some(tile_terrain) => {
+                        let [r, g, b] = terrain_color(*tile_terrain).0;
+                        [r, g, b, 255]
+                    }
+                    None => BG_COLOR,
+                }
+            };
+
+            let offset = ((py * out_w + px) * 4) as usize;
+            buffer[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+
+    Ok((out_w, out_h))
+}
+
+/// Place images row by row into a sheet with `columns` columns, `gap_px` transparent pixels apart.
+/// Every cell is as large as the largest image, smaller images sit in the top-left corner of their cell.
+pub fn render_contact_sheet(cells: &[RgbaImage], columns: usize, gap_px: u32) -> RgbaImage {
+    if cells.is_empty() {
+        return RgbaImage::new(0, 0);
+    }
+    let columns = columns.clamp(1, cells.len());
+    let rows = cells.len().div_ceil(columns);
+    let cell_w = cells.iter().map(RgbaImage::width).max().unwrap_or(0);
+    let cell_h = cells.iter().map(RgbaImage::height).max().unwrap_or(0);
+
+    let sheet_w = columns as u32 * cell_w + (columns as u32 - 1) * gap_px;
+    let sheet_h = rows as u32 * cell_h + (rows as u32 - 1) * gap_px;
+    let mut sheet = RgbaImage::new(sheet_w, sheet_h);
+    for (i, cell) in cells.iter().enumerate() {
+        let x = (i % columns) as u32 * (cell_w + gap_px);
+        let y = (i / columns) as u32 * (cell_h + gap_px);
+        image::imageops::replace(&mut sheet, cell, i64::from(x), i64::from(y));
+    }
+    sheet
+}
@@ -0,0 +1,67 @@
+use std::fs;
+
+use civorum_core::{BookmarkStore, bookmarks::BOOKMARK_THUMBNAIL_PX};
+use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes, map_types::MapTypes};
+
+fn temp_store(name: &str) -> BookmarkStore {
+    let dir = std::env::temp_dir().join(format!("civorum_bookmarks_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    BookmarkStore::new(dir)
+}
+
+#[test]
+fn bookmarks_survive_a_new_store() {
+    let store = temp_store("reload");
+    assert!(store.load().unwrap().is_empty());
+
+    let duel = Map::new_with_type(Some(7), MapSizes::Duel, MapTypes::Pangea);
+    let first = store.append(&duel, "one big continent").unwrap();
+    let second = store.append(&Map::new(Some(8), MapSizes::Duel), "").unwrap();
+
+    // a restarted app reads the same list
+    let reopened = BookmarkStore::new(store.dir());
+    assert_eq!(reopened.load().unwrap(), [first.clone(), second]);
+    assert_eq!(first.seed_string, duel.seed_string().to_string());
+    assert_eq!(first.note, "one big continent");
+
+    let thumbnail = image::open(reopened.thumbnail_path(&first)).unwrap();
+    assert_eq!(thumbnail.width().max(thumbnail.height()), BOOKMARK_THUMBNAIL_PX);
+
+    fs::remove_dir_all(store.dir()).unwrap();
+}
+
+#[test]
+fn regenerated_bookmarks_match_the_saved_map() {
+    let store = temp_store("regenerate");
+    let map = Map::new_with_type(Some(3), MapSizes::Duel, MapTypes::Continents);
+    let bookmark = store.append(&map, "islands in the north").unwrap();
+
+    let regenerated = bookmark.regenerate().unwrap();
+    assert!(regenerated.config_matches);
+    assert!(regenerated.fingerprint_matches);
+    assert_eq!(regenerated.map.fingerprint(), map.fingerprint());
+
+    // a bookmark from another config still loads, with the mismatch reported
+    let mut stale = bookmark.clone();
+    stale.config_hash = "0000000000000000".to_string();
+    stale.fingerprint = format!("{:016x}", map.fingerprint() ^ 1);
+    let regenerated = stale.regenerate().unwrap();
+    assert!(!regenerated.config_matches);
+    assert!(!regenerated.fingerprint_matches);
+
+    fs::remove_dir_all(store.dir()).unwrap();
+}
+
+#[test]
+fn save_replaces_the_list() {
+    let store = temp_store("save");
+    let bookmark = store.append(&Map::new(Some(1), MapSizes::Duel), "").unwrap();
+    store.save(&[]).unwrap();
+    assert!(store.load().unwrap().is_empty());
+    assert!(store.thumbnail_path(&bookmark).is_file());
+
+    fs::write(store.dir().join("bookmarks.json"), "not json").unwrap();
+    assert!(store.load().is_err());
+
+    fs::remove_dir_all(store.dir()).unwrap();
+}
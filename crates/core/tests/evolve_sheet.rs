@@ -0,0 +1,26 @@
+use civorum_core::{EVOLVE_GAP_PX, generate_evolve_cells, render_evolve_sheet, render_thumbnail};
+use civorum_mapgen::pipeline::{biomes::generate_map_with_type, map_sizes::MapSizes, map_types::MapTypes};
+
+#[test]
+fn top_left_cell_is_the_base_map() {
+    let size = MapSizes::Duel;
+    let (width, height) = size.dimensions();
+    let base = generate_map_with_type(&9, &size, MapTypes::Continents);
+
+    let cells = generate_evolve_cells(Some(9), size, MapTypes::Continents, 3).expect("shipped configs generate");
+    assert_eq!(cells[0].label(), "base");
+    assert_eq!(cells[0].terrain, base.terrain);
+    assert!(cells[1..].iter().all(|cell| !cell.perturbations.is_empty()));
+
+    let path = std::env::temp_dir().join(format!("civorum_evolve_{}.png", std::process::id()));
+    render_evolve_sheet(Some(9), size, MapTypes::Continents, (2, 2), 64, &path).expect("sheet renders");
+    let sheet = image::open(&path).expect("sheet is a png").to_rgba8();
+    let _ = std::fs::remove_file(&path);
+
+    let thumbnail = render_thumbnail(&base.terrain, width, height, 64);
+    assert_eq!(sheet.width(), 2 * thumbnail.width() + EVOLVE_GAP_PX);
+    assert_eq!(sheet.height(), 2 * thumbnail.height() + EVOLVE_GAP_PX);
+    for (x, y, pixel) in thumbnail.enumerate_pixels() {
+        assert_eq!(sheet.get_pixel(x, y), pixel);
+    }
+}
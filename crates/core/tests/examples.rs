@@ -0,0 +1,64 @@
+//! Runs the core function of every example on a Duel map, so the examples keep compiling and working.
+
+use std::fs;
+
+use civorum_mapgen::{
+    map_components::terrain::Feature,
+    pipeline::{document::MapDocument, map::Map, map_sizes::MapSizes, map_types::MapTypes},
+};
+
+#[allow(dead_code)]
+#[path = "../examples/analyze_seeds.rs"]
+mod analyze_seeds;
+#[allow(dead_code)]
+#[path = "../examples/custom_stage.rs"]
+mod custom_stage;
+#[allow(dead_code)]
+#[path = "../examples/generate_and_export.rs"]
+mod generate_and_export;
+#[allow(dead_code)]
+#[path = "../examples/pathfind.rs"]
+mod pathfind;
+
+#[test]
+fn generate_and_export_writes_a_document_and_a_png() {
+    let dir = std::env::temp_dir().join(format!("civorum_example_export_{}", std::process::id()));
+    let (json, png) = generate_and_export::run(4, MapSizes::Duel, &dir).unwrap();
+    assert!(png.is_file());
+    let document = MapDocument::load(&json).unwrap();
+    assert_eq!(document.to_map().unwrap().tiles().len(), MapSizes::Duel.grid_size());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_seeds_has_a_row_per_seed() {
+    let csv = analyze_seeds::run(0..3, MapSizes::Duel, MapTypes::Pangea);
+    let mut lines = csv.lines();
+    assert!(lines.next().is_some_and(|header| header.starts_with("seed,")));
+    let seeds: Vec<&str> = lines.map(|line| line.split(',').next().unwrap()).collect();
+    assert_eq!(seeds, ["0", "1", "2"]);
+}
+
+#[test]
+fn pathfind_connects_two_land_tiles() {
+    let (from, to, path) = pathfind::run(3, MapSizes::Duel).unwrap();
+    assert_ne!(from, to);
+    assert_eq!(path.tiles.first(), Some(&from));
+    assert_eq!(path.tiles.last(), Some(&to));
+    assert!(path.cost >= path.tiles.len() as u32 - 1);
+}
+
+#[test]
+fn custom_stage_adds_marshes_and_keeps_the_rest() {
+    let (before, after) = custom_stage::run(6, MapSizes::Duel).unwrap();
+    let marshes = |map: &Map| map.tiles().iter().filter(|tile| tile.feature() == Some(Feature::Marsh)).count();
+    assert!(marshes(&after) > marshes(&before));
+    assert_eq!(after.seed(), before.seed());
+    assert_eq!(after.map_type(), before.map_type());
+    for (old, new) in before.tiles().iter().zip(after.tiles()) {
+        assert_eq!((old.terrain(), old.is_hill(), old.rainfall()), (new.terrain(), new.is_hill(), new.rainfall()));
+        if new.feature() != Some(Feature::Marsh) {
+            assert_eq!(old.feature(), new.feature());
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::fs;
+
+use civorum_core::{GraphFormat, GraphSubset, export_adjacency};
+use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes};
+
+/// Export the edge list of a small map and return the (src, dst) pairs
+fn export_edges(map: &Map, subset: GraphSubset, name: &str) -> Vec<(usize, usize)> {
+    let path = std::env::temp_dir().join(format!("civorum_graph_{name}_{}.csv", std::process::id()));
+    export_adjacency(map, &path, GraphFormat::EdgeListCsv, subset).unwrap();
+    let raw = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    raw.lines()
+        .skip(1)
+        .map(|line| {
+            let mut fields = line.split(',');
+            let src = fields.next().unwrap().parse().unwrap();
+            let dst = fields.next().unwrap().parse().unwrap();
+            (src, dst)
+        })
+        .collect()
+}
+
+#[test]
+fn subsets_only_contain_matching_tiles() {
+    let map = Map::new(Some(7), MapSizes::Duel);
+    let is_water = |idx: usize| map.tiles()[idx].terrain().is_water();
+
+    let all = export_edges(&map, GraphSubset::All, "all");
+    let land = export_edges(&map, GraphSubset::LandOnly, "land");
+    let water = export_edges(&map, GraphSubset::WaterOnly, "water");
+
+    assert!(!land.is_empty() && !water.is_empty());
+    assert!(land.iter().all(|(a, b)| !is_water(*a) && !is_water(*b)));
+    assert!(water.iter().all(|(a, b)| is_water(*a) && is_water(*b)));
+
+    let mixed = all.iter().filter(|(a, b)| is_water(*a) != is_water(*b)).count();
+    assert_eq!(all.len(), land.len() + water.len() + mixed);
+}
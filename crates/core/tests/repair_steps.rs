@@ -0,0 +1,23 @@
+#![cfg(feature = "debug-render")]
+
+use std::fs;
+
+use civorum_core::generate_debug_png_for_each_repair_step;
+use civorum_mapgen::pipeline::{map_sizes::MapSizes, map_types::MapTypes};
+
+#[test]
+fn writes_one_png_per_step() {
+    let dir = std::env::temp_dir().join(format!("civorum_repair_steps_{}", std::process::id()));
+    let paths = generate_debug_png_for_each_repair_step(Some(3), MapSizes::Duel, MapTypes::Continents, 10, &dir).unwrap();
+
+    let names: Vec<String> = paths
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names[0], "step_00_initial.png");
+    assert!(names.len() < 3 || names[1] == "step_01_repair0.png");
+    assert_eq!(names[names.len() - 1], format!("step_{:02}_final.png", names.len() - 1));
+    assert!(paths.iter().all(|path| path.is_file()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,82 @@
+use civorum_core::{debug_render::terrain_color, render_thumbnail, thumbnail::thumbnail_dimensions};
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+use image::Rgba;
+
+const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+fn color(terrain: Terrain) -> Rgba<u8> {
+    let [r, g, b] = terrain_color(terrain).0;
+    Rgba([r, g, b, 255])
+}
+
+/// `max_px` that gives every tile of a map wider than high a `block` x `block` pixel square,
+/// the map is `width + 0.5` tiles wide
+fn exact_max_px(width: usize, block: u32) -> u32 {
+    (2 * width as u32 + 1) * block / 2
+}
+
+#[test]
+fn thumbnails_fit_the_long_side_and_keep_the_aspect_ratio() {
+    for size in MapSizes::ALL {
+        let (width, height) = size.dimensions();
+        for max_px in [64, 200, 512] {
+            let (w, h) = thumbnail_dimensions(width, height, max_px);
+            assert_eq!(w.max(h), max_px, "{size:?} at {max_px}px");
+            let aspect = (width as f32 + 0.5) / height as f32;
+            assert!((w as f32 / h as f32 - aspect).abs() * h as f32 <= 1.0, "{size:?} at {max_px}px: {w}x{h}");
+
+            let terrain = vec![Terrain::Plains; width * height];
+            assert_eq!(render_thumbnail(&terrain, width, height, max_px).dimensions(), (w, h));
+        }
+    }
+    assert_eq!(thumbnail_dimensions(0, 10, 64), (0, 0));
+    assert_eq!(thumbnail_dimensions(10, 10, 0), (0, 0));
+}
+
+#[test]
+fn land_and_water_pixels_are_proportional_to_the_tiles() {
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let (width, height) = MapSizes::Duel.dimensions();
+    let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    let water = [color(Terrain::Ocean), color(Terrain::CoastLake)];
+    assert!(terrain.iter().filter(|t| !t.is_water()).all(|&t| !water.contains(&color(t))));
+
+    let block = 4;
+    let img = render_thumbnail(&terrain, width, height, exact_max_px(width, block));
+    let count = |f: &dyn Fn(&Rgba<u8>) -> bool| img.pixels().filter(|pixel| f(pixel)).count();
+    let n_water = terrain.iter().filter(|t| t.is_water()).count();
+    let per_tile = (block * block) as usize;
+
+    assert_eq!(count(&|pixel| water.contains(pixel)), n_water * per_tile);
+    assert_eq!(count(&|pixel| *pixel != TRANSPARENT && !water.contains(pixel)), (terrain.len() - n_water) * per_tile);
+    // the half-tile strip left over in every row
+    assert_eq!(count(&|pixel| *pixel == TRANSPARENT), height * per_tile / 2);
+}
+
+#[test]
+fn odd_rows_are_shifted_by_half_a_block() {
+    // columns alternate between land and water, so every block edge is a color change
+    let (width, height) = (4, 2);
+    let terrain: Vec<Terrain> = (0..width * height)
+        .map(|idx| if idx % 2 == 0 { Terrain::Plains } else { Terrain::Ocean })
+        .collect();
+    let block = 4;
+    let img = render_thumbnail(&terrain, width, height, exact_max_px(width, block));
+    assert_eq!(img.dimensions(), (18, 8));
+
+    let row = |y: u32| (0..img.width()).map(|x| *img.get_pixel(x, y)).collect::<Vec<_>>();
+    let blocks = |tile_row: usize, offset: usize| {
+        let mut expected = vec![TRANSPARENT; 18];
+        for (x, pixel) in expected.iter_mut().enumerate().skip(offset).take(16) {
+            *pixel = color(terrain[tile_row * width + (x - offset) / block as usize]);
+        }
+        expected
+    };
+    for y in 0..block {
+        assert_eq!(row(y), blocks(0, 0), "even row, pixel row {y}");
+        assert_eq!(row(block + y), blocks(1, 2), "odd row, pixel row {}", block + y);
+    }
+}
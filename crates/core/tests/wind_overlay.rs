@@ -0,0 +1,16 @@
+use std::fs;
+
+use civorum_core::{DebugLayer, LayerOptions, render_debug_layer};
+use civorum_mapgen::pipeline::{map_sizes::MapSizes, map_types::MapTypes};
+
+#[test]
+fn wind_arrows_render_on_every_map_size() {
+    let dir = std::env::temp_dir().join(format!("civorum_wind_overlay_{}", std::process::id()));
+    for size in MapSizes::ALL {
+        let path = dir.join(format!("wind_{}.png", size.short_label()));
+        render_debug_layer(Some(5), size, MapTypes::Continents, DebugLayer::Wind, 10, &path, &LayerOptions::default())
+            .unwrap();
+        assert!(path.is_file(), "{size:?}");
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
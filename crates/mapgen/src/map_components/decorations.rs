@@ -0,0 +1,156 @@
+use rand_chacha::{
+    ChaCha12Rng,
+    rand_core::{Rng, SeedableRng},
+};
+
+use crate::{
+    map_components::{
+        hex_coords::{HEX_CIRCUMRADIUS, HexCoord},
+        terrain::{Feature, Terrain},
+        tile::Tile,
+    },
+    pipeline::helpers::tile_hash,
+};
+
+/// Distance from the center of a hex to its edges in world units (see `HexCoord::world_pos`)
+const HEX_APOTHEM: f32 = 0.5;
+
+/// Candidate positions tried per decoration before it is dropped
+const PLACEMENT_ATTEMPTS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Kind of prop a 3D client places at a decoration point
+pub enum DecorKind {
+    Tree,
+    Rock,
+    Shrub,
+    Reed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// One prop inside a tile, see `decoration_points`
+pub struct DecorPoint {
+    /// Position relative to the center of the hex in world units, as (x, z)
+    pub offset: (f32, f32),
+    pub kind: DecorKind,
+    /// Size factor of the prop model
+    pub scale: f32,
+    /// Rotation around the up axis in radians [0, 2π)
+    pub rotation: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How many props of one kind a tile gets and how large they are
+pub struct DecorRule {
+    pub kind: DecorKind,
+    pub min_count: u8,
+    pub max_count: u8,
+    pub min_scale: f32,
+    pub max_scale: f32,
+}
+
+impl DecorRule {
+    /// Rule with `min_count..=max_count` props of `kind` at scales 0.8 to 1.2
+    pub const fn new(kind: DecorKind, min_count: u8, max_count: u8) -> Self {
+        DecorRule {
+            kind,
+            min_count,
+            max_count,
+            min_scale: 0.8,
+            max_scale: 1.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Table the decoration points are derived from.
+/// A feature with rules replaces the rules of its terrain, terrains and features without rules get no props.
+pub struct DecorRules {
+    pub terrain: Vec<(Terrain, Vec<DecorRule>)>,
+    pub features: Vec<(Feature, Vec<DecorRule>)>,
+    /// Smallest distance between a point and the edges of its hex
+    pub edge_margin: f32,
+    /// Smallest distance between two points of a tile
+    pub min_spacing: f32,
+}
+
+impl DecorRules {
+    /// Rules of a tile with the given terrain and feature, empty if it has no props
+    pub fn rules_for(&self, terrain: Terrain, feature: Option<Feature>) -> &[DecorRule] {
+        let feature_rules = feature.and_then(|feature| self.features.iter().find(|(f, _)| *f == feature));
+        match feature_rules {
+            Some((_, rules)) => rules,
+            None => self.terrain.iter().find(|(t, _)| *t == terrain).map_or(&[], |(_, rules)| rules),
+        }
+    }
+}
+
+impl Default for DecorRules {
+    fn default() -> Self {
+        use DecorKind::*;
+
+        DecorRules {
+            terrain: vec![
+                (Terrain::Plains, vec![DecorRule::new(Shrub, 0, 2)]),
+                (Terrain::Grassland, vec![DecorRule::new(Shrub, 0, 3)]),
+                (Terrain::Desert, vec![DecorRule::new(Rock, 0, 2)]),
+                (Terrain::Tundra, vec![DecorRule::new(Rock, 1, 3), DecorRule::new(Shrub, 0, 1)]),
+                (Terrain::Snow, vec![DecorRule::new(Rock, 0, 2)]),
+            ],
+            features: vec![
+                (Feature::Woods, vec![DecorRule::new(Tree, 5, 12)]),
+                (Feature::Rainforest, vec![DecorRule::new(Tree, 8, 12), DecorRule::new(Shrub, 1, 3)]),
+                (Feature::Marsh, vec![DecorRule::new(Reed, 3, 6)]),
+                (Feature::Oasis, vec![DecorRule::new(Tree, 2, 4), DecorRule::new(Shrub, 1, 2)]),
+                (Feature::Reef, Vec::new()),
+                (Feature::Ice, Vec::new()),
+            ],
+            edge_margin: 0.06,
+            min_spacing: 0.1,
+        }
+    }
+}
+
+/// Prop positions inside a tile, e.g. trees in woods and rocks on tundra, the same for every client with this seed.
+/// Counts and kinds come from `rules`, positions are rejection sampled with a minimum spacing seeded with the
+/// tile hash, so every point lies strictly inside the hex polygon (see `HexCoord::world_corners`) by at least
+/// `edge_margin`. A point that finds no free spot is dropped, so crowded rules can get fewer points than their minimum.
+pub fn decoration_points(hex: HexCoord, tile: &Tile, seed: u64, rules: &DecorRules) -> Vec<DecorPoint> {
+    let tile_rules = rules.rules_for(tile.terrain(), tile.feature());
+    if tile_rules.is_empty() {
+        return Vec::new();
+    }
+
+    let hex_key = ((hex.y() as u32 as u64) << 32 | hex.x() as u32 as u64) as usize;
+    let mut rng = ChaCha12Rng::seed_from_u64(tile_hash(seed, hex_key));
+    // 24 random bits, so the value is exact in f32 and never rounds up to 1
+    let mut unit = || (rng.next_u32() >> 8) as f32 / (1u32 << 24) as f32;
+
+    let inner = HEX_APOTHEM - rules.edge_margin;
+    let inside = |x: f32, z: f32| x.abs() < inner && x.abs() / 2.0 + z.abs() * (3_f32.sqrt() / 2.0) < inner;
+    let mut points: Vec<DecorPoint> = Vec::new();
+    for rule in tile_rules {
+        let extra = rule.max_count.saturating_sub(rule.min_count) as f32;
+        let count = rule.min_count + (unit() * (extra + 1.0)) as u8;
+        for _ in 0..count {
+            let spot = (0..PLACEMENT_ATTEMPTS)
+                .map(|_| ((unit() * 2.0 - 1.0) * HEX_APOTHEM, (unit() * 2.0 - 1.0) * HEX_CIRCUMRADIUS))
+                .find(|&(x, z)| {
+                    inside(x, z)
+                        && points.iter().all(|point| {
+                            let (dx, dz) = (point.offset.0 - x, point.offset.1 - z);
+                            dx * dx + dz * dz >= rules.min_spacing * rules.min_spacing
+                        })
+                });
+            if let Some(offset) = spot {
+                points.push(DecorPoint {
+                    offset,
+                    kind: rule.kind,
+                    scale: rule.min_scale + unit() * (rule.max_scale - rule.min_scale),
+                    rotation: unit() * std::f32::consts::TAU,
+                });
+            }
+        }
+    }
+    points
+}
@@ -3,6 +3,7 @@
 /// NW  / \  NE
 /// W   |  | E
 /// SW  \  / SE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompassDirection {
     NE,
     E,
@@ -12,7 +13,32 @@ pub enum CompassDirection {
     NW,
 }
 
+impl CompassDirection {
+    /// All directions, clockwise starting at NE
+    pub const ALL: [CompassDirection; 6] = [
+        CompassDirection::NE,
+        CompassDirection::E,
+        CompassDirection::SE,
+        CompassDirection::SW,
+        CompassDirection::W,
+        CompassDirection::NW,
+    ];
+
+    /// Angle of the direction in degrees, counterclockwise from east with north up (E is 0, NE is 60)
+    pub fn angle_degrees(&self) -> f32 {
+        match self {
+            CompassDirection::E => 0.0,
+            CompassDirection::NE => 60.0,
+            CompassDirection::NW => 120.0,
+            CompassDirection::W => 180.0,
+            CompassDirection::SW => 240.0,
+            CompassDirection::SE => 300.0,
+        }
+    }
+}
+
 /// Basic struct to store hex coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct HexCoord {
     x: i32,
     y: i32,
@@ -34,6 +60,66 @@ impl HexCoord {
         self.y
     }
 
+    /// Convert to cube coordinates (q, r, s) with q + r + s = 0
+    pub fn to_cube(&self) -> (i32, i32, i32) {
+        let q = self.x - (self.y - (self.y & 1)) / 2;
+        let r = self.y;
+        (q, r, -q - r)
+    }
+
+    /// Convert cube coordinates (q, r, s) back to odd-r offset coordinates
+    pub fn from_cube(q: i32, r: i32) -> HexCoord {
+        HexCoord::new(q + (r - (r & 1)) / 2, r)
+    }
+
+    /// Center of the hex in world units, with neighboring centers 1 apart.
+    /// x grows to the east (odd rows shifted by half a hex), z grows with the row.
+    pub fn world_pos(&self) -> (f32, f32) {
+        let x = self.x as f32 + 0.5 * (self.y & 1) as f32;
+        let z = self.y as f32 * (3_f32.sqrt() / 2.0);
+        (x, z)
+    }
+
+    /// The six corners of the hex in world units, counterclockwise seen from above starting at the
+    /// east-north-east corner (same order as `hex_ring_mesh`). Neighboring hexes share two corners.
+    pub fn world_corners(&self) -> [(f32, f32); 6] {
+        let (x, z) = self.world_pos();
+        let half = HEX_CIRCUMRADIUS / 2.0;
+        [
+            (x + 0.5, z + half),
+            (x, z + HEX_CIRCUMRADIUS),
+            (x - 0.5, z + half),
+            (x - 0.5, z - half),
+            (x, z - HEX_CIRCUMRADIUS),
+            (x + 0.5, z - half),
+        ]
+    }
+
+    /// Hex containing a world position, inverse of `world_pos`
+    pub fn from_world_pos(x: f32, z: f32) -> HexCoord {
+        // fractional axial coordinates, then round in cube space
+        let r = z * 2.0 / 3_f32.sqrt();
+        let q = x - r / 2.0;
+        let s = -q - r;
+
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        HexCoord::from_cube(rq as i32, rr as i32)
+    }
+
+    /// Number of hex steps between two coordinates
+    pub fn distance(&self, other: &HexCoord) -> u32 {
+        let (aq, ar, as_) = self.to_cube();
+        let (bq, br, bs) = other.to_cube();
+        (aq - bq).unsigned_abs().max((ar - br).unsigned_abs()).max((as_ - bs).unsigned_abs())
+    }
+
     /// Return the coordinate of the neighbor in a given direction
     pub fn neighbor(&self, direction: &CompassDirection) -> HexCoord {
         let p = self.y & 1;
@@ -61,14 +147,40 @@ impl HexCoord {
     /// Return all 6 hex neighbors of a given tile.
     /// This returns a vector and tiles are arranged in the order NE, E, Se, SW, W, NW
     pub fn neighbors(&self) -> Vec<HexCoord> {
-        let directions = [
-            CompassDirection::NE,
-            CompassDirection::E,
-            CompassDirection::SE,
-            CompassDirection::SW,
-            CompassDirection::W,
-            CompassDirection::NW,
-        ];
-        directions.iter().map(|dir| self.neighbor(dir)).collect()
+        CompassDirection::ALL.iter().map(|dir| self.neighbor(dir)).collect()
     }
 }
+
+/// Distance from the center of a hex to its corners in world units (see `HexCoord::world_pos`)
+pub const HEX_CIRCUMRADIUS: f32 = 0.577_350_26;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Triangle mesh on the ground plane (y = 0), indices wind counterclockwise seen from above.
+pub struct HexRingMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+/// Flat pointy-top hex annulus around the origin, e.g. to outline the hovered tile.
+/// The radii are circumradii, `HEX_CIRCUMRADIUS` matches the tiles of the map.
+/// Corners `i` of the inner and outer hex are vertices `i` and `6 + i`.
+pub fn hex_ring_mesh(inner_radius: f32, outer_radius: f32) -> HexRingMesh {
+    let corner = |radius: f32, i: u32| {
+        let angle = (30.0 + 60.0 * i as f32).to_radians();
+        [radius * angle.cos(), 0.0, radius * angle.sin()]
+    };
+    let positions = (0..6)
+        .map(|i| corner(inner_radius, i))
+        .chain((0..6).map(|i| corner(outer_radius, i)))
+        .collect();
+
+    // one quad (two triangles) per side
+    let indices = (0..6)
+        .flat_map(|i| {
+            let next = (i + 1) % 6;
+            [i, next, 6 + i, next, 6 + next, 6 + i]
+        })
+        .collect();
+
+    HexRingMesh { positions, indices }
+}
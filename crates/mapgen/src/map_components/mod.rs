@@ -1,5 +1,9 @@
+pub mod decorations;
 pub mod hex_coords;
 pub mod resources;
+pub mod rivers;
 pub mod terrain;
 pub mod tile;
+pub mod tile_modifiers;
+pub mod tile_tags;
 pub mod yields;
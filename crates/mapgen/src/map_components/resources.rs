@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// The basic types of resources that can spawn in the world
 pub enum ResourceType {
     Bonus,
@@ -5,3 +8,17 @@ pub enum ResourceType {
     Luxury,
     Artifact,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Strategic resources, placed in veins of a few deposits close together (see `place_strategic_veins`)
+pub enum StrategicResource {
+    Iron,
+    Horses,
+    Niter,
+}
+
+impl StrategicResource {
+    /// Every strategic resource
+    pub const ALL: [StrategicResource; 3] = [StrategicResource::Iron, StrategicResource::Horses, StrategicResource::Niter];
+}
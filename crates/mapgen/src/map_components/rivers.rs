@@ -0,0 +1,48 @@
+use crate::map_components::yields::{BaseYields, Yields};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Classification of a river by its length in tiles.
+/// Minor < 5, Moderate 5-10, Major 11-20, Great > 20.
+pub enum RiverClass {
+    Minor,
+    Moderate,
+    Major,
+    Great,
+}
+
+impl RiverClass {
+    /// Classify a river by the number of tiles it runs through
+    pub fn from_length(length: usize) -> Self {
+        match length {
+            0..=4 => Self::Minor,
+            5..=10 => Self::Moderate,
+            11..=20 => Self::Major,
+            _ => Self::Great,
+        }
+    }
+
+    /// Major and Great rivers can be used for naval movement
+    pub fn is_navigable(&self) -> bool {
+        matches!(self, Self::Major | Self::Great)
+    }
+
+    /// Add the yield bonus of the river to the yields of a tile it runs through
+    pub fn apply_yield_bonus(&self, yields: &mut Yields) {
+        let (types, modifiers) = self.yield_bonus();
+        let _ = yields.set_yields(types, modifiers);
+    }
+
+    /// Take the yield bonus of the river off the yields again, e.g. when a larger river replaces it
+    pub fn remove_yield_bonus(&self, yields: &mut Yields) {
+        let (types, modifiers) = self.yield_bonus();
+        let _ = yields.set_yields(types, modifiers.into_iter().map(|modifier| -modifier).collect());
+    }
+
+    fn yield_bonus(&self) -> (Vec<BaseYields>, Vec<i32>) {
+        match self {
+            Self::Minor | Self::Moderate => (vec![BaseYields::Food], vec![1]),
+            Self::Major => (vec![BaseYields::Food], vec![2]),
+            Self::Great => (vec![BaseYields::Food, BaseYields::Gold], vec![2, 1]),
+        }
+    }
+}
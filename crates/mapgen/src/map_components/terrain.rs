@@ -1,6 +1,9 @@
-use crate::map_components::yields::Yields;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::map_components::yields::{BaseYields, Yields};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 /// The types of base terrain that exist in the game.
 /// All five terrain types have their Hill variants, where the hill denotes a difference in relief.
 /// There are two more types of base terrain, related to water.
@@ -17,17 +20,72 @@ pub enum Terrain {
 }
 
 impl Terrain {
+    /// Every terrain
+    pub const ALL: [Terrain; 8] = [
+        Self::Plains,
+        Self::Grassland,
+        Self::Desert,
+        Self::Tundra,
+        Self::Snow,
+        Self::CoastLake,
+        Self::Ocean,
+        Self::Mountain,
+    ];
+
+    /// Return whether this terrain is water (coast, lake or ocean)
+    pub fn is_water(&self) -> bool {
+        matches!(self, Self::CoastLake | Self::Ocean)
+    }
 
     pub fn base_yields(&self) -> Yields {
+        let appeal = self.default_appeal();
         match self {
-            Self::Plains => Yields::new(1, 1, 0, 0, 0, 0, 0),
-            Self::Grassland => Yields::new(2, 1, 0, 0, 0, 0, 0),
-            Self::Tundra => Yields::new(1, 0, 0, 0, 0, 0, 0),
-            Self::CoastLake => Yields::new(1, 0, 1, 0, 0, 0, 0),
-            Self::Ocean => Yields::new(1, 0, 0, 0, 0, 0, 0),
-            _ => Yields::new(0, 0, 0, 0, 0, 0, 0)
+            Self::Plains => Yields::new(1, 1, 0, 0, 0, 0, appeal),
+            Self::Grassland => Yields::new(2, 1, 0, 0, 0, 0, appeal),
+            Self::Tundra => Yields::new(1, 0, 0, 0, 0, 0, appeal),
+            Self::CoastLake => Yields::new(1, 0, 1, 0, 0, 0, appeal),
+            Self::Ocean => Yields::new(1, 0, 0, 0, 0, 0, appeal),
+            _ => Yields::new(0, 0, 0, 0, 0, 0, appeal)
         }
     }
+
+    /// Appeal the terrain adds to its own tile
+    pub const fn default_appeal(&self) -> i32 {
+        match self {
+            Self::Ocean => 0,
+            Self::CoastLake => 1,
+            Self::Grassland => 1,
+            Self::Plains => 0,
+            Self::Desert => -1,
+            Self::Tundra => -1,
+            Self::Snow => -2,
+            // alpine views
+            Self::Mountain => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Depth of a water tile. Deep water has water on all six sides, shallow water touches land or the map edge.
+pub enum WaterDepth {
+    Shallow,
+    Deep,
+}
+
+impl WaterDepth {
+    /// Add the yield bonus of the depth to the yields of a water tile, shallow water near the shore has more fish
+    pub fn apply_yield_bonus(&self, yields: &mut Yields) {
+        let _ = yields.set_yields(vec![BaseYields::Food], vec![self.food_bonus()]);
+    }
+
+    /// Take the yield bonus of the depth off the yields again
+    pub fn remove_yield_bonus(&self, yields: &mut Yields) {
+        let _ = yields.set_yields(vec![BaseYields::Food], vec![-self.food_bonus()]);
+    }
+
+    fn food_bonus(&self) -> i32 {
+        i32::from(*self == Self::Shallow)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,3 +102,40 @@ pub enum Feature {
     Reef,
     Ice,
 }
+
+impl Feature {
+    /// Every feature
+    pub const ALL: [Feature; 9] = [
+        Self::Woods,
+        Self::Rainforest,
+        Self::Marsh,
+        Self::Floodplains,
+        Self::Oasis,
+        Self::Fissure,
+        Self::VolanicSoil,
+        Self::Reef,
+        Self::Ice,
+    ];
+
+    /// Appeal the feature adds on top of the terrain appeal
+    pub const fn appeal_bonus(&self) -> i32 {
+        match self {
+            Self::Woods => 1,
+            Self::Rainforest => 1,
+            Self::Marsh => -2,
+            Self::Floodplains => -1,
+            Self::Oasis => 3,
+            Self::Reef => 1,
+            Self::Ice => -2,
+            Self::Fissure => 1,
+            Self::VolanicSoil => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Look of a desert tile. Only sand dunes can hold an oasis.
+pub enum DesertVariant {
+    SandDunes,
+    RockyDesert,
+}
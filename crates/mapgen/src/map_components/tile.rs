@@ -1,10 +1,15 @@
+use serde_json::{Value, json};
+
 use crate::map_components::{
-    hex_coords::{CompassDirection, HexCoord},
-    resources::ResourceType,
-    terrain::{Feature, Terrain},
-    yields::Yields,
+    hex_coords::HexCoord,
+    resources::{ResourceType, StrategicResource},
+    rivers::RiverClass,
+    terrain::{DesertVariant, Feature, Terrain, WaterDepth},
+    tile_modifiers::TileModifiers,
+    yields::{BaseYields, Yields},
 };
 
+#[derive(Debug, Clone, PartialEq)]
 /// Base implementation of a tile, that hold all the main information about the raw state, yields and appeal.
 pub struct Tile {
     // coordinations
@@ -12,16 +17,291 @@ pub struct Tile {
     // tile information
     base_terrain: Terrain,
     feature: Option<Feature>,
+    desert_variant: Option<DesertVariant>,
     hill: bool,
     passable: bool,
     yields: Yields,
+    modifiers: TileModifiers,
+    // climate
+    elevation: u8,
+    temperature: u8,
+    rainfall: u8,
+    temperature_range: u8,
+    geological_age: u8,
     // rivers and water
     river_edges: u8,
+    river_class: Option<RiverClass>,
+    river_width: u8,
+    water_depth: Option<WaterDepth>,
+    lowland: bool,
     freshwater: bool,
     ocean_acces: bool,
     // map related information
     resource: Option<ResourceType>,
+    strategic_resource: Option<StrategicResource>,
     landmass: String,
+    landmass_id: Option<usize>,
     // tile improvements todo
     owner: Option<String>,
 }
+
+impl Tile {
+    /// Instantiate a new tile from its coordinates and base terrain.
+    /// Yields start at the base yields of the terrain, everything else starts empty.
+    pub fn new(hex_coords: HexCoord, base_terrain: Terrain, hill: bool) -> Self {
+        Tile {
+            hex_coords,
+            base_terrain,
+            feature: None,
+            desert_variant: None,
+            hill,
+            passable: base_terrain != Terrain::Mountain,
+            yields: base_terrain.base_yields(),
+            modifiers: TileModifiers::default(),
+            elevation: 0,
+            temperature: 0,
+            rainfall: 0,
+            temperature_range: 0,
+            geological_age: 0,
+            river_edges: 0,
+            river_class: None,
+            river_width: 0,
+            water_depth: None,
+            lowland: false,
+            freshwater: false,
+            ocean_acces: false,
+            resource: None,
+            strategic_resource: None,
+            landmass: String::new(),
+            landmass_id: None,
+            owner: None,
+        }
+    }
+
+    /// Return the hex coordinates of the tile
+    pub fn hex_coords(&self) -> HexCoord {
+        self.hex_coords
+    }
+
+    /// Return the base terrain of the tile
+    pub fn terrain(&self) -> Terrain {
+        self.base_terrain
+    }
+
+    /// Return the feature on the tile, if any
+    pub fn feature(&self) -> Option<Feature> {
+        self.feature
+    }
+
+    /// Set the feature on the tile
+    pub fn set_feature(&mut self, feature: Option<Feature>) {
+        self.feature = feature;
+    }
+
+    /// Return the movement, defense and sight modifiers of the tile
+    pub fn modifiers(&self) -> TileModifiers {
+        self.modifiers
+    }
+
+    /// Set the movement, defense and sight modifiers, see `TileModifierRules::modifiers`
+    pub fn set_modifiers(&mut self, modifiers: TileModifiers) {
+        self.modifiers = modifiers;
+    }
+
+    /// Return the sub-variant of a desert tile (None for every other terrain)
+    pub fn desert_variant(&self) -> Option<DesertVariant> {
+        self.desert_variant
+    }
+
+    /// Set the sub-variant of a desert tile
+    pub fn set_desert_variant(&mut self, desert_variant: Option<DesertVariant>) {
+        self.desert_variant = desert_variant;
+    }
+
+    /// Return the yields of the tile
+    pub fn yields(&self) -> &Yields {
+        &self.yields
+    }
+
+    /// Set the appeal of the tile
+    pub fn set_appeal(&mut self, appeal: i32) {
+        self.yields.set_appeal(appeal);
+    }
+
+    /// Return whether the tile is a hill
+    pub fn is_hill(&self) -> bool {
+        self.hill
+    }
+
+    /// Return the elevation of the tile [0, 255]
+    pub fn elevation(&self) -> u8 {
+        self.elevation
+    }
+
+    /// Return the temperature of the tile [0, 255]
+    pub fn temperature(&self) -> u8 {
+        self.temperature
+    }
+
+    /// Return the rainfall of the tile [0, 255]
+    pub fn rainfall(&self) -> u8 {
+        self.rainfall
+    }
+
+    /// Return the seasonal temperature swing of the tile [0, 255]
+    pub fn temperature_range(&self) -> u8 {
+        self.temperature_range
+    }
+
+    /// Set the seasonal temperature swing of the tile
+    pub fn set_temperature_range(&mut self, temperature_range: u8) {
+        self.temperature_range = temperature_range;
+    }
+
+    /// Return the flavor age of the tile, 0 (young) to 255 (old)
+    pub fn geological_age(&self) -> u8 {
+        self.geological_age
+    }
+
+    /// Set the flavor age of the tile
+    pub fn set_geological_age(&mut self, geological_age: u8) {
+        self.geological_age = geological_age;
+    }
+
+    /// Set the elevation of the tile
+    pub fn set_elevation(&mut self, elevation: u8) {
+        self.elevation = elevation;
+    }
+
+    /// Set the climate values (elevation, temperature, rainfall) of the tile
+    pub fn set_climate(&mut self, elevation: u8, temperature: u8, rainfall: u8) {
+        self.elevation = elevation;
+        self.temperature = temperature;
+        self.rainfall = rainfall;
+    }
+
+    /// Return the class of the river running through this tile (None without a river)
+    pub fn river_class(&self) -> Option<RiverClass> {
+        self.river_class
+    }
+
+    /// Set the class of the river running through this tile and add its yield bonus.
+    /// If several rivers run through the tile, only the largest class counts, its bonus replaces the smaller one.
+    /// Other changes to the yields (appeal, features) are kept.
+    pub fn set_river_class(&mut self, river_class: RiverClass) {
+        if let Some(current) = self.river_class {
+            if current >= river_class {
+                return;
+            }
+            current.remove_yield_bonus(&mut self.yields);
+        }
+        self.river_class = Some(river_class);
+        river_class.apply_yield_bonus(&mut self.yields);
+    }
+
+    /// Return the width tier of the river running through this tile, 1 (narrow) to 3 (wide), 0 without a river
+    pub fn river_width(&self) -> u8 {
+        self.river_width
+    }
+
+    /// Set the width tier of the river running through this tile
+    pub fn set_river_width(&mut self, river_width: u8) {
+        self.river_width = river_width;
+    }
+
+    /// Return how deep the water of this tile is (None for land)
+    pub fn water_depth(&self) -> Option<WaterDepth> {
+        self.water_depth
+    }
+
+    /// Set how deep the water of this tile is and swap the yield bonus of the old depth for the new one
+    pub fn set_water_depth(&mut self, water_depth: Option<WaterDepth>) {
+        if let Some(current) = self.water_depth {
+            current.remove_yield_bonus(&mut self.yields);
+        }
+        self.water_depth = water_depth;
+        if let Some(depth) = water_depth {
+            depth.apply_yield_bonus(&mut self.yields);
+        }
+    }
+
+    /// Return whether this is flood-prone lowland, see `lowland_mask`
+    pub fn is_lowland(&self) -> bool {
+        self.lowland
+    }
+
+    /// Mark the tile as flood-prone lowland
+    pub fn set_lowland(&mut self, lowland: bool) {
+        self.lowland = lowland;
+    }
+
+    /// Whether naval units can move through this tile (water or a navigable river)
+    pub fn allows_naval_movement(&self) -> bool {
+        self.base_terrain.is_water() || self.river_class.is_some_and(|class| class.is_navigable())
+    }
+
+    /// Return the type of resource on this tile, if any
+    pub fn resource(&self) -> Option<ResourceType> {
+        self.resource
+    }
+
+    /// Return the strategic resource on this tile, if any
+    pub fn strategic_resource(&self) -> Option<StrategicResource> {
+        self.strategic_resource
+    }
+
+    /// Place a strategic resource on this tile, the resource type becomes `Strategic`
+    pub fn set_strategic_resource(&mut self, strategic_resource: StrategicResource) {
+        self.resource = Some(ResourceType::Strategic);
+        self.strategic_resource = Some(strategic_resource);
+    }
+
+    /// Return the id of the landmass this tile belongs to (None for water)
+    pub fn landmass_id(&self) -> Option<usize> {
+        self.landmass_id
+    }
+
+    /// Set the id of the landmass this tile belongs to
+    pub fn set_landmass_id(&mut self, landmass_id: Option<usize>) {
+        self.landmass_id = landmass_id;
+    }
+
+    /// Return the tile as a flat JSON object, for logging and tile info panels.
+    /// Enum values are written by name, missing feature, desert variant, river, water depth, resources and landmass are `null`.
+    pub fn to_json_value(&self) -> Value {
+        let name = |value: Option<String>| value.map_or(Value::Null, Value::String);
+        json!({
+            "x": self.hex_coords.x(),
+            "y": self.hex_coords.y(),
+            "terrain": format!("{:?}", self.base_terrain),
+            "feature": name(self.feature.map(|feature| format!("{feature:?}"))),
+            "desert_variant": name(self.desert_variant.map(|variant| format!("{variant:?}"))),
+            "hill": self.hill,
+            "passable": self.passable,
+            "movement_cost": self.modifiers.movement_cost,
+            "defense_bonus": self.modifiers.defense_bonus,
+            "sight_cost": self.modifiers.sight_cost,
+            "elevation": self.elevation,
+            "temperature": self.temperature,
+            "rainfall": self.rainfall,
+            "temperature_range": self.temperature_range,
+            "geological_age": self.geological_age,
+            "river": name(self.river_class.map(|class| format!("{class:?}"))),
+            "river_width": self.river_width,
+            "water_depth": name(self.water_depth.map(|depth| format!("{depth:?}"))),
+            "lowland": self.lowland,
+            "freshwater": self.freshwater,
+            "ocean_access": self.ocean_acces,
+            "resource": name(self.resource.map(|resource| format!("{resource:?}"))),
+            "strategic_resource": name(self.strategic_resource.map(|resource| format!("{resource:?}"))),
+            "landmass_id": self.landmass_id,
+            "yields_food": self.yields.get_yield(BaseYields::Food),
+            "yields_production": self.yields.get_yield(BaseYields::Production),
+            "yields_gold": self.yields.get_yield(BaseYields::Gold),
+            "yields_science": self.yields.get_yield(BaseYields::Science),
+            "yields_culture": self.yields.get_yield(BaseYields::Culture),
+            "yields_faith": self.yields.get_yield(BaseYields::Faith),
+            "yields_appeal": self.yields.get_yield(BaseYields::Appeal),
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::map_components::terrain::{Feature, Terrain};
+
+/// Movement cost of tiles a unit can not enter
+pub const IMPASSABLE: u8 = u8::MAX;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Gameplay modifiers of a tile for combat prototypes, see `TileModifierRules`.
+pub struct TileModifiers {
+    /// Movement points needed to enter the tile, `IMPASSABLE` if land units can not enter it
+    pub movement_cost: u8,
+    /// Combat strength bonus of a unit defending on the tile
+    pub defense_bonus: i8,
+    /// How much of a unit's sight range looking across the tile uses up
+    pub sight_cost: u8,
+}
+
+impl TileModifiers {
+    /// Modifiers with the given values
+    pub const fn new(movement_cost: u8, defense_bonus: i8, sight_cost: u8) -> Self {
+        TileModifiers {
+            movement_cost,
+            defense_bonus,
+            sight_cost,
+        }
+    }
+
+    /// Return whether land units can enter the tile
+    pub const fn is_passable(&self) -> bool {
+        self.movement_cost != IMPASSABLE
+    }
+
+    /// Add the modifiers of a hill or feature, impassable stays impassable
+    pub fn with(self, delta: TileModifiers) -> Self {
+        let movement_cost = if self.is_passable() && delta.is_passable() {
+            self.movement_cost.saturating_add(delta.movement_cost).min(IMPASSABLE - 1)
+        } else {
+            IMPASSABLE
+        };
+        TileModifiers {
+            movement_cost,
+            defense_bonus: self.defense_bonus.saturating_add(delta.defense_bonus),
+            sight_cost: self.sight_cost.saturating_add(delta.sight_cost),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Modifiers of every base terrain
+pub struct TerrainModifierTable {
+    pub plains: TileModifiers,
+    pub grassland: TileModifiers,
+    pub desert: TileModifiers,
+    pub tundra: TileModifiers,
+    pub snow: TileModifiers,
+    pub coast_lake: TileModifiers,
+    pub ocean: TileModifiers,
+    pub mountain: TileModifiers,
+}
+
+impl TerrainModifierTable {
+    /// Return the modifiers of a terrain
+    pub fn get(&self, terrain: Terrain) -> TileModifiers {
+        match terrain {
+            Terrain::Plains => self.plains,
+            Terrain::Grassland => self.grassland,
+            Terrain::Desert => self.desert,
+            Terrain::Tundra => self.tundra,
+            Terrain::Snow => self.snow,
+            Terrain::CoastLake => self.coast_lake,
+            Terrain::Ocean => self.ocean,
+            Terrain::Mountain => self.mountain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Modifiers every feature adds on top of its terrain
+pub struct FeatureModifierTable {
+    pub woods: TileModifiers,
+    pub rainforest: TileModifiers,
+    pub marsh: TileModifiers,
+    pub floodplains: TileModifiers,
+    pub oasis: TileModifiers,
+    pub fissure: TileModifiers,
+    pub volcanic_soil: TileModifiers,
+    pub reef: TileModifiers,
+    pub ice: TileModifiers,
+}
+
+impl FeatureModifierTable {
+    /// Return the modifiers a feature adds
+    pub fn get(&self, feature: Feature) -> TileModifiers {
+        match feature {
+            Feature::Woods => self.woods,
+            Feature::Rainforest => self.rainforest,
+            Feature::Marsh => self.marsh,
+            Feature::Floodplains => self.floodplains,
+            Feature::Oasis => self.oasis,
+            Feature::Fissure => self.fissure,
+            Feature::VolanicSoil => self.volcanic_soil,
+            Feature::Reef => self.reef,
+            Feature::Ice => self.ice,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Data-driven table the tile modifiers are derived from, loaded from the `tile_modifiers` section of `biomes.yaml`.
+/// A tile gets its terrain modifiers plus the hill and feature modifiers on top.
+/// The default rules are for land units, so water is impassable.
+pub struct TileModifierRules {
+    pub terrain: TerrainModifierTable,
+    /// Added to hills
+    pub hills: TileModifiers,
+    pub features: FeatureModifierTable,
+}
+
+impl TileModifierRules {
+    /// Modifiers of a tile with the given terrain, hill flag and feature
+    pub fn modifiers(&self, terrain: Terrain, hill: bool, feature: Option<Feature>) -> TileModifiers {
+        let mut modifiers = self.terrain.get(terrain);
+        if hill {
+            modifiers = modifiers.with(self.hills);
+        }
+        if let Some(feature) = feature {
+            modifiers = modifiers.with(self.features.get(feature));
+        }
+        modifiers
+    }
+}
+
+impl Default for TileModifierRules {
+    fn default() -> Self {
+        let open = TileModifiers::new(1, 0, 1);
+        let water = TileModifiers::new(IMPASSABLE, 0, 1);
+        let none = TileModifiers::new(0, 0, 0);
+        TileModifierRules {
+            terrain: TerrainModifierTable {
+                plains: open,
+                grassland: open,
+                desert: open,
+                tundra: open,
+                snow: open,
+                coast_lake: water,
+                ocean: water,
+                mountain: TileModifiers::new(IMPASSABLE, 0, 3),
+            },
+            hills: TileModifiers::new(1, 3, 1),
+            features: FeatureModifierTable {
+                woods: TileModifiers::new(1, 2, 1),
+                rainforest: TileModifiers::new(1, 2, 1),
+                marsh: TileModifiers::new(2, -2, 0),
+                floodplains: TileModifiers::new(0, -1, 0),
+                oasis: none,
+                fissure: none,
+                volcanic_soil: none,
+                reef: TileModifiers::new(1, 0, 0),
+                ice: TileModifiers::new(IMPASSABLE, 0, 0),
+            },
+        }
+    }
+}
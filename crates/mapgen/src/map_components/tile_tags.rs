@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Arbitrary string tags attached to tiles by scenario authors (e.g. "spawn:barbarian").
+/// Tag strings are interned once, and only tagged tiles take up memory.
+pub struct TileTags {
+    /// Intern table, a tag is referenced by its position
+    names: Vec<String>,
+    /// Tile index to the interned tags of the tile
+    tiles: BTreeMap<usize, Vec<u32>>,
+}
+
+impl TileTags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a tag to the tile at `idx`, adding it twice has no effect
+    pub fn add_tag(&mut self, idx: usize, tag: &str) {
+        let id = self.intern(tag);
+        let tags = self.tiles.entry(idx).or_default();
+        if !tags.contains(&id) {
+            tags.push(id);
+        }
+    }
+
+    /// Remove a tag from the tile at `idx`. Returns whether the tile had the tag.
+    pub fn remove_tag(&mut self, idx: usize, tag: &str) -> bool {
+        let Some(id) = self.lookup(tag) else {
+            return false;
+        };
+        let Some(tags) = self.tiles.get_mut(&idx) else {
+            return false;
+        };
+
+        let before = tags.len();
+        tags.retain(|t| *t != id);
+        let removed = tags.len() != before;
+        if tags.is_empty() {
+            self.tiles.remove(&idx);
+        }
+        removed
+    }
+
+    /// Check whether the tile at `idx` has the tag
+    pub fn has_tag(&self, idx: usize, tag: &str) -> bool {
+        match (self.lookup(tag), self.tiles.get(&idx)) {
+            (Some(id), Some(tags)) => tags.contains(&id),
+            _ => false,
+        }
+    }
+
+    /// Iterate over the tags of the tile at `idx`
+    pub fn tags_of(&self, idx: usize) -> impl Iterator<Item = &str> {
+        self.tiles
+            .get(&idx)
+            .into_iter()
+            .flatten()
+            .map(|id| self.names[*id as usize].as_str())
+    }
+
+    /// Iterate over the indices of all tiles with the tag, in ascending order
+    pub fn tiles_with_tag(&self, tag: &str) -> impl Iterator<Item = usize> {
+        let id = self.lookup(tag);
+        self.tiles
+            .iter()
+            .filter(move |(_, tags)| id.is_some_and(|id| tags.contains(&id)))
+            .map(|(idx, _)| *idx)
+    }
+
+    /// Number of distinct tag strings
+    pub fn n_distinct_tags(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Number of tiles that carry at least one tag
+    pub fn n_tagged_tiles(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Return the tags as a JSON object from every tag to the ascending indices of its tiles,
+    /// e.g. `{"spawn:barbarian": [3, 17]}`. Tags no tile carries any more are left out.
+    pub fn to_json_value(&self) -> Value {
+        let mut object = JsonMap::new();
+        for name in &self.names {
+            let tiles: Vec<usize> = self.tiles_with_tag(name).collect();
+            if !tiles.is_empty() {
+                object.insert(name.clone(), tiles.into());
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn lookup(&self, tag: &str) -> Option<u32> {
+        self.names.iter().position(|name| name == tag).map(|pos| pos as u32)
+    }
+
+    fn intern(&mut self, tag: &str) -> u32 {
+        match self.lookup(tag) {
+            Some(id) => id,
+            None => {
+                self.names.push(tag.to_string());
+                (self.names.len() - 1) as u32
+            }
+        }
+    }
+}
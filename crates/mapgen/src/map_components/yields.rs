@@ -11,6 +11,7 @@ pub enum BaseYields {
 }
 
 /// Base yields in the game of civ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// This also includes the appeal.
 pub struct Yields {
     food: i32,
@@ -77,6 +78,11 @@ impl Yields {
         ]
     }
 
+    /// Overwrite the appeal
+    pub fn set_appeal(&mut self, appeal: i32) {
+        self.appeal = appeal;
+    }
+
     /// Set the yields based on modifiers
     pub fn set_yields(
         &mut self,
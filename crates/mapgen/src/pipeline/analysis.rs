@@ -0,0 +1,746 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
+use serde_json::{Map as JsonMap, Value, json};
+
+use crate::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain},
+    pipeline::helpers::{LowlandConfig, neighbors_odd_r, tile_hash},
+};
+
+#[derive(Debug, Clone, Default)]
+/// Statistics of a single landmass, used for scenario balancing.
+pub struct LandmassInfo {
+    /// Id of the landmass, landmasses are ordered by size (0 is the largest)
+    pub id: usize,
+    /// Number of land tiles
+    pub size: usize,
+    /// Number of tiles per terrain
+    pub terrain_histogram: BTreeMap<Terrain, usize>,
+    pub hill_count: usize,
+    pub avg_temperature: f32,
+    pub avg_rainfall: f32,
+    /// Land tiles adjacent to ocean-connected water
+    pub coastal_tiles: usize,
+    /// Land tiles adjacent to a lake
+    pub lake_adjacent_tiles: usize,
+    /// Number of land-water tile edges
+    pub coastline_length: usize,
+    /// Shape score in (0, 1], see `hex_compactness`
+    pub compactness: f32,
+    /// Coast ruggedness factor the coast pass applied to the landmass, see `coast_ruggedness`.
+    /// None if the pass did not run (mirror maps, strength 0) or the map was built from tiles
+    pub ruggedness: Option<f32>,
+}
+
+impl LandmassInfo {
+    /// Return the statistics as a JSON object, the terrain histogram maps terrain names to tile counts
+    pub fn to_json_value(&self) -> Value {
+        let histogram: JsonMap<String, Value> = self
+            .terrain_histogram
+            .iter()
+            .map(|(terrain, count)| (format!("{terrain:?}"), Value::from(*count)))
+            .collect();
+        json!({
+            "id": self.id,
+            "size": self.size,
+            "terrain_histogram": histogram,
+            "hill_count": self.hill_count,
+            "avg_temperature": self.avg_temperature,
+            "avg_rainfall": self.avg_rainfall,
+            "coastal_tiles": self.coastal_tiles,
+            "lake_adjacent_tiles": self.lake_adjacent_tiles,
+            "coastline_length": self.coastline_length,
+            "compactness": self.compactness,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Map level statistics of the generated land.
+pub struct MapStatistics {
+    /// Fraction of tiles that are land
+    pub land_ratio: f32,
+    /// Number of separate landmasses
+    pub n_landmasses: usize,
+    /// Fraction of the land that belongs to the largest landmass
+    pub largest_ratio: f32,
+    /// Number of land-water tile edges over all landmasses
+    pub coastline_length: usize,
+    /// Compactness of the landmasses weighted by their size, 0 without land, see `hex_compactness`
+    pub compactness: f32,
+}
+
+impl MapStatistics {
+    /// One-line summary of how these statistics changed compared to `previous`
+    pub fn diff_summary(&self, previous: &MapStatistics) -> String {
+        format!(
+            "land ratio {:.3} ({:+.3}), landmasses {} ({:+}), largest ratio {:.3} ({:+.3}), coastline {} ({:+})",
+            self.land_ratio,
+            self.land_ratio - previous.land_ratio,
+            self.n_landmasses,
+            self.n_landmasses as i64 - previous.n_landmasses as i64,
+            self.largest_ratio,
+            self.largest_ratio - previous.largest_ratio,
+            self.coastline_length,
+            self.coastline_length as i64 - previous.coastline_length as i64,
+        )
+    }
+}
+
+/// Histogram as (bucket value, count) pairs, sorted by bucket value.
+pub type Histogram = Vec<(f32, u32)>;
+
+#[derive(Debug, Clone, Default)]
+/// Result of generating a map for a range of seeds.
+pub struct StressTestReport {
+    pub tested: usize,
+    /// Seeds whose repair loop finished within the iteration budget
+    pub converged: usize,
+    /// Seeds whose repair loop used the whole iteration budget
+    pub failed_seeds: Vec<u64>,
+    /// Every tested seed in order
+    pub seeds: Vec<StressTestSeed>,
+    /// Land ratio in buckets of 0.05
+    pub land_ratio_distribution: Histogram,
+    /// Number of landmasses per map
+    pub component_count_distribution: Histogram,
+}
+
+impl StressTestReport {
+    /// One row per seed as CSV, see `StressTestSeed`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "seed,land_ratio,landmasses,coastline_length,compactness,repair_passes,satisfied_constraints,constraints\n",
+        );
+        for row in &self.seeds {
+            csv.push_str(&format!(
+                "{},{:.4},{},{},{:.4},{},{},{}\n",
+                row.seed,
+                row.land_ratio,
+                row.n_landmasses,
+                row.coastline_length,
+                row.compactness,
+                row.repair_iterations,
+                row.satisfied_constraints,
+                row.constraints
+            ));
+        }
+        csv
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Outcome of a single seed of a stress test
+pub struct StressTestSeed {
+    pub seed: u64,
+    pub land_ratio: f32,
+    pub n_landmasses: usize,
+    /// See `MapStatistics::coastline_length`
+    pub coastline_length: usize,
+    /// See `MapStatistics::compactness`
+    pub compactness: f32,
+    pub repair_iterations: u32,
+    /// Acceptance constraints the map met, out of `constraints` (see `Map::constraint_outcomes`)
+    pub satisfied_constraints: usize,
+    pub constraints: usize,
+}
+
+/// Add one sample to a histogram, keeping the buckets sorted.
+pub fn add_to_histogram(histogram: &mut Histogram, bucket: f32) {
+    match histogram.iter().position(|(value, _)| *value >= bucket) {
+        Some(pos) if histogram[pos].0 == bucket => histogram[pos].1 += 1,
+        Some(pos) => histogram.insert(pos, (bucket, 1)),
+        None => histogram.push((bucket, 1)),
+    }
+}
+
+/// Compactness of a hex region with `area` tiles and `perimeter` land-water edges.
+/// Hex analog of 4*pi*area / perimeter^2: a hex disk of radius r has area 3r^2 + 3r + 1 and
+/// 12r + 6 edges, so 48 * area / perimeter^2 tends to 1 for large disks.
+/// Blobs score close to 1, snakes and fjords close to 0. The result is clamped to (0, 1].
+pub fn hex_compactness(area: usize, perimeter: usize) -> f32 {
+    if area == 0 {
+        return 0.0;
+    }
+    if perimeter == 0 {
+        return 1.0;
+    }
+    (48.0 * area as f32 / (perimeter * perimeter) as f32).min(1.0)
+}
+
+/// Label all water tiles that are connected to the map border (the ocean).
+/// Enclosed water is a lake.
+pub fn ocean_connected_water(terrain: &[Terrain], width: usize, height: usize) -> Vec<bool> {
+    debug_assert_eq!(terrain.len(), width * height);
+    let mut ocean = vec![false; width * height];
+    let mut q = VecDeque::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let border = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+            let idx = y * width + x;
+            if border && terrain[idx].is_water() {
+                ocean[idx] = true;
+                q.push_back((x, y));
+            }
+        }
+    }
+
+    while let Some((x, y)) = q.pop_front() {
+        for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+            let nidx = ny * width + nx;
+            if terrain[nidx].is_water() && !ocean[nidx] {
+                ocean[nidx] = true;
+                q.push_back((nx, ny));
+            }
+        }
+    }
+
+    ocean
+}
+
+/// Sizes of all lakes (water components that are not connected to the ocean).
+pub fn lake_sizes(terrain: &[Terrain], width: usize, height: usize) -> Vec<usize> {
+    lakes(terrain, width, height).iter().map(Vec::len).collect()
+}
+
+/// Tile indices of all lakes (water components that are not connected to the ocean),
+/// in the order of their first tile in row-major order.
+pub fn lakes(terrain: &[Terrain], width: usize, height: usize) -> Vec<Vec<usize>> {
+    let ocean = ocean_connected_water(terrain, width, height);
+    let mut visited = ocean.clone();
+    let mut lakes = Vec::new();
+    let mut q = VecDeque::new();
+
+    for start in 0..width * height {
+        if visited[start] || !terrain[start].is_water() {
+            continue;
+        }
+
+        visited[start] = true;
+        q.push_back((start % width, start / width));
+        let mut lake = Vec::new();
+        while let Some((x, y)) = q.pop_front() {
+            lake.push(y * width + x);
+            for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+                let nidx = ny * width + nx;
+                if terrain[nidx].is_water() && !visited[nidx] {
+                    visited[nidx] = true;
+                    q.push_back((nx, ny));
+                }
+            }
+        }
+        lakes.push(lake);
+    }
+
+    lakes
+}
+
+/// Land tiles bordering a lake, sorted by index
+pub fn lake_rim(lake: &[usize], terrain: &[Terrain], width: usize, height: usize) -> Vec<usize> {
+    let mut rim: Vec<usize> = lake
+        .iter()
+        .flat_map(|&idx| neighbors_odd_r(idx % width, idx / width, width, height))
+        .map(|(nx, ny)| ny * width + nx)
+        .filter(|&nidx| !terrain[nidx].is_water())
+        .collect();
+    rim.sort_unstable();
+    rim.dedup();
+    rim
+}
+
+/// Lakes whose whole rim is mountains (see `lakes`). No land unit can reach them and no river can drain them.
+pub fn dead_lakes(terrain: &[Terrain], width: usize, height: usize) -> Vec<Vec<usize>> {
+    lakes(terrain, width, height)
+        .into_iter()
+        .filter(|lake| {
+            let rim = lake_rim(lake, terrain, width, height);
+            !rim.is_empty() && rim.iter().all(|&idx| terrain[idx] == Terrain::Mountain)
+        })
+        .collect()
+}
+
+/// Distance in tiles from every land tile to the nearest water tile (0 for water).
+/// Without any water every tile is `u16::MAX`.
+pub fn coast_distance(terrain: &[Terrain], width: usize, height: usize) -> Vec<u16> {
+    debug_assert_eq!(terrain.len(), width * height);
+    let mut dist = vec![u16::MAX; width * height];
+    let mut q = VecDeque::new();
+    for (idx, t) in terrain.iter().enumerate() {
+        if t.is_water() {
+            dist[idx] = 0;
+            q.push_back((idx % width, idx / width));
+        }
+    }
+
+    while let Some((x, y)) = q.pop_front() {
+        let d = dist[y * width + x];
+        for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+            let nidx = ny * width + nx;
+            if dist[nidx] == u16::MAX {
+                dist[nidx] = d + 1;
+                q.push_back((nx, ny));
+            }
+        }
+    }
+
+    dist
+}
+
+/// Flood-prone land: tiles among the lowest `elevation_quantile` of the land (ties included) that lie within
+/// `water_radius` tiles of ocean-connected water or a river. Lakes do not count as water here.
+pub fn lowland_mask(
+    terrain: &[Terrain],
+    elevation: &[u8],
+    rivers: &[Vec<usize>],
+    width: usize,
+    height: usize,
+    config: &LowlandConfig,
+) -> Vec<bool> {
+    debug_assert_eq!(terrain.len(), width * height);
+    let mut land: Vec<u8> = terrain
+        .iter()
+        .zip(elevation)
+        .filter(|(t, _)| !t.is_water())
+        .map(|(_, e)| *e)
+        .collect();
+    let share = config.elevation_quantile.clamp(0.0, 1.0);
+    if land.is_empty() || share == 0.0 {
+        return vec![false; terrain.len()];
+    }
+    land.sort_unstable();
+    let cutoff = land[((land.len() as f32 * share).ceil() as usize).clamp(1, land.len()) - 1];
+
+    // distance to the ocean or a river, only up to the radius
+    let ocean = ocean_connected_water(terrain, width, height);
+    let mut dist = vec![u32::MAX; terrain.len()];
+    let mut q = VecDeque::new();
+    for idx in (0..terrain.len()).filter(|&idx| ocean[idx]).chain(rivers.iter().flatten().copied()) {
+        if dist[idx] == u32::MAX {
+            dist[idx] = 0;
+            q.push_back(idx);
+        }
+    }
+    while let Some(idx) = q.pop_front() {
+        let d = dist[idx];
+        if d >= config.water_radius {
+            continue;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let nidx = ny * width + nx;
+            if dist[nidx] == u32::MAX {
+                dist[nidx] = d + 1;
+                q.push_back(nidx);
+            }
+        }
+    }
+
+    (0..terrain.len())
+        .map(|idx| !terrain[idx].is_water() && elevation[idx] <= cutoff && dist[idx] <= config.water_radius)
+        .collect()
+}
+
+/// Label connected land components.
+/// Returns the landmass id per tile (None for water) and the size of every landmass.
+/// Ids are ordered by size, so landmass 0 is the largest (ties keep scan order).
+pub fn label_landmasses(terrain: &[Terrain], width: usize, height: usize) -> (Vec<Option<usize>>, Vec<usize>) {
+    label_components(|idx| !terrain[idx].is_water(), width, height)
+}
+
+/// Same as `label_landmasses` for the water tiles: oceans, seas and lakes that do not touch each other get their own id.
+pub fn label_water_bodies(terrain: &[Terrain], width: usize, height: usize) -> (Vec<Option<usize>>, Vec<usize>) {
+    label_components(|idx| terrain[idx].is_water(), width, height)
+}
+
+/// Same as `label_landmasses` for a landmask (1 = land).
+pub(crate) fn label_landmask(grid: &[u8], width: usize, height: usize) -> (Vec<Option<usize>>, Vec<usize>) {
+    label_components(|idx| grid[idx] == 1, width, height)
+}
+
+fn label_components(
+    is_land: impl Fn(usize) -> bool,
+    width: usize,
+    height: usize,
+) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut raw_ids = vec![usize::MAX; width * height];
+    let mut sizes = Vec::new();
+    let mut q = VecDeque::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if !is_land(idx) || raw_ids[idx] != usize::MAX {
+                continue;
+            }
+
+            let comp_id = sizes.len();
+            let mut size = 0usize;
+            raw_ids[idx] = comp_id;
+            q.push_back((x, y));
+
+            while let Some((cx, cy)) = q.pop_front() {
+                size += 1;
+                for (nx, ny) in neighbors_odd_r(cx, cy, width, height) {
+                    let nidx = ny * width + nx;
+                    if is_land(nidx) && raw_ids[nidx] == usize::MAX {
+                        raw_ids[nidx] = comp_id;
+                        q.push_back((nx, ny));
+                    }
+                }
+            }
+
+            sizes.push(size);
+        }
+    }
+
+    // re-number by size (descending)
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|a, b| sizes[*b].cmp(&sizes[*a]).then_with(|| a.cmp(b)));
+    let mut remap = vec![0usize; sizes.len()];
+    for (new_id, &old_id) in order.iter().enumerate() {
+        remap[old_id] = new_id;
+    }
+
+    let ids = raw_ids
+        .into_iter()
+        .map(|id| if id == usize::MAX { None } else { Some(remap[id]) })
+        .collect();
+    let sorted_sizes = order.iter().map(|&old_id| sizes[old_id]).collect();
+
+    (ids, sorted_sizes)
+}
+
+/// Coast ruggedness factor of a landmass in [0, 1], deterministic from the seed and the landmass id.
+/// Above 0.5 the coast pass carves notches into the landmass, below 0.5 it fills its bays.
+pub fn coast_ruggedness(seed: u64, landmass_id: usize) -> f32 {
+    // salt so the factor is independent of the other per-tile hashes of the same seed
+    let hash = tile_hash(seed ^ 0x636f_6173_745f_7275, landmass_id);
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Compute the statistics of every landmass in a single pass over the map.
+/// `landmass_ids` is the labeling returned by `label_landmasses`.
+pub fn landmass_stats(
+    terrain: &[Terrain],
+    hills: &[bool],
+    temperature: &[u8],
+    rainfall: &[u8],
+    landmass_ids: &[Option<usize>],
+    width: usize,
+    height: usize,
+) -> Vec<LandmassInfo> {
+    let n_landmasses = landmass_ids.iter().flatten().map(|id| id + 1).max().unwrap_or(0);
+    let mut infos: Vec<LandmassInfo> = (0..n_landmasses)
+        .map(|id| LandmassInfo {
+            id,
+            ..Default::default()
+        })
+        .collect();
+    let mut temp_sums = vec![0u64; n_landmasses];
+    let mut rain_sums = vec![0u64; n_landmasses];
+
+    let ocean = ocean_connected_water(terrain, width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let Some(id) = landmass_ids[idx] else {
+                continue;
+            };
+
+            let info = &mut infos[id];
+            info.size += 1;
+            *info.terrain_histogram.entry(terrain[idx]).or_insert(0) += 1;
+            if hills[idx] {
+                info.hill_count += 1;
+            }
+            temp_sums[id] += temperature[idx] as u64;
+            rain_sums[id] += rainfall[idx] as u64;
+
+            let mut coastal = false;
+            let mut lake_adjacent = false;
+            for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+                let nidx = ny * width + nx;
+                if !terrain[nidx].is_water() {
+                    continue;
+                }
+                info.coastline_length += 1;
+                if ocean[nidx] {
+                    coastal = true;
+                } else {
+                    lake_adjacent = true;
+                }
+            }
+            if coastal {
+                info.coastal_tiles += 1;
+            }
+            if lake_adjacent {
+                info.lake_adjacent_tiles += 1;
+            }
+        }
+    }
+
+    for (id, info) in infos.iter_mut().enumerate() {
+        if info.size > 0 {
+            info.avg_temperature = temp_sums[id] as f32 / info.size as f32;
+            info.avg_rainfall = rain_sums[id] as f32 / info.size as f32;
+        }
+        info.compactness = hex_compactness(info.size, info.coastline_length);
+    }
+
+    infos
+}
+
+/// Terrain pairs that should rarely touch, listed by `AdjacencyMatrix::anomalies`
+pub const ANOMALOUS_TERRAIN_PAIRS: [(Terrain, Terrain); 4] = [
+    (Terrain::Snow, Terrain::Desert),
+    (Terrain::Snow, Terrain::Grassland),
+    (Terrain::Tundra, Terrain::Desert),
+    (Terrain::Snow, Terrain::Plains),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How `AdjacencyMatrix::normalized` scales the counts
+pub enum AdjacencyNormalization {
+    /// Every count divided by the sum of the matrix
+    Total,
+    /// Every count divided by the sum of its row: the share of the neighbors of a terrain that are the other terrain
+    Rows,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Number of adjacent tile pairs per ordered terrain pair, indexed in the order of `Terrain::ALL`.
+/// Every pair of neighbors is counted once from each side, so the matrix is symmetric and a pair of
+/// equal terrains adds 2 to the diagonal. Matrices of several maps add up with `+` or `sum`.
+pub struct AdjacencyMatrix {
+    pub counts: [[u64; Terrain::ALL.len()]; Terrain::ALL.len()],
+}
+
+impl AdjacencyMatrix {
+    /// Ordered count: tiles of `from` next to a tile of `to`, summed over their neighbors
+    pub fn get(&self, from: Terrain, to: Terrain) -> u64 {
+        self.counts[terrain_index(from)][terrain_index(to)]
+    }
+
+    /// Number of neighbor pairs with these two terrains, in either order
+    pub fn unordered(&self, a: Terrain, b: Terrain) -> u64 {
+        if a == b { self.get(a, a) / 2 } else { self.get(a, b) }
+    }
+
+    /// Number of neighbor pairs on the map(s)
+    pub fn pair_count(&self) -> u64 {
+        self.counts.iter().flatten().sum::<u64>() / 2
+    }
+
+    /// The counts scaled to shares, all zero for an empty matrix or row
+    pub fn normalized(&self, normalization: AdjacencyNormalization) -> [[f64; Terrain::ALL.len()]; Terrain::ALL.len()] {
+        let total = self.counts.iter().flatten().sum::<u64>();
+        self.counts.map(|row| {
+            let divisor = match normalization {
+                AdjacencyNormalization::Total => total,
+                AdjacencyNormalization::Rows => row.iter().sum(),
+            };
+            row.map(|count| if divisor == 0 { 0.0 } else { count as f64 / divisor as f64 })
+        })
+    }
+
+    /// Pairs of `ANOMALOUS_TERRAIN_PAIRS` that occur, most frequent first
+    pub fn anomalies(&self) -> Vec<(Terrain, Terrain, u64)> {
+        let mut found: Vec<_> = ANOMALOUS_TERRAIN_PAIRS
+            .iter()
+            .map(|&(a, b)| (a, b, self.unordered(a, b)))
+            .filter(|(_, _, count)| *count > 0)
+            .collect();
+        found.sort_by_key(|(_, _, count)| std::cmp::Reverse(*count));
+        found
+    }
+
+    /// The ordered counts as CSV: a header row of terrain names, then one row per terrain
+    pub fn to_csv(&self) -> String {
+        let names: Vec<String> = Terrain::ALL.iter().map(|terrain| format!("{terrain:?}")).collect();
+        let mut csv = format!("terrain,{}\n", names.join(","));
+        for (name, row) in names.iter().zip(&self.counts) {
+            let counts: Vec<String> = row.iter().map(u64::to_string).collect();
+            csv.push_str(&format!("{name},{}\n", counts.join(",")));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for AdjacencyMatrix {
+    /// Table of the ordered counts with the terrain names as row and column labels
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>10}", "")?;
+        for terrain in Terrain::ALL {
+            write!(f, " {:>9}", format!("{terrain:?}"))?;
+        }
+        for (terrain, row) in Terrain::ALL.iter().zip(&self.counts) {
+            write!(f, "\n{:>10}", format!("{terrain:?}"))?;
+            for count in row {
+                write!(f, " {count:>9}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AddAssign for AdjacencyMatrix {
+    fn add_assign(&mut self, other: Self) {
+        for (row, other_row) in self.counts.iter_mut().zip(other.counts) {
+            for (count, other_count) in row.iter_mut().zip(other_row) {
+                *count += other_count;
+            }
+        }
+    }
+}
+
+impl Add for AdjacencyMatrix {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl Sum for AdjacencyMatrix {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(AdjacencyMatrix::default(), Add::add)
+    }
+}
+
+/// Count the terrain pairs of all neighboring tiles of a `width` x `height` odd-r grid in one pass.
+/// Every tile only looks at its E, SE and SW neighbors, so each pair of neighbors is visited once.
+pub fn terrain_adjacency_matrix(terrain: &[Terrain], width: usize, height: usize) -> AdjacencyMatrix {
+    debug_assert_eq!(terrain.len(), width * height);
+    let mut matrix = AdjacencyMatrix::default();
+    for y in 0..height {
+        // odd rows are shifted right by half a tile
+        let shift = y & 1;
+        for x in 0..width {
+            let a = terrain_index(terrain[y * width + x]);
+            let mut count = |nidx: usize| {
+                let b = terrain_index(terrain[nidx]);
+                matrix.counts[a][b] += 1;
+                matrix.counts[b][a] += 1;
+            };
+            if x + 1 < width {
+                count(y * width + x + 1);
+            }
+            if y + 1 < height {
+                if x + shift < width {
+                    count((y + 1) * width + x + shift);
+                }
+                if x + shift >= 1 {
+                    count((y + 1) * width + x + shift - 1);
+                }
+            }
+        }
+    }
+    matrix
+}
+
+fn terrain_index(terrain: Terrain) -> usize {
+    Terrain::ALL.iter().position(|t| *t == terrain).unwrap_or(0)
+}
+
+/// Hex steps around a tile whose terrains count towards its diversity, see `interest_point`
+pub const DIVERSITY_RADIUS: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Where a camera should start looking, see `interest_point`
+pub struct InterestPoint {
+    /// Row-major index of the tile to look at, always a tile of the largest landmass
+    pub tile: usize,
+    /// Distance from that tile in world units (see `HexCoord::world_pos`) that frames the whole landmass
+    pub radius: f32,
+}
+
+/// Starting point of the camera: the tile of the largest landmass closest to its centroid, moved halfway
+/// towards the most diverse spot of the landmass (highest entropy of the land terrains within `DIVERSITY_RADIUS`)
+/// within half the framing radius. The radius is half the diagonal of the landmass bounding box plus a hex,
+/// in world units. `landmass_ids` are the ids of `label_landmasses`, the largest landmass is the one with the
+/// most tiles (the smallest id on ties). None if there is no land.
+pub fn interest_point(
+    terrain: &[Terrain],
+    landmass_ids: &[Option<usize>],
+    width: usize,
+    height: usize,
+) -> Option<InterestPoint> {
+    debug_assert_eq!(terrain.len(), width * height);
+    let mut sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    for id in landmass_ids.iter().flatten() {
+        *sizes.entry(*id).or_default() += 1;
+    }
+    let largest = sizes.iter().max_by_key(|&(id, size)| (*size, Reverse(*id))).map(|(id, _)| *id)?;
+    let land: Vec<usize> = (0..landmass_ids.len()).filter(|&idx| landmass_ids[idx] == Some(largest)).collect();
+    let hex = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+
+    let n = land.len() as f32;
+    let (mut min_x, mut min_z, mut max_x, mut max_z) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    let (mut sum_x, mut sum_z) = (0.0, 0.0);
+    for &idx in &land {
+        let (x, z) = hex(idx).world_pos();
+        (min_x, min_z, max_x, max_z) = (min_x.min(x), min_z.min(z), max_x.max(x), max_z.max(z));
+        (sum_x, sum_z) = (sum_x + x, sum_z + z);
+    }
+    let radius = (max_x - min_x).hypot(max_z - min_z) / 2.0 + 1.0;
+    let closest_to = |(cx, cz): (f32, f32)| {
+        land.iter().copied().min_by(|&a, &b| {
+            let ((ax, az), (bx, bz)) = (hex(a).world_pos(), hex(b).world_pos());
+            let da = (ax - cx).powi(2) + (az - cz).powi(2);
+            let db = (bx - cx).powi(2) + (bz - cz).powi(2);
+            da.total_cmp(&db)
+        })
+    };
+    let center = closest_to((sum_x / n, sum_z / n))?;
+
+    let (center_x, center_z) = hex(center).world_pos();
+    let distance_to_center = |idx: usize| {
+        let (x, z) = hex(idx).world_pos();
+        (x - center_x).hypot(z - center_z)
+    };
+    let diverse = land
+        .iter()
+        .copied()
+        .filter(|&idx| distance_to_center(idx) <= radius / 2.0)
+        .map(|idx| (terrain_entropy(terrain, width, height, hex(idx)), idx))
+        // most diverse, then closest to the center
+        .max_by(|(ea, a), (eb, b)| ea.total_cmp(eb).then(distance_to_center(*b).total_cmp(&distance_to_center(*a))))
+        .map_or(center, |(_, idx)| idx);
+
+    let (diverse_x, diverse_z) = hex(diverse).world_pos();
+    let tile = closest_to(((center_x + diverse_x) / 2.0, (center_z + diverse_z) / 2.0))?;
+    Some(InterestPoint { tile, radius })
+}
+
+/// Shannon entropy (bits) of the terrains of the land tiles within `DIVERSITY_RADIUS` steps of `center`.
+/// Water is left out, otherwise every coast would look diverse
+fn terrain_entropy(terrain: &[Terrain], width: usize, height: usize, center: HexCoord) -> f32 {
+    let reach = DIVERSITY_RADIUS as i32;
+    let mut counts = [0u32; Terrain::ALL.len()];
+    let mut total = 0u32;
+    for y in (center.y() - reach).max(0)..=(center.y() + reach).min(height as i32 - 1) {
+        for x in (center.x() - reach - 1).max(0)..=(center.x() + reach + 1).min(width as i32 - 1) {
+            let tile = terrain[y as usize * width + x as usize];
+            if !tile.is_water() && center.distance(&HexCoord::new(x, y)) <= DIVERSITY_RADIUS {
+                counts[terrain_index(tile)] += 1;
+                total += 1;
+            }
+        }
+    }
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f32 / total as f32;
+            -p * p.log2()
+        })
+        .sum()
+}
@@ -0,0 +1,47 @@
+use crate::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::helpers::{AppealConfig, neighbors_odd_r},
+};
+
+/// Compute the appeal of every tile from the tile itself and its neighbors (Civ style).
+/// A land tile starts at the appeal of its terrain and feature (`Terrain::default_appeal`, `Feature::appeal_bonus`),
+/// each adjacent mountain, coast, woods, marsh, floodplains or desert adds the configured amount,
+/// the sum is clamped to `[config.min, config.max]`. Water tiles are neutral (0).
+pub fn compute_appeal(
+    terrain: &[Terrain],
+    features: &[Option<Feature>],
+    width: usize,
+    height: usize,
+    config: &AppealConfig,
+) -> Vec<i8> {
+    (0..width * height)
+        .map(|idx| {
+            if terrain[idx].is_water() {
+                return 0;
+            }
+
+            let own = terrain[idx].default_appeal() + features[idx].map_or(0, |feature| feature.appeal_bonus());
+            let neighbors: i32 = neighbors_odd_r(idx % width, idx / width, width, height)
+                .into_iter()
+                .map(|(nx, ny)| {
+                    let nidx = ny * width + nx;
+                    let terrain_appeal = match terrain[nidx] {
+                        Terrain::Mountain => config.mountain,
+                        Terrain::CoastLake => config.coast,
+                        Terrain::Desert => config.desert,
+                        _ => 0,
+                    };
+                    let feature_appeal = match features[nidx] {
+                        Some(Feature::Woods) => config.woods,
+                        Some(Feature::Marsh) => config.marsh,
+                        Some(Feature::Floodplains) => config.floodplains,
+                        _ => 0,
+                    };
+                    terrain_appeal as i32 + feature_appeal as i32
+                })
+                .sum();
+
+            (own + neighbors).clamp(config.min as i32, config.max as i32) as i8
+        })
+        .collect()
+}
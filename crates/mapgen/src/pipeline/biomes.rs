@@ -4,13 +4,22 @@ use rand_chacha::{
     rand_core::{Rng, SeedableRng},
 };
 
-use std::{collections::VecDeque, f64::consts::PI};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    f64::consts::PI,
+};
 
 use crate::{
-    map_components::terrain::Terrain,
+    map_components::terrain::{DesertVariant, Terrain},
     pipeline::{
-        helpers::{NoiseConfig, biomes_config, neighbors_odd_r},
-        land::generate_landmasses,
+        analysis::{dead_lakes, lake_rim},
+        helpers::{
+            BiomesConfig, ContinentalityConfig, DesertConfig, GeologicalAgeConfig, MirrorAxis, NoiseConfig, SmoothKernel,
+            TemperatureModel, TerrainShareConstraint, TerrainThresholds, WorldAge, biomes_config, landmasses_config,
+            neighbors_odd_r, tile_hash,
+        },
+        error::{GenError, validate_biomes_config},
+        land::{RepairIterationCount, try_generate_landmasses_with_ruggedness},
         map_sizes::MapSizes,
         map_types::MapTypes,
     },
@@ -22,6 +31,7 @@ use crate::{
 fn generate_temperature(seed: u64, size: &MapSizes) -> Vec<u8> {
     let cfg = &biomes_config().temperature;
     let (width, height) = size.dimensions();
+    let (scale_x, scale_y) = noise_scale(size);
 
     // Create a seed specifically for random generation
     // We use continental noise (overall change of temperature) and detail noise for some variation
@@ -51,8 +61,8 @@ fn generate_temperature(seed: u64, size: &MapSizes) -> Vec<u8> {
         for x in 0..width {
             let idx = y * width + x;
 
-            let wx = x as f64 + 0.5 * (y & 1) as f64;
-            let wy = y as f64 * ((3_f64).sqrt() / 2.);
+            let wx = (x as f64 + 0.5 * (y & 1) as f64) * scale_x;
+            let wy = y as f64 * ((3_f64).sqrt() / 2.) * scale_y;
 
             // Sample noise and add to eachother (70/30 split)
             let n_cont = cont.get([wx, wy]);
@@ -72,6 +82,73 @@ fn generate_temperature(seed: u64, size: &MapSizes) -> Vec<u8> {
     out
 }
 
+/// Use a seed to generate a temperature distribution on a spherical latitude model.
+/// The base temperature follows the annual mean insolation of a sphere with the configured axial tilt.
+/// The continental noise shifts the thermal equator (peak offset), the detail noise adds local variation.
+fn generate_temperature_v2(seed: u64, size: &MapSizes) -> Vec<u8> {
+    let cfg = &biomes_config().temperature;
+    let (width, height) = size.dimensions();
+    let (scale_x, scale_y) = noise_scale(size);
+
+    // Same seeding as the simple model so both models share their noise fields
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let noise_seed_cont = rng.next_u64();
+    let noise_seed_det = rng.next_u64();
+
+    let cont = Fbm::<OpenSimplex>::new(noise_seed_cont as u32)
+        .set_octaves(cfg.continental_octaves)
+        .set_frequency(1.0 / cfg.continental_scale);
+    let det = Fbm::<OpenSimplex>::new(noise_seed_det as u32)
+        .set_octaves(cfg.detail_octaves)
+        .set_frequency(1.0 / cfg.detail_scale);
+
+    // Second order legendre approximation of the annual mean insolation (North, 1975):
+    // s(lat) = 1 - 5/8 * P2(cos(tilt)) * P2(sin(lat))
+    let p2 = |v: f64| (3.0 * v * v - 1.0) * 0.5;
+    let tilt = cfg.axial_tilt_degrees.to_radians();
+    let s2 = 5.0 / 8.0 * p2(tilt.cos());
+    let insolation = |lat: f64| 1.0 - s2 * p2(lat.sin());
+
+    // Normalize against an untilted planet (equator 1.3125, poles 0.375) so the tilt flattens the gradient
+    let (s_min, s_max) = (0.375, 1.3125);
+
+    let mut out = vec![0u8; width * height];
+
+    for y in 0..height {
+        let lat = if height <= 1 {
+            0.5
+        } else {
+            y as f64 / (height as f64 - 1.0)
+        };
+        // latitude in radians from -pi/2 (north) to pi/2 (south)
+        let phi = (lat - 0.5) * PI;
+
+        for x in 0..width {
+            let idx = y * width + x;
+
+            let wx = (x as f64 + 0.5 * (y & 1) as f64) * scale_x;
+            let wy = y as f64 * ((3_f64).sqrt() / 2.) * scale_y;
+
+            let n_cont = cont.get([wx, wy]);
+            let n_det = det.get([wx, wy]);
+
+            // continental noise moves the peak of the insolation curve north/south
+            let shifted = (phi + cfg.continental_weight * cfg.base_amplitude * n_cont * PI)
+                .clamp(-PI / 2.0, PI / 2.0);
+            let base = ((insolation(shifted) - s_min) / (s_max - s_min)).clamp(0.0, 1.0);
+
+            // blend continental and detail noise like the simple model (70/30 split)
+            let noise = cfg.continental_weight * n_cont + cfg.detail_weight * n_det;
+            let amp = cfg.base_amplitude * (cfg.latitude_amp_floor + 0.5 * base);
+            let temp = (base + amp * noise).clamp(0.0, 1.0);
+
+            out[idx] = (temp * 255.0).round() as u8;
+        }
+    }
+
+    out
+}
+
 /// Generate a random simplex noise scaled to [0, 255]
 /// Used for rainfall and heightmap.
 fn generate_random_255(seed: u64, size: &MapSizes, noise_config: &NoiseConfig) -> Vec<u8> {
@@ -85,6 +162,7 @@ fn generate_random_255(seed: u64, size: &MapSizes, noise_config: &NoiseConfig) -
 
     let mut temp = vec![0u8; size.grid_size()];
     let (width, height) = size.dimensions();
+    let (scale_x, scale_y) = noise_scale(size);
 
     for y in 0..height {
         for x in 0..width {
@@ -93,8 +171,8 @@ fn generate_random_255(seed: u64, size: &MapSizes, noise_config: &NoiseConfig) -
             // using odd r hexes, we need to perform shifts
             // x shiftrs 0.5 on odd rows
             // y shifts by sqrt(3)/2
-            let wx = x as f64 + 0.5 * (y & 1) as f64;
-            let wy = y as f64 * ((3_f64).sqrt() / 2.);
+            let wx = (x as f64 + 0.5 * (y & 1) as f64) * scale_x;
+            let wy = y as f64 * ((3_f64).sqrt() / 2.) * scale_y;
 
             // sample noise
             // scale from [-1.0, 1.0] to [0, 255]
@@ -106,19 +184,84 @@ fn generate_random_255(seed: u64, size: &MapSizes, noise_config: &NoiseConfig) -
         }
     }
 
+    smooth_noise_layer(
+        &mut temp,
+        size,
+        noise_config.post_smooth_passes,
+        noise_config.post_smooth_kernel,
+    );
     temp
 }
 
+/// Factors for the noise sample coordinates: 1 normally, the size of a Standard map relative to this map
+/// with `scale_invariant`, so the noise fields stretch with the map instead of repeating in tile units
+fn noise_scale(size: &MapSizes) -> (f64, f64) {
+    if !landmasses_config().global.scale_invariant {
+        return (1.0, 1.0);
+    }
+    let (width, height) = size.dimensions();
+    let (reference_width, reference_height) = MapSizes::Standard.dimensions();
+    (reference_width as f64 / width as f64, reference_height as f64 / height as f64)
+}
+
+/// Replace every value of a noise layer with the `kernel` over itself and its neighbors, `passes` times.
+pub fn smooth_noise_layer(values: &mut [u8], size: &MapSizes, passes: usize, kernel: SmoothKernel) {
+    let (width, height) = size.dimensions();
+    for _ in 0..passes {
+        let previous = values.to_vec();
+        for (idx, value) in values.iter_mut().enumerate() {
+            let neighbors = neighbors_odd_r(idx % width, idx / width, width, height);
+            let ring = neighbors.iter().map(|(nx, ny)| previous[ny * width + nx] as u32);
+            *value = match kernel {
+                SmoothKernel::MajorityVote => {
+                    let mut sorted: Vec<u32> = ring.chain([previous[idx] as u32]).collect();
+                    sorted.sort_unstable();
+                    sorted[sorted.len() / 2] as u8
+                }
+                SmoothKernel::Average => {
+                    let sum = previous[idx] as u32 + ring.sum::<u32>();
+                    (sum as f32 / (neighbors.len() + 1) as f32).round() as u8
+                }
+                SmoothKernel::Gaussian => {
+                    let sum = 2 * previous[idx] as u32 + ring.sum::<u32>();
+                    (sum as f32 / (neighbors.len() + 2) as f32).round() as u8
+                }
+            };
+        }
+    }
+}
+
+/// Blur passes over the heightmap of old worlds
+const OLD_WORLD_BLUR_PASSES: usize = 3;
+
+/// Post-process the heightmap for the world age: young worlds get an unsharp mask and a contrast boost,
+/// so ranges are craggier, old worlds get hex-blurred so the terrain is smoother. Standard keeps the heightmap.
+pub fn apply_world_age(height: &mut [u8], size: &MapSizes, world_age: WorldAge) {
+    match world_age {
+        WorldAge::Standard => {}
+        WorldAge::Young => {
+            let mut blurred = height.to_vec();
+            smooth_noise_layer(&mut blurred, size, 1, SmoothKernel::Average);
+            for (value, blurred) in height.iter_mut().zip(blurred) {
+                let sharpened = 2.0 * *value as f32 - blurred as f32;
+                *value = (128.0 + (sharpened - 128.0) * 1.25).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+        WorldAge::Old => smooth_noise_layer(height, size, OLD_WORLD_BLUR_PASSES, SmoothKernel::Average),
+    }
+}
+
 /// Returns a mask where true is ocean
 /// Assumes landmask of 1 = land & 0 = water.
 /// This will only mark the oceans and the lakes, coastal tiles need to be marked separately
-fn ocean_mask(landmasses: &Vec<u8>, size: &MapSizes) -> Vec<bool> {
+pub(crate) fn ocean_mask(landmasses: &[u8], size: &MapSizes) -> Vec<bool> {
     let (width, height) = size.dimensions();
     let mut ocean = vec![false; size.grid_size()];
     let mut queue = VecDeque::new();
 
     // check north and south corners
     // do not neeed to check east-west since we wrap
+    // (every preset size has at least one row, so `height - 1` does not underflow)
     for x in 0..width {
         for y in [0, height - 1] {
             let idx = y * width + x;
@@ -144,7 +287,7 @@ fn ocean_mask(landmasses: &Vec<u8>, size: &MapSizes) -> Vec<bool> {
 }
 
 /// Function to mark the coastal tiles i.e. "ocean" tiles with at least one land neighbor
-fn coastal_water_mask(landmasses: &[u8], ocean: &[bool], size: &MapSizes) -> Vec<bool> {
+pub(crate) fn coastal_water_mask(landmasses: &[u8], ocean: &[bool], size: &MapSizes) -> Vec<bool> {
     let (width, height) = size.dimensions();
     let mut coast = vec![false; width * height];
 
@@ -170,108 +313,428 @@ fn coastal_water_mask(landmasses: &[u8], ocean: &[bool], size: &MapSizes) -> Vec
     coast
 }
 
-/// Assign terrains based on the landmasses, temperature, rainfall and heightmap
-/// Returns (Vec<Terrain>, Vec<bool>) for terrain and defining hills
-fn assign_terrain(
-    landmasses: &Vec<u8>,
-    temperature: &Vec<u8>,
-    rainfall: &Vec<u8>,
-    heightmap: &Vec<u8>,
+/// Replace the height of every land tile with the mean of itself and its neighbors, `passes` times.
+/// Water tiles keep their height, so the sea floor is never raised.
+pub fn smooth_land_heights(height: &mut [u8], landmasses: &[u8], size: &MapSizes, passes: usize) {
+    let (width, map_height) = size.dimensions();
+    for _ in 0..passes {
+        let previous = height.to_vec();
+        for (idx, value) in height.iter_mut().enumerate() {
+            if landmasses[idx] != 1 {
+                continue;
+            }
+            let neighbors = neighbors_odd_r(idx % width, idx / width, width, map_height);
+            let sum: u32 = previous[idx] as u32
+                + neighbors
+                    .iter()
+                    .map(|(nx, ny)| previous[ny * width + nx] as u32)
+                    .sum::<u32>();
+            *value = (sum as f32 / (neighbors.len() + 1) as f32).round() as u8;
+        }
+    }
+}
+
+/// Make land near the ocean wetter (onshore winds).
+/// Adds `boost * (1 - distance / max_dist)` to every tile closer than `max_dist` to the ocean, clamped to 255.
+/// Ocean tiles have distance 0 in `ocean_dist` and are left alone.
+pub fn blend_rainfall_with_ocean_distance(rainfall: &mut [u8], ocean_dist: &[u16], max_dist: u16, boost: u8) {
+    if max_dist == 0 {
+        return;
+    }
+    for (rain, &dist) in rainfall.iter_mut().zip(ocean_dist) {
+        if dist == 0 || dist >= max_dist {
+            continue;
+        }
+        let added = (boost as f32 * (1.0 - dist as f32 / max_dist as f32)).round() as u8;
+        *rain = rain.saturating_add(added);
+    }
+}
+
+/// Hex distance from every tile to the nearest ocean tile (lakes do not count), u16::MAX without ocean.
+fn ocean_distance(landmasses: &[u8], size: &MapSizes) -> Vec<u16> {
+    let (width, height) = size.dimensions();
+    let ocean = ocean_mask(landmasses, size);
+    let mut dist = vec![u16::MAX; width * height];
+    let mut queue = VecDeque::new();
+    for (idx, is_ocean) in ocean.iter().enumerate() {
+        if *is_ocean {
+            dist[idx] = 0;
+            queue.push_back((idx % width, idx / width));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let d = dist[y * width + x];
+        for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+            let nidx = ny * width + nx;
+            if dist[nidx] == u16::MAX {
+                dist[nidx] = d.saturating_add(1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    dist
+}
+
+/// Geological age per tile (flavor), 0 (young) to 255 (old).
+/// Land far from mountain ridges and coasts is old, land next to them is young, with some noise on top.
+/// Water tiles are 0.
+pub fn generate_geological_age(seed: u64, terrain: &[Terrain], size: &MapSizes, config: &GeologicalAgeConfig) -> Vec<u8> {
+    let is_mountain: Vec<bool> = terrain.iter().map(|t| *t == Terrain::Mountain).collect();
+    let is_water: Vec<bool> = terrain.iter().map(|t| t.is_water()).collect();
+    geological_age_from_masks(seed, &is_mountain, &is_water, size, config)
+}
+
+/// `generate_geological_age` from the mountain and water masks, which is all it reads of the terrain.
+pub(crate) fn geological_age_from_masks(
+    seed: u64,
+    is_mountain: &[bool],
+    is_water: &[bool],
     size: &MapSizes,
-) -> (Vec<Terrain>, Vec<bool>) {
-    let terrain_cfg = &biomes_config().terrain;
-    // *************************
-    // ** Mountains and hills **
-    // *************************
+    config: &GeologicalAgeConfig,
+) -> Vec<u8> {
+    let (width, height) = size.dimensions();
+    let ridge_dist = distance_to(is_mountain, size);
+    let coast_dist = distance_to(is_water, size);
+    let noise = generate_random_255(seed, size, &config.noise);
+
+    let total_weight = config.ridge_weight + config.coast_weight + config.noise_weight;
+    let scaled = |d: u32| (d as f32 / config.distance_scale.max(1.0)).min(1.0);
+    (0..width * height)
+        .map(|idx| {
+            if is_water[idx] || total_weight <= 0.0 {
+                return 0;
+            }
+            let age = config.ridge_weight * scaled(ridge_dist[idx])
+                + config.coast_weight * scaled(coast_dist[idx])
+                + config.noise_weight * noise[idx] as f32 / 255.0;
+            (age / total_weight * 255.0).round() as u8
+        })
+        .collect()
+}
+
+/// Sub-variant of every desert tile, None for all other terrain.
+/// Low-frequency noise splits deserts into large patches of sand dunes and rocky desert.
+pub fn generate_desert_variants(seed: u64, terrain: &[Terrain], size: &MapSizes, config: &DesertConfig) -> Vec<Option<DesertVariant>> {
+    let noise = generate_desert_variant_noise(seed, size, config);
+    terrain
+        .iter()
+        .zip(noise)
+        .map(|(t, value)| desert_variant(*t, value, config))
+        .collect()
+}
+
+pub(crate) fn generate_desert_variant_noise(seed: u64, size: &MapSizes, config: &DesertConfig) -> Vec<u8> {
+    generate_random_255(seed, size, &config.variant_noise)
+}
+
+/// Sub-variant of a tile from its terrain and desert variant noise
+pub(crate) fn desert_variant(terrain: Terrain, noise: u8, config: &DesertConfig) -> Option<DesertVariant> {
+    match terrain {
+        Terrain::Desert if noise >= config.rocky_threshold => Some(DesertVariant::RockyDesert),
+        Terrain::Desert => Some(DesertVariant::SandDunes),
+        _ => None,
+    }
+}
+
+/// Hex distance from every tile to the nearest source tile (u32::MAX without any source).
+fn distance_to(is_source: &[bool], size: &MapSizes) -> Vec<u32> {
+    let (width, height) = size.dimensions();
+    let mut dist = vec![u32::MAX; width * height];
+    let mut queue = VecDeque::new();
+    for (idx, source) in is_source.iter().enumerate() {
+        if *source {
+            dist[idx] = 0;
+            queue.push_back((idx % width, idx / width));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let d = dist[y * width + x];
+        for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+            let nidx = ny * width + nx;
+            if dist[nidx] == u32::MAX {
+                dist[nidx] = d + 1;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    dist
+}
+
+/// Seasonal temperature swing per tile (continentality).
+/// Low near the ocean (maritime), high deep inside large landmasses (continental).
+/// The swing grows with the distance to the ocean and is scaled down on small landmasses.
+/// Water tiles get the maritime range.
+pub fn generate_temperature_range(landmasses: &[u8], size: &MapSizes, config: &ContinentalityConfig) -> Vec<u8> {
+    let (width, height) = size.dimensions();
+    let ocean = ocean_mask(landmasses, size);
+
+    // distance from every land tile to the ocean
+    let mut dist = vec![u32::MAX; width * height];
+    let mut queue = VecDeque::new();
+    for (idx, is_ocean) in ocean.iter().enumerate() {
+        if *is_ocean {
+            dist[idx] = 0;
+            queue.push_back((idx % width, idx / width));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let d = dist[y * width + x];
+        for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+            let nidx = ny * width + nx;
+            if dist[nidx] == u32::MAX {
+                dist[nidx] = d + 1;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
 
-    // Create histogram of heights (only on land)
-    let mut height_histogram = [0u32; 256];
-    let mut land_count = 0;
-    for (i, _) in landmasses.iter().enumerate() {
-        if landmasses[i] == 1 {
-            height_histogram[heightmap[i] as usize] += 1;
-            land_count += 1;
+    // size of the landmass every land tile belongs to
+    let mut landmass_size = vec![0usize; width * height];
+    let mut visited = vec![false; width * height];
+    let mut component = Vec::new();
+    for start in 0..width * height {
+        if landmasses[start] != 1 || visited[start] {
+            continue;
+        }
+
+        component.clear();
+        visited[start] = true;
+        queue.push_back((start % width, start / width));
+        while let Some((x, y)) = queue.pop_front() {
+            component.push(y * width + x);
+            for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+                let nidx = ny * width + nx;
+                if landmasses[nidx] == 1 && !visited[nidx] {
+                    visited[nidx] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        for &idx in &component {
+            landmass_size[idx] = component.len();
         }
     }
 
-    // Use the histogram to find the top 5% of heights for the mountains
-    // The remaining top 20% of heights are for hills
-    let cutoff_mountains = f32::ceil(land_count as f32 * terrain_cfg.mountain_threshold) as u32;
-    let mut k_mountains: Option<u8> = None;
-    let cutoff_hills = f32::ceil(
-        land_count as f32 * (terrain_cfg.mountain_threshold + terrain_cfg.hill_threshold),
-    ) as u32;
-    let mut k_hills: Option<u8> = None;
+    let maritime = config.maritime_range as f32;
+    let span = config.continental_range as f32 - maritime;
+    (0..width * height)
+        .map(|idx| {
+            if landmasses[idx] != 1 {
+                return config.maritime_range;
+            }
+            // dist is at least 1 for land, coastal land is still maritime
+            debug_assert!(dist[idx] >= 1);
+            let distance_factor = ((dist[idx] - 1) as f32 / config.distance_scale.max(1.0)).min(1.0);
+            let size_factor = (landmass_size[idx] as f32 / config.size_scale.max(1.0)).min(1.0);
+            (maritime + span * distance_factor * size_factor).round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+/// Decide whether a temperature falls on the cold side of a threshold.
+/// Inside `window` around the threshold the decision is dithered per tile: the closer to the cold edge,
+/// the more likely the cold side, nudged towards cold for higher elevations.
+fn is_below_threshold(t: u8, threshold: u8, window: u8, elevation: u8, elevation_bias: f32, hash: u64) -> bool {
+    if window == 0 {
+        return t <= threshold;
+    }
+
+    let lo = threshold as i32 - window as i32;
+    let hi = threshold as i32 + window as i32;
+    let t = t as i32;
+    if t < lo {
+        return true;
+    }
+    if t > hi {
+        return false;
+    }
+
+    let p_cold = (hi - t) as f32 / (hi - lo) as f32;
+    let p_cold = (p_cold + elevation_bias * (elevation as f32 / 255.0 - 0.5)).clamp(0.0, 1.0);
+    let roll = (hash >> 11) as f32 / (1u64 << 53) as f32;
+    roll < p_cold
+}
 
-    let mut count = 0;
+/// Map-wide inputs of the terrain assignment: the mountain and hill height cutoffs and the water masks.
+/// With these every tile is assigned on its own (see `assign_tile`), so parts of the map can be assigned lazily.
+pub(crate) struct TerrainRules {
+    seed: u64,
+    k_mountains: u8,
+    k_hills: u8,
+    ocean_mask: Vec<bool>,
+    coast_mask: Vec<bool>,
+    thresholds: TerrainThresholds,
+}
 
-    for (index, occurrence) in height_histogram.iter().enumerate().rev() {
-        count += occurrence;
+impl TerrainRules {
+    pub(crate) fn new(
+        seed: u64,
+        landmasses: &[u8],
+        heightmap: &[u8],
+        size: &MapSizes,
+        thresholds: &TerrainThresholds,
+    ) -> Self {
+        let terrain_cfg = thresholds;
+        // *************************
+        // ** Mountains and hills **
+        // *************************
+
+        // Create histogram of heights (only on land)
+        let mut height_histogram = [0u32; 256];
+        let mut land_count = 0u32;
+        for (i, _) in landmasses.iter().enumerate() {
+            if landmasses[i] == 1 {
+                height_histogram[heightmap[i] as usize] += 1;
+                land_count += 1;
+            }
+        }
 
-        if k_mountains.is_none() && count >= cutoff_mountains {
-            k_mountains = Some(index as u8);
+        // Use the histogram to find the top 5% of heights for the mountains
+        // The remaining top 20% of heights are for hills
+        // The cutoffs are clamped to the land count, thresholds adding up to 1 can round above it
+        // The world age scales both shares, the heightmap post-processing alone does not move percentile cutoffs
+        let (mountain_threshold, hill_threshold) = biomes_config()
+            .world_age
+            .scale_thresholds(terrain_cfg.mountain_threshold, terrain_cfg.hill_threshold);
+        let cutoff_mountains = (f32::ceil(land_count as f32 * mountain_threshold) as u32).min(land_count);
+        let mut k_mountains: Option<u8> = None;
+        let cutoff_hills = (f32::ceil(
+            land_count as f32 * (mountain_threshold + hill_threshold),
+        ) as u32)
+            .min(land_count);
+        let mut k_hills: Option<u8> = None;
+
+        let mut count = 0;
+
+        for (index, occurrence) in height_histogram.iter().enumerate().rev() {
+            count += occurrence;
+
+            if k_mountains.is_none() && count >= cutoff_mountains {
+                k_mountains = Some(index as u8);
+            }
+
+            if k_hills.is_none() && count >= cutoff_hills {
+                k_hills = Some(index as u8);
+                break;
+            }
         }
 
-        if k_hills.is_none() && count >= cutoff_hills {
-            k_hills = Some(index as u8);
-            break;
+        // **********************
+        // ** Oceans and lakes **
+        // **********************
+
+        let ocean_mask = ocean_mask(landmasses, size);
+        let coast_mask = coastal_water_mask(landmasses, &ocean_mask, size);
+
+        // The running count reaches `land_count` at height 0 at the latest, so both cutoffs are always found
+        debug_assert!(k_mountains.is_some() && k_hills.is_some());
+        TerrainRules {
+            seed,
+            k_mountains: k_mountains.unwrap_or(0),
+            k_hills: k_hills.unwrap_or(0),
+            ocean_mask,
+            coast_mask,
+            thresholds: thresholds.clone(),
         }
     }
 
-    let k_mountains = k_mountains.expect("did not find mountain threshold");
-    let k_hills = k_hills.expect("did not find hill threshold");
+    /// Rules with the thresholds of `config`, nudged until the land meets its biome constraints (see `BiomeConstraints`).
+    /// Every adjustment only re-runs the terrain assignment, the layers stay the same.
+    /// Returns the outcome of the constraint pass, None if the config has no biome constraints.
+    pub(crate) fn constrained(
+        seed: u64,
+        layers: &BaseLayers,
+        size: &MapSizes,
+        config: &BiomesConfig,
+    ) -> (Self, Option<BiomeConstraintReport>) {
+        let mut thresholds = config.terrain.clone();
+        let mut rules = Self::new(seed, &layers.landmask, &layers.height, size, &thresholds);
+        let constraints = &config.biome_constraints;
+        if constraints.is_empty() {
+            return (rules, None);
+        }
+
+        let mut adjustments = 0;
+        loop {
+            let shares = rules.land_shares(layers);
+            let unmet: Vec<(TerrainShareConstraint, f32)> = constraints
+                .terrains
+                .iter()
+                .map(|constraint| (*constraint, shares.get(&constraint.terrain).copied().unwrap_or(0.0)))
+                .filter(|(constraint, share)| {
+                    constraint.min.is_some_and(|min| *share < min) || constraint.max.is_some_and(|max| *share > max)
+                })
+                .collect();
+            if unmet.is_empty() || adjustments == constraints.max_adjustments {
+                return (rules, Some(BiomeConstraintReport { adjustments, unmet }));
+            }
 
-    // **********************
-    // ** Oceans and lakes **
-    // **********************
+            for (constraint, share) in &unmet {
+                let too_much = constraint.max.is_some_and(|max| *share > max);
+                nudge_thresholds(&mut thresholds, constraint.terrain, too_much);
+            }
+            adjustments += 1;
+            rules = Self::new(seed, &layers.landmask, &layers.height, size, &thresholds);
+        }
+    }
 
-    let ocean_mask = ocean_mask(&landmasses, &size);
-    let coast_mask = coastal_water_mask(&landmasses, &ocean_mask, &size);
+    /// Share of the land every land terrain covers with these rules
+    fn land_shares(&self, layers: &BaseLayers) -> BTreeMap<Terrain, f32> {
+        let mut counts: BTreeMap<Terrain, usize> = BTreeMap::new();
+        let mut land_count = 0;
+        for i in 0..layers.landmask.len() {
+            if layers.landmask[i] == 1 {
+                let (terrain, _) = self.assign_tile(i, 1, layers.temperature[i], layers.rainfall[i], layers.height[i]);
+                *counts.entry(terrain).or_default() += 1;
+                land_count += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(terrain, count)| (terrain, count as f32 / land_count.max(1) as f32))
+            .collect()
+    }
 
-    // **************
-    // ** Terrains **
-    // **************
+    /// Whether a land tile of this height becomes a mountain
+    pub(crate) fn is_mountain(&self, height: u8) -> bool {
+        height >= self.k_mountains
+    }
 
-    let n = size.grid_size();
-    let mut terrain_vec = Vec::with_capacity(n);
-    let mut hill_vec = Vec::with_capacity(n);
+    /// Terrain and hill of tile `i` from its landmask, temperature, rainfall and height values
+    pub(crate) fn assign_tile(&self, i: usize, land: u8, t: u8, r: u8, h: u8) -> (Terrain, bool) {
 
-    for i in 0..n {
-        let l = landmasses[i];
-        
         //water
-        if l == 0{
-            let o = ocean_mask[i];
-
-            let is_coast = coast_mask[i];
-            let is_lake = !o;
-
-            terrain_vec.push(if is_lake || is_coast {
-                Terrain::CoastLake
-            } else {
-                Terrain::Ocean
-            });
-            hill_vec.push(false);
-            continue;
+        if land == 0 {
+            let is_coast = self.coast_mask[i];
+            let is_lake = !self.ocean_mask[i];
+            let terrain = if is_lake || is_coast { Terrain::CoastLake } else { Terrain::Ocean };
+            return (terrain, false);
         }
 
         // hills
-        let h = heightmap[i];
-        if h >= k_mountains {
-            terrain_vec.push(Terrain::Mountain);
-            hill_vec.push(false);
-            continue;
+        if self.is_mountain(h) {
+            return (Terrain::Mountain, false);
         }
+        (self.land_terrain(i, t, r, h), h >= self.k_hills)
+    }
 
-        let is_hill = h >= k_hills;
-        hill_vec.push(is_hill);
-
-
-        let t = temperature[i];
-        let r = rainfall[i];
-        let terrain = if t <= terrain_cfg.snow_temp_threshold {
+    /// Base terrain of land tile `i` that is not a mountain, from its temperature, rainfall and height values
+    pub(crate) fn land_terrain(&self, i: usize, t: u8, r: u8, h: u8) -> Terrain {
+        let terrain_cfg = &self.thresholds;
+        let hash = tile_hash(self.seed, i);
+        let below = |threshold: u8| {
+            is_below_threshold(
+                t,
+                threshold,
+                terrain_cfg.threshold_dither_window,
+                h,
+                terrain_cfg.dither_elevation_bias,
+                hash,
+            )
+        };
+        if below(terrain_cfg.snow_temp_threshold) {
             Terrain::Snow
-        } else if t <= terrain_cfg.tundra_temp_threshold {
+        } else if below(terrain_cfg.tundra_temp_threshold) {
             Terrain::Tundra
         } else if t >= terrain_cfg.desert_temp_threshold && r <= terrain_cfg.desert_rain_threshold
         {
@@ -280,43 +743,274 @@ fn assign_terrain(
             Terrain::Grassland
         } else {
             Terrain::Plains
-        };
+        }
+    }
+}
 
-        terrain_vec.push(terrain);
+/// Step by which a climate threshold is nudged per biome constraint adjustment
+const THRESHOLD_NUDGE: u8 = 8;
+/// Factor by which the mountain threshold is nudged per biome constraint adjustment
+const MOUNTAIN_NUDGE: f32 = 1.25;
+
+/// Move the thresholds that decide `terrain` so it covers less land (`too_much`) or more.
+/// Plains are what is left over, so they are nudged through the grassland threshold.
+fn nudge_thresholds(thresholds: &mut TerrainThresholds, terrain: Terrain, too_much: bool) {
+    let nudge = |value: &mut u8, up: bool| {
+        *value = if up { value.saturating_add(THRESHOLD_NUDGE) } else { value.saturating_sub(THRESHOLD_NUDGE) };
+    };
+    match terrain {
+        Terrain::Desert => {
+            nudge(&mut thresholds.desert_rain_threshold, !too_much);
+            nudge(&mut thresholds.desert_temp_threshold, too_much);
+        }
+        Terrain::Grassland => nudge(&mut thresholds.grassland_rain_threshold, too_much),
+        Terrain::Plains => nudge(&mut thresholds.grassland_rain_threshold, !too_much),
+        Terrain::Tundra => nudge(&mut thresholds.tundra_temp_threshold, !too_much),
+        Terrain::Snow => nudge(&mut thresholds.snow_temp_threshold, !too_much),
+        Terrain::Mountain => {
+            let factor = if too_much { 1.0 / MOUNTAIN_NUDGE } else { MOUNTAIN_NUDGE };
+            thresholds.mountain_threshold = (thresholds.mountain_threshold * factor).min(1.0 - thresholds.hill_threshold);
+        }
+        // rejected by `validate_biomes_config`
+        Terrain::CoastLake | Terrain::Ocean => {}
+    }
+}
 
+#[derive(Debug, Clone, PartialEq)]
+/// Outcome of the biome constraint pass, see `BiomeConstraints`
+pub struct BiomeConstraintReport {
+    /// Number of times the thresholds were nudged
+    pub adjustments: usize,
+    /// Constraints still missed after the last adjustment, with the share of the land their terrain covers
+    pub unmet: Vec<(TerrainShareConstraint, f32)>,
+}
+
+impl BiomeConstraintReport {
+    /// Whether every biome constraint holds
+    pub fn is_met(&self) -> bool {
+        self.unmet.is_empty()
+    }
+}
 
+/// Demote the lowest mountain on the rim of every dead lake (see `dead_lakes`) to a hill, so the lake can be reached.
+/// Ties are broken by the lower tile index. `base_terrain` gives the terrain under the new hill.
+/// Returns the indices of the demoted tiles, nothing changes if there are no dead lakes.
+pub fn open_dead_lakes(
+    terrain: &mut [Terrain],
+    hills: &mut [bool],
+    heightmap: &[u8],
+    size: &MapSizes,
+    base_terrain: impl Fn(usize) -> Terrain,
+) -> Vec<usize> {
+    let (width, height) = size.dimensions();
+    let mut opened = Vec::new();
+    for lake in dead_lakes(terrain, width, height) {
+        let rim = lake_rim(&lake, terrain, width, height);
+        let Some(&lowest) = rim.iter().min_by_key(|&&idx| (heightmap[idx], idx)) else {
+            continue;
+        };
+        terrain[lowest] = base_terrain(lowest);
+        hills[lowest] = true;
+        opened.push(lowest);
     }
+    opened
+}
+
+/// Assign terrains based on the landmasses, temperature, rainfall and heightmap
+/// Returns (Vec<Terrain>, Vec<bool>) for terrain and defining hills
+fn assign_terrain(rules: &TerrainRules, layers: &BaseLayers, size: &MapSizes) -> (Vec<Terrain>, Vec<bool>) {
+    (0..size.grid_size())
+        .map(|i| rules.assign_tile(i, layers.landmask[i], layers.temperature[i], layers.rainfall[i], layers.height[i]))
+        .unzip()
+}
 
-    (terrain_vec, hill_vec)
+/// All per-tile layers produced by map generation, in row-major order.
+pub struct MapLayers {
+    pub terrain: Vec<Terrain>,
+    pub height: Vec<u8>,
+    pub hills: Vec<bool>,
+    pub temperature: Vec<u8>,
+    pub rainfall: Vec<u8>,
+    /// Seasonal temperature swing (continentality)
+    pub temperature_range: Vec<u8>,
+    /// Flavor age of the land, 0 (young) to 255 (old), water is 0
+    pub geological_age: Vec<u8>,
+    /// Sub-variant of every desert tile, None for all other terrain
+    pub desert_variants: Vec<Option<DesertVariant>>,
+    /// Landmask after repair (1 = land, 0 = water)
+    pub landmask: Vec<u8>,
+    /// Number of repair passes the landmass generation needed
+    pub repair_iterations: RepairIterationCount,
+    /// Coast ruggedness the coast pass applied to the landmass of every land tile,
+    /// None where it did not run (mirror maps, strength 0), see `vary_coast_ruggedness`
+    pub coast_ruggedness: Vec<Option<f32>>,
+    /// Outcome of the biome constraint pass, None without biome constraints
+    pub biome_constraints: Option<BiomeConstraintReport>,
 }
 
 /// Creates landmasses, temperature, rainfall, height and ocean masks for the map.
 /// Assigns the respective terrains to each tile
-/// Returns a vec for the terrain, height, hills, temperatire and rain
-pub fn generate_map(seed: &u64, size: &MapSizes) -> (Vec<Terrain>, Vec<u8>, Vec<bool>, Vec<u8>, Vec<u8>) {
+/// Returns all generated layers
+pub fn generate_map(seed: &u64, size: &MapSizes) -> MapLayers {
     generate_map_with_type(seed, size, MapTypes::Continents)
 }
 
-/// Same as `generate_map` but allows selecting the landmass map type.
-pub fn generate_map_with_type(
-    seed: &u64,
-    size: &MapSizes,
-    map_type: MapTypes,
-) -> (Vec<Terrain>, Vec<u8>, Vec<bool>, Vec<u8>, Vec<u8>) {
+/// Copy the left half of a layer onto the right half and/or the top half onto the bottom half, following the mirror axis.
+pub(crate) fn mirror_layer<T: Copy>(values: &mut [T], width: usize, height: usize, axis: MirrorAxis) {
+    if axis.mirrors_columns() {
+        for y in 0..height {
+            for x in 0..width / 2 {
+                values[y * width + (width - 1 - x)] = values[y * width + x];
+            }
+        }
+    }
+    if axis.mirrors_rows() {
+        for y in 0..height / 2 {
+            values.copy_within(y * width..(y + 1) * width, (height - 1 - y) * width);
+        }
+    }
+}
+
+/// Mirror the terrain like `mirror_layer`.
+/// Odd-r neighbors are not symmetric, so the coast can differ between the halves.
+/// A water tile is coast if either side is, so no land borders the ocean directly.
+fn mirror_terrain(terrain: &mut [Terrain], width: usize, height: usize, axis: MirrorAxis) {
+    let mut mirror_pair = |left: usize, right: usize| {
+        if terrain[left].is_water() && terrain[right] == Terrain::CoastLake {
+            terrain[left] = Terrain::CoastLake;
+        }
+        terrain[right] = terrain[left];
+    };
+    if axis.mirrors_columns() {
+        for y in 0..height {
+            for x in 0..width / 2 {
+                mirror_pair(y * width + x, y * width + (width - 1 - x));
+            }
+        }
+    }
+    if axis.mirrors_rows() {
+        for y in 0..height / 2 {
+            for x in 0..width {
+                mirror_pair(y * width + x, (height - 1 - y) * width + x);
+            }
+        }
+    }
+}
+
+/// Layers that are cheap to generate for the whole map: the landmask, climate and heightmap,
+/// already mirrored for mirror maps that mirror their climate.
+pub(crate) struct BaseLayers {
+    pub(crate) landmask: Vec<u8>,
+    pub(crate) repair_iterations: RepairIterationCount,
+    /// Coast ruggedness applied to the landmass of every land tile, see `MapLayers::coast_ruggedness`
+    pub(crate) coast_ruggedness: Vec<Option<f32>>,
+    pub(crate) temperature: Vec<u8>,
+    pub(crate) rainfall: Vec<u8>,
+    pub(crate) height: Vec<u8>,
+    /// Axis the terrain has to be mirrored along, None if the map is not mirrored
+    pub(crate) mirror_axis: Option<MirrorAxis>,
+}
+
+/// Generate the landmasses, temperature, rainfall and heightmap, see `BaseLayers`.
+/// Fails if the biome or landmass config can not produce a map of this type.
+pub(crate) fn generate_base_layers(seed: u64, size: &MapSizes, map_type: MapTypes) -> Result<BaseLayers, GenError> {
     let config = biomes_config();
-    let land_seed = seed.clone();
-    let land = generate_landmasses(land_seed, size, map_type);
+    validate_biomes_config(&config)?;
+    let land_seed = seed;
+    let (land, repair_iterations, coast_ruggedness) =
+        try_generate_landmasses_with_ruggedness(land_seed, size, map_type)?;
 
     let temp_seed = seed + 1;
-    let temp = generate_temperature(temp_seed, size);
+    let mut temp = match config.temperature_model {
+        TemperatureModel::Simple => generate_temperature(temp_seed, size),
+        TemperatureModel::SphericalLat => generate_temperature_v2(temp_seed, size),
+    };
 
     let rain_seed = seed + 2;
-    let rain = generate_random_255(rain_seed, size, &config.rainfall);
+    let mut rain = generate_random_255(rain_seed, size, &config.rainfall.noise);
+    let ocean_dist = ocean_distance(&land, size);
+    blend_rainfall_with_ocean_distance(
+        &mut rain,
+        &ocean_dist,
+        config.rainfall.coastal_max_dist,
+        config.rainfall.coastal_boost,
+    );
 
     let height_seed = seed + 3;
-    let height = generate_random_255(height_seed, size, &config.heightmap);
+    let mut height = generate_random_255(height_seed, size, &config.heightmap);
+    if config.smooth_elevation_before_biome_assign {
+        smooth_land_heights(&mut height, &land, size, 1);
+    }
+    apply_world_age(&mut height, size, config.world_age);
+
+    // The landmask of mirror maps is symmetric, the climate has to be as well for fair biomes
+    let mirror = &landmasses_config().mirror;
+    let mirror_axis = (map_type == MapTypes::Mirror && mirror.mirror_climate).then_some(mirror.axis);
+    let (width, map_height) = size.dimensions();
+    if let Some(axis) = mirror_axis {
+        mirror_layer(&mut temp, width, map_height, axis);
+        mirror_layer(&mut rain, width, map_height, axis);
+        mirror_layer(&mut height, width, map_height, axis);
+    }
+
+    Ok(BaseLayers {
+        landmask: land,
+        repair_iterations,
+        coast_ruggedness,
+        temperature: temp,
+        rainfall: rain,
+        height,
+        mirror_axis,
+    })
+}
+
+/// Same as `generate_map` but allows selecting the landmass map type.
+///
+/// # Panics
+/// If the config is invalid, see `try_generate_map_with_type`. Never for the shipped configs.
+pub fn generate_map_with_type(seed: &u64, size: &MapSizes, map_type: MapTypes) -> MapLayers {
+    try_generate_map_with_type(seed, size, map_type).unwrap_or_else(|err| panic!("{err}"))
+}
 
-    let (terrain_vec, hill_vec) = assign_terrain(&land, &temp, &rain, &height, size);
+/// Same as `generate_map_with_type` but returns an error if the config can not produce a map of this type.
+pub fn try_generate_map_with_type(seed: &u64, size: &MapSizes, map_type: MapTypes) -> Result<MapLayers, GenError> {
+    let config = biomes_config();
+    let base = generate_base_layers(*seed, size, map_type)?;
+    let (width, map_height) = size.dimensions();
+
+    let (rules, biome_constraints) = TerrainRules::constrained(*seed, &base, size, &config);
+    let (mut terrain_vec, mut hill_vec) = assign_terrain(&rules, &base, size);
+    let mut temperature_range = generate_temperature_range(&base.landmask, size, &config.continentality);
+    if let Some(axis) = base.mirror_axis {
+        mirror_terrain(&mut terrain_vec, width, map_height, axis);
+        mirror_layer(&mut hill_vec, width, map_height, axis);
+        mirror_layer(&mut temperature_range, width, map_height, axis);
+    }
+    // Runs before the features, so rivers and floodplains see the opened rims
+    if config.terrain.open_dead_lakes {
+        open_dead_lakes(&mut terrain_vec, &mut hill_vec, &base.height, size, |i| {
+            rules.land_terrain(i, base.temperature[i], base.rainfall[i], base.height[i])
+        });
+    }
+    let mut geological_age = generate_geological_age(seed + 4, &terrain_vec, size, &config.geological_age);
+    let mut desert_variants = generate_desert_variants(seed + 5, &terrain_vec, size, &config.desert);
+    if let Some(axis) = base.mirror_axis {
+        mirror_layer(&mut geological_age, width, map_height, axis);
+        mirror_layer(&mut desert_variants, width, map_height, axis);
+    }
 
-    (terrain_vec, height, hill_vec, temp, rain)
+    Ok(MapLayers {
+        terrain: terrain_vec,
+        height: base.height,
+        hills: hill_vec,
+        temperature: base.temperature,
+        rainfall: base.rainfall,
+        temperature_range,
+        geological_age,
+        desert_variants,
+        landmask: base.landmask,
+        repair_iterations: base.repair_iterations,
+        coast_ruggedness: base.coast_ruggedness,
+        biome_constraints,
+    })
 }
@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+
+use crate::pipeline::{
+    analysis::{label_water_bodies, ocean_connected_water},
+    helpers::neighbors_odd_r,
+    text_map::GeneratedMapData,
+};
+
+/// Longest canal `find_canal_candidates` looks for, longer `max_length` values are capped to it
+pub const MAX_CANAL_LENGTH: usize = 3;
+/// Ocean tiles the sailing distance lower bounds are measured from
+const LANDMARKS: usize = 16;
+/// Candidates per requested one whose sailing distance is measured exactly
+const SHORTLIST_FACTOR: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Land tiles whose conversion to water would open a sea route, see `find_canal_candidates`
+pub struct CanalCandidate {
+    /// Row-major land tiles of the canal from one coast to the other
+    pub tiles: Vec<usize>,
+    /// Water bodies (`label_water_bodies` ids) on both ends, smaller id first.
+    /// Equal ids mean the canal is a shortcut within one ocean
+    pub oceans_connected: (usize, usize),
+    /// Sailing steps saved between the water tiles on both ends, `u32::MAX` if they were not connected before
+    pub distance_saved: u32,
+}
+
+impl CanalCandidate {
+    /// The canal connects two oceans that had no sea route between them
+    pub fn joins_separate_oceans(&self) -> bool {
+        self.oceans_connected.0 != self.oceans_connected.1
+    }
+}
+
+/// Find the best canal sites: straight or winding paths of 1 to `max_length` land tiles (at most
+/// `MAX_CANAL_LENGTH`) from one ocean coast to another, ranked by the sailing distance they save. Lakes are
+/// not oceans and canals into them are ignored. Returns at most `top_k` candidates that share no tile.
+///
+/// Measuring the sailing distance around every possible canal would need a search over the whole water per
+/// candidate. Instead every candidate is first scored with a lower bound of that distance: the sailing
+/// distances from `LANDMARKS` evenly spread ocean tiles give `|d(a) - d(b)| <= distance(a, b)` by the
+/// triangle inequality. Only the best `SHORTLIST_FACTOR * top_k` candidates by that estimate get an exact
+/// breadth-first search, so the work is bounded by a fixed number of searches over the water plus the
+/// enumeration of the short land paths. A canal whose distance the landmarks underestimate can be missed.
+pub fn find_canal_candidates(data: &GeneratedMapData, max_length: usize, top_k: usize) -> Vec<CanalCandidate> {
+    let (width, height) = (data.width, data.height);
+    let max_length = max_length.clamp(1, MAX_CANAL_LENGTH);
+    if top_k == 0 {
+        return Vec::new();
+    }
+    let ocean = ocean_connected_water(&data.terrain, width, height);
+    let (water_ids, _) = label_water_bodies(&data.terrain, width, height);
+    let neighbors = |idx: usize| {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(move |(x, y)| y * width + x)
+    };
+
+    let ocean_tiles: Vec<usize> = (0..ocean.len()).filter(|&idx| ocean[idx]).collect();
+    let stride = (ocean_tiles.len() / LANDMARKS).max(1);
+    let landmarks: Vec<Vec<u32>> = ocean_tiles
+        .iter()
+        .step_by(stride)
+        .take(LANDMARKS)
+        .map(|&from| sailing_distances(&ocean, from, None, width, height))
+        .collect();
+    let estimate = |a: usize, b: usize| {
+        landmarks
+            .iter()
+            .filter(|dist| dist[a] != u32::MAX && dist[b] != u32::MAX)
+            .map(|dist| dist[a].abs_diff(dist[b]))
+            .max()
+            .unwrap_or(0)
+    };
+
+    // (estimated saving, tiles, water tile at the start, water tile at the end)
+    let mut scored: Vec<(u32, Vec<usize>, usize, usize)> = Vec::new();
+    let mut path = Vec::with_capacity(max_length);
+    let is_land = |idx: usize| !data.terrain[idx].is_water();
+    for start in (0..data.terrain.len()).filter(|&idx| is_land(idx) && neighbors(idx).any(|n| ocean[n])) {
+        path.clear();
+        path.push(start);
+        extend_paths(&mut path, max_length, &is_land, width, height, &mut |tiles| {
+            let end = tiles[tiles.len() - 1];
+            // every path is found from both ends, keep one direction
+            if end < start {
+                return;
+            }
+            let canal_steps = tiles.len() as u32 + 1;
+            let mut best: Option<(u32, usize, usize)> = None;
+            for a in neighbors(start).filter(|&n| ocean[n]) {
+                for b in neighbors(end).filter(|&n| ocean[n]) {
+                    let before = if water_ids[a] == water_ids[b] { estimate(a, b) } else { u32::MAX };
+                    let saving = before.saturating_sub(canal_steps);
+                    if saving > 0 && best.is_none_or(|(best, ..)| saving > best) {
+                        best = Some((saving, a, b));
+                    }
+                }
+            }
+            if let Some((saving, a, b)) = best {
+                scored.push((saving, tiles.to_vec(), a, b));
+            }
+        });
+    }
+    scored.sort_by(|x, y| y.0.cmp(&x.0).then(x.1.len().cmp(&y.1.len())).then_with(|| x.1.cmp(&y.1)));
+
+    let mut used = vec![false; data.terrain.len()];
+    let mut candidates: Vec<CanalCandidate> = Vec::new();
+    for (_, tiles, a, b) in scored {
+        if candidates.len() >= top_k.saturating_mul(SHORTLIST_FACTOR) {
+            break;
+        }
+        if tiles.iter().any(|&idx| used[idx]) {
+            continue;
+        }
+        let distance_saved = if water_ids[a] == water_ids[b] {
+            let before = sailing_distances(&ocean, a, Some(b), width, height)[b];
+            let saved = before.saturating_sub(tiles.len() as u32 + 1);
+            if saved == 0 {
+                continue;
+            }
+            saved
+        } else {
+            u32::MAX
+        };
+        for &idx in &tiles {
+            used[idx] = true;
+        }
+        let (ia, ib) = (water_ids[a].unwrap_or(0), water_ids[b].unwrap_or(0));
+        candidates.push(CanalCandidate {
+            tiles,
+            oceans_connected: (ia.min(ib), ia.max(ib)),
+            distance_saved,
+        });
+    }
+    candidates.sort_by(|x, y| {
+        y.distance_saved
+            .cmp(&x.distance_saved)
+            .then(x.tiles.len().cmp(&y.tiles.len()))
+            .then_with(|| x.tiles.cmp(&y.tiles))
+    });
+    candidates.truncate(top_k);
+    candidates
+}
+
+/// Call `visit` with every simple path of up to `max_length` tiles that starts with `path` and stays on `passable`
+fn extend_paths(
+    path: &mut Vec<usize>,
+    max_length: usize,
+    passable: &impl Fn(usize) -> bool,
+    width: usize,
+    height: usize,
+    visit: &mut impl FnMut(&[usize]),
+) {
+    visit(path);
+    if path.len() >= max_length {
+        return;
+    }
+    let last = path[path.len() - 1];
+    for (nx, ny) in neighbors_odd_r(last % width, last / width, width, height) {
+        let next = ny * width + nx;
+        if passable(next) && !path.contains(&next) {
+            path.push(next);
+            extend_paths(path, max_length, passable, width, height, visit);
+            path.pop();
+        }
+    }
+}
+
+/// Sailing steps over `water` from `from` to every tile, `u32::MAX` where it can not sail.
+/// Stops once `target` is reached
+fn sailing_distances(water: &[bool], from: usize, target: Option<usize>, width: usize, height: usize) -> Vec<u32> {
+    let mut dist = vec![u32::MAX; water.len()];
+    let mut queue = VecDeque::from([from]);
+    dist[from] = 0;
+    while let Some(idx) = queue.pop_front() {
+        if Some(idx) == target {
+            break;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let next = ny * width + nx;
+            if water[next] && dist[next] == u32::MAX {
+                dist[next] = dist[idx] + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    dist
+}
@@ -0,0 +1,237 @@
+use std::sync::OnceLock;
+
+use crate::{
+    map_components::terrain::{DesertVariant, Terrain},
+    pipeline::{
+        biomes::{
+            BaseLayers, TerrainRules, desert_variant, generate_base_layers, generate_desert_variant_noise,
+            generate_temperature_range, geological_age_from_masks, mirror_layer,
+        },
+        error::GenError,
+        helpers::biomes_config,
+        land::RepairIterationCount,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+/// Default edge length of a chunk in tiles
+pub const DEFAULT_CHUNK_SIZE: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Terrain of one chunk, see `ChunkedMap::chunk`. The layers are row-major within the chunk.
+pub struct ChunkData {
+    /// Offset coordinates of the top-left tile of the chunk
+    pub x0: usize,
+    pub y0: usize,
+    /// Chunks at the right and bottom edge of the map can be smaller than the chunk size
+    pub width: usize,
+    pub height: usize,
+    pub terrain: Vec<Terrain>,
+    pub hills: Vec<bool>,
+    pub desert_variants: Vec<Option<DesertVariant>>,
+}
+
+impl ChunkData {
+    /// Index within the chunk of the tile at map offset coordinates (x, y), None if it is not in this chunk
+    pub fn local_index(&self, x: usize, y: usize) -> Option<usize> {
+        let inside = (self.x0..self.x0 + self.width).contains(&x) && (self.y0..self.y0 + self.height).contains(&y);
+        inside.then(|| (y - self.y0) * self.width + (x - self.x0))
+    }
+}
+
+/// Map whose terrain is assigned chunk by chunk on first access, for consumers that only look at one region at a time.
+///
+/// The landmask, climate and heightmap are generated for the whole map up front, as are the temperature range and
+/// the geological age, which only need the landmask and the mountain height cutoff. Coast classification also runs
+/// globally up front on the landmask. Terrain, hills and desert variants are assigned when a chunk is first accessed.
+/// Every tile only reads the global layers and a hash of its own index, so the access order does not change the
+/// result and a fully materialized map matches `generate_map_with_type` tile for tile.
+///
+/// Passes that need connected regions of the final terrain (rivers, oases, appeal) are not chunked, use `Map` for them.
+pub struct ChunkedMap {
+    size: MapSizes,
+    chunk_size: usize,
+    base: BaseLayers,
+    rules: TerrainRules,
+    temperature_range: Vec<u8>,
+    geological_age: Vec<u8>,
+    /// Desert variant noise, already mirrored for mirror maps
+    desert_variant_noise: Vec<u8>,
+    chunks: Vec<OnceLock<ChunkData>>,
+}
+
+impl ChunkedMap {
+    /// Generate the global layers of a map, chunks of `chunk_size` x `chunk_size` tiles are assigned lazily
+    ///
+    /// # Panics
+    /// If `chunk_size` is 0 or the config is invalid, see `try_new`.
+    pub fn new(seed: u64, size: MapSizes, map_type: MapTypes, chunk_size: usize) -> Self {
+        Self::try_new(seed, size, map_type, chunk_size).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `new` but returns an error if the config can not produce a map of this type
+    ///
+    /// # Panics
+    /// If `chunk_size` is 0.
+    pub fn try_new(seed: u64, size: MapSizes, map_type: MapTypes, chunk_size: usize) -> Result<Self, GenError> {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let config = biomes_config();
+        let base = generate_base_layers(seed, &size, map_type)?;
+        // the biome constraint pass assigns the whole map, the chunks then share its final thresholds
+        let (rules, _) = TerrainRules::constrained(seed, &base, &size, &config);
+        let (width, height) = size.dimensions();
+
+        let is_water: Vec<bool> = base.landmask.iter().map(|land| *land == 0).collect();
+        let is_mountain: Vec<bool> = base
+            .landmask
+            .iter()
+            .zip(&base.height)
+            .map(|(land, h)| *land == 1 && rules.is_mountain(*h))
+            .collect();
+        let mut temperature_range = generate_temperature_range(&base.landmask, &size, &config.continentality);
+        let mut geological_age =
+            geological_age_from_masks(seed + 4, &is_mountain, &is_water, &size, &config.geological_age);
+        let mut desert_variant_noise = generate_desert_variant_noise(seed + 5, &size, &config.desert);
+        if let Some(axis) = base.mirror_axis {
+            mirror_layer(&mut temperature_range, width, height, axis);
+            mirror_layer(&mut geological_age, width, height, axis);
+            mirror_layer(&mut desert_variant_noise, width, height, axis);
+        }
+
+        let chunk_count = width.div_ceil(chunk_size) * height.div_ceil(chunk_size);
+        Ok(ChunkedMap {
+            size,
+            chunk_size,
+            base,
+            rules,
+            temperature_range,
+            geological_age,
+            desert_variant_noise,
+            chunks: (0..chunk_count).map(|_| OnceLock::new()).collect(),
+        })
+    }
+
+    /// Return the chunk at chunk coordinates (cx, cy), assigning its terrain on first access
+    pub fn chunk(&self, cx: usize, cy: usize) -> &ChunkData {
+        let (across, down) = self.chunks_across();
+        assert!(cx < across && cy < down, "chunk ({cx}, {cy}) is outside the {across}x{down} chunks of the map");
+        self.chunks[cy * across + cx].get_or_init(|| self.build_chunk(cx, cy))
+    }
+
+    /// Return the chunk containing the tile at offset coordinates (x, y)
+    pub fn chunk_of(&self, x: usize, y: usize) -> &ChunkData {
+        self.chunk(x / self.chunk_size, y / self.chunk_size)
+    }
+
+    /// Number of chunks along the width and height of the map
+    pub fn chunks_across(&self) -> (usize, usize) {
+        let (width, height) = self.size.dimensions();
+        (width.div_ceil(self.chunk_size), height.div_ceil(self.chunk_size))
+    }
+
+    /// Number of chunks whose terrain has been assigned
+    pub fn materialized_chunks(&self) -> usize {
+        self.chunks.iter().filter(|chunk| chunk.get().is_some()).count()
+    }
+
+    /// Return the edge length of a chunk in tiles
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Return the size of the map
+    pub fn size(&self) -> &MapSizes {
+        &self.size
+    }
+
+    /// Landmask of the whole map (1 = land, 0 = water)
+    pub fn landmask(&self) -> &[u8] {
+        &self.base.landmask
+    }
+
+    /// Temperature of the whole map
+    pub fn temperature(&self) -> &[u8] {
+        &self.base.temperature
+    }
+
+    /// Rainfall of the whole map
+    pub fn rainfall(&self) -> &[u8] {
+        &self.base.rainfall
+    }
+
+    /// Heightmap of the whole map
+    pub fn height(&self) -> &[u8] {
+        &self.base.height
+    }
+
+    /// Seasonal temperature swing of the whole map
+    pub fn temperature_range(&self) -> &[u8] {
+        &self.temperature_range
+    }
+
+    /// Geological age of the whole map, see `Tile::geological_age`
+    pub fn geological_age(&self) -> &[u8] {
+        &self.geological_age
+    }
+
+    /// Number of repair passes the landmass generation needed
+    pub fn repair_iterations(&self) -> RepairIterationCount {
+        self.base.repair_iterations
+    }
+
+    fn build_chunk(&self, cx: usize, cy: usize) -> ChunkData {
+        let (width, height) = self.size.dimensions();
+        let (x0, y0) = (cx * self.chunk_size, cy * self.chunk_size);
+        let chunk_width = self.chunk_size.min(width - x0);
+        let chunk_height = self.chunk_size.min(height - y0);
+
+        let mut terrain = Vec::with_capacity(chunk_width * chunk_height);
+        let mut hills = Vec::with_capacity(chunk_width * chunk_height);
+        let mut desert_variants = Vec::with_capacity(chunk_width * chunk_height);
+        for y in y0..y0 + chunk_height {
+            for x in x0..x0 + chunk_width {
+                let (tile_terrain, hill) = self.assign_tile(x, y);
+                let noise = self.desert_variant_noise[y * width + x];
+                terrain.push(tile_terrain);
+                hills.push(hill);
+                desert_variants.push(desert_variant(tile_terrain, noise, &biomes_config().desert));
+            }
+        }
+
+        ChunkData {
+            x0,
+            y0,
+            width: chunk_width,
+            height: chunk_height,
+            terrain,
+            hills,
+            desert_variants,
+        }
+    }
+
+    /// Terrain and hill of one tile. Mirror maps take the tile they are reflected from,
+    /// with water being coast if any tile it is mirrored with is (like the eager pipeline).
+    fn assign_tile(&self, x: usize, y: usize) -> (Terrain, bool) {
+        let (width, height) = self.size.dimensions();
+        let assign_at = |x: usize, y: usize| {
+            let i = y * width + x;
+            let base = &self.base;
+            self.rules.assign_tile(i, base.landmask[i], base.temperature[i], base.rainfall[i], base.height[i])
+        };
+        let Some(axis) = self.base.mirror_axis else {
+            return assign_at(x, y);
+        };
+
+        let xs = if axis.mirrors_columns() { [x, width - 1 - x] } else { [x, x] };
+        let ys = if axis.mirrors_rows() { [y, height - 1 - y] } else { [y, y] };
+        let (terrain, hill) = assign_at(xs[0].min(xs[1]), ys[0].min(ys[1]));
+        if !terrain.is_water() {
+            return (terrain, hill);
+        }
+        let coast = xs
+            .iter()
+            .any(|&mx| ys.iter().any(|&my| assign_at(mx, my).0 == Terrain::CoastLake));
+        (if coast { Terrain::CoastLake } else { terrain }, false)
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Acceptance constraints of `ConstraintsConfig`, one per min/max pair
+pub enum ConstraintKind {
+    LandRatio,
+    LargestRatio,
+    Components,
+    Islands,
+    Lakes,
+    /// Compactness of the landmasses larger than an island
+    Compactness,
+}
+
+impl ConstraintKind {
+    /// Every constraint, in the order of `ConstraintsConfig`
+    pub const ALL: [ConstraintKind; 6] = [
+        ConstraintKind::LandRatio,
+        ConstraintKind::LargestRatio,
+        ConstraintKind::Components,
+        ConstraintKind::Islands,
+        ConstraintKind::Lakes,
+        ConstraintKind::Compactness,
+    ];
+
+    /// Name of the constraint without the min/max prefix, e.g. `components`
+    pub fn name(self) -> &'static str {
+        match self {
+            ConstraintKind::LandRatio => "land_ratio",
+            ConstraintKind::LargestRatio => "largest_ratio",
+            ConstraintKind::Components => "components",
+            ConstraintKind::Islands => "islands",
+            ConstraintKind::Lakes => "lakes",
+            ConstraintKind::Compactness => "compactness",
+        }
+    }
+
+    /// Field of `ConstraintsConfig` holding one bound, e.g. `min_components`
+    pub fn field(self, bound: ConstraintBound) -> String {
+        format!("{}_{}", bound.prefix(), self.name())
+    }
+
+    /// Counts are whole numbers, ratios and compactness are shown with two decimals
+    fn format_value(self, value: f32) -> String {
+        match self {
+            ConstraintKind::Components | ConstraintKind::Islands | ConstraintKind::Lakes => format!("{value:.0}"),
+            _ => format!("{value:.2}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Which side of its range a constraint missed
+pub enum ConstraintBound {
+    /// The value stayed below the minimum
+    Min,
+    /// The value went above the maximum
+    Max,
+}
+
+impl ConstraintBound {
+    fn prefix(self) -> &'static str {
+        match self {
+            ConstraintBound::Min => "min",
+            ConstraintBound::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a knob has to change to move a value towards its range
+pub enum KnobChange {
+    Increase,
+    Decrease,
+}
+
+impl KnobChange {
+    fn verb(self) -> &'static str {
+        match self {
+            KnobChange::Increase => "increasing",
+            KnobChange::Decrease => "decreasing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Draft and repair settings of `LandStyleConfig` that help when a constraint misses one of its bounds
+pub struct SuggestionRule {
+    pub kind: ConstraintKind,
+    pub bound: ConstraintBound,
+    /// Setting names with the change that moves the value back into range, most effective first
+    pub knobs: &'static [(&'static str, KnobChange)],
+}
+
+/// Knobs that influence each constraint, used by `suggestion`
+pub const SUGGESTION_RULES: [SuggestionRule; 11] = [
+    SuggestionRule {
+        kind: ConstraintKind::LandRatio,
+        bound: ConstraintBound::Min,
+        knobs: &[("base_land_percent", KnobChange::Increase)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::LandRatio,
+        bound: ConstraintBound::Max,
+        knobs: &[("base_land_percent", KnobChange::Decrease)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::LargestRatio,
+        bound: ConstraintBound::Min,
+        knobs: &[
+            ("pangea_connect_count", KnobChange::Increase),
+            ("pangea_fill_internal_count", KnobChange::Increase),
+        ],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::LargestRatio,
+        bound: ConstraintBound::Max,
+        knobs: &[
+            ("largest_carve_base_count", KnobChange::Increase),
+            ("largest_carve_trigger_ratio", KnobChange::Decrease),
+        ],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Components,
+        bound: ConstraintBound::Min,
+        knobs: &[("channel_carve_count", KnobChange::Increase)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Components,
+        bound: ConstraintBound::Max,
+        knobs: &[
+            ("pangea_connect_when_split", KnobChange::Increase),
+            ("smoothing_passes", KnobChange::Increase),
+        ],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Islands,
+        bound: ConstraintBound::Min,
+        knobs: &[("island_extra_missing_floor", KnobChange::Increase)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Lakes,
+        bound: ConstraintBound::Min,
+        knobs: &[("lake_blob_max", KnobChange::Increase), ("min_lake_size", KnobChange::Decrease)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Lakes,
+        bound: ConstraintBound::Max,
+        knobs: &[("min_lake_size", KnobChange::Increase)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Compactness,
+        bound: ConstraintBound::Min,
+        knobs: &[("smoothing_passes", KnobChange::Increase)],
+    },
+    SuggestionRule {
+        kind: ConstraintKind::Compactness,
+        bound: ConstraintBound::Max,
+        knobs: &[("fuzzy_flip_percent", KnobChange::Increase)],
+    },
+];
+
+/// Rule of a constraint bound, None for bounds no config has (`max_islands`)
+pub fn suggestion_rule(kind: ConstraintKind, bound: ConstraintBound) -> Option<&'static SuggestionRule> {
+    SUGGESTION_RULES.iter().find(|rule| rule.kind == kind && rule.bound == bound)
+}
+
+/// Explain a missed bound and what to change, e.g.
+/// `min_components=6 not reached (got 3); consider increasing channel_carve_count or lowering min_components`.
+/// Loosening the bound itself is always the last option.
+pub fn suggestion(kind: ConstraintKind, bound: ConstraintBound, target: f32, achieved: f32) -> String {
+    let field = kind.field(bound);
+    let (missed, loosen) = match bound {
+        ConstraintBound::Min => ("not reached", "lowering"),
+        ConstraintBound::Max => ("exceeded", "raising"),
+    };
+    let mut options: Vec<String> = suggestion_rule(kind, bound)
+        .map(|rule| rule.knobs)
+        .unwrap_or_default()
+        .iter()
+        .map(|(knob, change)| format!("{} {knob}", change.verb()))
+        .collect();
+    options.push(format!("{loosen} {field}"));
+    let last = options.pop().unwrap_or_default();
+    let options = if options.is_empty() {
+        last
+    } else {
+        format!("{} or {last}", options.join(", "))
+    };
+    format!(
+        "{field}={} {missed} (got {}); consider {options}",
+        kind.format_value(target),
+        kind.format_value(achieved)
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// How the final landmask of a map did on one acceptance constraint, see `Map::constraint_outcomes`
+pub struct ConstraintOutcome {
+    pub kind: ConstraintKind,
+    /// Lower end of the target range, None if the constraint has none
+    pub min: Option<f32>,
+    /// Upper end of the target range, None if the constraint has none
+    pub max: Option<f32>,
+    /// Measured value on the final landmask
+    pub achieved: f32,
+    pub satisfied: bool,
+    /// What to change in the config, only for unsatisfied constraints
+    pub suggestion: Option<String>,
+}
+
+impl ConstraintOutcome {
+    /// Check `achieved` against the range and add a suggestion for the missed bound
+    pub fn evaluate(kind: ConstraintKind, min: Option<f32>, max: Option<f32>, achieved: f32) -> Self {
+        let missed = match (min, max) {
+            (Some(min), _) if achieved < min => Some((ConstraintBound::Min, min)),
+            (_, Some(max)) if achieved > max => Some((ConstraintBound::Max, max)),
+            _ => None,
+        };
+        ConstraintOutcome {
+            kind,
+            min,
+            max,
+            achieved,
+            satisfied: missed.is_none(),
+            suggestion: missed.map(|(bound, target)| suggestion(kind, bound, target, achieved)),
+        }
+    }
+
+    /// Target range like `6-10`, `>= 2` or `<= 0.80`
+    pub fn target(&self) -> String {
+        let value = |value: f32| self.kind.format_value(value);
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("{}-{}", value(min), value(max)),
+            (Some(min), None) => format!(">= {}", value(min)),
+            (None, Some(max)) => format!("<= {}", value(max)),
+            (None, None) => "any".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConstraintOutcome {
+    /// One table row: name, target range, achieved value and `ok` or `unmet`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<14} {:>11} {:>8}  {}",
+            self.kind.name(),
+            self.target(),
+            self.kind.format_value(self.achieved),
+            if self.satisfied { "ok" } else { "unmet" }
+        )
+    }
+}
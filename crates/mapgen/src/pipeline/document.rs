@@ -0,0 +1,334 @@
+use std::{fmt, fs, io, path::Path};
+
+use serde_json::{Value, json};
+
+use crate::{
+    map_components::{
+        terrain::{Feature, Terrain},
+        tile_tags::TileTags,
+    },
+    pipeline::{
+        map::Map,
+        map_builder::{LayerError, MapBuilder},
+        recipe::Recipe,
+        text_map::GeneratedMapData,
+    },
+};
+
+/// Format version `MapDocument::to_json_value` writes
+pub const MAP_DOCUMENT_VERSION: u32 = 4;
+
+/// Migration from version `from` to `from + 1`, in version order. Each one only fills in the new fields.
+const MIGRATIONS: [Migration; 3] = [
+    Migration {
+        from: 1,
+        description: "add the hill layer, no hills",
+        apply: add_hill_layer,
+    },
+    Migration {
+        from: 2,
+        description: "add the feature layer, no features",
+        apply: add_feature_layer,
+    },
+    Migration {
+        from: 3,
+        description: "add the scenario tags, no tags",
+        apply: add_tags,
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+/// One step of the migration chain, see `MapDocument::from_json_value`
+pub struct Migration {
+    /// Version the migration reads, it writes `from + 1`
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&mut Value),
+}
+
+impl PartialEq for Migration {
+    /// There is one migration per version
+    fn eq(&self, other: &Self) -> bool {
+        self.from == other.from
+    }
+}
+
+impl Eq for Migration {}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{} -> v{}: {}", self.from, self.from + 1, self.description)
+    }
+}
+
+#[derive(Debug)]
+/// Errors of `MapDocument::load` and `MapDocument::from_json_value`
+pub enum DocumentError {
+    /// Reading the file failed
+    Io(io::Error),
+    /// The file is not valid JSON
+    InvalidJson(String),
+    /// The document was written by a newer version than this build reads
+    TooNew { found: u32, supported: u32 },
+    /// A field is missing or has a wrong value
+    InvalidField { field: &'static str, message: String },
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(source) => write!(f, "{source}"),
+            Self::InvalidJson(message) => write!(f, "invalid JSON: {message}"),
+            Self::TooNew { found, supported } => write!(
+                f,
+                "map document version {found} is newer than the supported version {supported}"
+            ),
+            Self::InvalidField { field, message } => write!(f, "field '{field}' {message}"),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Saved map layers with their format version.
+/// Loading an older version runs the migrations up to `MAP_DOCUMENT_VERSION` and lists them in `migrations_applied`.
+///
+/// Versions: 1 has `terrain`, 2 adds `hills`, 3 adds `features`, 4 adds the scenario `tags`.
+/// Any version may have a `recipe`.
+pub struct MapDocument {
+    /// Version the document was stored with
+    pub version: u32,
+    pub data: GeneratedMapData,
+    /// Recipe of the generated map the layers come from, None for authored or imported layers
+    pub recipe: Option<Recipe>,
+    /// Scenario tags of the tiles, see `Map::tags`
+    pub tags: TileTags,
+    /// Migrations run while loading, oldest first. Empty for current documents
+    pub migrations_applied: Vec<Migration>,
+}
+
+impl MapDocument {
+    /// Document of the current version holding `data`
+    pub fn new(data: GeneratedMapData) -> Self {
+        MapDocument {
+            version: MAP_DOCUMENT_VERSION,
+            data,
+            recipe: None,
+            tags: TileTags::new(),
+            migrations_applied: Vec::new(),
+        }
+    }
+
+    pub fn with_recipe(mut self, recipe: Recipe) -> Self {
+        self.recipe = Some(recipe);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: TileTags) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// The document as JSON: `version`, `width`, `height` and one row-major array per layer.
+    /// Terrain and feature names are the same as in `Tile::to_json_value`, the recipe is written as its string
+    /// and the tags as in `TileTags::to_json_value`.
+    pub fn to_json_value(&self) -> Value {
+        let data = &self.data;
+        let mut value = json!({
+            "version": MAP_DOCUMENT_VERSION,
+            "width": data.width,
+            "height": data.height,
+            "terrain": data.terrain.iter().map(|terrain| format!("{terrain:?}")).collect::<Vec<_>>(),
+            "hills": data.hills,
+            "features": data
+                .features
+                .iter()
+                .map(|feature| feature.map_or(Value::Null, |feature| Value::String(format!("{feature:?}"))))
+                .collect::<Vec<_>>(),
+            "tags": self.tags.to_json_value(),
+        });
+        if let Some(recipe) = &self.recipe {
+            value["recipe"] = Value::String(recipe.to_string());
+        }
+        value
+    }
+
+    /// Build a map of the layers with the tags, see `MapBuilder::from_map_data`
+    pub fn to_map(&self) -> Result<Map, LayerError> {
+        let mut map = MapBuilder::from_map_data(&self.data)?.build()?;
+        *map.tags_mut() = self.tags.clone();
+        Ok(map)
+    }
+
+    /// Read a document of any version up to `MAP_DOCUMENT_VERSION`, migrating older versions
+    pub fn from_json_value(mut value: Value) -> Result<Self, DocumentError> {
+        let version = read_usize(&value, "version")?;
+        let version = u32::try_from(version)
+            .ok()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| invalid("version", "must be at least 1"))?;
+        if version > MAP_DOCUMENT_VERSION {
+            return Err(DocumentError::TooNew {
+                found: version,
+                supported: MAP_DOCUMENT_VERSION,
+            });
+        }
+
+        let mut migrations_applied = Vec::new();
+        for migration in MIGRATIONS.iter().filter(|migration| migration.from >= version) {
+            (migration.apply)(&mut value);
+            migrations_applied.push(*migration);
+        }
+
+        let data = read_layers(&value)?;
+        let tags = read_tags(&value, data.width * data.height)?;
+        Ok(MapDocument {
+            version,
+            data,
+            recipe: read_recipe(&value)?,
+            tags,
+            migrations_applied,
+        })
+    }
+
+    /// Write the document as JSON
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string(&self.to_json_value()).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+
+    /// Read a document written by `save`, see `from_json_value`
+    pub fn load(path: &Path) -> Result<Self, DocumentError> {
+        let text = fs::read_to_string(path).map_err(DocumentError::Io)?;
+        let value = serde_json::from_str(&text).map_err(|err| DocumentError::InvalidJson(err.to_string()))?;
+        Self::from_json_value(value)
+    }
+}
+
+/// v1 -> v2: every tile without a hill
+fn add_hill_layer(value: &mut Value) {
+    let tiles = tile_count(value);
+    value["hills"] = Value::Array(vec![Value::Bool(false); tiles]);
+}
+
+/// v2 -> v3: every tile without a feature
+fn add_feature_layer(value: &mut Value) {
+    let tiles = tile_count(value);
+    value["features"] = Value::Array(vec![Value::Null; tiles]);
+}
+
+/// v3 -> v4: no tile has a tag
+fn add_tags(value: &mut Value) {
+    value["tags"] = Value::Object(serde_json::Map::new());
+}
+
+/// Number of tiles of the terrain layer, the one layer every version has
+fn tile_count(value: &Value) -> usize {
+    value["terrain"].as_array().map_or(0, Vec::len)
+}
+
+fn read_layers(value: &Value) -> Result<GeneratedMapData, DocumentError> {
+    let width = read_usize(value, "width")?;
+    let height = read_usize(value, "height")?;
+    if width == 0 || height == 0 {
+        return Err(invalid(if width == 0 { "width" } else { "height" }, "must be positive"));
+    }
+
+    let terrain = read_layer(value, "terrain", width * height, |tile| match tile {
+        Value::String(name) => find_name(name, &Terrain::ALL),
+        _ => None,
+    })?;
+    let hills = read_layer(value, "hills", width * height, Value::as_bool)?;
+    let features = read_layer(value, "features", width * height, |tile| match tile {
+        Value::Null => Some(None),
+        Value::String(name) => find_name(name, &Feature::ALL).map(Some),
+        _ => None,
+    })?;
+    Ok(GeneratedMapData {
+        width,
+        height,
+        terrain,
+        hills,
+        features,
+    })
+}
+
+/// Optional `recipe` string, any version may have one
+fn read_recipe(value: &Value) -> Result<Option<Recipe>, DocumentError> {
+    match value.get("recipe") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(recipe)) => Recipe::parse(recipe)
+            .map(Some)
+            .map_err(|err| invalid("recipe", &err.to_string())),
+        Some(_) => Err(invalid("recipe", "is not a string")),
+    }
+}
+
+/// `tags` object from every tag to the indices of its tiles
+fn read_tags(value: &Value, tiles: usize) -> Result<TileTags, DocumentError> {
+    let object = value
+        .get("tags")
+        .ok_or_else(|| invalid("tags", "is missing"))?
+        .as_object()
+        .ok_or_else(|| invalid("tags", "is not an object"))?;
+    let mut tags = TileTags::new();
+    for (tag, indices) in object {
+        let indices = indices
+            .as_array()
+            .ok_or_else(|| invalid("tags", &format!("has no index array for '{tag}'")))?;
+        for idx in indices {
+            let valid = idx.as_u64().and_then(|idx| usize::try_from(idx).ok()).filter(|idx| *idx < tiles);
+            let idx = valid.ok_or_else(|| invalid("tags", &format!("has an invalid tile {idx} for '{tag}'")))?;
+            tags.add_tag(idx, tag);
+        }
+    }
+    Ok(tags)
+}
+
+/// Read a row-major layer of `len` tiles, `parse` returns None for an invalid tile
+fn read_layer<T>(
+    value: &Value,
+    field: &'static str,
+    len: usize,
+    parse: impl Fn(&Value) -> Option<T>,
+) -> Result<Vec<T>, DocumentError> {
+    let tiles = value
+        .get(field)
+        .ok_or_else(|| invalid(field, "is missing"))?
+        .as_array()
+        .ok_or_else(|| invalid(field, "is not an array"))?;
+    if tiles.len() != len {
+        return Err(invalid(field, &format!("has {} tiles, expected width * height = {len}", tiles.len())));
+    }
+    tiles
+        .iter()
+        .enumerate()
+        .map(|(idx, tile)| parse(tile).ok_or_else(|| invalid(field, &format!("has an invalid value {tile} at {idx}"))))
+        .collect()
+}
+
+/// Enum value written by its `Debug` name
+fn find_name<T: fmt::Debug + Copy>(name: &str, all: &[T]) -> Option<T> {
+    all.iter().find(|candidate| format!("{candidate:?}") == name).copied()
+}
+
+fn read_usize(value: &Value, field: &'static str) -> Result<usize, DocumentError> {
+    let number = value.get(field).ok_or_else(|| invalid(field, "is missing"))?;
+    number
+        .as_u64()
+        .and_then(|number| usize::try_from(number).ok())
+        .ok_or_else(|| invalid(field, "is not a non-negative integer"))
+}
+
+fn invalid(field: &'static str, message: &str) -> DocumentError {
+    DocumentError::InvalidField {
+        field,
+        message: message.to_string(),
+    }
+}
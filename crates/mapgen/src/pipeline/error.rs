@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        helpers::{BiomesConfig, ConstraintsConfig, LandmassesConfig},
+        map_types::MapTypes,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Errors of the `try_generate_*` entry points. All of them come from configs that no map can satisfy,
+/// the pipeline itself does not fail for the preset map sizes.
+pub enum GenError {
+    /// A constraint of the map type has its minimum above its maximum, or a ratio outside of [0, 1]
+    InvalidConstraints { map_type: MapTypes, field: &'static str, reason: String },
+    /// The mountain and hill thresholds are not fractions of the land that add up to at most 1
+    InvalidTerrainThresholds { mountain: f32, hill: f32 },
+    /// The terra barrier width range is empty
+    InvalidTerraBarrier { min: usize, max: usize },
+    /// A latitude land profile weight is negative or not finite
+    InvalidLandProfile { weight: f32 },
+    /// A biome constraint bounds a water terrain, has a share outside of [0, 1] or its minimum above its maximum
+    InvalidBiomeConstraint { terrain: Terrain, reason: String },
+}
+
+impl fmt::Display for GenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidConstraints { map_type, field, reason } => {
+                write!(f, "invalid {map_type:?} constraint '{field}': {reason}")
+            }
+            Self::InvalidTerrainThresholds { mountain, hill } => write!(
+                f,
+                "invalid terrain thresholds: mountain {mountain} and hill {hill} must be in [0, 1] and add up to at most 1"
+            ),
+            Self::InvalidTerraBarrier { min, max } => {
+                write!(f, "invalid terra barrier: barrier_min {min} is above barrier_max {max}")
+            }
+            Self::InvalidLandProfile { weight } => {
+                write!(f, "invalid latitude land profile: weight {weight} must be a non-negative number")
+            }
+            Self::InvalidBiomeConstraint { terrain, reason } => {
+                write!(f, "invalid biome constraint for {terrain:?}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenError {}
+
+/// Check the landmass settings a map of the given type is generated with
+pub fn validate_landmasses_config(config: &LandmassesConfig, map_type: MapTypes) -> Result<(), GenError> {
+    validate_constraints(config.constraints_for(map_type), map_type)?;
+    if let Some(&weight) = config.global.latitude_land_profile.iter().find(|w| !w.is_finite() || **w < 0.0) {
+        return Err(GenError::InvalidLandProfile { weight });
+    }
+    if map_type == MapTypes::Terra && config.terra.barrier_min > config.terra.barrier_max {
+        return Err(GenError::InvalidTerraBarrier {
+            min: config.terra.barrier_min,
+            max: config.terra.barrier_max,
+        });
+    }
+    Ok(())
+}
+
+/// Check the biome settings every map is generated with
+pub fn validate_biomes_config(config: &BiomesConfig) -> Result<(), GenError> {
+    let (mountain, hill) = (config.terrain.mountain_threshold, config.terrain.hill_threshold);
+    let fraction = |value: f32| (0.0..=1.0).contains(&value);
+    if !fraction(mountain) || !fraction(hill) || mountain + hill > 1.0 {
+        return Err(GenError::InvalidTerrainThresholds { mountain, hill });
+    }
+    for constraint in &config.biome_constraints.terrains {
+        let invalid = |reason: &str| {
+            Err(GenError::InvalidBiomeConstraint {
+                terrain: constraint.terrain,
+                reason: reason.to_string(),
+            })
+        };
+        if constraint.terrain.is_water() {
+            return invalid("only land terrains can be bounded");
+        }
+        if constraint.min.into_iter().chain(constraint.max).any(|share| !fraction(share)) {
+            return invalid("shares must be in [0, 1]");
+        }
+        if let (Some(min), Some(max)) = (constraint.min, constraint.max)
+            && min > max
+        {
+            return invalid("min is above max");
+        }
+    }
+    Ok(())
+}
+
+fn validate_constraints(constraints: &ConstraintsConfig, map_type: MapTypes) -> Result<(), GenError> {
+    let invalid = |field, reason| Err(GenError::InvalidConstraints { map_type, field, reason });
+
+    let ratios = [
+        ("min_land_ratio", constraints.min_land_ratio),
+        ("max_land_ratio", constraints.max_land_ratio),
+        ("min_largest_ratio", constraints.min_largest_ratio),
+        ("max_largest_ratio", constraints.max_largest_ratio),
+    ];
+    for (field, value) in ratios {
+        if !(0.0..=1.0).contains(&value) {
+            return invalid(field, format!("{value} is not in [0, 1]"));
+        }
+    }
+
+    let ranges = [
+        ("min_land_ratio", constraints.min_land_ratio, constraints.max_land_ratio),
+        ("min_largest_ratio", constraints.min_largest_ratio, constraints.max_largest_ratio),
+        ("min_components", constraints.min_components as f32, constraints.max_components as f32),
+        ("min_lakes", constraints.min_lakes as f32, constraints.max_lakes as f32),
+        (
+            "min_compactness",
+            constraints.min_compactness.unwrap_or(f32::NEG_INFINITY),
+            constraints.max_compactness.unwrap_or(f32::INFINITY),
+        ),
+    ];
+    for (field, min, max) in ranges {
+        if min > max {
+            return invalid(field, format!("minimum {min} is above the maximum {max}"));
+        }
+    }
+    Ok(())
+}
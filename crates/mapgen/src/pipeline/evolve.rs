@@ -0,0 +1,195 @@
+use std::fmt;
+
+use crate::pipeline::{
+    helpers::{BiomesConfig, DraftConfig, LandmassesConfig, TerrainThresholds, tile_hash},
+    map_types::MapTypes,
+};
+
+/// Salt of the perturbation schedule hash, so it does not follow the tile hashes of the generation
+const SCHEDULE_SALT: u64 = 0x6576_6f6c_7665;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Numeric generation settings the evolve mode may perturb
+pub enum EvolveParam {
+    BaseLandPercent,
+    SmoothingPasses,
+    CenterBias,
+    SnowTempThreshold,
+    TundraTempThreshold,
+    DesertTempThreshold,
+    DesertRainThreshold,
+    GrasslandRainThreshold,
+}
+
+impl EvolveParam {
+    /// Every parameter that may be perturbed
+    pub const ALL: [EvolveParam; 8] = [
+        EvolveParam::BaseLandPercent,
+        EvolveParam::SmoothingPasses,
+        EvolveParam::CenterBias,
+        EvolveParam::SnowTempThreshold,
+        EvolveParam::TundraTempThreshold,
+        EvolveParam::DesertTempThreshold,
+        EvolveParam::DesertRainThreshold,
+        EvolveParam::GrasslandRainThreshold,
+    ];
+
+    /// Name of the setting in the config files
+    pub fn name(self) -> &'static str {
+        match self {
+            EvolveParam::BaseLandPercent => "base_land_percent",
+            EvolveParam::SmoothingPasses => "smoothing_passes",
+            EvolveParam::CenterBias => "center_bias",
+            EvolveParam::SnowTempThreshold => "snow_temp_threshold",
+            EvolveParam::TundraTempThreshold => "tundra_temp_threshold",
+            EvolveParam::DesertTempThreshold => "desert_temp_threshold",
+            EvolveParam::DesertRainThreshold => "desert_rain_threshold",
+            EvolveParam::GrasslandRainThreshold => "grassland_rain_threshold",
+        }
+    }
+
+    /// Size of a single perturbation of this parameter
+    pub fn step(self) -> f32 {
+        match self {
+            EvolveParam::BaseLandPercent => 2.0,
+            EvolveParam::SmoothingPasses => 1.0,
+            EvolveParam::CenterBias => 0.1,
+            _ => 5.0,
+        }
+    }
+
+    /// Current values of the parameter for a map of the given type (one per draft for the draft settings)
+    pub fn values(self, map_type: MapTypes, landmasses: &LandmassesConfig, biomes: &BiomesConfig) -> Vec<f32> {
+        match self.threshold(&biomes.terrain) {
+            Some(value) => vec![f32::from(value)],
+            None => landmasses
+                .drafts(map_type)
+                .into_iter()
+                .map(|draft| self.draft_value(draft))
+                .collect(),
+        }
+    }
+
+    /// Whether the parameter may take this value
+    fn is_valid(self, value: f32) -> bool {
+        let max = match self {
+            EvolveParam::BaseLandPercent => 100.0,
+            EvolveParam::SmoothingPasses => f32::INFINITY,
+            EvolveParam::CenterBias => 1.0,
+            _ => 255.0,
+        };
+        (0.0..=max).contains(&value)
+    }
+
+    fn draft_value(self, draft: &DraftConfig) -> f32 {
+        match self {
+            EvolveParam::BaseLandPercent => draft.base_land_percent as f32,
+            EvolveParam::SmoothingPasses => draft.smoothing_passes as f32,
+            _ => draft.center_bias,
+        }
+    }
+
+    fn set_draft_value(self, draft: &mut DraftConfig, value: f32) {
+        match self {
+            EvolveParam::BaseLandPercent => draft.base_land_percent = value.round() as u32,
+            EvolveParam::SmoothingPasses => draft.smoothing_passes = value.round() as usize,
+            _ => draft.center_bias = value,
+        }
+    }
+
+    fn threshold(self, terrain: &TerrainThresholds) -> Option<u8> {
+        match self {
+            EvolveParam::SnowTempThreshold => Some(terrain.snow_temp_threshold),
+            EvolveParam::TundraTempThreshold => Some(terrain.tundra_temp_threshold),
+            EvolveParam::DesertTempThreshold => Some(terrain.desert_temp_threshold),
+            EvolveParam::DesertRainThreshold => Some(terrain.desert_rain_threshold),
+            EvolveParam::GrasslandRainThreshold => Some(terrain.grassland_rain_threshold),
+            _ => None,
+        }
+    }
+
+    fn threshold_mut(self, terrain: &mut TerrainThresholds) -> Option<&mut u8> {
+        match self {
+            EvolveParam::SnowTempThreshold => Some(&mut terrain.snow_temp_threshold),
+            EvolveParam::TundraTempThreshold => Some(&mut terrain.tundra_temp_threshold),
+            EvolveParam::DesertTempThreshold => Some(&mut terrain.desert_temp_threshold),
+            EvolveParam::DesertRainThreshold => Some(&mut terrain.desert_rain_threshold),
+            EvolveParam::GrasslandRainThreshold => Some(&mut terrain.grassland_rain_threshold),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Change of a single parameter, displayed like `base_land_percent +2`
+pub struct Perturbation {
+    pub param: EvolveParam,
+    pub delta: f32,
+}
+
+impl fmt::Display for Perturbation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.param.name(), self.delta)
+    }
+}
+
+/// Perturbations of contact sheet cell `cell` (row-major).
+/// Cell 0 is the base map without any. The other cells step the parameters in turn, first up then down,
+/// and about every other cell also steps a second parameter picked from a hash of the cell index.
+pub fn perturbation_schedule(cell: usize) -> Vec<Perturbation> {
+    if cell == 0 {
+        return Vec::new();
+    }
+    let n_params = EvolveParam::ALL.len();
+    let step = |index: usize, up: bool| {
+        let param = EvolveParam::ALL[index];
+        Perturbation {
+            param,
+            delta: if up { param.step() } else { -param.step() },
+        }
+    };
+
+    let k = cell - 1;
+    let first = k % n_params;
+    let mut perturbations = vec![step(first, (k / n_params).is_multiple_of(2))];
+    let hash = tile_hash(SCHEDULE_SALT, cell);
+    if hash & 1 == 1 {
+        let second = (first + 1 + (hash >> 8) as usize % (n_params - 1)) % n_params;
+        perturbations.push(step(second, (hash >> 16) & 1 == 1));
+    }
+    perturbations
+}
+
+/// Apply perturbations to the configs a map of `map_type` is generated with and return the applied perturbations.
+/// A step that would leave the valid range of its parameter (e.g. fewer than 0 smoothing passes)
+/// is taken in the other direction, so every applied perturbation changes its parameter.
+pub fn apply_perturbations(
+    perturbations: &[Perturbation],
+    map_type: MapTypes,
+    landmasses: &mut LandmassesConfig,
+    biomes: &mut BiomesConfig,
+) -> Vec<Perturbation> {
+    perturbations
+        .iter()
+        .map(|&perturbation| {
+            let param = perturbation.param;
+            let current = param.values(map_type, landmasses, biomes);
+            let delta = if current.iter().all(|value| param.is_valid(value + perturbation.delta)) {
+                perturbation.delta
+            } else {
+                -perturbation.delta
+            };
+
+            match param.threshold_mut(&mut biomes.terrain) {
+                Some(threshold) => *threshold = (f32::from(*threshold) + delta) as u8,
+                None => {
+                    for draft in landmasses.drafts_mut(map_type) {
+                        let value = param.draft_value(draft) + delta;
+                        param.set_draft_value(draft, value);
+                    }
+                }
+            }
+            Perturbation { param, delta }
+        })
+        .collect()
+}
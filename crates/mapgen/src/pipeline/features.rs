@@ -1,14 +1,34 @@
-use itertools::Itertools;
+use std::collections::{BTreeSet, VecDeque};
 
 use crate::{
-    map_components::terrain::Terrain,
-    pipeline::{helpers::neighbors_odd_r, map_sizes::MapSizes},
+    map_components::{
+        hex_coords::HexCoord,
+        terrain::{DesertVariant, Feature, Terrain},
+    },
+    pipeline::{
+        biomes::ocean_mask,
+        helpers::{DesertConfig, neighbors_odd_r, tile_hash},
+        map_sizes::{MapScaling, MapSizes},
+    },
 };
 
+/// Score [0, 1] of a tile as a river source: `0.65 * elevation_score + 0.35 * rain_score`.
+/// The elevation score is the scaled elevation, a mountain that borders land a river can run on
+/// (neither mountain nor coast) counts as fully elevated.
+pub fn compute_river_score(terrain: Terrain, elevation: u8, rainfall: u8, borders_river_land: bool) -> f32 {
+    let elevation_score = if terrain == Terrain::Mountain && borders_river_land {
+        1.0
+    } else {
+        elevation as f32 / 255.0
+    };
+    let rain_score = rainfall as f32 / 255.0;
+    0.65 * elevation_score + 0.35 * rain_score
+}
+
 /// Find potential spots at which a river can start and end
 /// Good river starts are areas with high rainfall. We assign a score to them based on height as well to score them later
 /// Good river endings are lakes or coast
-/// Returns Vec<(bool, f32)> for starts and Vec<bool> end tiles
+/// Returns Vec<f32> start scores and Vec<bool> end tiles, both in row-major order
 fn find_river_potential(terrain_vec: &Vec<Terrain>, rain_vec: &Vec<u8>, height_vec: &Vec<u8>, map_size: &MapSizes) -> (Vec<f32>, Vec<bool>){
 
     let grid_size = map_size.grid_size();
@@ -21,30 +41,18 @@ fn find_river_potential(terrain_vec: &Vec<Terrain>, rain_vec: &Vec<u8>, height_v
         terrain == &Terrain::CoastLake
     }).collect();
 
-    // check each tile
+    // check each tile in row-major order
     // a mountain has good elevation, but since rivers run on tiles, we need to check for a mountain with a normal tile next to it (also no coast)
-    for x in 0..width {
-        for y in 0..height {
+    for y in 0..height {
+        for x in 0..width {
 
             let tile_idx = y * width + x;
-            
-            // mountain as strong source only if it borders non-mountain, non-coast land
-            let elevation_score = if terrain_vec[tile_idx] == Terrain::Mountain {
-                for (nx, ny) in neighbors_odd_r(x, y, width, height) {
-                    let nid = ny * width + nx;
-                    if terrain_vec[nid] != Terrain::Mountain && terrain_vec[nid] != Terrain::CoastLake {
-                        break;
-                    }
-                }
-                1.0
-            }
-            else {
-                height_vec[tile_idx] as f32/ 255.0 
-            };
+            let borders_river_land = neighbors_odd_r(x, y, width, height).into_iter().any(|(nx, ny)| {
+                let neighbor = terrain_vec[ny * width + nx];
+                neighbor != Terrain::Mountain && neighbor != Terrain::CoastLake
+            });
 
-            // assign score based on height and rainfall
-            let rain_score = (rain_vec[tile_idx] as f32/ 255.0);
-            let score = 0.65*elevation_score + 0.35*rain_score;
+            let score = compute_river_score(terrain_vec[tile_idx], height_vec[tile_idx], rain_vec[tile_idx], borders_river_land);
             starting_locations.push(score);
 
         }
@@ -53,27 +61,288 @@ fn find_river_potential(terrain_vec: &Vec<Terrain>, rain_vec: &Vec<u8>, height_v
     (starting_locations, ending_locations)
 }
 
-/// Deterministically choose from the starting and ending positions pick pairs
-/// For each map size we choose different amounts of pairs
-/// Duel: 2
-/// Tiny: 3
-/// Small: 3
-/// Standard: 4
-/// Large: 5
-/// Huge: 6
-fn pick_and_trace_rivers(starting_locations: Vec<f32>, ending_locations: Vec<bool>, terrain_vec: &Vec<Terrain>, map_size: &MapSizes) -> Vec<Vec<usize>> {
-
-    let n_pairs = map_size.number_rivers();
-    let river_vec = Vec::new();
-
-    river_vec
-    
-}
+/// Fewest tiles a river runs through, sources closer to their mouth are skipped
+const MIN_RIVER_LENGTH: usize = 5;
+/// Smallest hex distance between two river sources
+const MIN_SOURCE_DISTANCE: u32 = 5;
 
+/// Deterministically pick the river sources from the starting scores and trace each one to an ending.
+/// The number of rivers comes from the map size, see `MapScaling::rivers`.
+/// Rivers run on land that is neither mountain nor water. Each one starts at the best scored tile (a mountain
+/// starts its river on its highest river land neighbor) that is at least `MIN_SOURCE_DISTANCE` from the other
+/// sources and not on a river yet, then steps to the neighbor closest to an ending, the lowest one on ties.
+/// The next tile only depends on the current one, so a river that meets another follows it to the same mouth
+/// as a tributary. Returns every river as tile indices from source to mouth, a land tile next to an ending.
+fn pick_and_trace_rivers(
+    starting_locations: Vec<f32>,
+    ending_locations: Vec<bool>,
+    terrain_vec: &[Terrain],
+    height_vec: &[u8],
+    map_size: &MapSizes,
+) -> Vec<Vec<usize>> {
+    let n_pairs = MapScaling::for_size(map_size).rivers;
+    let (width, height) = map_size.dimensions();
+    let neighbors = |idx: usize| {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(move |(nx, ny)| ny * width + nx)
+    };
+    let coords = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+    let river_land = |idx: usize| terrain_vec[idx] != Terrain::Mountain && !terrain_vec[idx].is_water();
 
-pub fn place_features(terrain_vec: &Vec<Terrain>, rain_vec: &Vec<u8>, height_vec: &Vec<u8>, map_size: &MapSizes) {
+    // steps from every river land tile to the nearest land tile next to an ending
+    let mut distance = vec![usize::MAX; terrain_vec.len()];
+    let mut queue = VecDeque::new();
+    for idx in (0..terrain_vec.len()).filter(|&idx| river_land(idx)) {
+        if neighbors(idx).any(|n| ending_locations[n]) {
+            distance[idx] = 0;
+            queue.push_back(idx);
+        }
+    }
+    while let Some(idx) = queue.pop_front() {
+        for n in neighbors(idx) {
+            if river_land(n) && distance[n] == usize::MAX {
+                distance[n] = distance[idx] + 1;
+                queue.push_back(n);
+            }
+        }
+    }
+
+    // move mountain sources onto their highest river land neighbor, keep the best score per tile
+    let mut sources: Vec<(f32, usize)> = Vec::new();
+    for (idx, &score) in starting_locations.iter().enumerate() {
+        let start = if terrain_vec[idx] == Terrain::Mountain {
+            neighbors(idx).filter(|&n| river_land(n)).max_by_key(|&n| (height_vec[n], usize::MAX - n))
+        } else {
+            Some(idx).filter(|&idx| river_land(idx))
+        };
+        if let Some(start) = start.filter(|&start| distance[start] != usize::MAX) {
+            sources.push((score, start));
+        }
+    }
+    sources.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut on_river = vec![false; terrain_vec.len()];
+    let mut picked: Vec<usize> = Vec::new();
+    let mut rivers = Vec::new();
+    for (_, source) in sources {
+        if rivers.len() >= n_pairs {
+            break;
+        }
+        if on_river[source]
+            || distance[source] + 1 < MIN_RIVER_LENGTH
+            || picked.iter().any(|&other| coords(other).distance(&coords(source)) < MIN_SOURCE_DISTANCE)
+        {
+            continue;
+        }
+
+        let mut river = vec![source];
+        let mut current = source;
+        while distance[current] > 0 {
+            current = neighbors(current)
+                .filter(|&n| river_land(n) && distance[n] + 1 == distance[current])
+                .min_by_key(|&n| (height_vec[n], n))
+                .expect("a tile one step closer to an ending exists");
+            river.push(current);
+        }
+        for &idx in &river {
+            on_river[idx] = true;
+        }
+        picked.push(source);
+        rivers.push(river);
+    }
+    rivers
+}
+
+/// Place the features on the map
+/// Returns the rivers as lists of tile indices
+pub fn place_features(terrain_vec: &Vec<Terrain>, rain_vec: &Vec<u8>, height_vec: &Vec<u8>, map_size: &MapSizes) -> Vec<Vec<usize>> {
 
     let (river_starts, river_ends) = find_river_potential(terrain_vec, rain_vec, height_vec, map_size);
 
-    let _ = pick_and_trace_rivers(river_starts, river_ends, terrain_vec, map_size);
+    pick_and_trace_rivers(river_starts, river_ends, terrain_vec, height_vec, map_size)
+}
+
+/// Flow (river tiles upstream, including the tile itself) from which a river tile has width tier 2 and 3
+const WIDTH_TIER_2_FLOW: u32 = 6;
+const WIDTH_TIER_3_FLOW: u32 = 12;
+
+/// Width tier of every tile, 1 (narrow) to 3 (wide), 0 without a river.
+/// Flow accumulates along each river from source to mouth and adds up where tributaries merge,
+/// so the width never decreases downstream.
+pub fn river_width_tiers(rivers: &[Vec<usize>], grid_size: usize) -> Vec<u8> {
+    // downstream edges between river tiles, shared segments only count once
+    let edges: BTreeSet<(usize, usize)> = rivers
+        .iter()
+        .flat_map(|river| river.windows(2).map(|pair| (pair[0], pair[1])))
+        .filter(|(from, to)| from != to)
+        .collect();
+    let mut upstream_count = vec![0usize; grid_size];
+    for &(_, to) in &edges {
+        upstream_count[to] += 1;
+    }
+
+    // sum the flow in topological order, starting at the sources
+    let mut flow = vec![0u32; grid_size];
+    let mut queue: VecDeque<usize> = rivers
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|&idx| upstream_count[idx] == 0)
+        .collect();
+    while let Some(idx) = queue.pop_front() {
+        flow[idx] += 1;
+        for &(_, to) in edges.range((idx, 0)..=(idx, usize::MAX)) {
+            flow[to] += flow[idx];
+            upstream_count[to] -= 1;
+            if upstream_count[to] == 0 {
+                queue.push_back(to);
+            }
+        }
+    }
+
+    let mut tiers = vec![0u8; grid_size];
+    for &idx in rivers.iter().flatten() {
+        tiers[idx] = match flow[idx] {
+            0 => 0,
+            f if f >= WIDTH_TIER_3_FLOW => 3,
+            f if f >= WIDTH_TIER_2_FLOW => 2,
+            _ => 1,
+        };
+    }
+    tiers
+}
+
+/// Form a delta where a tier 3 river meets the ocean: 1-2 flat land tiles next to the mouth that also border
+/// the ocean get marsh or floodplains. Rivers ending in lakes and narrower rivers form no delta,
+/// tiles that already have a feature keep it.
+pub fn place_river_deltas(
+    seed: u64,
+    rivers: &[Vec<usize>],
+    width_tiers: &[u8],
+    terrain: &[Terrain],
+    size: &MapSizes,
+    features: &mut [Option<Feature>],
+) {
+    let (width, height) = size.dimensions();
+    let landmask: Vec<u8> = terrain.iter().map(|t| u8::from(!t.is_water())).collect();
+    let ocean = ocean_mask(&landmask, size);
+    let neighbors = |idx: usize| {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(move |(nx, ny)| ny * width + nx)
+    };
+    let borders_ocean = |idx: usize| neighbors(idx).any(|n| terrain[n].is_water() && ocean[n]);
+
+    for &mouth in rivers.iter().filter_map(|river| river.last()) {
+        if width_tiers[mouth] < 3 || !borders_ocean(mouth) {
+            continue;
+        }
+        let mut candidates: Vec<usize> = neighbors(mouth)
+            .filter(|&n| {
+                let t = terrain[n];
+                !t.is_water() && t != Terrain::Mountain && features[n].is_none() && borders_ocean(n)
+            })
+            .collect();
+        candidates.sort_by_key(|&n| tile_hash(seed, n));
+
+        let count = 1 + (tile_hash(seed, mouth) % 2) as usize;
+        for n in candidates.into_iter().take(count) {
+            features[n] = Some(if tile_hash(seed ^ 0x64_656c_7461, n).is_multiple_of(2) {
+                Feature::Marsh
+            } else {
+                Feature::Floodplains
+            });
+        }
+    }
+}
+
+/// Place oases in the deserts.
+/// Only flat sand dunes of connected desert regions with at least `oasis_min_region_size` tiles qualify,
+/// a region gets one oasis cluster per `oasis_min_region_size` tiles. Clusters are single tiles,
+/// occasionally 2-3 adjacent ones, and keep `oasis_min_distance` from every other cluster.
+/// Returns the feature of every tile (only oases, everything else is None)
+pub fn place_oases(
+    seed: u64,
+    terrain: &[Terrain],
+    hills: &[bool],
+    desert_variants: &[Option<DesertVariant>],
+    size: &MapSizes,
+    config: &DesertConfig,
+) -> Vec<Option<Feature>> {
+    let (width, height) = size.dimensions();
+    let min_region_size = config.oasis_min_region_size.max(1);
+    let coords = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+
+    // Label the connected desert regions
+    let mut region = vec![usize::MAX; width * height];
+    let mut region_sizes = Vec::new();
+    for start in 0..width * height {
+        if terrain[start] != Terrain::Desert || region[start] != usize::MAX {
+            continue;
+        }
+        let id = region_sizes.len();
+        let mut count = 0;
+        let mut queue = VecDeque::from([start]);
+        region[start] = id;
+        while let Some(idx) = queue.pop_front() {
+            count += 1;
+            for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                let nidx = ny * width + nx;
+                if terrain[nidx] == Terrain::Desert && region[nidx] == usize::MAX {
+                    region[nidx] = id;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+        region_sizes.push(count);
+    }
+
+    let can_hold_oasis = |idx: usize| {
+        desert_variants[idx] == Some(DesertVariant::SandDunes)
+            && !hills[idx]
+            && region_sizes[region[idx]] >= min_region_size
+    };
+    let mut remaining: Vec<usize> = region_sizes.iter().map(|count| count / min_region_size).collect();
+
+    // Candidates in a seeded order, so placement does not favor the top of the map
+    let mut candidates: Vec<usize> = (0..width * height).filter(|&idx| can_hold_oasis(idx)).collect();
+    candidates.sort_by_key(|&idx| tile_hash(seed, idx));
+
+    let mut features = vec![None; width * height];
+    let mut placed: Vec<HexCoord> = Vec::new();
+    let far_enough = |placed: &[HexCoord], idx: usize| {
+        let coord = coords(idx);
+        placed.iter().all(|other| other.distance(&coord) >= config.oasis_min_distance)
+    };
+    for idx in candidates {
+        if remaining[region[idx]] == 0 || !far_enough(&placed, idx) {
+            continue;
+        }
+        let cluster_start = placed.len();
+        features[idx] = Some(Feature::Oasis);
+        placed.push(coords(idx));
+        remaining[region[idx]] -= 1;
+
+        // Occasionally grow the oasis into a cluster of 2-3 tiles
+        let roll = tile_hash(seed ^ 0x6f61_7369_7300, idx);
+        if (roll % 1000) as f32 >= config.oasis_cluster_chance * 1000.0 {
+            continue;
+        }
+        let mut extra = 1 + (roll >> 32) as usize % 2;
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let nidx = ny * width + nx;
+            if extra == 0 {
+                break;
+            }
+            if can_hold_oasis(nidx) && features[nidx].is_none() && far_enough(&placed[..cluster_start], nidx) {
+                features[nidx] = Some(Feature::Oasis);
+                placed.push(coords(nidx));
+                extra -= 1;
+            }
+        }
+    }
+    features
 }
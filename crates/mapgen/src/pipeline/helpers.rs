@@ -1,16 +1,75 @@
-use serde::Deserialize;
-use std::{fs, path::PathBuf, sync::OnceLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    thread::LocalKey,
+};
 
-#[derive(Debug, Clone, Deserialize)]
+use crate::{
+    map_components::{resources::StrategicResource, terrain::{Feature, Terrain}, tile_modifiers::TileModifierRules},
+    pipeline::{
+        map_sizes::{MapScaling, MapSizes},
+        map_types::MapTypes,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Config for the biome settings loaded from `biomes.yaml`.
 pub struct BiomesConfig {
+    #[serde(default)]
+    pub temperature_model: TemperatureModel,
     pub terrain: TerrainThresholds,
     pub temperature: TemperatureConfig,
-    pub rainfall: NoiseConfig,
+    pub rainfall: RainfallConfig,
     pub heightmap: NoiseConfig,
+    #[serde(default)]
+    pub continentality: ContinentalityConfig,
+    /// Smooth the land heightmap once before the terrain is assigned, off when missing from the file
+    #[serde(default)]
+    pub smooth_elevation_before_biome_assign: bool,
+    #[serde(default)]
+    pub appeal: AppealConfig,
+    #[serde(default)]
+    pub geological_age: GeologicalAgeConfig,
+    #[serde(default)]
+    pub desert: DesertConfig,
+    /// Movement, defense and sight modifiers per terrain, hill and feature
+    #[serde(default)]
+    pub tile_modifiers: TileModifierRules,
+    #[serde(default)]
+    pub lowland: LowlandConfig,
+    #[serde(default)]
+    pub wind: WindConfig,
+    /// Ruggedness of the heightmap, see `WorldAge`
+    #[serde(default, skip_serializing_if = "WorldAge::is_standard")]
+    pub world_age: WorldAge,
+    /// Land share bounds per terrain, none by default
+    #[serde(default, skip_serializing_if = "BiomeConstraints::is_empty")]
+    pub biome_constraints: BiomeConstraints,
+}
+
+impl BiomesConfig {
+    /// Replace the world age, see `WorldAge`
+    pub fn with_world_age(mut self, world_age: WorldAge) -> Self {
+        self.world_age = world_age;
+        self
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Model used to compute the base temperature per latitude.
+pub enum TemperatureModel {
+    /// Cosine gradient from the equator to the poles.
+    #[default]
+    Simple,
+    /// Annual mean insolation on a sphere with axial tilt.
+    SphericalLat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Terrain thresholds used when converting climate and elevation into base terrain.
 pub struct TerrainThresholds {
     pub mountain_threshold: f32,
@@ -20,9 +79,60 @@ pub struct TerrainThresholds {
     pub desert_temp_threshold: u8,
     pub desert_rain_threshold: u8,
     pub grassland_rain_threshold: u8,
+    /// Temperature window around the snow/tundra thresholds in which the biome is dithered (0 = off).
+    #[serde(default)]
+    pub threshold_dither_window: u8,
+    /// How strongly elevation pushes dithered tiles towards the colder biome.
+    #[serde(default)]
+    pub dither_elevation_bias: f32,
+    /// Demote one mountain on the rim of every lake that is fully ringed by mountains to a hill,
+    /// see `open_dead_lakes`. Off by default.
+    #[serde(default)]
+    pub open_dead_lakes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Optional land composition bounds, checked after the terrain is assigned.
+/// When a bound is missed, the climate thresholds of the responsible terrain are nudged and only the terrain
+/// assignment runs again, up to `max_adjustments` times (see `TerrainRules::constrained`).
+pub struct BiomeConstraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terrains: Vec<TerrainShareConstraint>,
+    #[serde(default = "default_max_biome_adjustments")]
+    pub max_adjustments: usize,
+}
+
+fn default_max_biome_adjustments() -> usize {
+    12
+}
+
+impl Default for BiomeConstraints {
+    fn default() -> Self {
+        BiomeConstraints {
+            terrains: Vec::new(),
+            max_adjustments: default_max_biome_adjustments(),
+        }
+    }
+}
+
+impl BiomeConstraints {
+    /// True without any bounds, the terrain is then assigned once as configured
+    pub fn is_empty(&self) -> bool {
+        self.terrains.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Bounds on the share [0, 1] of the land one land terrain covers, None is unbounded
+pub struct TerrainShareConstraint {
+    pub terrain: Terrain,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f32>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Temperature noise settings loaded from `biomes.yaml`.
 pub struct TemperatureConfig {
     pub continental_octaves: usize,
@@ -33,16 +143,273 @@ pub struct TemperatureConfig {
     pub detail_weight: f64,
     pub base_amplitude: f64,
     pub latitude_amp_floor: f64,
+    /// Axial tilt of the `SphericalLat` model, Earth's tilt when missing from the file
+    #[serde(default = "default_axial_tilt_degrees")]
+    pub axial_tilt_degrees: f64,
+}
+
+fn default_axial_tilt_degrees() -> f64 {
+    23.5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Seasonal temperature range (continentality) settings loaded from `biomes.yaml`.
+pub struct ContinentalityConfig {
+    /// Range of coastal land and water tiles
+    pub maritime_range: u8,
+    /// Range deep inside the largest landmasses
+    pub continental_range: u8,
+    /// Distance to the ocean (in tiles) at which the range is fully continental
+    pub distance_scale: f32,
+    /// Landmass size (in tiles) from which the range can become fully continental
+    pub size_scale: f32,
+}
+
+impl Default for ContinentalityConfig {
+    fn default() -> Self {
+        ContinentalityConfig {
+            maritime_range: 30,
+            continental_range: 220,
+            distance_scale: 6.0,
+            size_scale: 400.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Appeal each adjacent tile adds to a land tile, loaded from `biomes.yaml`.
+/// The defaults are the appeal the terrain or feature adds to its own tile.
+pub struct AppealConfig {
+    pub mountain: i8,
+    pub coast: i8,
+    pub woods: i8,
+    pub marsh: i8,
+    pub floodplains: i8,
+    pub desert: i8,
+    /// Lowest appeal a tile can have
+    pub min: i8,
+    /// Highest appeal a tile can have
+    pub max: i8,
+}
+
+impl Default for AppealConfig {
+    fn default() -> Self {
+        AppealConfig {
+            mountain: Terrain::Mountain.default_appeal() as i8,
+            coast: Terrain::CoastLake.default_appeal() as i8,
+            woods: Feature::Woods.appeal_bonus() as i8,
+            marsh: Feature::Marsh.appeal_bonus() as i8,
+            floodplains: Feature::Floodplains.appeal_bonus() as i8,
+            desert: Terrain::Desert.default_appeal() as i8,
+            min: -4,
+            max: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Geological age (flavor) settings loaded from `biomes.yaml`.
+/// The weights are normalized, so only their ratio matters.
+pub struct GeologicalAgeConfig {
+    /// Weight of the distance to the nearest mountain
+    pub ridge_weight: f32,
+    /// Weight of the distance to the nearest water
+    pub coast_weight: f32,
+    pub noise_weight: f32,
+    /// Distance (in tiles) from which a tile counts as fully old
+    pub distance_scale: f32,
+    pub noise: NoiseConfig,
+}
+
+impl Default for GeologicalAgeConfig {
+    fn default() -> Self {
+        GeologicalAgeConfig {
+            ridge_weight: 0.45,
+            coast_weight: 0.35,
+            noise_weight: 0.2,
+            distance_scale: 8.0,
+            noise: NoiseConfig {
+                octaves: 3,
+                scale: 30.0,
+                post_smooth_passes: 0,
+                post_smooth_kernel: SmoothKernel::Average,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Desert sub-variant and oasis settings loaded from `biomes.yaml`.
+pub struct DesertConfig {
+    /// Low-frequency noise splitting deserts into sand dunes and rocky desert
+    pub variant_noise: NoiseConfig,
+    /// Desert tiles with a noise value from this threshold on are rocky
+    pub rocky_threshold: u8,
+    /// Smallest connected desert region (in tiles) that gets oases, one oasis cluster per this many tiles
+    pub oasis_min_region_size: usize,
+    /// Smallest hex distance between two oasis clusters
+    pub oasis_min_distance: u32,
+    /// Chance [0, 1] that an oasis grows into a cluster of 2-3 tiles
+    pub oasis_cluster_chance: f32,
+}
+
+impl Default for DesertConfig {
+    fn default() -> Self {
+        DesertConfig {
+            variant_noise: NoiseConfig {
+                octaves: 2,
+                scale: 40.0,
+                post_smooth_passes: 0,
+                post_smooth_kernel: SmoothKernel::Average,
+            },
+            rocky_threshold: 128,
+            oasis_min_region_size: 12,
+            oasis_min_distance: 3,
+            oasis_cluster_chance: 0.25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Flood-prone lowland settings loaded from `biomes.yaml`, see `lowland_mask`.
+pub struct LowlandConfig {
+    /// Share [0, 1] of the lowest land tiles that can be lowland
+    pub elevation_quantile: f32,
+    /// Largest distance (in tiles) to the ocean or a river
+    pub water_radius: u32,
+}
+
+impl Default for LowlandConfig {
+    fn default() -> Self {
+        LowlandConfig {
+            elevation_quantile: 0.2,
+            water_radius: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Prevailing wind bands loaded from `biomes.yaml`, see `WindLayer`.
+pub struct WindConfig {
+    /// Latitudes in degrees (0 at the equator, 90 at the poles) at which the prevailing wind turns
+    pub band_latitudes: Vec<f32>,
+}
+
+impl Default for WindConfig {
+    /// Trade winds up to 30°, westerlies up to 60° and polar easterlies beyond
+    fn default() -> Self {
+        WindConfig {
+            band_latitudes: vec![30.0, 60.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Rainfall noise plus the coastal wetness boost, loaded from `biomes.yaml`.
+pub struct RainfallConfig {
+    #[serde(flatten)]
+    pub noise: NoiseConfig,
+    /// Rainfall added to land right at the ocean, fading out inland
+    #[serde(default = "default_coastal_boost")]
+    pub coastal_boost: u8,
+    /// Distance to the ocean (in tiles) at which the boost is gone
+    #[serde(default = "default_coastal_max_dist")]
+    pub coastal_max_dist: u16,
+}
+
+fn default_coastal_boost() -> u8 {
+    30
+}
+
+fn default_coastal_max_dist() -> u16 {
+    8
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Generic FBM noise settings.
 pub struct NoiseConfig {
     pub octaves: usize,
     pub scale: f64,
+    /// Smoothing passes over the sampled layer, against checkerboard patterns at small scales (0 = off)
+    #[serde(default)]
+    pub post_smooth_passes: usize,
+    #[serde(default)]
+    pub post_smooth_kernel: SmoothKernel,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Kernel of the post-smoothing passes over a noise layer, applied to a tile and its neighbors.
+pub enum SmoothKernel {
+    /// Median of the tile and its neighbors, the value the majority of them is on either side of
+    MajorityVote,
+    /// Mean of the tile and its neighbors
+    #[default]
+    Average,
+    /// Weighted mean with weights [1, 2, 1]: the tile counts twice, each neighbor once
+    Gaussian,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How rugged the terrain is, like the world age setting of Civ.
+/// The heightmap is post-processed before the terrain is assigned, and since the mountain and hill cutoffs are
+/// percentiles the thresholds are scaled as well (see `threshold_multipliers`).
+pub enum WorldAge {
+    /// Sharpened heightmap with craggier ranges, more mountains and hills
+    Young,
+    /// The heightmap as generated
+    #[default]
+    Standard,
+    /// Blurred heightmap with smoother terrain, fewer mountains and hills
+    Old,
+}
+
+impl WorldAge {
+    /// Every world age, from young to old
+    pub const ALL: [WorldAge; 3] = [WorldAge::Young, WorldAge::Standard, WorldAge::Old];
+
+    /// Name of the world age on the command line and in `biomes.yaml`
+    pub fn name(self) -> &'static str {
+        match self {
+            WorldAge::Young => "young",
+            WorldAge::Standard => "standard",
+            WorldAge::Old => "old",
+        }
+    }
+
+    /// World age by name, case-insensitive
+    pub fn from_name(name: &str) -> Option<WorldAge> {
+        WorldAge::ALL
+            .into_iter()
+            .find(|age| age.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// True for the default age, which leaves the heightmap and thresholds as configured
+    pub fn is_standard(&self) -> bool {
+        *self == WorldAge::Standard
+    }
+
+    /// Factors for `mountain_threshold` and `hill_threshold`:
+    /// young 1.5 and 1.25, standard 1 and 1, old 0.5 and 0.8
+    pub fn threshold_multipliers(self) -> (f32, f32) {
+        match self {
+            WorldAge::Young => (1.5, 1.25),
+            WorldAge::Standard => (1.0, 1.0),
+            WorldAge::Old => (0.5, 0.8),
+        }
+    }
+
+    /// Mountain and hill thresholds of this age, kept below 1 together
+    pub fn scale_thresholds(self, mountain_threshold: f32, hill_threshold: f32) -> (f32, f32) {
+        let (mountain_factor, hill_factor) = self.threshold_multipliers();
+        let mountain = (mountain_threshold * mountain_factor).min(1.0);
+        let hill = (hill_threshold * hill_factor).min(1.0 - mountain);
+        (mountain, hill)
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Root config for land generation loaded from `landmasses.yml`.
 pub struct LandmassesConfig {
     pub global: LandGlobalConfig,
@@ -54,7 +421,170 @@ pub struct LandmassesConfig {
     pub mirror: MirrorConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl LandmassesConfig {
+    /// Full config of a built-in preset, the embedded defaults with the land ratio, draft land
+    /// and landmass constraints of every map type replaced. `landmasses.yml` is not read.
+    pub fn from_presets(preset: LandPreset) -> LandmassesConfig {
+        let mut config = default_landmasses_config();
+        let (min_land, max_land) = preset.land_ratio();
+        for map_type in MapTypes::ALL {
+            let mut constraints = config.constraints_for(map_type).clone();
+            // the drafts grow with the land the map type is repaired into
+            let land_scale = (min_land + max_land) / (constraints.min_land_ratio + constraints.max_land_ratio);
+            for draft in config.drafts_mut(map_type) {
+                draft.base_land_percent = ((draft.base_land_percent as f32 * land_scale).round() as u32).clamp(1, 100);
+            }
+
+            constraints.min_land_ratio = min_land;
+            constraints.max_land_ratio = max_land;
+            match preset {
+                LandPreset::EarthLike => {}
+                LandPreset::OceanWorld => {
+                    constraints.min_largest_ratio = 0.0;
+                    constraints.min_lakes = 0;
+                }
+                LandPreset::LandWorld => {
+                    constraints.min_largest_ratio = 0.6;
+                    constraints.max_largest_ratio = 1.0;
+                    constraints.min_components = 1;
+                    constraints.min_islands = 0;
+                    constraints.max_lakes *= 2;
+                }
+                LandPreset::BalancedIslands => {
+                    constraints.min_largest_ratio = 0.0;
+                    constraints.max_largest_ratio = 0.2;
+                    constraints.min_components = 8;
+                    constraints.max_components = 30;
+                    constraints.min_islands = 6;
+                }
+            }
+            config = config.with_constraint_override(map_type, constraints);
+        }
+        // each terra world is repaired on its own before the merge
+        for world in [&mut config.terra.old_world, &mut config.terra.new_world] {
+            world.constraints.min_land_ratio = min_land;
+            world.constraints.max_land_ratio = max_land;
+        }
+        if preset == LandPreset::EarthLike {
+            config.global.latitude_land_profile = EARTHLIKE_LAND_PROFILE.to_vec();
+        }
+        config
+    }
+
+    /// Return a copy with all count-type repair settings scaled by `map_area / standard_area`.
+    /// The settings are tuned for Standard maps, so smaller maps get fewer and larger maps more edits.
+    pub fn scale_for_map_size(&self, size: &MapSizes) -> LandmassesConfig {
+        let factor = size.grid_size() as f32 / MapSizes::Standard.grid_size() as f32;
+
+        let mut scaled = self.clone();
+        for repair in [
+            &mut scaled.continents.repair,
+            &mut scaled.small_continents.repair,
+            &mut scaled.island_continents.repair,
+            &mut scaled.pangea.repair,
+            &mut scaled.terra.old_world.repair,
+            &mut scaled.terra.new_world.repair,
+            &mut scaled.terra.merged_repair,
+            &mut scaled.mirror.base.repair,
+        ] {
+            repair.scale_counts(factor);
+        }
+        scaled
+    }
+
+    /// Return the constraints the final map of the given type is checked against
+    pub fn constraints_for(&self, map_type: MapTypes) -> &ConstraintsConfig {
+        match map_type {
+            MapTypes::Continents => &self.continents.constraints,
+            MapTypes::SmallContinents => &self.small_continents.constraints,
+            MapTypes::IslandsContinents => &self.island_continents.constraints,
+            MapTypes::Pangea => &self.pangea.constraints,
+            MapTypes::Terra => &self.terra.merged_constraints,
+            MapTypes::Mirror => &self.mirror.base.constraints,
+        }
+    }
+
+    /// Replace the style of one map type.
+    /// Terra uses the style for both the old and the new world, mirror for its half map.
+    pub fn with_style_override(mut self, map_type: MapTypes, style: LandStyleConfig) -> Self {
+        match map_type {
+            MapTypes::Continents => self.continents = style,
+            MapTypes::SmallContinents => self.small_continents = style,
+            MapTypes::IslandsContinents => self.island_continents = style,
+            MapTypes::Pangea => self.pangea = style,
+            MapTypes::Terra => {
+                self.terra.old_world = style.clone();
+                self.terra.new_world = style;
+            }
+            MapTypes::Mirror => self.mirror.base = style,
+        }
+        self
+    }
+
+    /// Replace the constraints of one map type (for terra the constraints of the merged map)
+    pub fn with_constraint_override(mut self, map_type: MapTypes, constraints: ConstraintsConfig) -> Self {
+        match map_type {
+            MapTypes::Continents => self.continents.constraints = constraints,
+            MapTypes::SmallContinents => self.small_continents.constraints = constraints,
+            MapTypes::IslandsContinents => self.island_continents.constraints = constraints,
+            MapTypes::Pangea => self.pangea.constraints = constraints,
+            MapTypes::Terra => self.terra.merged_constraints = constraints,
+            MapTypes::Mirror => self.mirror.base.constraints = constraints,
+        }
+        self
+    }
+
+    /// Replace the repair settings of one map type (for terra the repair of the merged map)
+    pub fn with_repair_override(mut self, map_type: MapTypes, repair: RepairConfig) -> Self {
+        match map_type {
+            MapTypes::Continents => self.continents.repair = repair,
+            MapTypes::SmallContinents => self.small_continents.repair = repair,
+            MapTypes::IslandsContinents => self.island_continents.repair = repair,
+            MapTypes::Pangea => self.pangea.repair = repair,
+            MapTypes::Terra => self.terra.merged_repair = repair,
+            MapTypes::Mirror => self.mirror.base.repair = repair,
+        }
+        self
+    }
+
+    /// Drafts a map of the given type starts from, terra has one for each world
+    pub fn drafts(&self, map_type: MapTypes) -> Vec<&DraftConfig> {
+        match map_type {
+            MapTypes::Continents => vec![&self.continents.draft],
+            MapTypes::SmallContinents => vec![&self.small_continents.draft],
+            MapTypes::IslandsContinents => vec![&self.island_continents.draft],
+            MapTypes::Pangea => vec![&self.pangea.draft],
+            MapTypes::Terra => vec![&self.terra.old_world.draft, &self.terra.new_world.draft],
+            MapTypes::Mirror => vec![&self.mirror.base.draft],
+        }
+    }
+
+    /// Same as `drafts` for editing
+    pub fn drafts_mut(&mut self, map_type: MapTypes) -> Vec<&mut DraftConfig> {
+        match map_type {
+            MapTypes::Continents => vec![&mut self.continents.draft],
+            MapTypes::SmallContinents => vec![&mut self.small_continents.draft],
+            MapTypes::IslandsContinents => vec![&mut self.island_continents.draft],
+            MapTypes::Pangea => vec![&mut self.pangea.draft],
+            MapTypes::Terra => vec![&mut self.terra.old_world.draft, &mut self.terra.new_world.draft],
+            MapTypes::Mirror => vec![&mut self.mirror.base.draft],
+        }
+    }
+
+    /// Turn scale-invariant generation on or off, see `LandGlobalConfig::scale_invariant`
+    pub fn with_scale_invariant(mut self, scale_invariant: bool) -> Self {
+        self.global.scale_invariant = scale_invariant;
+        self
+    }
+
+    /// Replace the latitude land profile of all map types
+    pub fn with_latitude_land_profile(mut self, profile: Vec<f32>) -> Self {
+        self.global.latitude_land_profile = profile;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Shared global settings used by all map styles.
 pub struct LandGlobalConfig {
     pub base_factor: usize,
@@ -66,9 +596,91 @@ pub struct LandGlobalConfig {
     pub mid_max_min: usize,
     pub mid_max_max: usize,
     pub mid_max_divisor: usize,
+    /// Scale count-type repair settings (tuned for Standard maps) to the map area.
+    #[serde(default = "default_scale_to_map_size")]
+    pub scale_to_map_size: bool,
+    /// Share of a landmass coast the ruggedness pass edits at full ruggedness (0 disables the pass)
+    #[serde(default)]
+    pub coast_ruggedness_strength: f32,
+    /// Relative land weight of equally tall latitude bands from north to south, see `EARTHLIKE_LAND_PROFILE`.
+    /// Empty or all weights equal is flat (no bias).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub latitude_land_profile: Vec<f32>,
+    /// Seed the land draft on the coarse grid of a Standard map and sample the climate noise relative to the
+    /// map size, so one seed gives a similar world at every map size. Off by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub scale_invariant: bool,
+}
+
+/// Latitude land profile with Earth's share of land per 20 degree band, from the north pole to the south pole
+pub const EARTHLIKE_LAND_PROFILE: [f32; 9] = [0.35, 0.6, 0.47, 0.33, 0.24, 0.22, 0.08, 0.05, 0.3];
+
+/// Built-in latitude land profile by name (`flat` or `earthlike`)
+pub fn land_profile_preset(name: &str) -> Option<Vec<f32>> {
+    match name.to_ascii_lowercase().as_str() {
+        "flat" => Some(Vec::new()),
+        "earthlike" => Some(EARTHLIKE_LAND_PROFILE.to_vec()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Built-in land settings that replace `landmasses.yml` entirely, see `LandmassesConfig::from_presets`
+pub enum LandPreset {
+    /// About 30% land, spread over the latitudes like on Earth
+    EarthLike,
+    /// About 80% water with small landmasses
+    OceanWorld,
+    /// About 80% land, mostly a single landmass like pangea
+    LandWorld,
+    /// Many similar sized islands and no dominant continent
+    BalancedIslands,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl LandPreset {
+    /// Every built-in preset
+    pub const ALL: [LandPreset; 4] = [
+        LandPreset::EarthLike,
+        LandPreset::OceanWorld,
+        LandPreset::LandWorld,
+        LandPreset::BalancedIslands,
+    ];
+
+    /// Name of the preset on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            LandPreset::EarthLike => "earth-like",
+            LandPreset::OceanWorld => "ocean-world",
+            LandPreset::LandWorld => "land-world",
+            LandPreset::BalancedIslands => "balanced-islands",
+        }
+    }
+
+    /// Preset by name, case-insensitive with optional hyphens and underscores (`earthlike`, `ocean_world`)
+    pub fn from_name(name: &str) -> Option<LandPreset> {
+        let normalized: String = name
+            .trim()
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        LandPreset::ALL
+            .into_iter()
+            .find(|preset| preset.name().replace('-', "") == normalized)
+    }
+
+    /// Land ratio range every map type of the preset is repaired into
+    fn land_ratio(self) -> (f32, f32) {
+        match self {
+            LandPreset::EarthLike => (0.25, 0.38),
+            LandPreset::OceanWorld => (0.12, 0.22),
+            LandPreset::LandWorld => (0.72, 0.88),
+            LandPreset::BalancedIslands => (0.30, 0.45),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Draft + constraints + repair knobs for a map style.
 pub struct LandStyleConfig {
     pub draft: DraftConfig,
@@ -76,7 +688,7 @@ pub struct LandStyleConfig {
     pub repair: RepairConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// First-pass generation controls before analysis/repair.
 pub struct DraftConfig {
     pub base_land_percent: u32,
@@ -84,9 +696,37 @@ pub struct DraftConfig {
     pub coast_island_percent: u32,
     pub smoothing_passes: usize,
     pub center_bias: f32,
+    /// Rows at the top and bottom of the map that are forced to water while drafting (polar ice extent).
+    /// The default of 1 matches the regular map border.
+    #[serde(default = "default_polar_water_rows")]
+    pub polar_water_rows: usize,
+    /// Width in tiles of a soft land exclusion band along the map border.
+    /// 0 (the default) forces the border to water while drafting, a wider band lowers the
+    /// land chance near the border instead, so land is less likely but possible there.
+    #[serde(default)]
+    pub coast_band_width: usize,
+    /// Land percent taken off `base_land_percent` for every tile a draft cell lies inside the coast band
+    #[serde(default = "default_coast_band_penalty_per_tile")]
+    pub coast_band_penalty_per_tile: f32,
+}
+
+fn default_scale_to_map_size() -> bool {
+    true
+}
+
+fn default_mirror_climate() -> bool {
+    true
+}
+
+fn default_polar_water_rows() -> usize {
+    1
+}
+
+fn default_coast_band_penalty_per_tile() -> f32 {
+    4.0
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 /// Acceptance constraints for analyze/repair.
 pub struct ConstraintsConfig {
     pub min_land_ratio: f32,
@@ -98,9 +738,42 @@ pub struct ConstraintsConfig {
     pub min_islands: usize,
     pub min_lakes: usize,
     pub max_lakes: usize,
+    /// Lowest compactness allowed for landmasses larger than an island (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_compactness: Option<f32>,
+    /// Highest compactness allowed for landmasses larger than an island (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_compactness: Option<f32>,
+}
+
+impl ConstraintsConfig {
+    /// Copy of these constraints with the set fields of `override_with` replaced.
+    /// Zero and NaN fields (and `None` compactness bounds) in `override_with` leave the field unchanged,
+    /// so `ConstraintsConfig { min_land_ratio: 0.4, ..Default::default() }` only changes the land ratio.
+    pub fn merge_override(&self, override_with: &ConstraintsConfig) -> ConstraintsConfig {
+        let ratio = |base: f32, value: f32| if value == 0.0 || value.is_nan() { base } else { value };
+        let count = |base: usize, value: usize| if value == 0 { base } else { value };
+        let bound = |base: Option<f32>, value: Option<f32>| match value {
+            Some(v) if v != 0.0 && !v.is_nan() => Some(v),
+            _ => base,
+        };
+        ConstraintsConfig {
+            min_land_ratio: ratio(self.min_land_ratio, override_with.min_land_ratio),
+            max_land_ratio: ratio(self.max_land_ratio, override_with.max_land_ratio),
+            min_largest_ratio: ratio(self.min_largest_ratio, override_with.min_largest_ratio),
+            max_largest_ratio: ratio(self.max_largest_ratio, override_with.max_largest_ratio),
+            min_components: count(self.min_components, override_with.min_components),
+            max_components: count(self.max_components, override_with.max_components),
+            min_islands: count(self.min_islands, override_with.min_islands),
+            min_lakes: count(self.min_lakes, override_with.min_lakes),
+            max_lakes: count(self.max_lakes, override_with.max_lakes),
+            min_compactness: bound(self.min_compactness, override_with.min_compactness),
+            max_compactness: bound(self.max_compactness, override_with.max_compactness),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Repair behavior knobs used by the deterministic post-processing pass.
 pub struct RepairConfig {
     pub largest_carve_trigger_ratio: f32,
@@ -119,9 +792,50 @@ pub struct RepairConfig {
     pub land_ratio_adjust_cap_divisor: usize,
     pub lake_blob_min: usize,
     pub lake_blob_max: usize,
+    /// Minimum width of a land connection left behind after carving (0 disables the check).
+    #[serde(default)]
+    pub min_connection_width: usize,
+    /// Minimum length of a one-tile-wide land string before it is resolved.
+    #[serde(default)]
+    pub thin_string_min_length: usize,
+    /// Thicken thin strings into an isthmus instead of carving them away.
+    #[serde(default)]
+    pub thicken_thin_strings: bool,
+    /// Protect land tiles holding peninsulas together from erosion (0 disables) and strait carving.
+    /// Tiles within `peninsula_min_width - 1` steps of such a tile are protected as well.
+    /// Strait carving always protects the neck tiles themselves, whatever the width.
+    #[serde(default)]
+    pub peninsula_min_width: usize,
+}
+
+impl RepairConfig {
+    /// Multiply all count-type fields by `factor`.
+    /// Counts that are enabled (> 0) never drop below 1, and blob ranges stay valid.
+    fn scale_counts(&mut self, factor: f32) {
+        let scale = |value: usize| {
+            if value == 0 {
+                0
+            } else {
+                ((value as f32 * factor).round() as usize).max(1)
+            }
+        };
+
+        self.largest_carve_base_count = scale(self.largest_carve_base_count);
+        self.channel_carve_count = scale(self.channel_carve_count);
+        self.island_extra_missing_floor = scale(self.island_extra_missing_floor);
+        self.pangea_fill_internal_count = scale(self.pangea_fill_internal_count);
+        self.pangea_connect_count = scale(self.pangea_connect_count);
+        self.pangea_connect_when_split = scale(self.pangea_connect_when_split);
+        self.terra_grow_budget = scale(self.terra_grow_budget);
+
+        self.island_min_blob = scale(self.island_min_blob);
+        self.island_max_blob = scale(self.island_max_blob).max(self.island_min_blob);
+        self.lake_blob_min = scale(self.lake_blob_min);
+        self.lake_blob_max = scale(self.lake_blob_max).max(self.lake_blob_min);
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Terra settings for ocean barrier and split-world generation.
 pub struct TerraConfig {
     pub old_world: LandStyleConfig,
@@ -132,16 +846,112 @@ pub struct TerraConfig {
     pub barrier_max: usize,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Mirror settings where the right side is reflected from the left side.
 pub struct MirrorConfig {
     pub base: LandStyleConfig,
     pub half_smoothing_passes: usize,
+    /// Also mirror temperature, rainfall and heightmap so both halves get the same biomes
+    #[serde(default = "default_mirror_climate")]
+    pub mirror_climate: bool,
+    /// Axis the map is mirrored along
+    #[serde(default)]
+    pub axis: MirrorAxis,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How mirror maps are reflected. The left and/or top part of the map is generated, the rest is reflected from it.
+pub enum MirrorAxis {
+    /// Left half reflected onto the right half
+    #[default]
+    Vertical,
+    /// Top half reflected onto the bottom half
+    Horizontal,
+    /// Top-left quarter reflected onto the other three quarters
+    FourWay,
+}
+
+impl MirrorAxis {
+    /// Whether the right half is reflected from the left half
+    pub fn mirrors_columns(&self) -> bool {
+        matches!(self, Self::Vertical | Self::FourWay)
+    }
+
+    /// Whether the bottom half is reflected from the top half
+    pub fn mirrors_rows(&self) -> bool {
+        matches!(self, Self::Horizontal | Self::FourWay)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Resource placement settings loaded from `resources.yaml`.
+pub struct ResourcesConfig {
+    pub strategic: StrategicConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Vein placement of the strategic resources, see `place_strategic_veins`.
+pub struct StrategicConfig {
+    /// Veins on a Standard map, scaled by the map area
+    pub veins_per_standard_map: f32,
+    /// Share of the veins kept on islands-continents maps, whose small landmasses rarely fit a vein
+    pub islands_continents_factor: f32,
+    /// Smallest landmass (in tiles) guaranteed a vein, 0 uses the island size limit of the map (`MapScaling::island_max`)
+    pub guaranteed_min_landmass: usize,
+    pub iron: VeinConfig,
+    pub horses: VeinConfig,
+    pub niter: VeinConfig,
+}
+
+impl StrategicConfig {
+    /// Return the vein settings of a resource
+    pub fn get(&self, resource: StrategicResource) -> &VeinConfig {
+        match resource {
+            StrategicResource::Iron => &self.iron,
+            StrategicResource::Horses => &self.horses,
+            StrategicResource::Niter => &self.niter,
+        }
+    }
+
+    /// Most veins a map of this size and type gets, guaranteed veins included
+    pub fn vein_budget(&self, size: &MapSizes, map_type: MapTypes) -> usize {
+        let factor = if map_type == MapTypes::IslandsContinents { self.islands_continents_factor } else { 1.0 };
+        let veins = self.veins_per_standard_map * MapScaling::for_size(size).area_ratio * factor;
+        (veins.round() as usize).max(1)
+    }
+
+    /// Smallest landmass (in tiles) on a map of this size that is guaranteed a vein
+    pub fn guaranteed_min_landmass(&self, size: &MapSizes) -> usize {
+        match self.guaranteed_min_landmass {
+            0 => MapScaling::for_size(size).island_max + 1,
+            tiles => tiles,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Shape and placement of the veins of one strategic resource
+pub struct VeinConfig {
+    /// Relative share of the veins
+    pub weight: u32,
+    pub min_deposits: usize,
+    pub max_deposits: usize,
+    /// Every deposit lies within this many tiles of the first one, picked per vein from the range
+    pub min_radius: u32,
+    pub max_radius: u32,
+    /// Land terrains a deposit can be on
+    pub terrains: Vec<Terrain>,
+    /// How strongly old terrain (see `generate_geological_age`) is preferred for vein centers,
+    /// 0 (the age does not matter) to 1 (the youngest terrain never holds a center). Guaranteed veins ignore it
+    #[serde(default)]
+    pub age_weight: f32,
 }
 
 /// Default biome config used when `biomes.yaml` is not available.
 pub fn default_biomes_config() -> BiomesConfig {
     BiomesConfig {
+        temperature_model: TemperatureModel::default(),
         terrain: TerrainThresholds {
             mountain_threshold: 0.05,
             hill_threshold: 0.2,
@@ -150,6 +960,9 @@ pub fn default_biomes_config() -> BiomesConfig {
             desert_temp_threshold: 150,
             desert_rain_threshold: 85,
             grassland_rain_threshold: 155,
+            threshold_dither_window: 6,
+            dither_elevation_bias: 0.3,
+            open_dead_lakes: false,
         },
         temperature: TemperatureConfig {
             continental_octaves: 4,
@@ -160,15 +973,34 @@ pub fn default_biomes_config() -> BiomesConfig {
             detail_weight: 0.3,
             base_amplitude: 0.18,
             latitude_amp_floor: 0.5,
+            axial_tilt_degrees: default_axial_tilt_degrees(),
         },
-        rainfall: NoiseConfig {
-            octaves: 5,
-            scale: 60.0,
+        rainfall: RainfallConfig {
+            noise: NoiseConfig {
+                octaves: 5,
+                scale: 60.0,
+                post_smooth_passes: 0,
+                post_smooth_kernel: SmoothKernel::Average,
+            },
+            coastal_boost: default_coastal_boost(),
+            coastal_max_dist: default_coastal_max_dist(),
         },
         heightmap: NoiseConfig {
             octaves: 5,
             scale: 40.0,
+            post_smooth_passes: 0,
+            post_smooth_kernel: SmoothKernel::Average,
         },
+        continentality: ContinentalityConfig::default(),
+        smooth_elevation_before_biome_assign: true,
+        appeal: AppealConfig::default(),
+        geological_age: GeologicalAgeConfig::default(),
+        desert: DesertConfig::default(),
+        tile_modifiers: TileModifierRules::default(),
+        lowland: LowlandConfig::default(),
+        wind: WindConfig::default(),
+        world_age: WorldAge::Standard,
+        biome_constraints: BiomeConstraints::default(),
     }
 }
 
@@ -187,6 +1019,9 @@ fn default_style(
             coast_island_percent,
             smoothing_passes,
             center_bias,
+            polar_water_rows: default_polar_water_rows(),
+            coast_band_width: 0,
+            coast_band_penalty_per_tile: default_coast_band_penalty_per_tile(),
         },
         constraints,
         repair: RepairConfig {
@@ -206,6 +1041,10 @@ fn default_style(
             land_ratio_adjust_cap_divisor: 10,
             lake_blob_min: 4,
             lake_blob_max: 7,
+            min_connection_width: 0,
+            thin_string_min_length: 0,
+            thicken_thin_strings: false,
+            peninsula_min_width: 0,
         },
     }
 }
@@ -222,6 +1061,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 2,
         min_lakes: 1,
         max_lakes: 4,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     let small_constraints = ConstraintsConfig {
@@ -234,6 +1075,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 6,
         min_lakes: 1,
         max_lakes: 6,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     let island_constraints = ConstraintsConfig {
@@ -246,6 +1089,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 12,
         min_lakes: 0,
         max_lakes: 3,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     let pangea_constraints = ConstraintsConfig {
@@ -258,6 +1103,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 1,
         min_lakes: 1,
         max_lakes: 6,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     let terra_merged_constraints = ConstraintsConfig {
@@ -270,6 +1117,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 2,
         min_lakes: 1,
         max_lakes: 4,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     let mirror_constraints = ConstraintsConfig {
@@ -282,6 +1131,8 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         min_islands: 2,
         min_lakes: 0,
         max_lakes: 5,
+        min_compactness: None,
+        max_compactness: None,
     };
 
     LandmassesConfig {
@@ -295,6 +1146,10 @@ pub fn default_landmasses_config() -> LandmassesConfig {
             mid_max_min: 120,
             mid_max_max: 260,
             mid_max_divisor: 28,
+            scale_to_map_size: default_scale_to_map_size(),
+            coast_ruggedness_strength: 0.0,
+            latitude_land_profile: Vec::new(),
+            scale_invariant: false,
         },
         continents: default_style(9, 7, 5, 2, 0.0, continents_constraints),
         small_continents: default_style(8, 12, 8, 1, 0.0, small_constraints),
@@ -321,6 +1176,10 @@ pub fn default_landmasses_config() -> LandmassesConfig {
                 land_ratio_adjust_cap_divisor: 10,
                 lake_blob_min: 4,
                 lake_blob_max: 7,
+                min_connection_width: 0,
+                thin_string_min_length: 0,
+                thicken_thin_strings: false,
+                peninsula_min_width: 0,
             },
             barrier_min: 6,
             barrier_max: 12,
@@ -328,6 +1187,31 @@ pub fn default_landmasses_config() -> LandmassesConfig {
         mirror: MirrorConfig {
             base: default_style(9, 9, 5, 1, 0.0, mirror_constraints),
             half_smoothing_passes: 2,
+            mirror_climate: default_mirror_climate(),
+            axis: MirrorAxis::Vertical,
+        },
+    }
+}
+
+/// Default resource config used when `resources.yaml` is not available.
+pub fn default_resources_config() -> ResourcesConfig {
+    let vein = |weight, terrains: &[Terrain]| VeinConfig {
+        weight,
+        min_deposits: 2,
+        max_deposits: 5,
+        min_radius: 4,
+        max_radius: 6,
+        terrains: terrains.to_vec(),
+        age_weight: 0.0,
+    };
+    ResourcesConfig {
+        strategic: StrategicConfig {
+            veins_per_standard_map: 14.0,
+            islands_continents_factor: 0.5,
+            guaranteed_min_landmass: 0,
+            iron: vein(3, &[Terrain::Plains, Terrain::Grassland, Terrain::Desert, Terrain::Tundra, Terrain::Snow]),
+            horses: vein(3, &[Terrain::Plains, Terrain::Grassland]),
+            niter: vein(2, &[Terrain::Plains, Terrain::Grassland, Terrain::Desert, Terrain::Tundra]),
         },
     }
 }
@@ -348,64 +1232,246 @@ pub fn landmasses_config_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../landmasses.yml")
 }
 
+/// Location of `resources.yaml`.
+pub fn resources_config_path() -> PathBuf {
+    if let Ok(path) = std::env::var("CIVORUM_RESOURCES_CONFIG") {
+        return PathBuf::from(path);
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../resources.yaml")
+}
+
+/// Load and parse biome config from yaml, returning a readable error on failure.
+pub fn try_load_biomes_config() -> Result<BiomesConfig, String> {
+    try_load_biomes_config_from(&biomes_config_path())
+}
+
+/// Load and parse the biome config at `path`, returning a readable error on failure.
+pub fn try_load_biomes_config_from(path: &Path) -> Result<BiomesConfig, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read biome config at '{}': {err}", path.display()))?;
+    serde_yaml::from_str::<BiomesConfig>(&raw)
+        .map_err(|err| format!("Failed to parse biome config at '{}': {err}", path.display()))
+}
+
+/// Load and parse landmass config from yaml, returning a readable error on failure.
+pub fn try_load_landmasses_config() -> Result<LandmassesConfig, String> {
+    try_load_landmasses_config_from(&landmasses_config_path())
+}
+
+/// Load and parse the landmass config at `path`, returning a readable error on failure.
+pub fn try_load_landmasses_config_from(path: &Path) -> Result<LandmassesConfig, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read landmass config at '{}': {err}", path.display()))?;
+    serde_yaml::from_str::<LandmassesConfig>(&raw)
+        .map_err(|err| format!("Failed to parse landmass config at '{}': {err}", path.display()))
+}
+
+/// Load and parse resource config from yaml, returning a readable error on failure.
+pub fn try_load_resources_config() -> Result<ResourcesConfig, String> {
+    try_load_resources_config_from(&resources_config_path())
+}
+
+/// Load and parse the resource config at `path`, returning a readable error on failure.
+pub fn try_load_resources_config_from(path: &Path) -> Result<ResourcesConfig, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read resource config at '{}': {err}", path.display()))?;
+    serde_yaml::from_str::<ResourcesConfig>(&raw)
+        .map_err(|err| format!("Failed to parse resource config at '{}': {err}", path.display()))
+}
+
 /// Load and parse biome config from yaml.
 pub fn load_biomes_config() -> BiomesConfig {
-    let path = biomes_config_path();
-    match fs::read_to_string(&path) {
-        Ok(raw) => match serde_yaml::from_str::<BiomesConfig>(&raw) {
-            Ok(config) => config,
-            Err(err) => {
-                eprintln!(
-                    "Failed to parse biome config at '{}': {err}. Falling back to defaults.",
-                    path.display()
-                );
-                default_biomes_config()
-            }
-        },
-        Err(err) => {
-            eprintln!(
-                "Failed to read biome config at '{}': {err}. Falling back to defaults.",
-                path.display()
-            );
-            default_biomes_config()
-        }
-    }
+    try_load_biomes_config().unwrap_or_else(|err| {
+        eprintln!("{err}. Falling back to defaults.");
+        default_biomes_config()
+    })
 }
 
 /// Load and parse landmass config from yaml.
 pub fn load_landmasses_config() -> LandmassesConfig {
-    let path = landmasses_config_path();
-    match fs::read_to_string(&path) {
-        Ok(raw) => match serde_yaml::from_str::<LandmassesConfig>(&raw) {
-            Ok(config) => config,
-            Err(err) => {
-                eprintln!(
-                    "Failed to parse landmass config at '{}': {err}. Falling back to defaults.",
-                    path.display()
-                );
-                default_landmasses_config()
-            }
-        },
-        Err(err) => {
-            eprintln!(
-                "Failed to read landmass config at '{}': {err}. Falling back to defaults.",
-                path.display()
-            );
-            default_landmasses_config()
+    try_load_landmasses_config().unwrap_or_else(|err| {
+        eprintln!("{err}. Falling back to defaults.");
+        default_landmasses_config()
+    })
+}
+
+/// Load and parse resource config from yaml.
+pub fn load_resources_config() -> ResourcesConfig {
+    try_load_resources_config().unwrap_or_else(|err| {
+        eprintln!("{err}. Falling back to defaults.");
+        default_resources_config()
+    })
+}
+
+fn biomes_slot() -> &'static RwLock<Arc<BiomesConfig>> {
+    static CONFIG: OnceLock<RwLock<Arc<BiomesConfig>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Arc::new(load_biomes_config())))
+}
+
+fn landmasses_slot() -> &'static RwLock<Arc<LandmassesConfig>> {
+    static CONFIG: OnceLock<RwLock<Arc<LandmassesConfig>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Arc::new(load_landmasses_config())))
+}
+
+fn resources_slot() -> &'static RwLock<Arc<ResourcesConfig>> {
+    static CONFIG: OnceLock<RwLock<Arc<ResourcesConfig>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Arc::new(load_resources_config())))
+}
+
+thread_local! {
+    static SCOPED_BIOMES: RefCell<Option<Arc<BiomesConfig>>> = const { RefCell::new(None) };
+    static SCOPED_LANDMASSES: RefCell<Option<Arc<LandmassesConfig>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `scoped` holding `config` on the current thread, the previous config is restored afterwards
+/// (also if `f` panics) and `config` is dropped once no caller holds it anymore
+fn with_scoped_config<T: 'static, R>(
+    scoped: &'static LocalKey<RefCell<Option<Arc<T>>>>,
+    config: T,
+    f: impl FnOnce() -> R,
+) -> R {
+    struct Restore<T: 'static>(&'static LocalKey<RefCell<Option<Arc<T>>>>, Option<Arc<T>>);
+    impl<T> Drop for Restore<T> {
+        fn drop(&mut self) {
+            let previous = self.1.take();
+            self.0.with(|slot| *slot.borrow_mut() = previous);
         }
     }
+
+    let previous = scoped.with(|slot| slot.borrow_mut().replace(Arc::new(config)));
+    let _restore = Restore(scoped, previous);
+    f()
+}
+
+/// Cached biome config singleton, or the config of an enclosing `with_biomes_config` on this thread.
+pub fn biomes_config() -> Arc<BiomesConfig> {
+    SCOPED_BIOMES
+        .with(|slot| slot.borrow().clone())
+        .unwrap_or_else(|| biomes_slot().read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone())
+}
+
+/// Run `f` with `biomes_config` returning `config` on the current thread only, see `with_landmasses_config`.
+pub fn with_biomes_config<R>(config: BiomesConfig, f: impl FnOnce() -> R) -> R {
+    with_scoped_config(&SCOPED_BIOMES, config, f)
+}
+
+/// Cached landmass config singleton, or the config of an enclosing `with_landmasses_config` on this thread.
+pub fn landmasses_config() -> Arc<LandmassesConfig> {
+    SCOPED_LANDMASSES
+        .with(|slot| slot.borrow().clone())
+        .unwrap_or_else(|| landmasses_slot().read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone())
 }
 
-/// Cached biome config singleton.
-pub fn biomes_config() -> &'static BiomesConfig {
-    static CONFIG: OnceLock<BiomesConfig> = OnceLock::new();
-    CONFIG.get_or_init(load_biomes_config)
+/// Run `f` with `landmasses_config` returning `config` on the current thread only.
+/// Unlike `set_landmasses_config` this does not affect maps generated on other threads.
+/// The config is freed once `f` returned and no caller holds it anymore.
+pub fn with_landmasses_config<R>(config: LandmassesConfig, f: impl FnOnce() -> R) -> R {
+    with_scoped_config(&SCOPED_LANDMASSES, config, f)
 }
 
-/// Cached landmass config singleton.
-pub fn landmasses_config() -> &'static LandmassesConfig {
-    static CONFIG: OnceLock<LandmassesConfig> = OnceLock::new();
-    CONFIG.get_or_init(load_landmasses_config)
+/// Cached resource config singleton.
+pub fn resources_config() -> Arc<ResourcesConfig> {
+    resources_slot().read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+/// Re-read all config files and replace the cached configs.
+/// Nothing is replaced if any file fails to load, so a malformed edit keeps the previous configs.
+/// Callers still holding a previous config keep it until they drop it.
+pub fn reload_configs() -> Result<(), String> {
+    reload_configs_from(&landmasses_config_path(), &biomes_config_path(), &resources_config_path())
+}
+
+/// Same as `reload_configs` for config files at other locations
+pub fn reload_configs_from(landmasses: &Path, biomes: &Path, resources: &Path) -> Result<(), String> {
+    let landmasses = try_load_landmasses_config_from(landmasses)?;
+    let biomes = try_load_biomes_config_from(biomes)?;
+    let resources = try_load_resources_config_from(resources)?;
+
+    *landmasses_slot().write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(landmasses);
+    *biomes_slot().write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(biomes);
+    *resources_slot().write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(resources);
+    Ok(())
+}
+
+/// Replace the cached biome config, e.g. with different tile modifier rules.
+/// Callers still holding the previous config keep it, like in `reload_configs`.
+pub fn set_biomes_config(config: BiomesConfig) {
+    *biomes_slot().write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(config);
+}
+
+/// Replace the cached landmass config, e.g. with a preset built by the `with_*_override` methods.
+/// Callers still holding the previous config keep it, like in `reload_configs`.
+pub fn set_landmasses_config(config: LandmassesConfig) {
+    *landmasses_slot().write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(config);
+}
+
+/// Landmass config for generating a map of the given size.
+/// Scaled with `scale_for_map_size` unless `global.scale_to_map_size` is disabled.
+pub fn landmasses_config_for(size: &MapSizes) -> LandmassesConfig {
+    let config = landmasses_config();
+    if config.global.scale_to_map_size {
+        config.scale_for_map_size(size)
+    } else {
+        config.as_ref().clone()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The fully merged config a map is generated with.
+/// Together with the seed, size and map type this is everything needed to reproduce a map.
+pub struct EffectiveConfig {
+    pub landmasses: LandmassesConfig,
+    pub biomes: BiomesConfig,
+    #[serde(default = "default_resources_config")]
+    pub resources: ResourcesConfig,
+}
+
+impl EffectiveConfig {
+    /// Stable canonical serialization of the config (YAML with fields in declaration order).
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("config structs always serialize")
+    }
+
+    /// Parse a config previously written with `to_yaml`.
+    pub fn from_yaml(raw: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(raw)
+    }
+
+    /// Stable 64-bit FNV-1a hash of the canonical serialization.
+    /// Unlike `DefaultHasher` this does not change between Rust versions.
+    pub fn hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.to_yaml().bytes().fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+/// Return the effective config used for generation (after file + env).
+pub fn effective_config() -> EffectiveConfig {
+    EffectiveConfig {
+        landmasses: landmasses_config().as_ref().clone(),
+        biomes: biomes_config().as_ref().clone(),
+        resources: resources_config().as_ref().clone(),
+    }
+}
+
+/// Hash of the effective config, used to tie a seed to the config it was generated with.
+pub fn config_hash() -> u64 {
+    effective_config().hash()
+}
+
+/// Deterministic hash of a tile index for a given seed (splitmix64 finalizer).
+/// Used for per-tile decisions that must not depend on RNG stream order.
+pub fn tile_hash(seed: u64, idx: usize) -> u64 {
+    let mut z = seed
+        .wrapping_add((idx as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+        .wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
 /// Helper function for odd-r neighbors for pointy-top hexes.
@@ -452,3 +1518,13 @@ pub fn neighbors_odd_r(x: usize, y: usize, width: usize, height: usize) -> Vec<(
 
     out
 }
+
+/// Iterate over every pair of adjacent tiles once, as `(a, b)` row-major indices with `a < b`.
+pub fn adjacency_pairs(width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..width * height).flat_map(move |idx| {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(move |(nx, ny)| (idx, ny * width + nx))
+            .filter(|(a, b)| a < b)
+    })
+}
@@ -1,17 +1,23 @@
-use std::collections::VecDeque;
+use std::{cell::RefCell, collections::VecDeque};
 
 use rand_chacha::{
     ChaCha12Rng,
     rand_core::{Rng, SeedableRng},
 };
 
-use crate::pipeline::{
-    helpers::{
-        ConstraintsConfig, DraftConfig, LandGlobalConfig, RepairConfig, landmasses_config,
-        neighbors_odd_r,
+use crate::{
+    map_components::hex_coords::HexCoord,
+    pipeline::{
+        analysis::{coast_ruggedness, hex_compactness, label_landmask},
+        constraints::{ConstraintKind, ConstraintOutcome},
+        error::{GenError, validate_landmasses_config},
+        helpers::{
+            ConstraintsConfig, DraftConfig, LandGlobalConfig, MirrorAxis, RepairConfig, landmasses_config,
+            landmasses_config_for, neighbors_odd_r,
+        },
+        map_sizes::{MapScaling, MapSizes},
+        map_types::MapTypes,
     },
-    map_sizes::MapSizes,
-    map_types::MapTypes,
 };
 
 #[derive(Default)]
@@ -27,9 +33,102 @@ struct LandAnalysis {
     largest_component_idx: Option<usize>,
     land_component_sizes: Vec<usize>,
     land_component_ids: Vec<usize>,
+    /// (component id, compactness) of every component larger than an island
+    major_compactness: Vec<(usize, f32)>,
     ocean_mask: Vec<bool>,
 }
 
+#[derive(Default)]
+/// Scratch vectors of the flood fills in `analyze_landmask_into`, cleared and refilled instead of reallocated.
+struct AnalysisBuffers {
+    queue: VecDeque<(usize, usize)>,
+    water_component_ids: Vec<usize>,
+    water_component_sizes: Vec<usize>,
+    touches_border: Vec<bool>,
+    perimeters: Vec<usize>,
+}
+
+/// RNG and scratch vectors shared by the repair helpers across repair iterations,
+/// the candidate rankings are cleared and refilled instead of reallocated.
+struct RepairScratch<'r> {
+    rng: &'r mut ChaCha12Rng,
+    buffers: AnalysisBuffers,
+    /// (score, tie-break, tile) candidates of the carve and grow repairs
+    candidates: Vec<(i32, u64, usize)>,
+    /// (tie-break, tile) candidates of `sprinkle_islands` and the coastal candidates of `force_land_ratio`
+    tiles: Vec<(u64, usize)>,
+    /// (tie-break, tile) fallback candidates of `force_land_ratio`
+    fallback_tiles: Vec<(u64, usize)>,
+}
+
+impl<'r> RepairScratch<'r> {
+    fn new(rng: &'r mut ChaCha12Rng) -> Self {
+        RepairScratch {
+            rng,
+            buffers: AnalysisBuffers::default(),
+            candidates: Vec::new(),
+            tiles: Vec::new(),
+            fallback_tiles: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+/// Number of repair passes that actually ran before the constraints were met (or the budget ran out).
+pub struct RepairIterationCount(pub u32);
+
+impl RepairIterationCount {
+    /// Return the number of repair passes
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// True when the repair loop used the whole `max_repair_iters` budget,
+    /// which means the constraints were most likely not met.
+    pub fn exhausted_budget(&self) -> bool {
+        self.0 as usize >= landmasses_config().global.max_repair_iters
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Landmask snapshot taken while `GenerationStepRecorder::record` runs.
+pub struct GenerationStep {
+    /// `initial` before the first repair pass, `repair<n>` after pass n and `final` for the finished landmask
+    pub label: String,
+    /// Landmask at this step (1 = land, 0 = water)
+    pub landmask: Vec<u8>,
+}
+
+thread_local! {
+    static RECORDED_STEPS: RefCell<Option<Vec<GenerationStep>>> = const { RefCell::new(None) };
+}
+
+/// Collects the landmask of every repair pass, to debug how the repair loop converges.
+pub struct GenerationStepRecorder;
+
+impl GenerationStepRecorder {
+    /// Run `f` and return its result with the steps of every landmass generated by it on this thread.
+    /// Terra and mirror maps record the steps of their repair loop on the full map.
+    pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<GenerationStep>) {
+        let previous = RECORDED_STEPS.replace(Some(Vec::new()));
+        let result = f();
+        let steps = RECORDED_STEPS.replace(previous).unwrap_or_default();
+        (result, steps)
+    }
+}
+
+/// Save a copy of the landmask if a recorder is running, the label is only built then
+fn record_step(label: impl FnOnce() -> String, grid: &[u8]) {
+    RECORDED_STEPS.with_borrow_mut(|steps| {
+        if let Some(steps) = steps {
+            steps.push(GenerationStep {
+                label: label(),
+                landmask: grid.to_vec(),
+            });
+        }
+    });
+}
+
 #[derive(Clone, Copy)]
 /// Internal enum describing the repair behavior for each map style.
 enum RepairStyle {
@@ -38,24 +137,250 @@ enum RepairStyle {
     IslandContinents,
     Pangea,
     Terra,
-    Mirror,
+    Mirror(MirrorAxis),
 }
 
 /// Generate land for the requested map type.
-pub fn generate_landmasses(seed: u64, size: &MapSizes, map_type: MapTypes) -> Vec<u8> {
-    match map_type {
+///
+/// # Panics
+/// If the landmass config is invalid, see `try_generate_landmasses`. Never for the shipped configs.
+pub fn generate_landmasses(seed: u64, size: &MapSizes, map_type: MapTypes) -> (Vec<u8>, RepairIterationCount) {
+    try_generate_landmasses(seed, size, map_type).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Same as `generate_landmasses` but returns an error if the landmass config can not produce a map of this type.
+pub fn try_generate_landmasses(
+    seed: u64,
+    size: &MapSizes,
+    map_type: MapTypes,
+) -> Result<(Vec<u8>, RepairIterationCount), GenError> {
+    let (grid, iterations, _) = try_generate_landmasses_with_ruggedness(seed, size, map_type)?;
+    Ok((grid, iterations))
+}
+
+/// Landmask, repair passes and per tile coast ruggedness, see `try_generate_landmasses_with_ruggedness`
+pub(crate) type RuggedLandmasses = (Vec<u8>, RepairIterationCount, Vec<Option<f32>>);
+
+/// Same as `try_generate_landmasses`, also returns the coast ruggedness the coast pass applied to the
+/// landmass of every land tile (see `vary_coast_ruggedness`). All None on mirror maps.
+pub(crate) fn try_generate_landmasses_with_ruggedness(
+    seed: u64,
+    size: &MapSizes,
+    map_type: MapTypes,
+) -> Result<RuggedLandmasses, GenError> {
+    validate_landmasses_config(&landmasses_config_for(size), map_type)?;
+    let (mut grid, iterations) = match map_type {
         MapTypes::Continents => generate_continents(seed, size),
         MapTypes::SmallContinents => generate_small_continents(seed, size),
         MapTypes::IslandsContinents => generate_island_continents(seed, size),
         MapTypes::Pangea => generate_pangea(seed, size),
         MapTypes::Mirror => generate_mirror(seed, size),
         MapTypes::Terra => generate_terra(seed, size),
+    };
+
+    // per-landmass edits would break the symmetry of mirror maps
+    let ruggedness = if map_type != MapTypes::Mirror {
+        let (width, height) = size.dimensions();
+        let strength = landmasses_config().global.coast_ruggedness_strength;
+        vary_coast_ruggedness(&mut grid, width, height, seed, strength)
+    } else {
+        vec![None; grid.len()]
+    };
+    record_step(|| "final".to_string(), &grid);
+    Ok((grid, iterations, ruggedness))
+}
+
+/// Vary the coast texture per landmass after the repair loop.
+/// Every landmass gets a factor from `coast_ruggedness`: rugged landmasses lose their most exposed coast
+/// tiles (fjord-like notches), smooth landmasses get their bays filled. `strength` is the share of a coast
+/// edited at full ruggedness, 0 leaves the grid untouched.
+/// Edits never split, remove or merge landmasses, and the net land change is compensated afterwards.
+/// Returns the factor applied to the landmass of every land tile, all None if the pass did not run.
+pub fn vary_coast_ruggedness(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    seed: u64,
+    strength: f32,
+) -> Vec<Option<f32>> {
+    if strength <= 0.0 || width < 3 || height < 3 {
+        return vec![None; grid.len()];
+    }
+
+    let mut rng = ChaCha12Rng::seed_from_u64(seed ^ 0x7275_6767_6564_6e73);
+    let (mut ids, sizes) = label_landmask(grid, width, height);
+
+    let factors: Vec<f32> = (0..sizes.len()).map(|id| coast_ruggedness(seed, id)).collect();
+    let mut net: i64 = 0;
+    for (id, (coast_land, coast_water)) in coast_tiles(grid, &ids, width, height, sizes.len()).into_iter().enumerate() {
+        let ruggedness = factors[id];
+        let amount = (ruggedness - 0.5).abs() * 2.0;
+        if ruggedness > 0.5 {
+            let budget = (strength * amount * coast_land.len() as f32).round() as usize;
+            net -= carve_notches(grid, &mut ids, width, height, coast_land, budget, &mut rng) as i64;
+        } else {
+            let budget = (strength * amount * coast_water.len() as f32).round() as usize;
+            net += fill_bays(grid, &mut ids, width, height, coast_water, budget, &mut rng) as i64;
+        }
+    }
+
+    // compensate on all coasts so the land total stays the same
+    let (all_land, all_water): (Vec<Vec<usize>>, Vec<Vec<usize>>) =
+        coast_tiles(grid, &ids, width, height, sizes.len()).into_iter().unzip();
+    if net > 0 {
+        let candidates = all_land.into_iter().flatten().collect();
+        carve_notches(grid, &mut ids, width, height, candidates, net as usize, &mut rng);
+    } else if net < 0 {
+        let candidates = all_water.into_iter().flatten().collect();
+        fill_bays(grid, &mut ids, width, height, candidates, net.unsigned_abs() as usize, &mut rng);
+    }
+    ids.into_iter().map(|id| id.map(|id| factors[id])).collect()
+}
+
+/// Per landmass: its interior land tiles next to water, and the interior water tiles that only touch it.
+fn coast_tiles(
+    grid: &[u8],
+    ids: &[Option<usize>],
+    width: usize,
+    height: usize,
+    n_landmasses: usize,
+) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let mut coasts = vec![(Vec::new(), Vec::new()); n_landmasses];
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let idx = y * width + x;
+            let neighbors = neighbors_odd_r(x, y, width, height);
+            if let Some(id) = ids[idx] {
+                if neighbors.iter().any(|(nx, ny)| grid[ny * width + nx] == 0) {
+                    coasts[id].0.push(idx);
+                }
+            } else if let Some(id) = single_adjacent_landmass(ids, &neighbors, width) {
+                coasts[id].1.push(idx);
+            }
+        }
+    }
+    coasts
+}
+
+/// The landmass all land neighbors belong to, None without land neighbors or with several landmasses.
+fn single_adjacent_landmass(ids: &[Option<usize>], neighbors: &[(usize, usize)], width: usize) -> Option<usize> {
+    let mut found = None;
+    for id in neighbors.iter().filter_map(|(nx, ny)| ids[ny * width + nx]) {
+        if found.is_some_and(|f| f != id) {
+            return None;
+        }
+        found = Some(id);
+    }
+    found
+}
+
+/// Remove up to `budget` coast tiles, the ones with the most water neighbors first.
+/// A tile is only removed if its land neighbors form a single run around it, so its landmass stays connected.
+fn carve_notches(
+    grid: &mut [u8],
+    ids: &mut [Option<usize>],
+    width: usize,
+    height: usize,
+    candidates: Vec<usize>,
+    budget: usize,
+    rng: &mut ChaCha12Rng,
+) -> usize {
+    let mut ranked: Vec<(usize, u64, usize)> = candidates
+        .into_iter()
+        .map(|idx| {
+            let water = neighbors_odd_r(idx % width, idx / width, width, height)
+                .into_iter()
+                .filter(|(nx, ny)| grid[ny * width + nx] == 0)
+                .count();
+            (usize::MAX - water, rng.next_u64(), idx)
+        })
+        .collect();
+    ranked.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut removed = 0;
+    for (_, _, idx) in ranked {
+        if removed == budget {
+            break;
+        }
+        if grid[idx] != 1 {
+            continue;
+        }
+
+        let ring: Vec<bool> = neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(|(nx, ny)| grid[ny * width + nx] == 1)
+            .collect();
+        let runs = (0..ring.len()).filter(|&i| ring[i] && !ring[(i + ring.len() - 1) % ring.len()]).count();
+        if runs == 1 {
+            grid[idx] = 0;
+            ids[idx] = None;
+            removed += 1;
+        }
     }
+    removed
+}
+
+/// Fill up to `budget` water tiles, the ones with the most land neighbors first.
+/// A tile is only filled if all its land neighbors belong to the same landmass, so nothing merges.
+fn fill_bays(
+    grid: &mut [u8],
+    ids: &mut [Option<usize>],
+    width: usize,
+    height: usize,
+    candidates: Vec<usize>,
+    budget: usize,
+    rng: &mut ChaCha12Rng,
+) -> usize {
+    let mut ranked: Vec<(usize, u64, usize)> = candidates
+        .into_iter()
+        .map(|idx| {
+            let land = neighbors_odd_r(idx % width, idx / width, width, height)
+                .into_iter()
+                .filter(|(nx, ny)| grid[ny * width + nx] == 1)
+                .count();
+            (usize::MAX - land, rng.next_u64(), idx)
+        })
+        .collect();
+    ranked.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut filled = 0;
+    for (_, _, idx) in ranked {
+        if filled == budget {
+            break;
+        }
+        if grid[idx] != 0 {
+            continue;
+        }
+
+        let neighbors = neighbors_odd_r(idx % width, idx / width, width, height);
+        if let Some(id) = single_adjacent_landmass(ids, &neighbors, width) {
+            grid[idx] = 1;
+            ids[idx] = Some(id);
+            filled += 1;
+        }
+    }
+    filled
+}
+
+/// The zoom draft the generator of `map_type` starts from, before the repair loop.
+/// Terra returns the old world draft over the whole map, mirror the draft of its full map.
+pub fn land_draft(seed: u64, size: &MapSizes, map_type: MapTypes) -> Vec<u8> {
+    let cfg = landmasses_config_for(size);
+    let draft = match map_type {
+        MapTypes::Continents => &cfg.continents.draft,
+        MapTypes::SmallContinents => &cfg.small_continents.draft,
+        MapTypes::IslandsContinents => &cfg.island_continents.draft,
+        MapTypes::Pangea => &cfg.pangea.draft,
+        MapTypes::Terra => &cfg.terra.old_world.draft,
+        MapTypes::Mirror => &cfg.mirror.base.draft,
+    };
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    generate_zoom_draft(&mut child_rng(&mut rng), size, &cfg.global, draft, None)
 }
 
 /// Generate a continents-style map with deterministic analyze/repair.
-pub fn generate_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+pub fn generate_continents(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
 
     let mut grid = generate_zoom_draft(
@@ -66,7 +391,7 @@ pub fn generate_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         None,
     );
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
@@ -75,12 +400,12 @@ pub fn generate_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         RepairStyle::Continents,
         &mut grid,
     );
-    grid
+    (grid, iterations)
 }
 
 /// Generate a small-continents map with deterministic analyze/repair.
-pub fn generate_small_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+pub fn generate_small_continents(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
 
     let mut grid = generate_zoom_draft(
@@ -91,7 +416,7 @@ pub fn generate_small_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         None,
     );
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
@@ -100,12 +425,12 @@ pub fn generate_small_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         RepairStyle::SmallContinents,
         &mut grid,
     );
-    grid
+    (grid, iterations)
 }
 
 /// Generate an island-continents (archipelago-like) map with deterministic analyze/repair.
-pub fn generate_island_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+pub fn generate_island_continents(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
 
     let mut grid = generate_zoom_draft(
@@ -116,7 +441,7 @@ pub fn generate_island_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         None,
     );
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
@@ -125,12 +450,12 @@ pub fn generate_island_continents(seed: u64, size: &MapSizes) -> Vec<u8> {
         RepairStyle::IslandContinents,
         &mut grid,
     );
-    grid
+    (grid, iterations)
 }
 
 /// Generate a pangea-style map with deterministic analyze/repair.
-pub fn generate_pangea(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+pub fn generate_pangea(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
 
     let mut grid = generate_zoom_draft(
@@ -141,7 +466,7 @@ pub fn generate_pangea(seed: u64, size: &MapSizes) -> Vec<u8> {
         None,
     );
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
@@ -150,18 +475,20 @@ pub fn generate_pangea(seed: u64, size: &MapSizes) -> Vec<u8> {
         RepairStyle::Pangea,
         &mut grid,
     );
-    grid
+    (grid, iterations)
 }
 
 /// Generate a terra map with old/new world split by a deterministic ocean barrier.
-pub fn generate_terra(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+pub fn generate_terra(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
     let (width, height) = size.dimensions();
 
     let vertical = (rng.next_u32() & 1) == 0;
+    // `try_generate_landmasses` rejects barrier_min > barrier_max, a direct call with an empty range
+    // saturates to a span of 1 and always uses barrier_min
     let barrier_span = cfg.terra.barrier_max.saturating_sub(cfg.terra.barrier_min) + 1;
-    let mut barrier_w = cfg.terra.barrier_min + (rng.next_u32() as usize % barrier_span.max(1));
+    let mut barrier_w = cfg.terra.barrier_min + (rng.next_u32() as usize % barrier_span);
     barrier_w = barrier_w.min(width.saturating_sub(2).max(1));
 
     let mut old_side = vec![true; width * height];
@@ -231,7 +558,7 @@ pub fn generate_terra(seed: u64, size: &MapSizes) -> Vec<u8> {
 
     enforce_border_water(&mut grid, width, height);
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
@@ -242,17 +569,7 @@ pub fn generate_terra(seed: u64, size: &MapSizes) -> Vec<u8> {
     );
 
     // Keep terra to exactly two major worlds split by ocean.
-    enforce_terra_two_worlds(
-        &mut grid,
-        width,
-        height,
-        vertical,
-        barrier_w.max(3),
-        &old_side,
-        &new_side,
-        &cfg.global,
-        &mut rng,
-    );
+    enforce_terra_two_worlds(&mut grid, width, height, &old_side, &new_side, &cfg.global, &mut rng);
 
     if vertical {
         let split = width / 2;
@@ -277,15 +594,17 @@ pub fn generate_terra(seed: u64, size: &MapSizes) -> Vec<u8> {
     }
 
     enforce_border_water(&mut grid, width, height);
-    grid
+    (grid, iterations)
 }
 
-/// Generate a perfectly mirrored map by creating and repairing half, then reflecting.
-pub fn generate_mirror(seed: u64, size: &MapSizes) -> Vec<u8> {
-    let cfg = landmasses_config();
+/// Generate a perfectly mirrored map by creating and repairing half (a quarter for four-way mirrors), then reflecting.
+pub fn generate_mirror(seed: u64, size: &MapSizes) -> (Vec<u8>, RepairIterationCount) {
+    let cfg = landmasses_config_for(size);
+    let axis = cfg.mirror.axis;
     let mut rng = ChaCha12Rng::seed_from_u64(seed);
     let (width, height) = size.dimensions();
-    let half_w = width.div_ceil(2);
+    let half_w = if axis.mirrors_columns() { width.div_ceil(2) } else { width };
+    let half_h = if axis.mirrors_rows() { height.div_ceil(2) } else { height };
 
     let full = generate_zoom_draft(
         &mut child_rng(&mut rng),
@@ -295,8 +614,8 @@ pub fn generate_mirror(seed: u64, size: &MapSizes) -> Vec<u8> {
         None,
     );
 
-    let mut half = vec![0u8; half_w * height];
-    for y in 0..height {
+    let mut half = vec![0u8; half_w * half_h];
+    for y in 0..half_h {
         for x in 0..half_w {
             half[y * half_w + x] = full[y * width + x];
         }
@@ -304,12 +623,12 @@ pub fn generate_mirror(seed: u64, size: &MapSizes) -> Vec<u8> {
 
     for _ in 0..cfg.mirror.half_smoothing_passes {
         let mut next = half.clone();
-        for y in 0..height {
+        for y in 0..half_h {
             for x in 0..half_w {
                 let idx = y * half_w + x;
                 let mut land_n = 0;
                 let mut water_n = 0;
-                for (nx, ny) in neighbors_odd_r(x, y, half_w, height) {
+                for (nx, ny) in neighbors_odd_r(x, y, half_w, half_h) {
                     if half[ny * half_w + nx] == 1 {
                         land_n += 1;
                     } else {
@@ -327,21 +646,21 @@ pub fn generate_mirror(seed: u64, size: &MapSizes) -> Vec<u8> {
     }
 
     let mut grid = vec![0u8; width * height];
-    mirror_vertical_into(&half, &mut grid, width, height);
+    mirror_into(&half, half_w, half_h, &mut grid, width, height);
 
-    run_repair_loop(
+    let iterations = run_repair_loop(
         &mut rng,
         size,
         &cfg.global,
         &cfg.mirror.base.constraints,
         &cfg.mirror.base.repair,
-        RepairStyle::Mirror,
+        RepairStyle::Mirror(axis),
         &mut grid,
     );
 
-    enforce_vertical_mirror(&mut grid, width, height);
+    enforce_mirror(&mut grid, width, height, axis);
     enforce_border_water(&mut grid, width, height);
-    grid
+    (grid, iterations)
 }
 
 /// Create a deterministic child RNG from the parent RNG stream.
@@ -358,34 +677,44 @@ fn run_repair_loop(
     repair: &RepairConfig,
     style: RepairStyle,
     grid: &mut Vec<u8>,
-) {
+) -> RepairIterationCount {
     let (width, height) = size.dimensions();
-    let island_max = dynamic_island_max(size, global);
-    let mid_max = dynamic_mid_max(size, global);
+    let scaling = MapScaling::for_size(size);
+    let island_max = scaling.island_max;
+    let mut iterations = 0u32;
+    let mut scratch = RepairScratch::new(rng);
+    let mut analysis = LandAnalysis::default();
+    record_step(|| "initial".to_string(), grid);
 
     for _ in 0..global.max_repair_iters {
-        let analysis = analyze_landmask(grid, width, height, island_max, mid_max, global.min_lake_size);
+        analyze_landmask_into(
+            &mut scratch.buffers,
+            &mut analysis,
+            grid,
+            width,
+            height,
+            &scaling,
+            global.min_lake_size,
+        );
         if satisfies(&analysis, constraints) {
             break;
         }
+        iterations += 1;
 
         match style {
             RepairStyle::Continents => {
                 if analysis.largest_ratio > repair.largest_carve_trigger_ratio {
                     let over = (analysis.largest_ratio - repair.largest_carve_target_ratio).max(0.0);
-                    let map_scale = (width * height) as f32 / (84.0 * 54.0);
-                    let k = ((repair.largest_carve_base_count as f32
-                        + (over * repair.largest_carve_scale))
-                        * map_scale.max(1.0))
-                        .ceil() as usize;
-                    carve_straits(grid, width, height, &analysis, rng, k);
+                    // counts are already scaled to the map size by `landmasses_config_for`
+                    let k = (repair.largest_carve_base_count as f32 + (over * repair.largest_carve_scale)).ceil()
+                        as usize;
+                    carve_straits(grid, width, height, &analysis, k, repair.peninsula_min_width, &mut scratch);
+                    resolve_thin_strings(grid, width, height, repair, island_max);
                 }
                 if analysis.n_components < constraints.min_components {
                     let missing = constraints.min_components - analysis.n_components;
-                    let map_scale = (width * height) as f32 / (84.0 * 54.0);
-                    let base = ((repair.channel_carve_count.max(4) as f32) * map_scale.max(1.0))
-                        .ceil() as usize;
-                    channel_carve(grid, width, height, &analysis, rng, base * missing);
+                    let base = repair.channel_carve_count.max(4);
+                    channel_carve(grid, width, height, &analysis, base * missing, &mut scratch);
                 }
                 if analysis.n_islands < constraints.min_islands {
                     let missing = constraints.min_islands - analysis.n_islands;
@@ -393,33 +722,25 @@ fn run_repair_loop(
                         grid,
                         width,
                         height,
-                        rng,
                         missing,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
                 if analysis.n_lakes < constraints.min_lakes {
-                    carve_lakes(
-                        grid,
-                        width,
-                        height,
-                        &analysis,
-                        rng,
-                        constraints.min_lakes - analysis.n_lakes,
-                        repair.lake_blob_min,
-                        repair.lake_blob_max,
-                    );
+                    carve_missing_lakes_with(grid, width, height, global, constraints, repair, &mut scratch);
                 }
             }
             RepairStyle::SmallContinents => {
                 if analysis.largest_ratio > repair.largest_carve_trigger_ratio {
                     let over = (analysis.largest_ratio - repair.largest_carve_target_ratio).max(0.0);
                     let k = repair.largest_carve_base_count + (over * repair.largest_carve_scale).ceil() as usize;
-                    carve_straits(grid, width, height, &analysis, rng, k);
+                    carve_straits(grid, width, height, &analysis, k, repair.peninsula_min_width, &mut scratch);
+                    resolve_thin_strings(grid, width, height, repair, island_max);
                 }
                 if analysis.n_components < constraints.min_components && repair.channel_carve_count > 0 {
-                    channel_carve(grid, width, height, &analysis, rng, repair.channel_carve_count);
+                    channel_carve(grid, width, height, &analysis, repair.channel_carve_count, &mut scratch);
                 }
                 if analysis.n_islands < constraints.min_islands {
                     let missing = constraints.min_islands - analysis.n_islands;
@@ -427,28 +748,21 @@ fn run_repair_loop(
                         grid,
                         width,
                         height,
-                        rng,
                         missing,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
                 if analysis.n_lakes < constraints.min_lakes {
-                    carve_lakes(
-                        grid,
-                        width,
-                        height,
-                        &analysis,
-                        rng,
-                        constraints.min_lakes - analysis.n_lakes,
-                        repair.lake_blob_min,
-                        repair.lake_blob_max,
-                    );
+                    carve_missing_lakes_with(grid, width, height, global, constraints, repair, &mut scratch);
                 }
             }
             RepairStyle::IslandContinents => {
                 let cap = (analysis.land_tiles as f32 * repair.erode_cap_ratio) as usize;
-                erode_largest_component(grid, width, height, &analysis, rng, cap);
+                let protected =
+                    enforce_peninsula_preservation(grid, width, height, &analysis, repair.peninsula_min_width);
+                erode_largest_component(grid, width, height, &analysis, cap, &protected, &mut scratch);
 
                 if analysis.n_islands < constraints.min_islands {
                     let missing = constraints.min_islands - analysis.n_islands;
@@ -457,10 +771,10 @@ fn run_repair_loop(
                         grid,
                         width,
                         height,
-                        rng,
                         count,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
             }
@@ -471,17 +785,10 @@ fn run_repair_loop(
                         width,
                         height,
                         &analysis,
-                        rng,
                         repair.pangea_fill_internal_count,
+                        &mut scratch,
                     );
-                    connect_to_largest(
-                        grid,
-                        width,
-                        height,
-                        &analysis,
-                        rng,
-                        repair.pangea_connect_count,
-                    );
+                    connect_to_largest(grid, width, height, &analysis, scratch.rng, repair.pangea_connect_count);
                 }
                 if analysis.n_components > constraints.max_components {
                     connect_to_largest(
@@ -489,7 +796,7 @@ fn run_repair_loop(
                         width,
                         height,
                         &analysis,
-                        rng,
+                        scratch.rng,
                         repair.pangea_connect_when_split,
                     );
                 }
@@ -498,119 +805,103 @@ fn run_repair_loop(
                         grid,
                         width,
                         height,
-                        rng,
                         1,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
                 if analysis.n_lakes < constraints.min_lakes {
-                    carve_lakes(
-                        grid,
-                        width,
-                        height,
-                        &analysis,
-                        rng,
-                        constraints.min_lakes - analysis.n_lakes,
-                        repair.lake_blob_min,
-                        repair.lake_blob_max,
-                    );
+                    carve_missing_lakes_with(grid, width, height, global, constraints, repair, &mut scratch);
                 }
             }
             RepairStyle::Terra => {
                 if analysis.second_ratio < 0.20 {
-                    grow_land(grid, width, height, rng, repair.terra_grow_budget);
+                    let budget = repair.terra_grow_budget;
+                    let profile = &global.latitude_land_profile;
+                    grow_land(grid, width, height, budget, profile, &mut scratch);
                 }
                 if analysis.n_islands < constraints.min_islands {
                     sprinkle_islands(
                         grid,
                         width,
                         height,
-                        rng,
                         repair.island_extra_missing_floor,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
                 if analysis.n_lakes < constraints.min_lakes {
-                    carve_lakes(
+                    carve_missing_lakes_with(grid, width, height, global, constraints, repair, &mut scratch);
+                }
+            }
+            RepairStyle::Mirror(axis) => {
+                if analysis.largest_ratio > repair.largest_carve_trigger_ratio {
+                    carve_straits(
                         grid,
                         width,
                         height,
                         &analysis,
-                        rng,
-                        constraints.min_lakes - analysis.n_lakes,
-                        repair.lake_blob_min,
-                        repair.lake_blob_max,
+                        repair.largest_carve_base_count,
+                        repair.peninsula_min_width,
+                        &mut scratch,
                     );
-                }
-            }
-            RepairStyle::Mirror => {
-                if analysis.largest_ratio > repair.largest_carve_trigger_ratio {
-                    carve_straits(grid, width, height, &analysis, rng, repair.largest_carve_base_count);
+                    resolve_thin_strings(grid, width, height, repair, island_max);
                 }
                 if analysis.n_islands < constraints.min_islands {
                     sprinkle_islands(
                         grid,
                         width,
                         height,
-                        rng,
                         repair.island_extra_missing_floor,
                         repair.island_min_blob,
                         repair.island_max_blob,
+                        &mut scratch,
                     );
                 }
-                enforce_vertical_mirror(grid, width, height);
+                enforce_mirror(grid, width, height, axis);
             }
         }
 
+        adjust_compactness(grid, width, height, &analysis, constraints, scratch.rng);
+
         adjust_land_ratio(
             grid,
             width,
             height,
-            rng,
-            constraints.min_land_ratio,
-            constraints.max_land_ratio,
+            constraints,
             repair.land_ratio_adjust_cap_divisor,
+            &global.latitude_land_profile,
+            &mut scratch,
         );
         enforce_border_water(grid, width, height);
 
-        if let RepairStyle::Mirror = style {
-            enforce_vertical_mirror(grid, width, height);
+        if let RepairStyle::Mirror(axis) = style {
+            enforce_mirror(grid, width, height, axis);
         }
+        record_step(|| format!("repair{}", iterations - 1), grid);
     }
 
     // Final hard constraint pass: enforce configured land-ratio bounds directly.
-    force_land_ratio(
-        grid,
-        width,
-        height,
-        rng,
-        constraints.min_land_ratio,
-        constraints.max_land_ratio,
-    );
+    force_land_ratio(grid, width, height, constraints, &global.latitude_land_profile, &mut scratch);
     enforce_border_water(grid, width, height);
 
     // Final hard topology pass: enforce minimum land component count for map styles that need it.
     if constraints.min_components > 1 {
-        ensure_min_components(grid, width, height, global, constraints, rng);
+        ensure_min_components(grid, width, height, global, constraints, repair, &mut scratch);
     }
 
     // Component splitting can slightly move land ratio, so enforce ratio one more time.
-    force_land_ratio(
-        grid,
-        width,
-        height,
-        rng,
-        constraints.min_land_ratio,
-        constraints.max_land_ratio,
-    );
+    force_land_ratio(grid, width, height, constraints, &global.latitude_land_profile, &mut scratch);
     enforce_border_water(grid, width, height);
 
-    if let RepairStyle::Mirror = style {
-        enforce_vertical_mirror(grid, width, height);
+    if let RepairStyle::Mirror(axis) = style {
+        enforce_mirror(grid, width, height, axis);
         enforce_border_water(grid, width, height);
     }
+
+    RepairIterationCount(iterations)
 }
 
 /// Build an initial land draft using coarse seeding, zoom, and smoothing.
@@ -623,23 +914,35 @@ fn generate_zoom_draft(
 ) -> Vec<u8> {
     let (width, height) = size.dimensions();
 
-    let mut w = width.div_ceil(global.base_factor).max(2);
-    let mut h = height.div_ceil(global.base_factor).max(2);
+    // scale-invariant drafts seed the same coarse grid at every size: the grid of a Standard map at a quarter of
+    // the base factor, fine enough that the repair loop keeps the seeded layout
+    let (seed_w, seed_h) = if global.scale_invariant {
+        let (w, h) = MapSizes::Standard.dimensions();
+        let factor = (global.base_factor / 4).max(1);
+        (w.div_ceil(factor), h.div_ceil(factor))
+    } else {
+        (width.div_ceil(global.base_factor), height.div_ceil(global.base_factor))
+    };
+    let mut w = seed_w.max(2).min(width);
+    let mut h = seed_h.max(2).min(height);
     let mut grid = vec![0u8; w * h];
 
     let center_x = (w as f32 - 1.0) * (0.35 + 0.3 * (rng.next_u32() as f32 / u32::MAX as f32));
     let center_y = (h as f32 - 1.0) * (0.35 + 0.3 * (rng.next_u32() as f32 / u32::MAX as f32));
+    let band_weights = relative_band_weights(&global.latitude_land_profile);
+    let soft_border = params.coast_band_width > 0;
+    let coast_penalty = apply_coast_band_mask(w, h, width.div_ceil(w), params);
 
     for y in 0..h {
         for x in 0..w {
             let idx = y * w + x;
             let border = x == 0 || x + 1 == w || y == 0 || y + 1 == h;
-            if border {
+            if border && !soft_border {
                 grid[idx] = 0;
                 continue;
             }
 
-            let mut p = params.base_land_percent as f32;
+            let mut p = params.base_land_percent as f32 - coast_penalty[idx];
             if params.center_bias > 0.0 {
                 let dx = (x as f32 - center_x) / (w as f32 * 0.45);
                 let dy = (y as f32 - center_y) / (h as f32 * 0.45);
@@ -647,6 +950,9 @@ fn generate_zoom_draft(
                 let boost = (1.0 - d2).max(0.0) * 40.0 * params.center_bias;
                 p += boost;
             }
+            if let Some(weights) = &band_weights {
+                p *= weights[latitude_band(y, h, weights.len())];
+            }
 
             grid[idx] = if ((rng.next_u32() % 100) as f32) < p {
                 1
@@ -661,19 +967,45 @@ fn generate_zoom_draft(
         let new_h = (h * 2).min(height);
         let mut next = vec![0u8; new_w * new_h];
 
+        // scale-invariant steps stretch the grid over the map instead of cutting off the last step
+        let parent_of = |n: usize, old: usize, new: usize| {
+            if global.scale_invariant { n * old / new } else { (n / 2).min(old - 1) }
+        };
+
+        // Land votes of the 2x2 parent block east and south of every tile of a parent row, 0 or 4 is uniform.
+        // Consecutive output rows mostly share their parent row, so the votes are only counted when it changes.
+        let parent_x: Vec<usize> = (0..new_w).map(|nx| parent_of(nx, w, new_w)).collect();
+        let mut votes = vec![0u8; w];
+        let mut votes_row = None;
         for ny in 0..new_h {
-            for nx in 0..new_w {
-                let px = (nx / 2).min(w - 1);
-                let py = (ny / 2).min(h - 1);
-                let pe = (px + 1).min(w - 1);
+            let py = parent_of(ny, h, new_h);
+            let row = &grid[py * w..(py + 1) * w];
+            if votes_row != Some(py) {
                 let ps = (py + 1).min(h - 1);
+                let south_row = &grid[ps * w..(ps + 1) * w];
+                for (px, vote) in votes.iter_mut().enumerate() {
+                    let pe = (px + 1).min(w - 1);
+                    *vote = row[px] + row[pe] + south_row[px] + south_row[pe];
+                }
+                votes_row = Some(py);
+            }
+
+            let out = &mut next[ny * new_w..(ny + 1) * new_w];
+            let mut nx = 0;
+            while nx < new_w {
+                let land_votes = votes[parent_x[nx]];
 
-                let parent = grid[py * w + px];
-                let east = grid[py * w + pe];
-                let south = grid[ps * w + px];
-                let diag = grid[ps * w + pe];
+                // Uniform blocks draw no random numbers, so a run of them is filled at once without changing the stream
+                if land_votes == 0 || land_votes == 4 {
+                    let start = nx;
+                    while nx < new_w && votes[parent_x[nx]] == land_votes {
+                        nx += 1;
+                    }
+                    out[start..nx].fill(land_votes / 4);
+                    continue;
+                }
 
-                let land_votes = parent + east + south + diag;
+                let parent = row[parent_x[nx]];
                 let mut value = if land_votes > 2 {
                     1
                 } else if land_votes < 2 {
@@ -682,20 +1014,21 @@ fn generate_zoom_draft(
                     parent
                 };
 
-                let mixed = land_votes > 0 && land_votes < 4;
-                if mixed {
-                    if rng.next_u32() % 100 < params.fuzzy_flip_percent {
-                        value = 1 - value;
-                    }
-                    if value == 0 && rng.next_u32() % 100 < params.coast_island_percent {
-                        value = 1;
-                    }
+                if rng.next_u32() % 100 < params.fuzzy_flip_percent {
+                    value = 1 - value;
+                }
+                if value == 0 && rng.next_u32() % 100 < params.coast_island_percent {
+                    value = 1;
                 }
-                next[ny * new_w + nx] = value;
+                out[nx] = value;
+                nx += 1;
             }
         }
 
-        enforce_border_water(&mut next, new_w, new_h);
+        if !soft_border {
+            enforce_border_water(&mut next, new_w, new_h);
+        }
+        enforce_polar_water(&mut next, new_w, new_h, params.polar_water_rows);
         grid = next;
         w = new_w;
         h = new_h;
@@ -722,7 +1055,9 @@ fn generate_zoom_draft(
                 }
             }
         }
-        enforce_border_water(&mut next, width, height);
+        if !soft_border {
+            enforce_border_water(&mut next, width, height);
+        }
         grid = next;
     }
 
@@ -734,23 +1069,53 @@ fn generate_zoom_draft(
         }
     }
 
-    enforce_border_water(&mut grid, width, height);
+    if !soft_border {
+        enforce_border_water(&mut grid, width, height);
+    }
+    enforce_polar_water(&mut grid, width, height, params.polar_water_rows);
     grid
 }
 
-/// Analyze a landmask and return all stats needed by the repair loop.
-fn analyze_landmask(
+/// Land percent penalty of every cell of a `width` x `height` draft grid whose cells cover `cell_size` map tiles.
+/// Cells inside the coast band lose `coast_band_penalty_per_tile` for every tile they lie closer to the border
+/// than `coast_band_width`, so the border cells get the largest penalty. All zero when the band is disabled.
+fn apply_coast_band_mask(width: usize, height: usize, cell_size: usize, params: &DraftConfig) -> Vec<f32> {
+    let band = params.coast_band_width;
+    let mut penalty = vec![0.0; width * height];
+    if band == 0 {
+        return penalty;
+    }
+    for y in 0..height {
+        for x in 0..width {
+            let cells = x.min(width - 1 - x).min(y).min(height - 1 - y);
+            let distance = cells * cell_size;
+            if distance < band {
+                penalty[y * width + x] = params.coast_band_penalty_per_tile * (band - distance) as f32;
+            }
+        }
+    }
+    penalty
+}
+
+/// Analyze a landmask into `analysis`, reusing its vectors and the scratch in `buffers`.
+fn analyze_landmask_into(
+    buffers: &mut AnalysisBuffers,
+    analysis: &mut LandAnalysis,
     grid: &[u8],
     width: usize,
     height: usize,
-    island_max: usize,
-    mid_max: usize,
+    scaling: &MapScaling,
     min_lake_size: usize,
-) -> LandAnalysis {
+) {
+    let (island_max, mid_max) = (scaling.island_max, scaling.mid_max);
     let n = width * height;
-    let mut land_component_ids = vec![usize::MAX; n];
-    let mut land_component_sizes = Vec::new();
-    let mut q = VecDeque::new();
+    let land_component_ids = &mut analysis.land_component_ids;
+    let land_component_sizes = &mut analysis.land_component_sizes;
+    land_component_ids.clear();
+    land_component_ids.resize(n, usize::MAX);
+    land_component_sizes.clear();
+    let q = &mut buffers.queue;
+    q.clear();
 
     for y in 0..height {
         for x in 0..width {
@@ -803,31 +1168,62 @@ fn analyze_landmask(
         .filter(|&&s| s <= island_max || (s <= mid_max && s < island_max * 2))
         .count();
 
-    let (ocean_mask, n_lakes) = analyze_water(grid, width, height, min_lake_size);
+    let n_lakes = analyze_water_into(buffers, &mut analysis.ocean_mask, grid, width, height, min_lake_size);
 
-    LandAnalysis {
-        land_ratio,
-        largest_ratio,
-        second_ratio,
-        n_components: land_component_sizes.len(),
-        n_islands,
-        n_lakes,
-        land_tiles,
-        largest_component_idx,
-        land_component_sizes,
-        land_component_ids,
-        ocean_mask,
+    let perimeters = &mut buffers.perimeters;
+    perimeters.clear();
+    perimeters.resize(land_component_sizes.len(), 0);
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if grid[idx] != 1 {
+                continue;
+            }
+            perimeters[land_component_ids[idx]] += neighbors_odd_r(x, y, width, height)
+                .into_iter()
+                .filter(|(nx, ny)| grid[ny * width + nx] == 0)
+                .count();
+        }
     }
+    analysis.major_compactness.clear();
+    analysis.major_compactness.extend(
+        land_component_sizes
+            .iter()
+            .enumerate()
+            .filter(|(_, sz)| **sz > island_max)
+            .map(|(id, &sz)| (id, hex_compactness(sz, perimeters[id]))),
+    );
+
+    analysis.land_ratio = land_ratio;
+    analysis.largest_ratio = largest_ratio;
+    analysis.second_ratio = second_ratio;
+    analysis.n_components = land_component_sizes.len();
+    analysis.n_islands = n_islands;
+    analysis.n_lakes = n_lakes;
+    analysis.land_tiles = land_tiles;
+    analysis.largest_component_idx = largest_component_idx;
 }
 
-/// Analyze water components, classify ocean, and count lakes.
-fn analyze_water(grid: &[u8], width: usize, height: usize, min_lake_size: usize) -> (Vec<bool>, usize) {
+/// Analyze water components, classify ocean into `ocean_mask`, and count lakes.
+fn analyze_water_into(
+    buffers: &mut AnalysisBuffers,
+    ocean_mask: &mut Vec<bool>,
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    min_lake_size: usize,
+) -> usize {
     let n = width * height;
-    let mut water_component_ids = vec![usize::MAX; n];
-    let mut water_component_sizes = Vec::new();
-    let mut touches_border = Vec::new();
-
-    let mut q = VecDeque::new();
+    let water_component_ids = &mut buffers.water_component_ids;
+    let water_component_sizes = &mut buffers.water_component_sizes;
+    let touches_border = &mut buffers.touches_border;
+    water_component_ids.clear();
+    water_component_ids.resize(n, usize::MAX);
+    water_component_sizes.clear();
+    touches_border.clear();
+
+    let q = &mut buffers.queue;
+    q.clear();
     for y in 0..height {
         for x in 0..width {
             let idx = y * width + x;
@@ -862,7 +1258,8 @@ fn analyze_water(grid: &[u8], width: usize, height: usize, min_lake_size: usize)
         }
     }
 
-    let mut ocean_mask = vec![false; n];
+    ocean_mask.clear();
+    ocean_mask.resize(n, false);
     for i in 0..n {
         if grid[i] != 0 {
             continue;
@@ -873,13 +1270,11 @@ fn analyze_water(grid: &[u8], width: usize, height: usize, min_lake_size: usize)
         }
     }
 
-    let n_lakes = water_component_sizes
+    water_component_sizes
         .iter()
         .enumerate()
         .filter(|(i, sz)| !touches_border[*i] && **sz >= min_lake_size)
-        .count();
-
-    (ocean_mask, n_lakes)
+        .count()
 }
 
 /// Check whether the current map satisfies all configured constraints.
@@ -893,26 +1288,102 @@ fn satisfies(a: &LandAnalysis, c: &ConstraintsConfig) -> bool {
         && a.n_islands >= c.min_islands
         && a.n_lakes >= c.min_lakes
         && a.n_lakes <= c.max_lakes
+        && a.major_compactness.iter().all(|(_, score)| {
+            c.min_compactness.is_none_or(|min| *score >= min) && c.max_compactness.is_none_or(|max| *score <= max)
+        })
+}
+
+/// Measure a finished landmask against the constraints of its map type, with the same analysis as the
+/// repair loop. There is one outcome per constraint of `ConstraintKind::ALL`; compactness is only
+/// included when it has a bound and the map has a landmass larger than an island.
+pub fn evaluate_constraints(grid: &[u8], size: &MapSizes, map_type: MapTypes) -> Vec<ConstraintOutcome> {
+    let config = landmasses_config_for(size);
+    let constraints = config.constraints_for(map_type);
+    let (width, height) = size.dimensions();
+    let scaling = MapScaling::for_size(size);
+    let mut analysis = LandAnalysis::default();
+    analyze_landmask_into(
+        &mut AnalysisBuffers::default(),
+        &mut analysis,
+        grid,
+        width,
+        height,
+        &scaling,
+        config.global.min_lake_size,
+    );
+
+    let count = |value: usize| Some(value as f32);
+    let mut outcomes = vec![
+        ConstraintOutcome::evaluate(
+            ConstraintKind::LandRatio,
+            Some(constraints.min_land_ratio),
+            Some(constraints.max_land_ratio),
+            analysis.land_ratio,
+        ),
+        ConstraintOutcome::evaluate(
+            ConstraintKind::LargestRatio,
+            Some(constraints.min_largest_ratio),
+            Some(constraints.max_largest_ratio),
+            analysis.largest_ratio,
+        ),
+        ConstraintOutcome::evaluate(
+            ConstraintKind::Components,
+            count(constraints.min_components),
+            count(constraints.max_components),
+            analysis.n_components as f32,
+        ),
+        ConstraintOutcome::evaluate(
+            ConstraintKind::Islands,
+            count(constraints.min_islands),
+            None,
+            analysis.n_islands as f32,
+        ),
+        ConstraintOutcome::evaluate(
+            ConstraintKind::Lakes,
+            count(constraints.min_lakes),
+            count(constraints.max_lakes),
+            analysis.n_lakes as f32,
+        ),
+    ];
+
+    let scores = analysis.major_compactness.iter().map(|(_, score)| *score);
+    let lowest = scores.clone().reduce(f32::min);
+    let highest = scores.reduce(f32::max);
+    let (min, max) = (constraints.min_compactness, constraints.max_compactness);
+    if let (Some(lowest), Some(highest)) = (lowest, highest)
+        && (min.is_some() || max.is_some())
+    {
+        // the lowest score decides the minimum and the highest the maximum, report the one that failed
+        let max_missed = max.is_some_and(|max| highest > max) && min.is_none_or(|min| lowest >= min);
+        let achieved = if max_missed { highest } else { lowest };
+        outcomes.push(ConstraintOutcome::evaluate(ConstraintKind::Compactness, min, max, achieved));
+    }
+    outcomes
 }
 
 /// Carve coastal choke points on the largest component to split oversized landmasses.
+/// Peninsula necks (see `peninsula_necks`, widened by `min_neck_width`) are never carved,
+/// so a single carved tile cannot cut a peninsula off.
 fn carve_straits(
     grid: &mut [u8],
     width: usize,
     height: usize,
     analysis: &LandAnalysis,
-    rng: &mut ChaCha12Rng,
     k: usize,
+    min_neck_width: usize,
+    scratch: &mut RepairScratch,
 ) {
     let Some(largest_id) = analysis.largest_component_idx else {
         return;
     };
+    let protected = peninsula_necks(grid, width, height, min_neck_width);
+    let RepairScratch { rng, candidates, .. } = scratch;
 
-    let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+    candidates.clear();
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
             let idx = y * width + x;
-            if grid[idx] != 1 || analysis.land_component_ids[idx] != largest_id {
+            if grid[idx] != 1 || analysis.land_component_ids[idx] != largest_id || protected[idx] {
                 continue;
             }
 
@@ -934,21 +1405,199 @@ fn carve_straits(
     }
 
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
-    for (_, _, idx) in candidates.into_iter().take(k) {
+    for &(_, _, idx) in candidates.iter().take(k) {
         grid[idx] = 0;
     }
 }
 
+/// Resolve one-tile-wide land strings left behind by carving.
+/// A string is a run of land tiles where each tile has exactly 2 land neighbors that do not touch each other.
+/// Strings of at least `thin_string_min_length` tiles that are the sole connection between two sub-regions
+/// larger than `island_max` are either carved away or thickened into an isthmus of `min_connection_width`.
+/// Thickening grows `min_connection_width - 1` rings of land around the string, on both of its sides,
+/// so a one-tile string becomes about `2 * min_connection_width - 1` tiles wide (3 for a width of 2).
+pub fn resolve_thin_strings(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    repair: &RepairConfig,
+    island_max: usize,
+) {
+    if repair.min_connection_width < 2 || repair.thin_string_min_length == 0 {
+        return;
+    }
+
+    let n = width * height;
+    let mut is_string = vec![false; n];
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let idx = y * width + x;
+            if grid[idx] != 1 {
+                continue;
+            }
+
+            let land: Vec<(usize, usize)> = neighbors_odd_r(x, y, width, height)
+                .into_iter()
+                .filter(|(nx, ny)| grid[ny * width + nx] == 1)
+                .collect();
+            if land.len() != 2 {
+                continue;
+            }
+
+            // the two land neighbors must not touch, otherwise the tile is just a corner of a blob
+            let (ax, ay) = land[0];
+            let touching = neighbors_odd_r(ax, ay, width, height).contains(&land[1]);
+            is_string[idx] = !touching;
+        }
+    }
+
+    // Group string tiles into runs
+    let mut run_ids = vec![usize::MAX; n];
+    let mut runs: Vec<Vec<usize>> = Vec::new();
+    let mut q = VecDeque::new();
+    for start in 0..n {
+        if !is_string[start] || run_ids[start] != usize::MAX {
+            continue;
+        }
+
+        let run_id = runs.len();
+        let mut run = Vec::new();
+        run_ids[start] = run_id;
+        q.push_back(start);
+        while let Some(idx) = q.pop_front() {
+            run.push(idx);
+            for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                let nidx = ny * width + nx;
+                if is_string[nidx] && run_ids[nidx] == usize::MAX {
+                    run_ids[nidx] = run_id;
+                    q.push_back(nidx);
+                }
+            }
+        }
+        runs.push(run);
+    }
+
+    // tiles of the run being resolved, set and cleared again for every run
+    let mut in_run = vec![false; n];
+    for run in runs {
+        if run.len() < repair.thin_string_min_length {
+            continue;
+        }
+        for &idx in &run {
+            in_run[idx] = true;
+        }
+        resolve_thin_string(grid, width, height, repair, island_max, &run, &in_run);
+        for &idx in &run {
+            in_run[idx] = false;
+        }
+    }
+}
+
+/// Carve or thicken one string of `resolve_thin_strings` if it is the sole connection between two large
+/// sub-regions. `in_run` marks the tiles of `run`.
+fn resolve_thin_string(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    repair: &RepairConfig,
+    island_max: usize,
+    run: &[usize],
+    in_run: &[bool],
+) {
+    // The land tiles touching the run from outside are the two anchors of the string
+    let mut anchors: Vec<usize> = Vec::new();
+    for &idx in run {
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let nidx = ny * width + nx;
+            if grid[nidx] == 1 && !in_run[nidx] && !anchors.contains(&nidx) {
+                anchors.push(nidx);
+            }
+        }
+    }
+    if anchors.len() != 2 {
+        return;
+    }
+
+    // Flood each side without crossing the string to see if it is the sole connection
+    let (side_a, reached_b) = land_side_size(grid, width, height, anchors[0], anchors[1], in_run);
+    if reached_b || side_a <= island_max {
+        return;
+    }
+    let (side_b, _) = land_side_size(grid, width, height, anchors[1], anchors[0], in_run);
+    if side_b <= island_max {
+        return;
+    }
+
+    if repair.thicken_thin_strings {
+        let mut frontier = run.to_vec();
+        for _ in 1..repair.min_connection_width {
+            let mut next = Vec::new();
+            for &idx in &frontier {
+                for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                    if nx == 0 || ny == 0 || nx + 1 == width || ny + 1 == height {
+                        continue;
+                    }
+                    let nidx = ny * width + nx;
+                    if grid[nidx] == 0 {
+                        grid[nidx] = 1;
+                        next.push(nidx);
+                    }
+                }
+            }
+            frontier = next;
+        }
+    } else {
+        for &idx in run {
+            grid[idx] = 0;
+        }
+    }
+}
+
+/// Flood land from `start` without entering the tiles marked in `blocked`.
+/// Returns the number of tiles reached and whether `target` was reached.
+fn land_side_size(
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    start: usize,
+    target: usize,
+    blocked: &[bool],
+) -> (usize, bool) {
+    let mut visited = vec![false; grid.len()];
+    let mut q = VecDeque::new();
+    let mut size = 0usize;
+    let mut reached = false;
+
+    visited[start] = true;
+    q.push_back(start);
+    while let Some(idx) = q.pop_front() {
+        size += 1;
+        if idx == target {
+            reached = true;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let nidx = ny * width + nx;
+            if grid[nidx] == 1 && !visited[nidx] && !blocked[nidx] {
+                visited[nidx] = true;
+                q.push_back(nidx);
+            }
+        }
+    }
+
+    (size, reached)
+}
+
 /// Carve channels in mixed coastline regions to increase component count.
 fn channel_carve(
     grid: &mut [u8],
     width: usize,
     height: usize,
     analysis: &LandAnalysis,
-    rng: &mut ChaCha12Rng,
     k: usize,
+    scratch: &mut RepairScratch,
 ) {
-    let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+    let RepairScratch { rng, candidates, .. } = scratch;
+    candidates.clear();
 
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
@@ -976,7 +1625,7 @@ fn channel_carve(
     }
 
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
-    for (_, _, idx) in candidates.into_iter().take(k) {
+    for &(_, _, idx) in candidates.iter().take(k) {
         grid[idx] = 0;
     }
 }
@@ -986,12 +1635,13 @@ fn sprinkle_islands(
     grid: &mut [u8],
     width: usize,
     height: usize,
-    rng: &mut ChaCha12Rng,
     count: usize,
     min_blob: usize,
     max_blob: usize,
+    scratch: &mut RepairScratch,
 ) {
-    let mut candidates: Vec<(u64, usize)> = Vec::new();
+    let RepairScratch { rng, tiles: candidates, .. } = scratch;
+    candidates.clear();
 
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
@@ -1013,7 +1663,7 @@ fn sprinkle_islands(
     let blob_span = max_blob.saturating_sub(min_blob) + 1;
 
     let mut placed = 0usize;
-    for (_, center_idx) in candidates {
+    for &(_, center_idx) in candidates.iter() {
         if placed >= count || grid[center_idx] != 0 {
             continue;
         }
@@ -1023,25 +1673,71 @@ fn sprinkle_islands(
     }
 }
 
-/// Carve inland lake blobs away from ocean-connected water.
+/// Carve lakes until `min_lakes` is met, based on a fresh analysis of the grid.
+/// Earlier passes in the same iteration (straits, channels) can open a lake to the ocean,
+/// so the stale analysis would under-count lakes and carve more water than needed.
+/// Every missing lake is one blob of at most `lake_blob_max` tiles.
+pub fn carve_missing_lakes(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    seed: u64,
+    global: &LandGlobalConfig,
+    constraints: &ConstraintsConfig,
+    repair: &RepairConfig,
+) {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    carve_missing_lakes_with(grid, width, height, global, constraints, repair, &mut RepairScratch::new(&mut rng));
+}
+
+/// `carve_missing_lakes` with the rng and vectors of the repair loop
+fn carve_missing_lakes_with(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    global: &LandGlobalConfig,
+    constraints: &ConstraintsConfig,
+    repair: &RepairConfig,
+    scratch: &mut RepairScratch,
+) {
+    let scaling = MapScaling::for_dimensions(width, height);
+    let mut analysis = LandAnalysis::default();
+    analyze_landmask_into(
+        &mut scratch.buffers,
+        &mut analysis,
+        grid,
+        width,
+        height,
+        &scaling,
+        global.min_lake_size,
+    );
+    if analysis.n_lakes >= constraints.min_lakes {
+        return;
+    }
+
+    carve_lakes(grid, width, height, &analysis, constraints.min_lakes - analysis.n_lakes, repair, scratch);
+}
+
+/// Carve inland lake blobs of `lake_blob_min..=lake_blob_max` tiles away from ocean-connected water.
 fn carve_lakes(
     grid: &mut [u8],
     width: usize,
     height: usize,
     analysis: &LandAnalysis,
-    rng: &mut ChaCha12Rng,
     count: usize,
-    min_blob: usize,
-    max_blob: usize,
+    repair: &RepairConfig,
+    scratch: &mut RepairScratch,
 ) {
+    let (min_blob, max_blob) = (repair.lake_blob_min, repair.lake_blob_max);
+    let RepairScratch { rng, candidates, .. } = scratch;
     let dist = inland_distance_to_ocean(grid, &analysis.ocean_mask, width, height);
-    let mut candidates: Vec<(u16, u64, usize)> = Vec::new();
+    candidates.clear();
 
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
             let idx = y * width + x;
             if grid[idx] == 1 && dist[idx] >= 3 {
-                candidates.push((dist[idx], rng.next_u64(), idx));
+                candidates.push((i32::from(dist[idx]), rng.next_u64(), idx));
             }
         }
     }
@@ -1049,36 +1745,151 @@ fn carve_lakes(
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
     let blob_span = max_blob.saturating_sub(min_blob) + 1;
 
-    for (_, _, idx) in candidates.into_iter().take(count) {
+    for &(_, _, idx) in candidates.iter().take(count) {
         let blob_size = min_blob + (rng.next_u32() as usize % blob_span.max(1));
         grow_blob_from_center(grid, width, height, idx, 0, blob_size, rng);
     }
 }
 
+/// Mark the land tiles that must survive erosion so peninsulas stay attached, see `peninsula_necks`.
+/// A `min_width` of 0 disables the protection.
+fn enforce_peninsula_preservation(
+    grid: &[u8],
+    width: usize,
+    height: usize,
+    analysis: &LandAnalysis,
+    min_width: usize,
+) -> Vec<bool> {
+    if min_width == 0 || analysis.land_tiles == 0 {
+        return vec![false; width * height];
+    }
+    peninsula_necks(grid, width, height, min_width)
+}
+
+/// Mark the land tiles holding peninsulas together: the articulation points of every land component
+/// (tiles whose removal would disconnect part of the component) plus all land within `min_width - 1`
+/// steps of them. A `min_width` of 0 or 1 marks only the articulation points.
+pub fn peninsula_necks(grid: &[u8], width: usize, height: usize, min_width: usize) -> Vec<bool> {
+    let n = width * height;
+    let mut protected = articulation_points(grid, width, height);
+
+    // Widen the protected necks
+    let mut dist = vec![usize::MAX; n];
+    let mut q = VecDeque::new();
+    for idx in 0..n {
+        if protected[idx] {
+            dist[idx] = 0;
+            q.push_back(idx);
+        }
+    }
+    while let Some(idx) = q.pop_front() {
+        if dist[idx] + 1 >= min_width {
+            continue;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let nidx = ny * width + nx;
+            if grid[nidx] == 1 && dist[nidx] == usize::MAX {
+                dist[nidx] = dist[idx] + 1;
+                protected[nidx] = true;
+                q.push_back(nidx);
+            }
+        }
+    }
+
+    protected
+}
+
+/// Find the articulation points of the land graph with an iterative Tarjan DFS.
+fn articulation_points(grid: &[u8], width: usize, height: usize) -> Vec<bool> {
+    let n = width * height;
+    let mut disc = vec![usize::MAX; n];
+    let mut low = vec![0usize; n];
+    let mut is_articulation = vec![false; n];
+    let mut time = 0usize;
+
+    let land_neighbors = |idx: usize| -> Vec<usize> {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(|(nx, ny)| ny * width + nx)
+            .filter(|&nidx| grid[nidx] == 1)
+            .collect()
+    };
+
+    for root in 0..n {
+        if grid[root] != 1 || disc[root] != usize::MAX {
+            continue;
+        }
+
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        let mut root_children = 0usize;
+
+        // (tile, parent, land neighbors, next neighbor to visit)
+        let mut stack: Vec<(usize, usize, Vec<usize>, usize)> = vec![(root, usize::MAX, land_neighbors(root), 0)];
+        while let Some((u, parent, neighbors, next)) = stack.last_mut() {
+            let (u, parent) = (*u, *parent);
+            if *next < neighbors.len() {
+                let v = neighbors[*next];
+                *next += 1;
+                if disc[v] == usize::MAX {
+                    disc[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    stack.push((v, u, land_neighbors(v), 0));
+                } else if v != parent {
+                    low[u] = low[u].min(disc[v]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            if parent == usize::MAX {
+                continue;
+            }
+            low[parent] = low[parent].min(low[u]);
+            if parent == root {
+                root_children += 1;
+            } else if low[u] >= disc[parent] {
+                is_articulation[parent] = true;
+            }
+        }
+
+        if root_children >= 2 {
+            is_articulation[root] = true;
+        }
+    }
+
+    is_articulation
+}
+
 /// Erode exposed coastal tiles from the largest component until it is under a target cap.
+/// Tiles marked in `protected` (see `enforce_peninsula_preservation`) are never eroded.
 fn erode_largest_component(
     grid: &mut [u8],
     width: usize,
     height: usize,
     analysis: &LandAnalysis,
-    rng: &mut ChaCha12Rng,
     cap: usize,
+    protected: &[bool],
+    scratch: &mut RepairScratch,
 ) {
     let Some(largest_id) = analysis.largest_component_idx else {
         return;
     };
+    let RepairScratch { rng, candidates, .. } = scratch;
 
     let largest_size = analysis.land_component_sizes[largest_id];
     if largest_size <= cap {
         return;
     }
 
-    let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+    candidates.clear();
 
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
             let idx = y * width + x;
-            if grid[idx] != 1 || analysis.land_component_ids[idx] != largest_id {
+            if grid[idx] != 1 || analysis.land_component_ids[idx] != largest_id || protected[idx] {
                 continue;
             }
 
@@ -1095,25 +1906,92 @@ fn erode_largest_component(
 
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
     let remove_count = (largest_size - cap).min(candidates.len());
-    for (_, _, idx) in candidates.into_iter().take(remove_count) {
+    for &(_, _, idx) in candidates.iter().take(remove_count) {
         grid[idx] = 0;
     }
 }
 
+/// Push major landmasses back into the configured compactness range.
+/// Too blobby landmasses get a noisy coastline (coastal tiles flipped in both directions),
+/// too fjordy ones get a smoothing pass that fills bays and removes thin spits.
+fn adjust_compactness(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    analysis: &LandAnalysis,
+    constraints: &ConstraintsConfig,
+    rng: &mut ChaCha12Rng,
+) {
+    for &(component, score) in &analysis.major_compactness {
+        let too_blobby = constraints.max_compactness.is_some_and(|max| score > max);
+        let too_fjordy = constraints.min_compactness.is_some_and(|min| score < min);
+        if !too_blobby && !too_fjordy {
+            continue;
+        }
+
+        // collect the coast of the component: its land tiles and the water next to them
+        let mut coast: Vec<(u64, usize)> = Vec::new();
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                let idx = y * width + x;
+                if grid[idx] != 1 || analysis.land_component_ids[idx] != component {
+                    continue;
+                }
+                for (nx, ny) in neighbors_odd_r(x, y, width, height) {
+                    let nidx = ny * width + nx;
+                    if grid[nidx] == 0 {
+                        coast.push((rng.next_u64(), idx));
+                        coast.push((rng.next_u64(), nidx));
+                    }
+                }
+            }
+        }
+        coast.sort_unstable();
+        coast.dedup_by_key(|(_, idx)| *idx);
+
+        if too_blobby {
+            // flip a quarter of the coast to roughen it
+            let flips = (coast.len() / 4).max(1);
+            for (_, idx) in coast.into_iter().take(flips) {
+                let (x, y) = (idx % width, idx / width);
+                if x > 0 && y > 0 && x + 1 < width && y + 1 < height {
+                    grid[idx] = 1 - grid[idx];
+                }
+            }
+        } else {
+            // majority smoothing on the coast
+            let snapshot = grid.to_vec();
+            for (_, idx) in coast {
+                let (x, y) = (idx % width, idx / width);
+                let land_n = neighbors_odd_r(x, y, width, height)
+                    .into_iter()
+                    .filter(|(nx, ny)| snapshot[ny * width + nx] == 1)
+                    .count();
+                if snapshot[idx] == 0 && land_n >= 4 {
+                    grid[idx] = 1;
+                } else if snapshot[idx] == 1 && land_n <= 2 {
+                    grid[idx] = 0;
+                }
+            }
+        }
+    }
+}
+
 /// Fill narrow channels inside the main continent to strengthen a pangea shape.
 fn fill_internal_straits(
     grid: &mut [u8],
     width: usize,
     height: usize,
     analysis: &LandAnalysis,
-    rng: &mut ChaCha12Rng,
     max_fill: usize,
+    scratch: &mut RepairScratch,
 ) {
     let Some(largest_id) = analysis.largest_component_idx else {
         return;
     };
+    let RepairScratch { rng, candidates, .. } = scratch;
 
-    let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+    candidates.clear();
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
             let idx = y * width + x;
@@ -1141,7 +2019,7 @@ fn fill_internal_straits(
     }
 
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
-    for (_, _, idx) in candidates.into_iter().take(max_fill) {
+    for &(_, _, idx) in candidates.iter().take(max_fill) {
         grid[idx] = 1;
     }
 }
@@ -1179,8 +2057,17 @@ fn connect_to_largest(
 }
 
 /// Expand land from near-coast candidates to increase total land ratio.
-fn grow_land(grid: &mut [u8], width: usize, height: usize, rng: &mut ChaCha12Rng, budget: usize) {
-    let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+fn grow_land(
+    grid: &mut [u8],
+    width: usize,
+    height: usize,
+    budget: usize,
+    profile: &[f32],
+    scratch: &mut RepairScratch,
+) {
+    let RepairScratch { rng, candidates, .. } = scratch;
+    candidates.clear();
+    let preferred = preferred_bands(grid, width, height, profile, true);
 
     for y in 1..height.saturating_sub(1) {
         for x in 1..width.saturating_sub(1) {
@@ -1195,27 +2082,29 @@ fn grow_land(grid: &mut [u8], width: usize, height: usize, rng: &mut ChaCha12Rng
                 .count() as i32;
 
             if land_n >= 2 {
-                candidates.push((land_n, rng.next_u64(), idx));
+                let bonus = band_bonus(preferred.as_deref(), y, height);
+                candidates.push((land_n + bonus, rng.next_u64(), idx));
             }
         }
     }
 
     candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
-    for (_, _, idx) in candidates.into_iter().take(budget) {
+    for &(_, _, idx) in candidates.iter().take(budget) {
         grid[idx] = 1;
     }
 }
 
-/// Adjust global land ratio by growing/shrinking near-coast tiles.
+/// Adjust global land ratio towards the constraints by growing/shrinking near-coast tiles.
 fn adjust_land_ratio(
     grid: &mut [u8],
     width: usize,
     height: usize,
-    rng: &mut ChaCha12Rng,
-    min_ratio: f32,
-    max_ratio: f32,
+    constraints: &ConstraintsConfig,
     cap_divisor: usize,
+    profile: &[f32],
+    scratch: &mut RepairScratch,
 ) {
+    let (min_ratio, max_ratio) = (constraints.min_land_ratio, constraints.max_land_ratio);
     let total = width * height;
     let land = grid.iter().filter(|&&v| v == 1).count();
     let ratio = land as f32 / total as f32;
@@ -1224,12 +2113,14 @@ fn adjust_land_ratio(
     if ratio < min_ratio {
         let target = (min_ratio * total as f32).ceil() as usize;
         let need = target.saturating_sub(land).min(cap);
-        grow_land(grid, width, height, rng, need);
+        grow_land(grid, width, height, need, profile, scratch);
     } else if ratio > max_ratio {
         let target = (max_ratio * total as f32).floor() as usize;
         let need = land.saturating_sub(target).min(cap);
+        let preferred = preferred_bands(grid, width, height, profile, false);
 
-        let mut candidates: Vec<(i32, u64, usize)> = Vec::new();
+        let RepairScratch { rng, candidates, .. } = scratch;
+        candidates.clear();
         for y in 1..height.saturating_sub(1) {
             for x in 1..width.saturating_sub(1) {
                 let idx = y * width + x;
@@ -1243,13 +2134,14 @@ fn adjust_land_ratio(
                     .count() as i32;
 
                 if water_n >= 2 {
-                    candidates.push((water_n, rng.next_u64(), idx));
+                    let bonus = band_bonus(preferred.as_deref(), y, height);
+                    candidates.push((water_n + bonus, rng.next_u64(), idx));
                 }
             }
         }
 
         candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
-        for (_, _, idx) in candidates.into_iter().take(need) {
+        for &(_, _, idx) in candidates.iter().take(need) {
             grid[idx] = 0;
         }
     }
@@ -1260,13 +2152,19 @@ fn force_land_ratio(
     grid: &mut [u8],
     width: usize,
     height: usize,
-    rng: &mut ChaCha12Rng,
-    min_ratio: f32,
-    max_ratio: f32,
+    constraints: &ConstraintsConfig,
+    profile: &[f32],
+    scratch: &mut RepairScratch,
 ) {
     let total = width * height;
-    let min_land = (min_ratio * total as f32).ceil() as usize;
-    let max_land = (max_ratio * total as f32).floor() as usize;
+    let min_land = (constraints.min_land_ratio * total as f32).ceil() as usize;
+    let max_land = (constraints.max_land_ratio * total as f32).floor() as usize;
+    let RepairScratch {
+        rng,
+        tiles: coastal,
+        fallback_tiles: any,
+        ..
+    } = scratch;
 
     // Grow phase.
     loop {
@@ -1275,8 +2173,9 @@ fn force_land_ratio(
             break;
         }
 
-        let mut coastal_water: Vec<(u64, usize)> = Vec::new();
-        let mut any_water: Vec<(u64, usize)> = Vec::new();
+        let (coastal_water, any_water) = (&mut *coastal, &mut *any);
+        coastal_water.clear();
+        any_water.clear();
         for y in 1..height.saturating_sub(1) {
             for x in 1..width.saturating_sub(1) {
                 let idx = y * width + x;
@@ -1298,11 +2197,13 @@ fn force_land_ratio(
             break;
         }
 
+        let preferred = preferred_bands(grid, width, height, profile, true);
+        let key = |&(tie, idx): &(u64, usize)| (-band_bonus(preferred.as_deref(), idx / width, height), tie);
         if !coastal_water.is_empty() {
-            coastal_water.sort_unstable_by_key(|v| v.0);
+            coastal_water.sort_unstable_by_key(key);
             grid[coastal_water[0].1] = 1;
         } else {
-            any_water.sort_unstable_by_key(|v| v.0);
+            any_water.sort_unstable_by_key(key);
             grid[any_water[0].1] = 1;
         }
     }
@@ -1314,8 +2215,9 @@ fn force_land_ratio(
             break;
         }
 
-        let mut coastal_land: Vec<(u64, usize)> = Vec::new();
-        let mut any_land: Vec<(u64, usize)> = Vec::new();
+        let (coastal_land, any_land) = (&mut *coastal, &mut *any);
+        coastal_land.clear();
+        any_land.clear();
         for y in 1..height.saturating_sub(1) {
             for x in 1..width.saturating_sub(1) {
                 let idx = y * width + x;
@@ -1337,16 +2239,65 @@ fn force_land_ratio(
             break;
         }
 
+        let preferred = preferred_bands(grid, width, height, profile, false);
+        let key = |&(tie, idx): &(u64, usize)| (-band_bonus(preferred.as_deref(), idx / width, height), tie);
         if !coastal_land.is_empty() {
-            coastal_land.sort_unstable_by_key(|v| v.0);
+            coastal_land.sort_unstable_by_key(key);
             grid[coastal_land[0].1] = 0;
         } else {
-            any_land.sort_unstable_by_key(|v| v.0);
+            any_land.sort_unstable_by_key(key);
             grid[any_land[0].1] = 0;
         }
     }
 }
 
+/// Weights of a latitude land profile relative to their mean, None for a flat profile.
+fn relative_band_weights(profile: &[f32]) -> Option<Vec<f32>> {
+    let first = *profile.first()?;
+    if profile.iter().all(|&weight| weight == first) {
+        return None;
+    }
+    let mean = profile.iter().sum::<f32>() / profile.len() as f32;
+    Some(profile.iter().map(|weight| weight / mean).collect())
+}
+
+/// Latitude band of row `y` when `height` rows are split into `bands` equally tall bands
+fn latitude_band(y: usize, height: usize, bands: usize) -> usize {
+    (y * bands / height.max(1)).min(bands - 1)
+}
+
+/// Per latitude band, whether it has less land (`grow`) or more land (`!grow`) than its share of the current land
+/// under the profile. None for a flat profile.
+fn preferred_bands(grid: &[u8], width: usize, height: usize, profile: &[f32], grow: bool) -> Option<Vec<bool>> {
+    let weights = relative_band_weights(profile)?;
+    let bands = weights.len();
+    let mut rows = vec![0usize; bands];
+    let mut land = vec![0usize; bands];
+    for y in 0..height {
+        let band = latitude_band(y, height, bands);
+        rows[band] += 1;
+        land[band] += grid[y * width..(y + 1) * width].iter().filter(|&&v| v == 1).count();
+    }
+
+    let total_land = land.iter().sum::<usize>() as f32;
+    let total_weight: f32 = weights.iter().zip(&rows).map(|(weight, &n)| weight * n as f32).sum();
+    let preferred = (0..bands)
+        .map(|band| {
+            let target = total_land * weights[band] * rows[band] as f32 / total_weight.max(f32::EPSILON);
+            if grow { (land[band] as f32) < target } else { land[band] as f32 > target }
+        })
+        .collect();
+    Some(preferred)
+}
+
+/// Score bonus for repair candidates in row `y` if its latitude band is preferred
+fn band_bonus(preferred: Option<&[bool]>, y: usize, height: usize) -> i32 {
+    match preferred {
+        Some(preferred) if preferred[latitude_band(y, height, preferred.len())] => 8,
+        _ => 0,
+    }
+}
+
 /// Ensure maps that require multiple land components do not end as a single supercontinent.
 fn ensure_min_components(
     grid: &mut [u8],
@@ -1354,23 +2305,32 @@ fn ensure_min_components(
     height: usize,
     global: &LandGlobalConfig,
     constraints: &ConstraintsConfig,
-    rng: &mut ChaCha12Rng,
+    repair: &RepairConfig,
+    scratch: &mut RepairScratch,
 ) {
-    let island_max = dynamic_island_max(&size_from_dims(width, height), global);
-    let mid_max = dynamic_mid_max(&size_from_dims(width, height), global);
+    let scaling = MapScaling::for_dimensions(width, height);
 
+    let mut analysis = LandAnalysis::default();
     for _ in 0..8 {
-        let analysis = analyze_landmask(grid, width, height, island_max, mid_max, global.min_lake_size);
+        analyze_landmask_into(
+            &mut scratch.buffers,
+            &mut analysis,
+            grid,
+            width,
+            height,
+            &scaling,
+            global.min_lake_size,
+        );
         if analysis.n_components >= constraints.min_components {
             break;
         }
 
         let missing = constraints.min_components - analysis.n_components;
-        let map_scale = (width * height) as f32 / (84.0 * 54.0);
-        let k = ((8.0 * map_scale.max(1.0)).ceil() as usize) * missing;
+        let k = ((8.0 * scaling.area_ratio.max(1.0)).ceil() as usize) * missing;
 
-        carve_straits(grid, width, height, &analysis, rng, k);
-        channel_carve(grid, width, height, &analysis, rng, (k / 2).max(1));
+        carve_straits(grid, width, height, &analysis, k, repair.peninsula_min_width, scratch);
+        channel_carve(grid, width, height, &analysis, (k / 2).max(1), scratch);
+        resolve_thin_strings(grid, width, height, repair, scaling.island_max);
         enforce_border_water(grid, width, height);
     }
 }
@@ -1380,25 +2340,39 @@ fn enforce_terra_two_worlds(
     grid: &mut [u8],
     width: usize,
     height: usize,
-    _vertical: bool,
-    _barrier_w: usize,
     old_side: &[bool],
     new_side: &[bool],
     global: &LandGlobalConfig,
     rng: &mut ChaCha12Rng,
 ) {
-    let island_max = dynamic_island_max(&size_from_dims(width, height), global);
-    let mid_max = dynamic_mid_max(&size_from_dims(width, height), global);
+    let scaling = MapScaling::for_dimensions(width, height);
 
-    let mut analysis =
-        analyze_landmask(grid, width, height, island_max, mid_max, global.min_lake_size);
+    let mut buffers = AnalysisBuffers::default();
+    let mut analysis = LandAnalysis::default();
+    analyze_landmask_into(
+        &mut buffers,
+        &mut analysis,
+        grid,
+        width,
+        height,
+        &scaling,
+        global.min_lake_size,
+    );
     let mut old_comp = dominant_component_on_mask(&analysis, old_side);
     let mut new_comp = dominant_component_on_mask(&analysis, new_side);
 
     // If the new-world side has no continent, seed one.
     if new_comp.is_none() || new_comp == old_comp {
         seed_new_world_component(grid, width, height, new_side, rng);
-        analysis = analyze_landmask(grid, width, height, island_max, mid_max, global.min_lake_size);
+        analyze_landmask_into(
+            &mut buffers,
+            &mut analysis,
+            grid,
+            width,
+            height,
+            &scaling,
+            global.min_lake_size,
+        );
         old_comp = dominant_component_on_mask(&analysis, old_side);
         new_comp = dominant_component_on_mask(&analysis, new_side);
     }
@@ -1429,6 +2403,8 @@ fn dominant_component_on_mask(analysis: &LandAnalysis, side_mask: &[bool]) -> Op
         return None;
     }
 
+    // component ids are indices into the component sizes, and the side masks cover the whole grid
+    debug_assert_eq!(side_mask.len(), analysis.land_component_ids.len());
     let mut overlap = vec![0usize; analysis.land_component_sizes.len()];
     for (idx, &cid) in analysis.land_component_ids.iter().enumerate() {
         if cid == usize::MAX || !side_mask[idx] {
@@ -1470,19 +2446,6 @@ fn seed_new_world_component(
     grow_blob_from_center(grid, width, height, center_idx, 1, blob_size, rng);
 }
 
-/// Build a synthetic map-size enum from dimensions for dynamic thresholds.
-fn size_from_dims(width: usize, height: usize) -> MapSizes {
-    match (width, height) {
-        (44, 26) => MapSizes::Duel,
-        (60, 38) => MapSizes::Tiny,
-        (74, 46) => MapSizes::Small,
-        (84, 54) => MapSizes::Standard,
-        (96, 60) => MapSizes::Large,
-        (106, 66) => MapSizes::Huge,
-        _ => MapSizes::Standard,
-    }
-}
-
 /// Compute inland distance from each land tile to ocean using BFS.
 fn inland_distance_to_ocean(grid: &[u8], ocean_mask: &[bool], width: usize, height: usize) -> Vec<u16> {
     let mut dist = vec![u16::MAX; width * height];
@@ -1570,18 +2533,11 @@ fn grow_blob_from_center(
     }
 }
 
-/// Compute dynamic island threshold from map size and global config.
-fn dynamic_island_max(size: &MapSizes, global: &LandGlobalConfig) -> usize {
-    (size.grid_size() / global.island_max_divisor.max(1)).clamp(global.island_max_min, global.island_max_max)
-}
-
-/// Compute dynamic mid-size threshold from map size and global config.
-fn dynamic_mid_max(size: &MapSizes, global: &LandGlobalConfig) -> usize {
-    (size.grid_size() / global.mid_max_divisor.max(1)).clamp(global.mid_max_min, global.mid_max_max)
-}
-
 /// Force water on all map borders.
 fn enforce_border_water(grid: &mut [u8], width: usize, height: usize) {
+    if width == 0 || height == 0 {
+        return;
+    }
     for x in 0..width {
         grid[x] = 0;
         grid[(height - 1) * width + x] = 0;
@@ -1592,19 +2548,36 @@ fn enforce_border_water(grid: &mut [u8], width: usize, height: usize) {
     }
 }
 
-/// Mirror half-map into full-map along vertical axis.
-fn mirror_vertical_into(half: &[u8], out: &mut [u8], width: usize, height: usize) {
-    let half_w = width.div_ceil(2);
+/// Force the top and bottom `rows` rows of the grid to water.
+fn enforce_polar_water(grid: &mut [u8], width: usize, height: usize, rows: usize) {
+    let rows = rows.min(height);
+    grid[..rows * width].fill(0);
+    grid[(height - rows) * width..].fill(0);
+}
+
+/// Reflect the generated part of a mirror map (`half_w` x `half_h`, top-left) into the full map.
+fn mirror_into(half: &[u8], half_w: usize, half_h: usize, out: &mut [u8], width: usize, height: usize) {
     for y in 0..height {
+        let src_y = if y < half_h { y } else { height - 1 - y };
         for x in 0..width {
             let src_x = if x < half_w { x } else { width - 1 - x };
-            out[y * width + x] = half[y * half_w + src_x];
+            out[y * width + x] = half[src_y * half_w + src_x];
         }
     }
 }
 
-/// Enforce exact vertical symmetry in-place.
-fn enforce_vertical_mirror(grid: &mut [u8], width: usize, height: usize) {
+/// Enforce exact symmetry along the mirror axis in-place.
+fn enforce_mirror(grid: &mut [u8], width: usize, height: usize, axis: MirrorAxis) {
+    if axis.mirrors_columns() {
+        enforce_vertical_mirror(grid, width, height);
+    }
+    if axis.mirrors_rows() {
+        enforce_horizontal_mirror(grid, width, height);
+    }
+}
+
+/// Enforce exact vertical symmetry in-place, the left half is copied onto the right half.
+pub fn enforce_vertical_mirror(grid: &mut [u8], width: usize, height: usize) {
     let half_w = width.div_ceil(2);
     for y in 0..height {
         for x in 0..half_w {
@@ -1615,6 +2588,14 @@ fn enforce_vertical_mirror(grid: &mut [u8], width: usize, height: usize) {
     }
 }
 
+/// Enforce exact horizontal symmetry in-place, the top half is copied onto the bottom half.
+pub fn enforce_horizontal_mirror(grid: &mut [u8], width: usize, height: usize) {
+    for y in 0..height / 2 {
+        let my = height - 1 - y;
+        grid.copy_within(y * width..(y + 1) * width, my * width);
+    }
+}
+
 /// Compute center tile of one component using component IDs.
 fn component_center(analysis: &LandAnalysis, component_id: usize, width: usize) -> (usize, usize) {
     let mut sx = 0usize;
@@ -1666,18 +2647,7 @@ fn draw_soft_line(
 
 /// Hex distance helper (offset odd-r -> cube conversion).
 fn hex_distance_offset(a: (usize, usize), b: (usize, usize)) -> usize {
-    let ac = oddr_to_cube(a.0 as i32, a.1 as i32);
-    let bc = oddr_to_cube(b.0 as i32, b.1 as i32);
-    ((ac.0 - bc.0)
-        .abs()
-        .max((ac.1 - bc.1).abs())
-        .max((ac.2 - bc.2).abs())) as usize
-}
-
-/// Convert odd-r offset hex coordinates to cube coordinates.
-fn oddr_to_cube(col: i32, row: i32) -> (i32, i32, i32) {
-    let x = col - (row - (row & 1)) / 2;
-    let z = row;
-    let y = -x - z;
-    (x, y, z)
+    let a = HexCoord::new(a.0 as i32, a.1 as i32);
+    let b = HexCoord::new(b.0 as i32, b.1 as i32);
+    a.distance(&b) as usize
 }
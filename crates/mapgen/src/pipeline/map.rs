@@ -1,23 +1,152 @@
+use noise::{Fbm, MultiFractal, NoiseFn, OpenSimplex};
+use serde_json::{Value, json};
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    fs,
+    io::{self, Write},
+    ops::Range,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
 use crate::{
-    map_components::{terrain::Terrain, tile::Tile},
+    map_components::{
+        hex_coords::{CompassDirection, HexCoord},
+        rivers::RiverClass,
+        terrain::{DesertVariant, Feature, Terrain, WaterDepth},
+        tile::Tile,
+        tile_modifiers::TileModifiers,
+        tile_tags::TileTags,
+        yields::BaseYields,
+    },
     pipeline::{
-        biomes::{generate_map, generate_map_with_type},
-        features::place_features,
+        analysis::{
+            InterestPoint, LandmassInfo, MapStatistics, StressTestReport, StressTestSeed, add_to_histogram,
+            interest_point, label_landmasses, landmass_stats, lowland_mask,
+        },
+        appeal::compute_appeal,
+        constraints::ConstraintOutcome,
+        document::MapDocument,
+        error::GenError,
+        biomes::{
+            BiomeConstraintReport, MapLayers, coastal_water_mask, generate_geological_age, try_generate_map_with_type,
+            generate_temperature_range, ocean_mask, smooth_land_heights,
+        },
+        features::{place_features, place_oases, place_river_deltas, river_width_tiers},
+        helpers::{
+            ConstraintsConfig, biomes_config, config_hash, landmasses_config, landmasses_config_for, neighbors_odd_r,
+            resources_config, with_landmasses_config,
+        },
+        land::{RepairIterationCount, evaluate_constraints},
+        map_builder::LayerError,
         map_sizes::MapSizes,
         map_types::MapTypes,
+        ndjson::write_ndjson,
+        pathfinding::{self, CoastalGroups, Navigation, Path as MovePath, movement_cost},
+        recipe::Recipe,
+        resources::{StrategicVein, place_strategic_veins},
+        sanity::check_map,
+        starts::{StartPlacementError, StartPlacementOptions, StartPlacementReport, place_starts},
+        text_map::GeneratedMapData,
+        water_bodies::{WaterBodyInfo, water_bodies},
+        wind::WindLayer,
     },
 };
 
-/// Map struct that holds all tiles as well as information about itself
+/// Number of landmasses, largest first, whose statistics the summary JSON includes
+pub const SUMMARY_LANDMASSES: usize = 5;
+
+#[derive(Debug, Clone)]
+/// Map struct that holds all tiles as well as information about itself.
+/// The tiles and other per-tile data are shared behind an `Arc`, so cloning a map is cheap and clones can be
+/// read from several threads. Mutating the tiles (`tiles_mut`) copies them first if another clone shares them.
 pub struct Map {
     seed: Option<u64>,
     size: MapSizes,
+    map_type: MapTypes,
+    /// `config_hash` of the config the map was generated with
+    config_hash: u64,
+    data: Arc<MapData>,
+    repair_iterations: RepairIterationCount,
+    /// Outcome of the biome constraint pass, None without biome constraints
+    biome_constraints: Option<BiomeConstraintReport>,
+    /// Acceptance constraints of the map type measured on the final landmask
+    constraint_outcomes: Vec<ConstraintOutcome>,
+    /// Prevailing wind per row
+    wind: WindLayer,
+    tags: TileTags,
+}
+
+#[derive(Debug, Clone)]
+/// Bulk data of a `Map`, shared between its clones
+struct MapData {
     tiles: Vec<Tile>,
+    /// Rivers as lists of tile indices, from source to mouth
+    rivers: Vec<Vec<usize>>,
+    landmasses: Vec<LandmassInfo>,
+    strategic_veins: Vec<StrategicVein>,
+    /// Appeal per tile, water tiles are 0
+    appeal: Vec<i8>,
+    /// Water body id per tile, None for land
+    water_body_ids: Vec<Option<usize>>,
+    water_bodies: Vec<WaterBodyInfo>,
 }
 
 impl Map {
     /// Instatiate a new map with a given seed (or randomly assigned) and size
     pub fn new(seed: Option<u64>, size: MapSizes) -> Self {
+        Self::new_with_type(seed, size, MapTypes::Continents)
+    }
+
+    /// Same as `new` but allows selecting the map type
+    ///
+    /// # Panics
+    /// If the config is invalid, see `try_new_with_type`. Never for the preset sizes with the shipped configs.
+    pub fn new_with_type(seed: Option<u64>, size: MapSizes, map_type: MapTypes) -> Self {
+        Self::try_new_with_type(seed, size, map_type).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `new_with_type` but returns an error if the config can not produce a map of this type
+    pub fn try_new_with_type(seed: Option<u64>, size: MapSizes, map_type: MapTypes) -> Result<Self, GenError> {
+        let map = Self::generate(seed, size, map_type)?;
+        warn_if_not_converged(map.repair_iterations);
+        Ok(map)
+    }
+
+    /// Same as `new_with_type` but with some acceptance constraints of the map type replaced for this map only.
+    /// `extra_constraints` is merged into the configured constraints with `ConstraintsConfig::merge_override`,
+    /// so zero fields keep their configured value. Counts are overridden before they are scaled to the map size.
+    ///
+    /// # Panics
+    /// If the merged constraints are invalid, e.g. a minimum above its maximum, see `try_generate_with_constraints`.
+    pub fn generate_with_constraints(
+        size: MapSizes,
+        seed: Option<u64>,
+        map_type: MapTypes,
+        extra_constraints: &ConstraintsConfig,
+    ) -> Self {
+        Self::try_generate_with_constraints(size, seed, map_type, extra_constraints)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `generate_with_constraints` but returns an error if the merged constraints can not be met
+    pub fn try_generate_with_constraints(
+        size: MapSizes,
+        seed: Option<u64>,
+        map_type: MapTypes,
+        extra_constraints: &ConstraintsConfig,
+    ) -> Result<Self, GenError> {
+        let config = landmasses_config();
+        let constraints = config.constraints_for(map_type).merge_override(extra_constraints);
+        let config = config.as_ref().clone().with_constraint_override(map_type, constraints);
+        with_landmasses_config(config, || Self::try_new_with_type(seed, size, map_type))
+    }
+
+    /// Run the generation pipeline and build the tiles
+    fn generate(seed: Option<u64>, size: MapSizes, map_type: MapTypes) -> Result<Self, GenError> {
         // use given seed or choose the default seed (13)
         let internal_seed = match seed {
             Some(value) => value,
@@ -25,27 +154,1387 @@ impl Map {
         };
 
         // Create basic landmasses and Terrains
-        let (terrain_vec, height, hill_vec, temp, rain) = generate_map(&internal_seed, &size);
+        let layers = try_generate_map_with_type(&internal_seed, &size, map_type)?;
+        let rivers = place_features(&layers.terrain, &layers.rainfall, &layers.height, &size);
+        let mut features = place_oases(
+            internal_seed,
+            &layers.terrain,
+            &layers.hills,
+            &layers.desert_variants,
+            &size,
+            &biomes_config().desert,
+        );
+        let width_tiers = river_width_tiers(&rivers, size.grid_size());
+        place_river_deltas(internal_seed, &rivers, &width_tiers, &layers.terrain, &size, &mut features);
 
-        todo!()
+        Ok(Self::from_generated_layers(seed, size, map_type, &layers, rivers, features))
     }
 
-    pub fn debug_terrains(seed: Option<u64>, size: MapSizes, map_type: MapTypes) -> (Vec<Terrain>, Vec<bool>) {
+    /// Build the tiles, landmass statistics and appeal from generated layers
+    fn from_generated_layers(
+        seed: Option<u64>,
+        size: MapSizes,
+        map_type: MapTypes,
+        layers: &MapLayers,
+        rivers: Vec<Vec<usize>>,
+        features: Vec<Option<Feature>>,
+    ) -> Self {
+        // Label the landmasses and collect their statistics
+        let (width, height) = size.dimensions();
+        let (landmass_ids, _) = label_landmasses(&layers.terrain, width, height);
+        let mut landmasses = landmass_stats(
+            &layers.terrain,
+            &layers.hills,
+            &layers.temperature,
+            &layers.rainfall,
+            &landmass_ids,
+            width,
+            height,
+        );
+        for (id, ruggedness) in landmass_ids.iter().zip(&layers.coast_ruggedness) {
+            if let (Some(id), Some(ruggedness)) = (id, ruggedness) {
+                landmasses[*id].ruggedness.get_or_insert(*ruggedness);
+            }
+        }
+
+        // Build the tiles in row-major order (odd-r offset coordinates)
+        let modifier_rules = &biomes_config().tile_modifiers;
+        let water_depths = water_depths(&layers.terrain, width, height);
+        let mut tiles: Vec<Tile> = (0..size.grid_size())
+            .map(|idx| {
+                let coords = HexCoord::new((idx % width) as i32, (idx / width) as i32);
+                let mut tile = Tile::new(coords, layers.terrain[idx], layers.hills[idx]);
+                tile.set_climate(layers.height[idx], layers.temperature[idx], layers.rainfall[idx]);
+                tile.set_temperature_range(layers.temperature_range[idx]);
+                tile.set_geological_age(layers.geological_age[idx]);
+                tile.set_desert_variant(layers.desert_variants[idx]);
+                tile.set_feature(features[idx]);
+                tile.set_modifiers(modifier_rules.modifiers(layers.terrain[idx], layers.hills[idx], features[idx]));
+                tile.set_landmass_id(landmass_ids[idx]);
+                tile.set_water_depth(water_depths[idx]);
+                tile
+            })
+            .collect();
+
+        mark_rivers(&mut tiles, &rivers);
+        let lowland = lowland_mask(&layers.terrain, &layers.height, &rivers, width, height, &biomes_config().lowland);
+        for (tile, lowland) in tiles.iter_mut().zip(lowland) {
+            tile.set_lowland(lowland);
+        }
+
+        // Appeal depends on the neighbors, so it runs after all features are in place
+        let terrain: Vec<Terrain> = tiles.iter().map(|tile| tile.terrain()).collect();
+        let features: Vec<_> = tiles.iter().map(|tile| tile.feature()).collect();
+        let appeal = compute_appeal(&terrain, &features, width, height, &biomes_config().appeal);
+        for (tile, value) in tiles.iter_mut().zip(&appeal) {
+            if !tile.terrain().is_water() {
+                tile.set_appeal(*value as i32);
+            }
+        }
+
+        let strategic_veins = place_strategic_veins(
+            seed.unwrap_or(12) + 6,
+            &tiles,
+            &landmasses,
+            &size,
+            map_type,
+            &resources_config().strategic,
+        );
+        for vein in &strategic_veins {
+            for &idx in &vein.deposits {
+                tiles[idx].set_strategic_resource(vein.resource);
+            }
+        }
+
+        let (water_body_ids, water_bodies) = Self::water_bodies_of(seed, &size, &layers.terrain);
+        Map {
+            seed,
+            size,
+            map_type,
+            config_hash: config_hash(),
+            data: Arc::new(MapData {
+                tiles,
+                rivers,
+                landmasses,
+                strategic_veins,
+                appeal,
+                water_body_ids,
+                water_bodies,
+            }),
+            repair_iterations: layers.repair_iterations,
+            biome_constraints: layers.biome_constraints.clone(),
+            constraint_outcomes: evaluate_constraints(&layers.landmask, &size, map_type),
+            wind: WindLayer::new(width, height, &biomes_config().wind),
+            tags: TileTags::new(),
+        }
+    }
+
+    /// Map of existing tiles in row-major order, without running a generator. The landmass statistics,
+    /// appeal and constraint outcomes are derived from the tiles. The map has no rivers (the river class of
+    /// the tiles is kept) and no strategic veins. To build the tiles from layers use `MapBuilder`.
+    /// Fails if there is not one tile per index of the size or a tile is not at the hex of its index.
+    pub fn from_layers(size: MapSizes, tiles: Vec<Tile>) -> Result<Self, LayerError> {
+        Self::from_tiles(None, size, MapTypes::Continents, tiles)
+    }
+
+    /// Replace the rivers and mark their tiles, see `MapBuilder::rivers`. Indices must be on the map
+    pub(crate) fn set_rivers(&mut self, rivers: Vec<Vec<usize>>) {
+        let data = Arc::make_mut(&mut self.data);
+        mark_rivers(&mut data.tiles, &rivers);
+        data.rivers = rivers;
+    }
+
+    /// `from_layers` with the seed and map type the map reports
+    pub(crate) fn from_tiles(
+        seed: Option<u64>,
+        size: MapSizes,
+        map_type: MapTypes,
+        tiles: Vec<Tile>,
+    ) -> Result<Self, LayerError> {
+        let (width, height) = size.dimensions();
+        if tiles.len() != size.grid_size() {
+            return Err(LayerError::LengthMismatch {
+                layer: "tiles",
+                expected: size.grid_size(),
+                found: tiles.len(),
+            });
+        }
+        for (index, tile) in tiles.iter().enumerate() {
+            let expected = HexCoord::new((index % width) as i32, (index / width) as i32);
+            if tile.hex_coords() != expected {
+                return Err(LayerError::HexMismatch {
+                    index,
+                    expected,
+                    found: tile.hex_coords(),
+                });
+            }
+        }
+
+        let terrain: Vec<Terrain> = tiles.iter().map(|tile| tile.terrain()).collect();
+        let hills: Vec<bool> = tiles.iter().map(|tile| tile.is_hill()).collect();
+        let temperature: Vec<u8> = tiles.iter().map(|tile| tile.temperature()).collect();
+        let rainfall: Vec<u8> = tiles.iter().map(|tile| tile.rainfall()).collect();
+        let (landmass_ids, _) = label_landmasses(&terrain, width, height);
+        let landmasses = landmass_stats(&terrain, &hills, &temperature, &rainfall, &landmass_ids, width, height);
+        let appeal = tiles
+            .iter()
+            .map(|tile| {
+                let appeal = if tile.terrain().is_water() { 0 } else { tile.yields().get_yield(BaseYields::Appeal) };
+                appeal.clamp(i8::MIN as i32, i8::MAX as i32) as i8
+            })
+            .collect();
+        let landmask: Vec<u8> = terrain.iter().map(|terrain| u8::from(!terrain.is_water())).collect();
+
+        let (water_body_ids, water_bodies) = Self::water_bodies_of(seed, &size, &terrain);
+        Ok(Map {
+            seed,
+            size,
+            map_type,
+            config_hash: config_hash(),
+            data: Arc::new(MapData {
+                tiles,
+                rivers: Vec::new(),
+                landmasses,
+                strategic_veins: Vec::new(),
+                appeal,
+                water_body_ids,
+                water_bodies,
+            }),
+            repair_iterations: RepairIterationCount::default(),
+            biome_constraints: None,
+            constraint_outcomes: evaluate_constraints(&landmask, &size, map_type),
+            wind: WindLayer::new(width, height, &biomes_config().wind),
+            tags: TileTags::new(),
+        })
+    }
+
+    /// Water bodies of the terrain, with lakes as large as the lake constraints of the size count them
+    fn water_bodies_of(seed: Option<u64>, size: &MapSizes, terrain: &[Terrain]) -> (Vec<Option<usize>>, Vec<WaterBodyInfo>) {
+        let (width, height) = size.dimensions();
+        let min_lake_size = landmasses_config_for(size).global.min_lake_size;
+        water_bodies(terrain, width, height, seed.unwrap_or(12), min_lake_size)
+    }
+
+    /// Generate the layers and rivers for the debug renderer, without building tiles
+    pub fn debug_terrains(
+        seed: Option<u64>,
+        size: MapSizes,
+        map_type: MapTypes,
+    ) -> Result<(MapLayers, Vec<Vec<usize>>), GenError> {
         let internal_seed = match seed {
             Some(value) => value,
             None => 12,
         };
 
         // Create basic landmasses and Terrains
-        let (terrain_vec, height, hill_vec, _temp, rain) =
-            generate_map_with_type(&internal_seed, &size, map_type);
+        let layers = try_generate_map_with_type(&internal_seed, &size, map_type)?;
+        warn_if_not_converged(layers.repair_iterations);
 
-        let _ = place_features(&terrain_vec, &rain, &height, &size);
+        let rivers = place_features(&layers.terrain, &layers.rainfall, &layers.height, &size);
+
+        Ok((layers, rivers))
+    }
 
-        (terrain_vec, hill_vec)
+    /// Return the seed the map was generated with (None if the default seed was used)
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Return the size of the map
+    pub fn size(&self) -> &MapSizes {
+        &self.size
+    }
+
+    /// Return the map type the map was generated with
+    pub fn map_type(&self) -> MapTypes {
+        self.map_type
+    }
+
+    /// Return the shareable seed string that regenerates this map
+    pub fn seed_string(&self) -> SeedString {
+        SeedString {
+            seed: self.seed.unwrap_or(12),
+            size: self.size,
+            map_type: self.map_type,
+        }
+    }
+
+    /// Return the `config_hash` of the config the map was generated with (or built with, see `MapBuilder`).
+    /// Captured when the map is made, so a config installed afterwards does not change it
+    pub fn config_hash(&self) -> u64 {
+        self.config_hash
+    }
+
+    /// Return the recipe of the seed, size and map type with the hash of the config the map was generated with.
+    /// Options that changed the config (e.g. a land preset) are only in the hash, the CLI adds them
+    pub fn recipe(&self) -> Recipe {
+        let seed = self.seed_string();
+        Recipe::new(seed.seed, seed.size, seed.map_type).with_config_hash(self.config_hash)
+    }
+
+    /// Stable FNV-1a hash of the terrain, hill and feature layers and the config hash, e.g. to check that a seed
+    /// still gives the same map with the same config
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.data.tiles
+            .iter()
+            .flat_map(|tile| {
+                let terrain = Terrain::ALL.iter().position(|t| *t == tile.terrain()).unwrap_or(0) as u8;
+                let feature = tile.feature().and_then(|feature| Feature::ALL.iter().position(|f| *f == feature));
+                [terrain, u8::from(tile.is_hill()), feature.map_or(0, |idx| idx as u8 + 1)]
+            })
+            .chain(self.config_hash.to_le_bytes())
+            .fold(FNV_OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Return all tiles of the map in row-major order
+    pub fn tiles(&self) -> &[Tile] {
+        &self.data.tiles
+    }
+
+    /// Return all tiles mutably, e.g. for an editor. Copies the shared data first if a clone of the map
+    /// still uses it, so edits never show up in other clones.
+    /// The derived data (landmass statistics, appeal, rivers) is not updated
+    pub fn tiles_mut(&mut self) -> &mut [Tile] {
+        &mut Arc::make_mut(&mut self.data).tiles
+    }
+
+    /// Whether both maps share their tiles and per-tile data, true for clones that were not mutated since
+    pub fn shares_data_with(&self, other: &Map) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    /// Return the map as a JSON object: seed string, config hash (16 hex digits), size, type, dimensions,
+    /// the coastline length and compactness of `statistics`, the warnings of `check_map`,
+    /// the statistics of the `SUMMARY_LANDMASSES` largest landmasses (see `LandmassInfo::to_json_value`),
+    /// the tags (see `TileTags::to_json_value`) and every tile (see `Tile::to_json_value`) in row-major order
+    pub fn to_json_value(&self) -> Value {
+        let mut value = self.json_header();
+        value["tiles"] = self.data.tiles.iter().map(Tile::to_json_value).collect();
+        value
+    }
+
+    /// Write the map as NDJSON, one line at a time: the fields of `to_json_value` without the tiles,
+    /// then every tile (see `Tile::to_json_value`) with its row-major `index` on its own line.
+    /// Can be read back with `import_ndjson`.
+    pub fn export_ndjson(&self, writer: impl Write) -> io::Result<()> {
+        let tiles = self.data.tiles.iter().enumerate().map(|(idx, tile)| {
+            let mut value = tile.to_json_value();
+            value["index"] = idx.into();
+            value
+        });
+        write_ndjson(writer, &self.json_header(), tiles)
+    }
+
+    /// Map-level fields of `to_json_value`
+    fn json_header(&self) -> Value {
+        let (width, height) = self.size.dimensions();
+        let stats = self.statistics();
+        json!({
+            "seed": self.seed,
+            "seed_string": self.seed_string().to_string(),
+            "recipe": self.recipe().to_string(),
+            "config_hash": format!("{:016x}", self.config_hash),
+            "size": self.size.short_label(),
+            "map_type": self.map_type.to_string(),
+            "width": width,
+            "height": height,
+            "wind": self.wind.row_angles(),
+            "constraints": serde_json::to_value(&self.constraint_outcomes).unwrap_or_default(),
+            "sanity": serde_json::to_value(check_map(self)).unwrap_or_default(),
+            "water_bodies": serde_json::to_value(&self.data.water_bodies).unwrap_or_default(),
+            "coastline_length": stats.coastline_length,
+            "compactness": stats.compactness,
+            "landmasses": self
+                .data
+                .landmasses
+                .iter()
+                .take(SUMMARY_LANDMASSES)
+                .map(LandmassInfo::to_json_value)
+                .collect::<Vec<_>>(),
+            "tags": self.tags.to_json_value(),
+        })
+    }
+
+    /// Save the terrain, hill and feature layers with the recipe and the tags as a `MapDocument`,
+    /// read them back with `MapDocument::load`
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        MapDocument::new(self.to_map_data())
+            .with_recipe(self.recipe())
+            .with_tags(self.tags.clone())
+            .save(path)
+    }
+
+    /// Return the terrain, hill and feature layers, e.g. for `write_text_map` or `export_ndjson`
+    pub fn to_map_data(&self) -> GeneratedMapData {
+        let (width, height) = self.size.dimensions();
+        GeneratedMapData {
+            width,
+            height,
+            terrain: self.data.tiles.iter().map(|tile| tile.terrain()).collect(),
+            hills: self.data.tiles.iter().map(|tile| tile.is_hill()).collect(),
+            features: self.data.tiles.iter().map(|tile| tile.feature()).collect(),
+        }
+    }
+
+    /// Return the appeal of every tile in row-major order, water tiles are 0
+    pub fn appeal(&self) -> &[i8] {
+        &self.data.appeal
+    }
+
+    /// Return the geological age of every tile in row-major order, see `Tile::geological_age`
+    pub fn geological_age(&self) -> Vec<u8> {
+        self.data.tiles.iter().map(|tile| tile.geological_age()).collect()
+    }
+
+    /// Return the desert sub-variant of every tile in row-major order, see `Tile::desert_variant`
+    pub fn desert_variants(&self) -> Vec<Option<DesertVariant>> {
+        self.data.tiles.iter().map(|tile| tile.desert_variant()).collect()
+    }
+
+    /// Return whether every tile is flood-prone lowland in row-major order, see `Tile::is_lowland`
+    pub fn lowland(&self) -> Vec<bool> {
+        self.data.tiles.iter().map(|tile| tile.is_lowland()).collect()
+    }
+
+    /// Return the scenario tags attached to tiles
+    pub fn tags(&self) -> &TileTags {
+        &self.tags
+    }
+
+    /// Return the scenario tags for editing
+    pub fn tags_mut(&mut self) -> &mut TileTags {
+        &mut self.tags
+    }
+
+    /// Return the rivers as lists of tile indices, from source to mouth
+    pub fn rivers(&self) -> &[Vec<usize>] {
+        &self.data.rivers
+    }
+
+    /// Classify every river by its length.
+    /// Returns (index into `rivers()`, class) for each river.
+    pub fn river_length_classification(&self) -> Vec<(usize, RiverClass)> {
+        self.data.rivers
+            .iter()
+            .enumerate()
+            .map(|(i, river)| (i, RiverClass::from_length(river.len())))
+            .collect()
+    }
+
+    /// Return how many repair passes the landmass generation needed
+    pub fn repair_iterations(&self) -> RepairIterationCount {
+        self.repair_iterations
+    }
+
+    /// Return whether the biome constraints were met and after how many adjustments, None without biome constraints
+    pub fn biome_constraints(&self) -> Option<&BiomeConstraintReport> {
+        self.biome_constraints.as_ref()
+    }
+
+    /// Return how the final landmask did on each acceptance constraint of the map type, with a suggested
+    /// config change for every unsatisfied one. The repair loop can run out of passes without meeting them all
+    pub fn constraint_outcomes(&self) -> &[ConstraintOutcome] {
+        &self.constraint_outcomes
+    }
+
+    /// Return the number of satisfied acceptance constraints, see `constraint_outcomes`
+    pub fn satisfied_constraints(&self) -> usize {
+        self.constraint_outcomes.iter().filter(|outcome| outcome.satisfied).count()
+    }
+
+    /// Return the prevailing wind per row, see `WindConfig`
+    pub fn wind(&self) -> &WindLayer {
+        &self.wind
+    }
+
+    /// Return the prevailing wind direction at a row-major tile index
+    pub fn wind_at(&self, tile_idx: usize) -> CompassDirection {
+        self.wind.wind_at(tile_idx)
+    }
+
+    /// Return the statistics of every landmass, ordered by size (largest first)
+    pub fn landmass_stats(&self) -> &[LandmassInfo] {
+        &self.data.landmasses
+    }
+
+    /// Return every water body (oceans, seas, lakes and ponds), ordered by size (largest first), see `water_bodies`
+    pub fn water_bodies(&self) -> &[WaterBodyInfo] {
+        &self.data.water_bodies
+    }
+
+    /// Return the water body of a tile, None for land and indices off the map
+    pub fn water_body_at(&self, tile_idx: usize) -> Option<&WaterBodyInfo> {
+        let id = (*self.data.water_body_ids.get(tile_idx)?)?;
+        self.data.water_bodies.get(id)
+    }
+
+    /// Return the veins the strategic resources were placed in, see `place_strategic_veins`
+    pub fn strategic_veins(&self) -> &[StrategicVein] {
+        &self.data.strategic_veins
+    }
+
+    /// Place one start per player, see `place_starts`. The tie break follows the seed of the map
+    pub fn place_starts(
+        &self,
+        players: usize,
+        options: &StartPlacementOptions,
+    ) -> Result<StartPlacementReport, StartPlacementError> {
+        place_starts(&self.to_map_data(), &self.data.rivers, players, self.seed.unwrap_or(12), options)
+    }
+
+    /// Return the map level statistics of the generated land
+    pub fn statistics(&self) -> MapStatistics {
+        let land_tiles: usize = self.data.landmasses.iter().map(|info| info.size).sum();
+        let largest = self.data.landmasses.first().map(|info| info.size).unwrap_or(0);
+
+        MapStatistics {
+            land_ratio: land_tiles as f32 / self.data.tiles.len().max(1) as f32,
+            n_landmasses: self.data.landmasses.len(),
+            largest_ratio: largest as f32 / land_tiles.max(1) as f32,
+            coastline_length: self.data.landmasses.iter().map(|info| info.coastline_length).sum(),
+            compactness: self.data.landmasses.iter().map(|info| info.compactness * info.size as f32).sum::<f32>()
+                / land_tiles.max(1) as f32,
+        }
+    }
+
+    /// Generate a map for every seed in the range and report how the repair loop behaved.
+    ///
+    /// # Panics
+    /// If the config is invalid, like `new_with_type`.
+    pub fn stress_test(seed_range: Range<u64>, size: MapSizes, map_type: MapTypes) -> StressTestReport {
+        let mut report = StressTestReport::default();
+
+        for seed in seed_range {
+            let map = Map::generate(Some(seed), size, map_type).unwrap_or_else(|err| panic!("{err}"));
+            let stats = map.statistics();
+
+            report.tested += 1;
+            if map.repair_iterations().exhausted_budget() {
+                report.failed_seeds.push(seed);
+            } else {
+                report.converged += 1;
+            }
+
+            report.seeds.push(StressTestSeed {
+                seed,
+                land_ratio: stats.land_ratio,
+                n_landmasses: stats.n_landmasses,
+                coastline_length: stats.coastline_length,
+                compactness: stats.compactness,
+                repair_iterations: map.repair_iterations().get(),
+                satisfied_constraints: map.satisfied_constraints(),
+                constraints: map.constraint_outcomes().len(),
+            });
+            let ratio_bucket = (stats.land_ratio / 0.05).floor() * 0.05;
+            add_to_histogram(&mut report.land_ratio_distribution, ratio_bucket);
+            add_to_histogram(&mut report.component_count_distribution, stats.n_landmasses as f32);
+        }
+
+        report
+    }
+
+    /// Return which continents can reach each other along the coast.
+    /// `matrix[i][j]` is true if a path over shallow water (`Navigation::CoastOnly`) connects a coastal tile of
+    /// continent `i` to one of continent `j`. Every continent borders the open ocean, so deep water would connect
+    /// them all; continents apart by deep ocean are not connected, and neither are two shores of an enclosed sea
+    /// whose coasts do not meet. `matrix[i][i]` is always true.
+    /// Continents are the landmasses from `landmass_stats`, so continent 0 is the largest.
+    pub fn continent_connectivity_matrix(&self) -> Vec<Vec<bool>> {
+        let coasts = self.continent_coasts();
+        coasts
+            .iter()
+            .enumerate()
+            .map(|(i, groups)| {
+                coasts
+                    .iter()
+                    .enumerate()
+                    .map(|(j, other)| i == j || groups.iter().any(|group| other.contains(group)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Return whether continents `a` and `b` can reach each other along the coast,
+    /// see `continent_connectivity_matrix`
+    pub fn continents_are_connected(&self, a: usize, b: usize) -> bool {
+        let coasts = self.continent_coasts();
+        match (coasts.get(a), coasts.get(b)) {
+            (Some(groups), Some(other)) => a == b || groups.iter().any(|group| other.contains(group)),
+            _ => false,
+        }
+    }
+
+    /// Write the sea connectivity of the continents as a Graphviz DOT graph, renderable with `dot -Tpng`.
+    /// Every continent is a node labeled with its id and area, continents that can reach each other along the
+    /// coast (see `continent_connectivity_matrix`) share an edge labeled "sea".
+    pub fn export_to_dot_graph(&self, out_path: &Path) -> Result<(), io::Error> {
+        let matrix = self.continent_connectivity_matrix();
+
+        let mut out = String::from("graph continents {\n");
+        for info in &self.data.landmasses {
+            out.push_str(&format!("  {0} [label=\"Continent {0}\\n{1} tiles\"];\n", info.id, info.size));
+        }
+        for (a, row) in matrix.iter().enumerate() {
+            for b in (a + 1..row.len()).filter(|&b| row[b]) {
+                out.push_str(&format!("  {a} -- {b} [label=\"sea\"];\n"));
+            }
+        }
+        out.push_str("}\n");
+
+        if let Some(parent) = out_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(out_path, out)
+    }
+
+    /// Shallow water components every continent borders, indexed by landmass id
+    fn continent_coasts(&self) -> Vec<HashSet<u32>> {
+        let (width, _) = self.size.dimensions();
+        let groups = self.coastal_groups(Navigation::CoastOnly);
+        let mut coasts = vec![HashSet::new(); self.data.landmasses.len()];
+        for (idx, tile) in self.data.tiles.iter().enumerate() {
+            if let Some(id) = tile.landmass_id() {
+                coasts[id].extend(groups.groups(HexCoord::new((idx % width) as i32, (idx / width) as i32)));
+            }
+        }
+        coasts
+    }
+
+    /// Return a copy of the map with the sea level raised (`delta > 0`) or lowered (`delta < 0`).
+    /// `delta` is in [-1.0, 1.0] elevation units, where 1.0 spans the elevations of the land (rising) or the
+    /// water (falling) on this map. Land elevation counts up from the sea level and water elevation counts
+    /// down to it (higher is shallower), so rising floods the lowest `delta` of the land and falling exposes
+    /// the shallowest `-delta` of the water as grassland.
+    /// Rivers end where they reach flooded tiles; coasts, landmasses and appeal are recomputed.
+    pub fn apply_sea_level_change(&self, delta: f32) -> Map {
+        let delta = delta.clamp(-1.0, 1.0);
+        let (width, height) = self.size.dimensions();
+
+        let mut landmask: Vec<u8> = self.data.tiles.iter().map(|tile| u8::from(!tile.terrain().is_water())).collect();
+        let mut terrain: Vec<Terrain> = self.data.tiles.iter().map(|tile| tile.terrain()).collect();
+        let mut hills: Vec<bool> = self.data.tiles.iter().map(|tile| tile.is_hill()).collect();
+
+        let (water_lo, water_hi) = self.elevation_range(false);
+        let flood_below = self.flood_threshold(delta);
+        let expose_above = water_hi as f32 + delta * (water_hi as f32 - water_lo as f32 + 1.0);
+
+        for (idx, tile) in self.data.tiles.iter().enumerate() {
+            let elevation = tile.elevation() as f32;
+            if delta > 0.0 && landmask[idx] == 1 && elevation < flood_below {
+                landmask[idx] = 0;
+                hills[idx] = false;
+            } else if delta < 0.0 && landmask[idx] == 0 && elevation > expose_above {
+                landmask[idx] = 1;
+                terrain[idx] = Terrain::Grassland;
+            }
+        }
+
+        // Water tiles get reclassified against the new coastline
+        let ocean = ocean_mask(&landmask, &self.size);
+        let coast = coastal_water_mask(&landmask, &ocean, &self.size);
+        for idx in 0..width * height {
+            if landmask[idx] == 0 {
+                terrain[idx] = if coast[idx] || !ocean[idx] { Terrain::CoastLake } else { Terrain::Ocean };
+            }
+        }
+
+        let rivers = self
+            .data
+            .rivers
+            .iter()
+            .map(|river| river.iter().copied().take_while(|idx| landmask[*idx] == 1).collect::<Vec<_>>())
+            .filter(|river| !river.is_empty())
+            .collect();
+
+        // Deserts that stay land keep their variant and oases
+        let desert_variants: Vec<Option<DesertVariant>> = self
+            .data
+            .tiles
+            .iter()
+            .zip(&terrain)
+            .map(|(tile, t)| tile.desert_variant().filter(|_| *t == Terrain::Desert))
+            .collect();
+        let features: Vec<Option<Feature>> = self
+            .data
+            .tiles
+            .iter()
+            .zip(&landmask)
+            .map(|(tile, land)| tile.feature().filter(|_| *land == 1))
+            .collect();
+        let geological_age = generate_geological_age(
+            self.seed.unwrap_or(12) + 4,
+            &terrain,
+            &self.size,
+            &biomes_config().geological_age,
+        );
+        let layers = MapLayers {
+            terrain,
+            height: self.data.tiles.iter().map(|tile| tile.elevation()).collect(),
+            hills,
+            temperature: self.data.tiles.iter().map(|tile| tile.temperature()).collect(),
+            rainfall: self.data.tiles.iter().map(|tile| tile.rainfall()).collect(),
+            temperature_range: generate_temperature_range(&landmask, &self.size, &biomes_config().continentality),
+            geological_age,
+            desert_variants,
+            coast_ruggedness: self
+                .data
+                .tiles
+                .iter()
+                .zip(&landmask)
+                .map(|(tile, land)| {
+                    let info = tile.landmass_id().filter(|_| *land == 1).map(|id| &self.data.landmasses[id]);
+                    info.and_then(|info| info.ruggedness)
+                })
+                .collect(),
+            landmask,
+            repair_iterations: self.repair_iterations,
+            biome_constraints: self.biome_constraints.clone(),
+        };
+
+        let mut map = Self::from_generated_layers(self.seed, self.size, self.map_type, &layers, rivers, features);
+        map.config_hash = self.config_hash;
+        map.tags = self.tags.clone();
+        map
+    }
+
+    /// Land tiles flooded by `steps` equal raises of the sea level, without changing the map.
+    /// Entry `k` holds the sorted indices of every tile `apply_sea_level_change((k + 1) / steps)` floods,
+    /// so a tile flooded at one step stays flooded at all later steps and the last step floods all land.
+    pub fn simulate_sea_level_rise(&self, steps: usize) -> Vec<Vec<usize>> {
+        (1..=steps)
+            .map(|step| {
+                let flood_below = self.flood_threshold(step as f32 / steps as f32);
+                (0..self.data.tiles.len())
+                    .filter(|&idx| {
+                        let tile = &self.data.tiles[idx];
+                        !tile.terrain().is_water() && (tile.elevation() as f32) < flood_below
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Land elevation below which a sea level rise of `delta` (0 to 1) floods the land
+    fn flood_threshold(&self, delta: f32) -> f32 {
+        let (land_lo, land_hi) = self.elevation_range(true);
+        land_lo as f32 + delta * (land_hi as f32 - land_lo as f32 + 1.0)
+    }
+
+    /// Elevation range of the land or the water tiles
+    fn elevation_range(&self, land: bool) -> (u8, u8) {
+        self.data.tiles
+            .iter()
+            .filter(|tile| tile.terrain().is_water() != land)
+            .fold((u8::MAX, u8::MIN), |(lo, hi), tile| (lo.min(tile.elevation()), hi.max(tile.elevation())))
+    }
+
+    /// Sample FBM noise at the center of every tile, in row-major order like `tiles`.
+    /// Values are in [0.0, 1.0]. Meant for modders to perturb a layer without regenerating the map,
+    /// e.g. `tile.set_elevation(...)` with `overlay[idx]` as a weight.
+    pub fn generate_noise_overlay(&self, seed: u64, octaves: usize, scale: f64) -> Vec<f32> {
+        let fbm = Fbm::<OpenSimplex>::new(seed as u32)
+            .set_octaves(octaves)
+            .set_frequency(1.0 / scale);
+
+        self.data.tiles
+            .iter()
+            .map(|tile| {
+                // same odd-r shifts as the generation noise
+                let coords = tile.hex_coords();
+                let wx = coords.x() as f64 + 0.5 * (coords.y() & 1) as f64;
+                let wy = coords.y() as f64 * ((3_f64).sqrt() / 2.);
+                ((fbm.get([wx, wy]) + 1.0) / 2.0).clamp(0.0, 1.0) as f32
+            })
+            .collect()
+    }
+
+    /// Return the corners of a tile in world units, see `HexCoord::world_corners` for the order
+    pub fn tile_polygon(&self, hex: HexCoord) -> [(f32, f32); 6] {
+        hex.world_corners()
+    }
+
+    /// Return the (min, max) corners of the box around every tile polygon in world units, as (x, z)
+    pub fn world_bounds(&self) -> ((f32, f32), (f32, f32)) {
+        self.data.tiles
+            .iter()
+            .flat_map(|tile| tile.hex_coords().world_corners())
+            .fold(
+                ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN)),
+                |((min_x, min_z), (max_x, max_z)), (x, z)| ((min_x.min(x), min_z.min(z)), (max_x.max(x), max_z.max(z))),
+            )
+    }
+
+    /// Return the center of `world_bounds`, e.g. to aim a camera at the whole map
+    pub fn world_center(&self) -> (f32, f32) {
+        let ((min_x, min_z), (max_x, max_z)) = self.world_bounds();
+        ((min_x + max_x) / 2.0, (min_z + max_z) / 2.0)
+    }
+
+    /// Return where a camera should start looking instead of `world_center`: a tile of the largest
+    /// landmass with the distance that frames it, see `interest_point`. None if the map has no land
+    pub fn interest_point(&self) -> Option<InterestPoint> {
+        let (width, height) = self.size.dimensions();
+        let terrain: Vec<Terrain> = self.data.tiles.iter().map(|tile| tile.terrain()).collect();
+        let landmass_ids: Vec<Option<usize>> = self.data.tiles.iter().map(|tile| tile.landmass_id()).collect();
+        interest_point(&terrain, &landmass_ids, width, height)
+    }
+
+    /// Return the hex at a world position (see `HexCoord::world_pos`), None if it is off the map
+    pub fn hex_at_world_pos(&self, x: f32, z: f32) -> Option<HexCoord> {
+        let hex = HexCoord::from_world_pos(x, z);
+        self.tile_index(hex).map(|_| hex)
+    }
+
+    /// Return the tile at a world position, None if it is off the map
+    pub fn tile_at_world_pos(&self, x: f32, z: f32) -> Option<&Tile> {
+        let idx = self.tile_index(HexCoord::from_world_pos(x, z))?;
+        self.data.tiles.get(idx)
+    }
+
+    /// Same as `tile_at_world_pos` but returns a mutable tile
+    pub fn tile_mut_at_world_pos(&mut self, x: f32, z: f32) -> Option<&mut Tile> {
+        let idx = self.tile_index(HexCoord::from_world_pos(x, z))?;
+        self.tiles_mut().get_mut(idx)
+    }
+
+    /// Row-major index of a hex, None if it is off the map
+    fn tile_index(&self, hex: HexCoord) -> Option<usize> {
+        let (width, height) = self.size.dimensions();
+        let (x, y) = (usize::try_from(hex.x()).ok()?, usize::try_from(hex.y()).ok()?);
+        (x < width && y < height).then_some(y * width + x)
+    }
+
+    /// Hexes of one offset row from west to east with their elevation [0, 255], for cross-sections along a latitude.
+    /// Empty if the row is off the map.
+    pub fn generate_elevation_profile(&self, row: u32) -> Vec<(HexCoord, f32)> {
+        let (width, height) = self.size.dimensions();
+        let row = row as usize;
+        if row >= height {
+            return Vec::new();
+        }
+        self.data.tiles[row * width..(row + 1) * width]
+            .iter()
+            .map(|tile| (tile.hex_coords(), f32::from(tile.elevation())))
+            .collect()
+    }
+
+    /// Same as `generate_elevation_profile` for the hexes with axial coordinate `r`, sorted by `q`.
+    /// The map uses odd-r offset coordinates, where `r` is the row, so this is the profile of row `r`.
+    pub fn generate_elevation_profile_axial(&self, r: i32) -> Vec<(HexCoord, f32)> {
+        u32::try_from(r).map(|row| self.generate_elevation_profile(row)).unwrap_or_default()
+    }
+
+    /// Return the movement, defense and sight modifiers of every tile in row-major order
+    pub fn tile_modifiers(&self) -> Vec<TileModifiers> {
+        self.data.tiles.iter().map(|tile| tile.modifiers()).collect()
+    }
+
+    /// Cheapest path for a land unit between two hexes, using the movement cost of every entered tile.
+    /// Returns None if either hex is off the map or the goal can not be reached.
+    pub fn find_path(&self, from: HexCoord, to: HexCoord) -> Option<MovePath> {
+        self.find_path_with(from, to, |tile| movement_cost(&tile.modifiers()))
+    }
+
+    /// Same as `find_path` with a custom cost of entering a tile, None if it can not be entered
+    pub fn find_path_with(
+        &self,
+        from: HexCoord,
+        to: HexCoord,
+        cost: impl Fn(&Tile) -> Option<u32>,
+    ) -> Option<MovePath> {
+        let (width, height) = self.size.dimensions();
+        pathfinding::find_path(width, height, from, to, |idx| cost(&self.data.tiles[idx]))
+    }
+
+    /// Whether a unit on `from` with the given sight range sees `to`, using the sight cost of the tiles between them
+    pub fn has_line_of_sight(&self, from: HexCoord, to: HexCoord, range: u32) -> bool {
+        let (width, height) = self.size.dimensions();
+        pathfinding::has_line_of_sight(width, height, from, to, range, |idx| {
+            u32::from(self.data.tiles[idx].modifiers().sight_cost)
+        })
+    }
+
+    /// Sailing distance between two coastal tiles, see `pathfinding::sea_distance`
+    pub fn sea_distance(&self, from: HexCoord, to: HexCoord, navigation: Navigation) -> Option<u32> {
+        let (width, height) = self.size.dimensions();
+        let terrain: Vec<Terrain> = self.data.tiles.iter().map(|tile| tile.terrain()).collect();
+        pathfinding::sea_distance(&terrain, width, height, from, to, navigation)
+    }
+
+    /// Land tiles a ship from `from` can land on within `budget` steps, see `pathfinding::sea_reachable`
+    pub fn sea_reachable(&self, from: HexCoord, budget: u32, navigation: Navigation) -> Vec<(HexCoord, u32)> {
+        let (width, height) = self.size.dimensions();
+        let terrain: Vec<Terrain> = self.data.tiles.iter().map(|tile| tile.terrain()).collect();
+        pathfinding::sea_reachable(&terrain, width, height, from, budget, navigation)
+    }
+
+    /// Sailable water components of the map, to skip `sea_distance` for tiles on separate waters
+    pub fn coastal_groups(&self, navigation: Navigation) -> CoastalGroups {
+        let (width, height) = self.size.dimensions();
+        let terrain: Vec<Terrain> = self.data.tiles.iter().map(|tile| tile.terrain()).collect();
+        CoastalGroups::new(&terrain, width, height, navigation)
+    }
+
+    /// Return the neighbors of a hex that lie on the map
+    pub fn neighbors(&self, hex: HexCoord) -> Vec<HexCoord> {
+        let (width, height) = self.size.dimensions();
+        hex.neighbors()
+            .into_iter()
+            .filter(|n| n.x() >= 0 && n.y() >= 0 && (n.x() as usize) < width && (n.y() as usize) < height)
+            .collect()
+    }
+
+    /// Return the neighbors of a hex that lie on the map and have the given terrain
+    pub fn neighbors_of_type(&self, hex: HexCoord, terrain: Terrain) -> Vec<HexCoord> {
+        self.neighbor_tiles(hex)
+            .filter(|tile| tile.terrain() == terrain)
+            .map(|tile| tile.hex_coords())
+            .collect()
+    }
+
+    /// Count the neighbors of a hex with the given terrain, without allocating
+    pub fn count_neighbors_of_type(&self, hex: HexCoord, terrain: Terrain) -> usize {
+        self.neighbor_tiles(hex).filter(|tile| tile.terrain() == terrain).count()
+    }
+
+    /// Check whether any neighbor of a hex on the map matches the predicate
+    pub fn any_neighbor_matches(&self, hex: HexCoord, pred: impl Fn(&Tile) -> bool) -> bool {
+        self.neighbor_tiles(hex).any(pred)
+    }
+
+    /// Check whether all neighbors of a hex on the map match the predicate (true if it has none)
+    pub fn all_neighbors_match(&self, hex: HexCoord, pred: impl Fn(&Tile) -> bool) -> bool {
+        self.neighbor_tiles(hex).all(pred)
+    }
+
+    /// Lazily iterate over the hexes at exactly `radius` steps from `center` that lie on the map, without allocating.
+    /// The ring starts `radius` steps east of the center and runs counterclockwise, radius 0 is the center itself.
+    pub fn ring_iterator(&self, center: HexCoord, radius: u32) -> RingIter<'_> {
+        RingIter {
+            hex: HexCoord::new(center.x() + radius as i32, center.y()),
+            side: 0,
+            step: 0,
+            radius,
+            map: self,
+        }
+    }
+
+    /// Lazily iterate over the hexes on the map within `radius` steps of `center`, ring by ring from the center outward
+    pub fn spiral_iterator(&self, center: HexCoord, radius: u32) -> SpiralIter<'_> {
+        SpiralIter {
+            center,
+            max_radius: radius,
+            ring: self.ring_iterator(center, 0),
+        }
+    }
+
+    /// Fraction of the tiles within `radius` steps of every tile (on the map, the tile itself included)
+    /// that satisfy `feature_fn`, in row-major order.
+    /// The hexes in range form one contiguous span per row, so the counts come from per-row prefix sums
+    /// instead of a search around every tile.
+    pub fn density_map(&self, feature_fn: impl Fn(&Tile) -> bool, radius: usize) -> Vec<f32> {
+        let (width, _) = self.size.dimensions();
+        // prefix[y * (width + 1) + x] counts the matching tiles of row y west of column x
+        let mut prefix = Vec::with_capacity(self.data.tiles.len() + self.data.tiles.len() / width.max(1));
+        for row in self.data.tiles.chunks(width.max(1)) {
+            let mut count = 0u32;
+            prefix.push(0);
+            for tile in row {
+                count += u32::from(feature_fn(tile));
+                prefix.push(count);
+            }
+        }
+
+        self.data.tiles
+            .iter()
+            .map(|tile| {
+                let (mut matching, mut total) = (0u32, 0u32);
+                for (row, west, east) in self.range_row_spans(tile.hex_coords(), radius) {
+                    let start = row * (width + 1);
+                    matching += prefix[start + east + 1] - prefix[start + west];
+                    total += (east + 1 - west) as u32;
+                }
+                matching as f32 / total as f32
+            })
+            .collect()
+    }
+
+    /// Whether at least `threshold` of the tiles within `radius` steps of `hex` satisfy `feature_fn`,
+    /// see `density_map`. Stops as soon as the outcome is certain. False for hexes off the map.
+    pub fn is_dense(&self, hex: HexCoord, feature_fn: impl Fn(&Tile) -> bool, radius: usize, threshold: f32) -> bool {
+        if self.tile_index(hex).is_none() {
+            return false;
+        }
+        let (width, _) = self.size.dimensions();
+        let total: usize = self.range_row_spans(hex, radius).map(|(_, west, east)| east + 1 - west).sum();
+        let needed = (threshold * total as f32).ceil().max(0.0) as usize;
+
+        let (mut matching, mut seen) = (0, 0);
+        for (row, west, east) in self.range_row_spans(hex, radius) {
+            for tile in &self.data.tiles[row * width + west..=row * width + east] {
+                if matching >= needed {
+                    return true;
+                }
+                if matching + (total - seen) < needed {
+                    return false;
+                }
+                matching += usize::from(feature_fn(tile));
+                seen += 1;
+            }
+        }
+        matching >= needed
+    }
+
+    /// Spans (row, westmost x, eastmost x) of the hexes on the map within `radius` steps of `center`, one per row
+    fn range_row_spans(&self, center: HexCoord, radius: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+        let (width, height) = self.size.dimensions();
+        let (q, r, _) = center.to_cube();
+        let radius = radius.min(width + height) as i32;
+        (-radius..=radius).filter_map(move |dr| {
+            let row = r + dr;
+            if row < 0 || row as usize >= height {
+                return None;
+            }
+            let west = HexCoord::from_cube(q + (-radius).max(-dr - radius), row).x().max(0);
+            let east = HexCoord::from_cube(q + radius.min(radius - dr), row).x().min(width as i32 - 1);
+            (west <= east).then_some((row as usize, west as usize, east as usize))
+        })
+    }
+
+    /// Classify the water of a hex as deep (water on all six sides) or shallow (next to land or the map edge).
+    /// Returns None for land and for hexes off the map.
+    pub fn water_depth_at(&self, hex: HexCoord) -> Option<WaterDepth> {
+        let tile = &self.data.tiles[self.tile_index(hex)?];
+        if !tile.terrain().is_water() {
+            return None;
+        }
+        let water_neighbors = self.neighbor_tiles(hex).filter(|n| n.terrain().is_water()).count();
+        Some(if water_neighbors == 6 { WaterDepth::Deep } else { WaterDepth::Shallow })
+    }
+
+    /// Iterate over the tiles next to a hex that lie on the map
+    fn neighbor_tiles(&self, hex: HexCoord) -> impl Iterator<Item = &Tile> {
+        CompassDirection::ALL
+            .iter()
+            .filter_map(move |dir| self.tile_index(hex.neighbor(dir)))
+            .map(|idx| &self.data.tiles[idx])
+    }
+
+    /// Replace the elevation of every land tile with the mean of itself and its neighbors, `passes` times.
+    /// Water tiles keep their elevation. Terrain is not reassigned.
+    pub fn smooth_elevation(&mut self, passes: usize) {
+        let mut elevation: Vec<u8> = self.data.tiles.iter().map(|tile| tile.elevation()).collect();
+        let landmask: Vec<u8> = self.data.tiles.iter().map(|tile| u8::from(!tile.terrain().is_water())).collect();
+        smooth_land_heights(&mut elevation, &landmask, &self.size, passes);
+        for (tile, elevation) in self.tiles_mut().iter_mut().zip(elevation) {
+            tile.set_elevation(elevation);
+        }
+    }
+
+    /// Return the (min, max) corners of the hex coordinates on the map.
+    /// `min` holds the smallest x and y, `max` holds the largest x and y.
+    pub fn bounding_hex_box(&self) -> (HexCoord, HexCoord) {
+        if self.data.tiles.is_empty() {
+            return (HexCoord::new(0, 0), HexCoord::new(0, 0));
+        }
+
+        let mut min = (i32::MAX, i32::MAX);
+        let mut max = (i32::MIN, i32::MIN);
+
+        for tile in &self.data.tiles {
+            let coords = tile.hex_coords();
+            min = (min.0.min(coords.x()), min.1.min(coords.y()));
+            max = (max.0.max(coords.x()), max.1.max(coords.y()));
+        }
+
+        (HexCoord::new(min.0, min.1), HexCoord::new(max.0, max.1))
+    }
+
+    /// Return the hex closest to the centroid of all tiles.
+    /// The centroid is computed on the hex centers, so the odd-row shift is taken into account.
+    pub fn center_hex(&self) -> HexCoord {
+        if self.data.tiles.is_empty() {
+            return HexCoord::new(0, 0);
+        }
+
+        // same shifts as the noise sampling: x shifts 0.5 on odd rows, y by sqrt(3)/2
+        let center_of = |coords: HexCoord| {
+            let wx = coords.x() as f64 + 0.5 * (coords.y() & 1) as f64;
+            let wy = coords.y() as f64 * ((3_f64).sqrt() / 2.);
+            (wx, wy)
+        };
+
+        let n = self.data.tiles.len() as f64;
+        let (sum_x, sum_y) = self.data.tiles.iter().fold((0.0, 0.0), |acc, tile| {
+            let (wx, wy) = center_of(tile.hex_coords());
+            (acc.0 + wx, acc.1 + wy)
+        });
+        let (cx, cy) = (sum_x / n, sum_y / n);
+
+        self.data.tiles
+            .iter()
+            .map(|tile| tile.hex_coords())
+            .min_by(|a, b| {
+                let (ax, ay) = center_of(*a);
+                let (bx, by) = center_of(*b);
+                let da = (ax - cx).powi(2) + (ay - cy).powi(2);
+                let db = (bx - cx).powi(2) + (by - cy).powi(2);
+                da.total_cmp(&db)
+            })
+            .unwrap_or(HexCoord::new(0, 0))
+    }
+
+    /// Count how many tiles of each terrain exist on the map
+    pub fn terrain_counts(&self) -> BTreeMap<Terrain, usize> {
+        let mut counts = BTreeMap::new();
+        for tile in &self.data.tiles {
+            *counts.entry(tile.terrain()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Print a compact table of terrain counts to stdout, sorted by count (descending)
+    pub fn print_compact_legend(&self) {
+        let total = self.data.tiles.len().max(1) as f32;
+        let mut rows: Vec<(Terrain, usize)> = self.terrain_counts().into_iter().collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        println!("{:<10} {:>8} {:>5}", "Terrain", "Count", "Pct");
+        println!("{} {} {}", "-".repeat(10), "-".repeat(8), "-".repeat(5));
+        for (terrain, count) in rows {
+            let pct = count as f32 / total * 100.0;
+            println!("{:<10} {:>8} {:>4.1}%", format!("{terrain:?}"), count, pct);
+        }
     }
 
     pub fn show(self) {
         todo!()
     }
 }
+
+/// Set the river class and width tier of the tiles the rivers run through
+fn mark_rivers(tiles: &mut [Tile], rivers: &[Vec<usize>]) {
+    let width_tiers = river_width_tiers(rivers, tiles.len());
+    for river in rivers {
+        let class = RiverClass::from_length(river.len());
+        for &idx in river {
+            tiles[idx].set_river_class(class);
+            tiles[idx].set_river_width(width_tiers[idx]);
+        }
+    }
+}
+
+/// Water depth of every tile in row-major order, see `Map::water_depth_at`
+pub(crate) fn water_depths(terrain: &[Terrain], width: usize, height: usize) -> Vec<Option<WaterDepth>> {
+    (0..terrain.len())
+        .map(|idx| {
+            if !terrain[idx].is_water() {
+                return None;
+            }
+            let neighbors = neighbors_odd_r(idx % width, idx / width, width, height);
+            let deep = neighbors.len() == 6 && neighbors.iter().all(|&(x, y)| terrain[y * width + x].is_water());
+            Some(if deep { WaterDepth::Deep } else { WaterDepth::Shallow })
+        })
+        .collect()
+}
+
+/// Warn when the repair loop used its whole budget, the map may not satisfy its constraints.
+fn warn_if_not_converged(iterations: RepairIterationCount) {
+    if iterations.exhausted_budget() {
+        eprintln!(
+            "Landmass repair did not converge within {} iterations, the map may not satisfy its constraints.",
+            iterations.get()
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Shareable description of a generated map: seed, size and map type.
+/// Written as `<size><type>-<seed in hex>-<checksum>`, e.g. `MC-2A-5`.
+pub struct SeedString {
+    pub seed: u64,
+    pub size: MapSizes,
+    pub map_type: MapTypes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Errors when parsing a seed string.
+pub enum ParseSeedStringError {
+    InvalidFormat,
+    InvalidChecksum,
+    UnknownMapType,
+    UnknownMapSize,
+}
+
+impl fmt::Display for ParseSeedStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidFormat => "invalid seed string format, expected <size><type>-<seed>-<checksum>",
+            Self::InvalidChecksum => "seed string checksum does not match",
+            Self::UnknownMapType => "unknown map type in seed string",
+            Self::UnknownMapSize => "unknown map size in seed string",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ParseSeedStringError {}
+
+impl SeedString {
+    /// 4-bit checksum over the size, map type and seed
+    pub fn checksum(&self) -> u8 {
+        let mut sum = (size_code(self.size) as u8 ^ type_code(self.map_type) as u8) & 0xF;
+        // rotate the 4-bit sum before mixing in every nibble of the seed, so swapped digits are caught
+        for shift in (0..64).step_by(4) {
+            sum = ((sum << 1) | (sum >> 3)) & 0xF;
+            sum ^= ((self.seed >> shift) & 0xF) as u8;
+        }
+        sum
+    }
+}
+
+impl fmt::Display for SeedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}-{:X}-{:X}",
+            size_code(self.size),
+            type_code(self.map_type),
+            self.seed,
+            self.checksum()
+        )
+    }
+}
+
+impl FromStr for SeedString {
+    type Err = ParseSeedStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let [codes, seed, checksum] = parts.as_slice() else {
+            return Err(ParseSeedStringError::InvalidFormat);
+        };
+
+        let mut chars = codes.chars();
+        let (Some(size_char), Some(type_char), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(ParseSeedStringError::InvalidFormat);
+        };
+        let seed = u64::from_str_radix(seed, 16).map_err(|_| ParseSeedStringError::InvalidFormat)?;
+        if checksum.len() != 1 {
+            return Err(ParseSeedStringError::InvalidFormat);
+        }
+        let checksum = u8::from_str_radix(checksum, 16).map_err(|_| ParseSeedStringError::InvalidFormat)?;
+
+        let parsed = SeedString {
+            seed,
+            size: size_from_code(size_char.to_ascii_uppercase()).ok_or(ParseSeedStringError::UnknownMapSize)?,
+            map_type: type_from_code(type_char.to_ascii_uppercase()).ok_or(ParseSeedStringError::UnknownMapType)?,
+        };
+        if parsed.checksum() != checksum {
+            return Err(ParseSeedStringError::InvalidChecksum);
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Directions of the six ring sides, counterclockwise starting from the east corner
+const RING_SIDES: [CompassDirection; 6] = [
+    CompassDirection::NW,
+    CompassDirection::W,
+    CompassDirection::SW,
+    CompassDirection::SE,
+    CompassDirection::E,
+    CompassDirection::NE,
+];
+
+/// Hexes on a ring around a center that lie on the map, see `Map::ring_iterator`.
+pub struct RingIter<'a> {
+    hex: HexCoord,
+    side: u8,
+    step: u32,
+    radius: u32,
+    map: &'a Map,
+}
+
+impl Iterator for RingIter<'_> {
+    type Item = HexCoord;
+
+    fn next(&mut self) -> Option<HexCoord> {
+        // the center is a ring with a single hex
+        if self.radius == 0 {
+            if self.side > 0 {
+                return None;
+            }
+            self.side = 1;
+            return self.map.tile_index(self.hex).map(|_| self.hex);
+        }
+
+        while (self.side as usize) < RING_SIDES.len() {
+            let hex = self.hex;
+            self.hex = hex.neighbor(&RING_SIDES[self.side as usize]);
+            self.step += 1;
+            if self.step == self.radius {
+                self.step = 0;
+                self.side += 1;
+            }
+            if self.map.tile_index(hex).is_some() {
+                return Some(hex);
+            }
+        }
+        None
+    }
+}
+
+/// Hexes within a radius around a center that lie on the map, ring by ring, see `Map::spiral_iterator`.
+pub struct SpiralIter<'a> {
+    center: HexCoord,
+    max_radius: u32,
+    ring: RingIter<'a>,
+}
+
+impl Iterator for SpiralIter<'_> {
+    type Item = HexCoord;
+
+    fn next(&mut self) -> Option<HexCoord> {
+        loop {
+            if let Some(hex) = self.ring.next() {
+                return Some(hex);
+            }
+            if self.ring.radius >= self.max_radius {
+                return None;
+            }
+            self.ring = self.ring.map.ring_iterator(self.center, self.ring.radius + 1);
+        }
+    }
+}
+
+/// Parse a seed string and generate the map it describes
+pub fn generate_map_with_seed_string(seed_str: &str) -> Result<Map, ParseSeedStringError> {
+    let parsed: SeedString = seed_str.parse()?;
+    Ok(Map::new_with_type(Some(parsed.seed), parsed.size, parsed.map_type))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Tile hit by a cursor ray, see `cursor_ray_to_tile`.
+pub struct PickHit {
+    /// Row-major index of the tile
+    pub index: usize,
+    pub hex: HexCoord,
+    /// Point where the ray meets the ground plane (x, z)
+    pub world_pos: (f32, f32),
+}
+
+/// Intersect a cursor ray with the ground plane (y = 0) and return the tile under it.
+/// World x and z follow `HexCoord::world_pos`, y points up.
+/// Returns None for rays that are degenerate, parallel to the ground, point away from it or miss the map.
+pub fn cursor_ray_to_tile(origin: [f32; 3], direction: [f32; 3], map: &Map) -> Option<PickHit> {
+    if origin.iter().chain(&direction).any(|v| !v.is_finite()) || direction[1].abs() < f32::EPSILON {
+        return None;
+    }
+
+    let t = -origin[1] / direction[1];
+    if t < 0.0 {
+        return None;
+    }
+
+    let world_pos = (origin[0] + direction[0] * t, origin[2] + direction[2] * t);
+    let hex = map.hex_at_world_pos(world_pos.0, world_pos.1)?;
+    let (width, _) = map.size().dimensions();
+    Some(PickHit {
+        index: hex.y() as usize * width + hex.x() as usize,
+        hex,
+        world_pos,
+    })
+}
+
+fn size_code(size: MapSizes) -> char {
+    match size {
+        MapSizes::Duel => 'D',
+        MapSizes::Tiny => 'T',
+        MapSizes::Small => 'S',
+        MapSizes::Standard => 'M',
+        MapSizes::Large => 'L',
+        MapSizes::Huge => 'H',
+    }
+}
+
+fn size_from_code(code: char) -> Option<MapSizes> {
+    match code {
+        'D' => Some(MapSizes::Duel),
+        'T' => Some(MapSizes::Tiny),
+        'S' => Some(MapSizes::Small),
+        'M' => Some(MapSizes::Standard),
+        'L' => Some(MapSizes::Large),
+        'H' => Some(MapSizes::Huge),
+        _ => None,
+    }
+}
+
+fn type_code(map_type: MapTypes) -> char {
+    match map_type {
+        MapTypes::Continents => 'C',
+        MapTypes::SmallContinents => 'S',
+        MapTypes::IslandsContinents => 'I',
+        MapTypes::Pangea => 'P',
+        MapTypes::Mirror => 'M',
+        MapTypes::Terra => 'T',
+    }
+}
+
+fn type_from_code(code: char) -> Option<MapTypes> {
+    match code {
+        'C' => Some(MapTypes::Continents),
+        'S' => Some(MapTypes::SmallContinents),
+        'I' => Some(MapTypes::IslandsContinents),
+        'P' => Some(MapTypes::Pangea),
+        'M' => Some(MapTypes::Mirror),
+        'T' => Some(MapTypes::Terra),
+        _ => None,
+    }
+}
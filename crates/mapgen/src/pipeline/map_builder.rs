@@ -0,0 +1,236 @@
+use std::fmt;
+
+use crate::{
+    map_components::{
+        hex_coords::HexCoord,
+        terrain::{Feature, Terrain},
+        tile::Tile,
+    },
+    pipeline::{
+        analysis::label_landmasses,
+        appeal::compute_appeal,
+        helpers::biomes_config,
+        map::{Map, water_depths},
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        text_map::GeneratedMapData,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Errors of `Map::from_layers` and `MapBuilder::build`
+pub enum LayerError {
+    /// A layer does not have one entry per tile
+    LengthMismatch {
+        layer: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// The tile at a row-major index has the coordinates of another index
+    HexMismatch {
+        index: usize,
+        expected: HexCoord,
+        found: HexCoord,
+    },
+    /// The dimensions are not those of a `MapSizes` preset
+    UnsupportedDimensions { width: usize, height: usize },
+    /// A river runs through a tile index that is not on the map
+    RiverOutOfBounds { index: usize, tiles: usize },
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { layer, expected, found } => {
+                write!(f, "layer '{layer}' has {found} entries, expected one per tile ({expected})")
+            }
+            Self::HexMismatch { index, expected, found } => write!(
+                f,
+                "tile {index} is at ({}, {}), expected ({}, {})",
+                found.x(),
+                found.y(),
+                expected.x(),
+                expected.y()
+            ),
+            Self::UnsupportedDimensions { width, height } => {
+                write!(f, "{width}x{height} is not the size of a map size preset")
+            }
+            Self::RiverOutOfBounds { index, tiles } => {
+                write!(f, "a river runs through tile {index}, the map has {tiles} tiles")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+#[derive(Debug, Clone)]
+/// Build a `Map` from explicit layers without running a generator, e.g. in tests or importers.
+/// Layers that are not set are filled with defaults: ocean, no hills or features and 0 for the climate.
+/// Landmass ids, water depth, appeal and tile modifiers are derived from the layers like for a generated map.
+pub struct MapBuilder {
+    size: MapSizes,
+    seed: Option<u64>,
+    map_type: MapTypes,
+    terrain: Option<Vec<Terrain>>,
+    hills: Option<Vec<bool>>,
+    features: Option<Vec<Option<Feature>>>,
+    elevation: Option<Vec<u8>>,
+    temperature: Option<Vec<u8>>,
+    rainfall: Option<Vec<u8>>,
+    rivers: Vec<Vec<usize>>,
+}
+
+impl MapBuilder {
+    /// Builder of a map of the given size with every layer at its default
+    pub fn new(size: MapSizes) -> Self {
+        MapBuilder {
+            size,
+            seed: None,
+            map_type: MapTypes::Continents,
+            terrain: None,
+            hills: None,
+            features: None,
+            elevation: None,
+            temperature: None,
+            rainfall: None,
+            rivers: Vec::new(),
+        }
+    }
+
+    /// Builder with the terrain, hill and feature layers of imported map data,
+    /// e.g. from `parse_text_map`, `import_ndjson` or `MapDocument::load`
+    pub fn from_map_data(data: &GeneratedMapData) -> Result<Self, LayerError> {
+        let size = MapSizes::from_dimensions(data.width, data.height).ok_or(LayerError::UnsupportedDimensions {
+            width: data.width,
+            height: data.height,
+        })?;
+        Ok(MapBuilder::new(size)
+            .terrain(data.terrain.clone())
+            .hills(data.hills.clone())
+            .features(data.features.clone()))
+    }
+
+    /// Builder with every layer, the seed and the map type of an existing map, e.g. to run a custom
+    /// stage on a generated map. The rebuilt map has no rivers or strategic veins, like `Map::from_layers`
+    pub fn from_map(map: &Map) -> Self {
+        let tiles = map.tiles();
+        let mut builder = MapBuilder::new(*map.size())
+            .map_type(map.map_type())
+            .terrain(tiles.iter().map(Tile::terrain).collect())
+            .hills(tiles.iter().map(Tile::is_hill).collect())
+            .features(tiles.iter().map(Tile::feature).collect())
+            .elevation(tiles.iter().map(Tile::elevation).collect())
+            .temperature(tiles.iter().map(Tile::temperature).collect())
+            .rainfall(tiles.iter().map(Tile::rainfall).collect());
+        builder.seed = map.seed();
+        builder
+    }
+
+    /// Seed reported by the map, it is not used to generate anything
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Map type the acceptance constraints are checked against, continents by default
+    pub fn map_type(mut self, map_type: MapTypes) -> Self {
+        self.map_type = map_type;
+        self
+    }
+
+    /// Terrain of every tile in row-major order
+    pub fn terrain(mut self, terrain: Vec<Terrain>) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// Hill flag of every tile
+    pub fn hills(mut self, hills: Vec<bool>) -> Self {
+        self.hills = Some(hills);
+        self
+    }
+
+    /// Feature of every tile
+    pub fn features(mut self, features: Vec<Option<Feature>>) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Elevation [0, 255] of every tile
+    pub fn elevation(mut self, elevation: Vec<u8>) -> Self {
+        self.elevation = Some(elevation);
+        self
+    }
+
+    /// Temperature [0, 255] of every tile
+    pub fn temperature(mut self, temperature: Vec<u8>) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Rainfall [0, 255] of every tile
+    pub fn rainfall(mut self, rainfall: Vec<u8>) -> Self {
+        self.rainfall = Some(rainfall);
+        self
+    }
+
+    /// Rivers as tile indices from source to mouth, none by default. Their tiles get the river class
+    /// and width tier like the traced rivers of a generated map
+    pub fn rivers(mut self, rivers: Vec<Vec<usize>>) -> Self {
+        self.rivers = rivers;
+        self
+    }
+
+    /// Build the tiles and the map, fails if a layer does not have one entry per tile
+    /// or a river leaves the map
+    pub fn build(self) -> Result<Map, LayerError> {
+        let n = self.size.grid_size();
+        if let Some(&index) = self.rivers.iter().flatten().find(|&&index| index >= n) {
+            return Err(LayerError::RiverOutOfBounds { index, tiles: n });
+        }
+        let (width, height) = self.size.dimensions();
+        let terrain = layer("terrain", self.terrain, n, Terrain::Ocean)?;
+        let hills = layer("hills", self.hills, n, false)?;
+        let features = layer("features", self.features, n, None)?;
+        let elevation = layer("elevation", self.elevation, n, 0)?;
+        let temperature = layer("temperature", self.temperature, n, 0)?;
+        let rainfall = layer("rainfall", self.rainfall, n, 0)?;
+
+        let (landmass_ids, _) = label_landmasses(&terrain, width, height);
+        let depths = water_depths(&terrain, width, height);
+        let appeal = compute_appeal(&terrain, &features, width, height, &biomes_config().appeal);
+        let modifier_rules = &biomes_config().tile_modifiers;
+        let tiles = (0..n)
+            .map(|idx| {
+                let coords = HexCoord::new((idx % width) as i32, (idx / width) as i32);
+                let mut tile = Tile::new(coords, terrain[idx], hills[idx]);
+                tile.set_climate(elevation[idx], temperature[idx], rainfall[idx]);
+                tile.set_feature(features[idx]);
+                tile.set_modifiers(modifier_rules.modifiers(terrain[idx], hills[idx], features[idx]));
+                tile.set_landmass_id(landmass_ids[idx]);
+                tile.set_water_depth(depths[idx]);
+                if !terrain[idx].is_water() {
+                    tile.set_appeal(appeal[idx] as i32);
+                }
+                tile
+            })
+            .collect();
+        let mut map = Map::from_tiles(self.seed, self.size, self.map_type, tiles)?;
+        map.set_rivers(self.rivers);
+        Ok(map)
+    }
+}
+
+/// The layer if it has one entry per tile, or `default` for every tile if it is not set
+fn layer<T: Clone>(name: &'static str, values: Option<Vec<T>>, n: usize, default: T) -> Result<Vec<T>, LayerError> {
+    match values {
+        Some(values) if values.len() != n => Err(LayerError::LengthMismatch {
+            layer: name,
+            expected: n,
+            found: values.len(),
+        }),
+        Some(values) => Ok(values),
+        None => Ok(vec![default; n]),
+    }
+}
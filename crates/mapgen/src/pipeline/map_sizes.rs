@@ -1,4 +1,9 @@
+use std::{fmt, str::FromStr};
+
+use crate::pipeline::helpers::landmasses_config;
+
 /// The types of map sizes that exist for generating a map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MapSizes {
     Duel,
     Tiny,
@@ -21,6 +26,38 @@ impl MapSizes {
         }
     }
 
+    /// All map sizes, smallest first
+    pub const ALL: [MapSizes; 6] = [Self::Duel, Self::Tiny, Self::Small, Self::Standard, Self::Large, Self::Huge];
+
+    /// Human-readable name with dimensions and tile count, e.g. `Standard (84×54, 4536 tiles)`
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Duel => "Duel (44×26, 1144 tiles)",
+            Self::Tiny => "Tiny (60×38, 2280 tiles)",
+            Self::Small => "Small (74×46, 3404 tiles)",
+            Self::Standard => "Standard (84×54, 4536 tiles)",
+            Self::Large => "Large (96×60, 5760 tiles)",
+            Self::Huge => "Huge (106×66, 6996 tiles)",
+        }
+    }
+
+    /// Lowercase name for file names and command line arguments, e.g. `standard`
+    pub const fn short_label(&self) -> &'static str {
+        match self {
+            Self::Duel => "duel",
+            Self::Tiny => "tiny",
+            Self::Small => "small",
+            Self::Standard => "standard",
+            Self::Large => "large",
+            Self::Huge => "huge",
+        }
+    }
+
+    /// Preset with these dimensions, None for any other width and height
+    pub fn from_dimensions(width: usize, height: usize) -> Option<MapSizes> {
+        Self::ALL.into_iter().find(|size| size.dimensions() == (width, height))
+    }
+
     /// Return the grid size for this map
     pub fn grid_size(&self) -> usize {
         match self {
@@ -32,21 +69,101 @@ impl MapSizes {
             Self::Huge => 106 * 66,
         }
     }
+}
 
-    /// Duel: 2
-    /// Tiny: 3
-    /// Small: 3
-    /// Standard: 4
-    /// Large: 5
-    /// Huge: 6
-    pub fn number_rivers(&self) -> usize{
-        match self {
-            Self::Duel => 2,
-            Self::Tiny => 3,
-            Self::Small => 3,
-            Self::Standard => 4,
-            Self::Large => 5,
-            Self::Huge => 6
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error of parsing a `MapSizes`, holds the rejected input
+pub struct ParseMapSizesError(pub String);
+
+impl fmt::Display for ParseMapSizesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = MapSizes::ALL.iter().map(MapSizes::short_label).collect();
+        write!(f, "invalid size '{}'. Use one of: {}", self.0, names.join(", "))
+    }
+}
+
+impl std::error::Error for ParseMapSizesError {}
+
+impl FromStr for MapSizes {
+    type Err = ParseMapSizesError;
+
+    /// Parse the `short_label` case-insensitively, e.g. `standard` or `Standard`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MapSizes::ALL
+            .into_iter()
+            .find(|size| size.short_label().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseMapSizesError(s.to_string()))
+    }
+}
+
+/// Per-preset counts as (tile count, rivers, wonders, minimum start distance), ordered by tile count.
+const PRESET_COUNTS: [(usize, usize, usize, usize); 6] = [
+    (44 * 26, 2, 2, 7),
+    (60 * 38, 3, 3, 8),
+    (74 * 46, 3, 4, 8),
+    (84 * 54, 4, 5, 9),
+    (96 * 60, 5, 6, 9),
+    (106 * 66, 6, 7, 10),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Per-size constants of map generation, derived from the map dimensions.
+/// Counts of the presets are tabled, other dimensions interpolate them linearly in tile count between the
+/// two nearest presets (rounded to the nearest integer) and use the values of the smallest or largest preset
+/// outside that range. The island thresholds follow the divisors of the global land config for any size.
+pub struct MapScaling {
+    /// Tile count relative to a Standard map, which the generation settings are tuned for
+    pub area_ratio: f32,
+    /// Number of rivers to trace
+    pub rivers: usize,
+    /// Largest landmass (in tiles) that still counts as an island
+    pub island_max: usize,
+    /// Largest landmass (in tiles) that counts as mid-sized
+    pub mid_max: usize,
+    /// Default number of natural wonders
+    pub wonders: usize,
+    /// Default minimum hex distance between start positions
+    pub min_start_distance: usize,
+}
+
+impl MapScaling {
+    /// Scaling for one of the map size presets
+    pub fn for_size(size: &MapSizes) -> Self {
+        let (width, height) = size.dimensions();
+        Self::for_dimensions(width, height)
+    }
+
+    /// Scaling for arbitrary map dimensions, see `MapScaling` for how non-preset sizes are handled
+    pub fn for_dimensions(width: usize, height: usize) -> Self {
+        let tiles = width * height;
+        let global = &landmasses_config().global;
+
+        MapScaling {
+            area_ratio: tiles as f32 / MapSizes::Standard.grid_size() as f32,
+            rivers: interpolate_preset(tiles, |(_, rivers, _, _)| rivers),
+            island_max: (tiles / global.island_max_divisor.max(1)).clamp(global.island_max_min, global.island_max_max),
+            mid_max: (tiles / global.mid_max_divisor.max(1)).clamp(global.mid_max_min, global.mid_max_max),
+            wonders: interpolate_preset(tiles, |(_, _, wonders, _)| wonders),
+            min_start_distance: interpolate_preset(tiles, |(_, _, _, distance)| distance),
         }
     }
 }
+
+/// Linear interpolation of a preset count in tile count between the two nearest presets
+fn interpolate_preset(tiles: usize, value: impl Fn((usize, usize, usize, usize)) -> usize) -> usize {
+    let first = PRESET_COUNTS[0];
+    let last = PRESET_COUNTS[PRESET_COUNTS.len() - 1];
+    if tiles <= first.0 {
+        return value(first);
+    }
+    if tiles >= last.0 {
+        return value(last);
+    }
+
+    // first.0 < tiles < last.0, so a later preset is found and `upper` is at least 1
+    let upper = PRESET_COUNTS.iter().position(|preset| preset.0 >= tiles).expect("tiles is below the last preset");
+    let (lo, hi) = (PRESET_COUNTS[upper - 1], PRESET_COUNTS[upper]);
+    let t = (tiles - lo.0) as f32 / (hi.0 - lo.0) as f32;
+    let (lo_value, hi_value) = (value(lo) as f32, value(hi) as f32);
+    (lo_value + (hi_value - lo_value) * t).round() as usize
+}
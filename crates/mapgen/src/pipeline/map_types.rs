@@ -1,7 +1,8 @@
+use std::{fmt, str::FromStr};
 
 /// Basic map types that can be generated
 /// Less than the original, but still of interest
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MapTypes{
     Continents,
     SmallContinents,
@@ -10,3 +11,61 @@ pub enum MapTypes{
     Mirror,
     Terra
 }
+
+impl MapTypes {
+    /// Every map type, in declaration order
+    pub const ALL: [MapTypes; 6] = [
+        MapTypes::Continents,
+        MapTypes::SmallContinents,
+        MapTypes::IslandsContinents,
+        MapTypes::Pangea,
+        MapTypes::Mirror,
+        MapTypes::Terra,
+    ];
+}
+
+impl fmt::Display for MapTypes {
+    /// Lowercase hyphen-separated name, e.g. `small-continents`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MapTypes::Continents => "continents",
+            MapTypes::SmallContinents => "small-continents",
+            MapTypes::IslandsContinents => "islands-continents",
+            MapTypes::Pangea => "pangea",
+            MapTypes::Mirror => "mirror",
+            MapTypes::Terra => "terra",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error of parsing a `MapTypes`, holds the rejected input
+pub struct ParseMapTypesError(pub String);
+
+impl fmt::Display for ParseMapTypesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = MapTypes::ALL.iter().map(MapTypes::to_string).collect();
+        write!(f, "invalid map type '{}'. Use one of: {}", self.0, names.join(", "))
+    }
+}
+
+impl std::error::Error for ParseMapTypesError {}
+
+impl FromStr for MapTypes {
+    type Err = ParseMapTypesError;
+
+    /// Parse the `Display` name case-insensitively, hyphens and underscores are optional (`smallcontinents`, `small_continents`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .trim()
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        MapTypes::ALL
+            .into_iter()
+            .find(|map_type| map_type.to_string().replace('-', "") == normalized)
+            .ok_or_else(|| ParseMapTypesError(s.to_string()))
+    }
+}
@@ -1,7 +1,26 @@
+pub mod analysis;
+pub mod appeal;
 pub mod biomes;
+pub mod canals;
+pub mod chunked;
+pub mod constraints;
+pub mod document;
+pub mod error;
+pub mod evolve;
 pub mod map;
+pub mod map_builder;
 pub mod map_sizes;
 pub mod features;
 pub mod map_types;
+pub mod names;
+pub mod ndjson;
 pub mod land;
-mod helpers;
\ No newline at end of file
+pub mod pathfinding;
+pub mod recipe;
+pub mod helpers;
+pub mod resources;
+pub mod sanity;
+pub mod starts;
+pub mod text_map;
+pub mod water_bodies;
+pub mod wind;
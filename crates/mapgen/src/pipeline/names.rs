@@ -0,0 +1,43 @@
+use crate::pipeline::helpers::tile_hash;
+
+const ONSETS: [&str; 16] = ["k", "th", "v", "m", "s", "dr", "l", "n", "gr", "b", "t", "r", "z", "h", "f", "sh"];
+const VOWELS: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "ia", "ou"];
+const CODAS: [&str; 8] = ["n", "l", "th", "r", "s", "lt", "nd", "x"];
+
+/// Made-up place name of two or three syllables, e.g. "Kalth", the same for the same seed and key.
+/// Different keys can give the same name, see `unique_place_names`
+pub fn place_name(seed: u64, key: usize) -> String {
+    let mut hash = tile_hash(seed ^ 0x6e61_6d65, key);
+    let mut take = |n: usize| {
+        let value = (hash % n as u64) as usize;
+        hash /= n as u64;
+        value
+    };
+    let syllables = 2 + take(2);
+    let mut name = String::new();
+    for _ in 0..syllables {
+        name.push_str(ONSETS[take(ONSETS.len())]);
+        name.push_str(VOWELS[take(VOWELS.len())]);
+    }
+    name.push_str(CODAS[take(CODAS.len())]);
+
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// One `place_name` per key, in the same order. A key whose name is already taken draws again with
+/// `key + attempt * stride` until the name is new, so use a `stride` larger than every key (e.g. the tile count)
+pub fn unique_place_names(seed: u64, keys: &[usize], stride: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(keys.len());
+    for &key in keys {
+        let name = (0..)
+            .map(|attempt: usize| place_name(seed, key + attempt * stride.max(1)))
+            .find(|name| !names.contains(name))
+            .unwrap_or_default();
+        names.push(name);
+    }
+    names
+}
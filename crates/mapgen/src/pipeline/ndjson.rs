@@ -0,0 +1,171 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::text_map::GeneratedMapData,
+};
+
+#[derive(Debug)]
+/// Errors of `import_ndjson`, with the 1-based line they were found on
+pub enum NdjsonError {
+    /// Reading the line failed
+    Io { line: usize, source: io::Error },
+    /// The line is not valid JSON
+    InvalidJson { line: usize, message: String },
+    /// A field is missing or has a wrong value
+    InvalidField { line: usize, field: &'static str, message: String },
+    /// More tile lines than the header announced
+    ExtraTile { line: usize },
+    /// Fewer tile lines than the header announced
+    TileCount { expected: usize, found: usize },
+    /// The input has no header line
+    MissingHeader,
+}
+
+impl fmt::Display for NdjsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { line, source } => write!(f, "line {line}: {source}"),
+            Self::InvalidJson { line, message } => write!(f, "line {line}: invalid JSON: {message}"),
+            Self::InvalidField { line, field, message } => write!(f, "line {line}: field '{field}' {message}"),
+            Self::ExtraTile { line } => write!(f, "line {line}: more tiles than width * height"),
+            Self::TileCount { expected, found } => write!(f, "found {found} tiles, expected {expected}"),
+            Self::MissingHeader => f.write_str("missing header line"),
+        }
+    }
+}
+
+impl std::error::Error for NdjsonError {}
+
+/// Write a map as NDJSON without building the whole document: a header line `{"width", "height"}`,
+/// then one line per tile in row-major order with `index`, `x`, `y`, `terrain`, `hill` and `feature`.
+/// Terrain and feature names are the same as in `Tile::to_json_value`, so `Map::export_ndjson` output
+/// can be read back with `import_ndjson` as well.
+pub fn export_ndjson(data: &GeneratedMapData, writer: impl Write) -> io::Result<()> {
+    let header = json!({ "width": data.width, "height": data.height });
+    let tiles = (0..data.width * data.height).map(|idx| {
+        json!({
+            "index": idx,
+            "x": idx % data.width,
+            "y": idx / data.width,
+            "terrain": format!("{:?}", data.terrain[idx]),
+            "hill": data.hills[idx],
+            "feature": data.features[idx].map_or(Value::Null, |feature| Value::String(format!("{feature:?}"))),
+        })
+    });
+    write_ndjson(writer, &header, tiles)
+}
+
+/// Write the header and the tiles one line at a time
+pub(crate) fn write_ndjson(
+    mut writer: impl Write,
+    header: &Value,
+    tiles: impl Iterator<Item = Value>,
+) -> io::Result<()> {
+    serde_json::to_writer(&mut writer, header)?;
+    writer.write_all(b"\n")?;
+    for tile in tiles {
+        serde_json::to_writer(&mut writer, &tile)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Read a map written by `export_ndjson` or `Map::export_ndjson` line by line.
+/// Tiles must come in row-major order, `hill` and `feature` may be left out and other fields are ignored.
+/// Stops at the first invalid line. Blank lines are skipped.
+pub fn import_ndjson(reader: impl BufRead) -> Result<GeneratedMapData, NdjsonError> {
+    let mut data: Option<GeneratedMapData> = None;
+    let mut next = 0;
+    for (i, text) in reader.lines().enumerate() {
+        let line = i + 1;
+        let text = text.map_err(|source| NdjsonError::Io { line, source })?;
+        if text.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(&text).map_err(|err| NdjsonError::InvalidJson {
+            line,
+            message: err.to_string(),
+        })?;
+
+        let Some(data) = &mut data else {
+            let width = read_usize(&value, "width", line)?;
+            let height = read_usize(&value, "height", line)?;
+            if width == 0 || height == 0 {
+                return Err(invalid(line, if width == 0 { "width" } else { "height" }, "must be positive"));
+            }
+            data = Some(GeneratedMapData::filled(width, height, Terrain::Ocean));
+            continue;
+        };
+
+        if next == data.width * data.height {
+            return Err(NdjsonError::ExtraTile { line });
+        }
+        if read_usize(&value, "index", line)? != next {
+            return Err(invalid(line, "index", &format!("is not {next}, tiles must be in row-major order")));
+        }
+        if read_usize(&value, "x", line)? != next % data.width {
+            return Err(invalid(line, "x", "does not match the index"));
+        }
+        if read_usize(&value, "y", line)? != next / data.width {
+            return Err(invalid(line, "y", "does not match the index"));
+        }
+
+        data.terrain[next] = read_name(&value, "terrain", line, &Terrain::ALL)?
+            .ok_or_else(|| invalid(line, "terrain", "is missing"))?;
+        data.hills[next] = match value.get("hill") {
+            None | Some(Value::Null) => false,
+            Some(hill) => hill.as_bool().ok_or_else(|| invalid(line, "hill", "is not a boolean"))?,
+        };
+        data.features[next] = read_name(&value, "feature", line, &Feature::ALL)?;
+        next += 1;
+    }
+
+    let data = data.ok_or(NdjsonError::MissingHeader)?;
+    if next != data.width * data.height {
+        return Err(NdjsonError::TileCount {
+            expected: data.width * data.height,
+            found: next,
+        });
+    }
+    Ok(data)
+}
+
+fn invalid(line: usize, field: &'static str, message: &str) -> NdjsonError {
+    NdjsonError::InvalidField {
+        line,
+        field,
+        message: message.to_string(),
+    }
+}
+
+fn read_usize(value: &Value, field: &'static str, line: usize) -> Result<usize, NdjsonError> {
+    let number = value.get(field).ok_or_else(|| invalid(line, field, "is missing"))?;
+    number
+        .as_u64()
+        .and_then(|number| usize::try_from(number).ok())
+        .ok_or_else(|| invalid(line, field, "is not a non-negative integer"))
+}
+
+/// Enum value written by its `Debug` name, None if the field is missing or null
+fn read_name<T: fmt::Debug + Copy>(
+    value: &Value,
+    field: &'static str,
+    line: usize,
+    all: &[T],
+) -> Result<Option<T>, NdjsonError> {
+    let name = match value.get(field) {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::String(name)) => name,
+        Some(_) => return Err(invalid(line, field, "is not a string")),
+    };
+    all.iter()
+        .find(|candidate| format!("{candidate:?}") == *name)
+        .map(|found| Some(*found))
+        .ok_or_else(|| invalid(line, field, &format!("has unknown value '{name}'")))
+}
@@ -0,0 +1,345 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain, tile_modifiers::TileModifiers},
+    pipeline::helpers::neighbors_odd_r,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Cheapest route found by `find_path`
+pub struct Path {
+    /// Hexes from the start to the goal, both included
+    pub tiles: Vec<HexCoord>,
+    /// Sum of the costs of every entered tile (the start is not entered)
+    pub cost: u32,
+}
+
+/// Default cost of entering a tile: its movement cost, None if it is impassable
+pub fn movement_cost(modifiers: &TileModifiers) -> Option<u32> {
+    modifiers.is_passable().then_some(u32::from(modifiers.movement_cost))
+}
+
+/// Cheapest path between two hexes of a `width` x `height` odd-r grid.
+/// `cost` returns the cost of entering the tile at a row-major index, None if it can not be entered.
+/// Returns None if either hex is off the grid or the goal can not be reached.
+/// Ties between equally cheap paths are broken by tile index, so the result is deterministic.
+pub fn find_path(
+    width: usize,
+    height: usize,
+    from: HexCoord,
+    to: HexCoord,
+    cost: impl Fn(usize) -> Option<u32>,
+) -> Option<Path> {
+    find_path_directed(width, height, from, to, |_, next| cost(next))
+}
+
+/// Same as `find_path` but the cost of a step also depends on the tile it leaves, e.g. `wind_assisted_cost`.
+/// `cost(from, to)` gets the row-major indices of both tiles of the step.
+pub fn find_path_directed(
+    width: usize,
+    height: usize,
+    from: HexCoord,
+    to: HexCoord,
+    cost: impl Fn(usize, usize) -> Option<u32>,
+) -> Option<Path> {
+    let start = grid_index(from, width, height)?;
+    let goal = grid_index(to, width, height)?;
+
+    let mut best = vec![u32::MAX; width * height];
+    let mut came_from = vec![usize::MAX; width * height];
+    let mut queue = BinaryHeap::new();
+    best[start] = 0;
+    queue.push(Reverse((0u32, start)));
+
+    while let Some(Reverse((distance, idx))) = queue.pop() {
+        if idx == goal {
+            break;
+        }
+        if distance > best[idx] {
+            continue;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let next = ny * width + nx;
+            let Some(step) = cost(idx, next) else {
+                continue;
+            };
+            let candidate = distance.saturating_add(step);
+            if candidate < best[next] {
+                best[next] = candidate;
+                came_from[next] = idx;
+                queue.push(Reverse((candidate, next)));
+            }
+        }
+    }
+
+    if best[goal] == u32::MAX {
+        return None;
+    }
+    let mut tiles = vec![goal];
+    while let Some(&idx) = tiles.last()
+        && idx != start
+    {
+        tiles.push(came_from[idx]);
+    }
+    tiles.reverse();
+    Some(Path {
+        tiles: tiles
+            .into_iter()
+            .map(|idx| HexCoord::new((idx % width) as i32, (idx / width) as i32))
+            .collect(),
+        cost: best[goal],
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Water a ship may sail on
+pub enum Navigation {
+    /// Only shallow water (`Terrain::CoastLake`), like the ships of the ancient era
+    CoastOnly,
+    /// Shallow and deep water
+    #[default]
+    Open,
+}
+
+impl Navigation {
+    pub fn can_sail(self, terrain: Terrain) -> bool {
+        match self {
+            Navigation::CoastOnly => terrain == Terrain::CoastLake,
+            Navigation::Open => terrain.is_water(),
+        }
+    }
+}
+
+/// Sailing distance between two tiles of a `width` x `height` odd-r grid, usually coastal land tiles (ports).
+/// The ship embarks onto water next to `from`, every step enters a tile the navigation can sail on,
+/// and the last step lands on `to` from the water next to it.
+/// Returns None if either hex is off the grid or no water route connects them.
+pub fn sea_distance(
+    terrain: &[Terrain],
+    width: usize,
+    height: usize,
+    from: HexCoord,
+    to: HexCoord,
+    navigation: Navigation,
+) -> Option<u32> {
+    let start = grid_index(from, width, height)?;
+    let goal = grid_index(to, width, height)?;
+    let distances = sea_distances(terrain, width, height, start, u32::MAX, navigation, Some(goal));
+    (distances[goal] != u32::MAX).then_some(distances[goal])
+}
+
+/// Land tiles a ship embarking at `from` can land on within `budget` steps, with their sea distance.
+/// Ordered by distance and then by tile index, `from` itself is not included.
+pub fn sea_reachable(
+    terrain: &[Terrain],
+    width: usize,
+    height: usize,
+    from: HexCoord,
+    budget: u32,
+    navigation: Navigation,
+) -> Vec<(HexCoord, u32)> {
+    let Some(start) = grid_index(from, width, height) else {
+        return Vec::new();
+    };
+    let distances = sea_distances(terrain, width, height, start, budget, navigation, None);
+    let mut reachable: Vec<(usize, u32)> = distances
+        .into_iter()
+        .enumerate()
+        .filter(|&(idx, distance)| idx != start && distance != u32::MAX && !terrain[idx].is_water())
+        .collect();
+    reachable.sort_unstable_by_key(|&(idx, distance)| (distance, idx));
+    reachable
+        .into_iter()
+        .map(|(idx, distance)| (HexCoord::new((idx % width) as i32, (idx / width) as i32), distance))
+        .collect()
+}
+
+/// Breadth-first search over the sailable tiles from `start`, see `sea_distance`.
+/// Tiles that can not be sailed on are only landed on and not left again. Stops early once `goal` is reached.
+fn sea_distances(
+    terrain: &[Terrain],
+    width: usize,
+    height: usize,
+    start: usize,
+    budget: u32,
+    navigation: Navigation,
+    goal: Option<usize>,
+) -> Vec<u32> {
+    let sailable = |idx: usize| navigation.can_sail(terrain[idx]);
+    let mut distances = vec![u32::MAX; width * height];
+    distances[start] = 0;
+    // a ship can not start on water it can not sail on
+    if terrain[start].is_water() && !sailable(start) {
+        return distances;
+    }
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(idx) = queue.pop_front() {
+        if Some(idx) == goal {
+            break;
+        }
+        let distance = distances[idx];
+        if (idx != start && !sailable(idx)) || distance >= budget {
+            continue;
+        }
+        for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+            let next = ny * width + nx;
+            // land only from the water, never step from the start onto land directly
+            if distances[next] != u32::MAX || !(sailable(next) || (sailable(idx) && !terrain[next].is_water())) {
+                continue;
+            }
+            distances[next] = distance + 1;
+            queue.push_back(next);
+        }
+    }
+    distances
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Sailable water components and the components every tile touches, to tell in O(1) whether a ship
+/// can sail between two tiles before running `sea_distance`.
+/// A tile touches at most three components: neighbors next to each other around a hex are adjacent,
+/// so sailable neighbors in a row belong to the same component.
+pub struct CoastalGroups {
+    width: usize,
+    height: usize,
+    /// Components a sailable tile is part of or a land tile borders, unused slots are None
+    groups: Vec<[Option<u32>; 3]>,
+    count: usize,
+}
+
+impl CoastalGroups {
+    /// Label the components of the water the navigation can sail on
+    pub fn new(terrain: &[Terrain], width: usize, height: usize, navigation: Navigation) -> Self {
+        let sailable = |idx: usize| navigation.can_sail(terrain[idx]);
+        let mut component = vec![None; width * height];
+        let mut count = 0u32;
+        for seed in 0..width * height {
+            if component[seed].is_some() || !sailable(seed) {
+                continue;
+            }
+            component[seed] = Some(count);
+            let mut queue = VecDeque::from([seed]);
+            while let Some(idx) = queue.pop_front() {
+                for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                    let next = ny * width + nx;
+                    if component[next].is_none() && sailable(next) {
+                        component[next] = Some(count);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        let groups = (0..width * height)
+            .map(|idx| {
+                let mut groups = [None; 3];
+                if component[idx].is_some() {
+                    groups[0] = component[idx];
+                } else if !terrain[idx].is_water() {
+                    let mut touching: Vec<u32> = neighbors_odd_r(idx % width, idx / width, width, height)
+                        .into_iter()
+                        .filter_map(|(nx, ny)| component[ny * width + nx])
+                        .collect();
+                    touching.sort_unstable();
+                    touching.dedup();
+                    for (slot, id) in groups.iter_mut().zip(touching) {
+                        *slot = Some(id);
+                    }
+                }
+                groups
+            })
+            .collect();
+        CoastalGroups {
+            width,
+            height,
+            groups,
+            count: count as usize,
+        }
+    }
+
+    /// Number of sailable water components
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Components a tile is part of or borders, empty for inland tiles, unsailable water and hexes off the grid
+    pub fn groups(&self, hex: HexCoord) -> impl Iterator<Item = u32> + '_ {
+        grid_index(hex, self.width, self.height)
+            .into_iter()
+            .flat_map(|idx| self.groups[idx].iter().flatten().copied())
+    }
+
+    /// Whether a ship can sail between the tiles, i.e. `sea_distance` is Some for two different tiles
+    pub fn share_water(&self, a: HexCoord, b: HexCoord) -> bool {
+        self.groups(a).any(|group| self.groups(b).any(|other| other == group))
+    }
+}
+
+/// Hexes on the straight line between two hexes, both included
+pub fn hex_line(from: HexCoord, to: HexCoord) -> Vec<HexCoord> {
+    let steps = from.distance(&to);
+    let (q0, r0, _) = from.to_cube();
+    let (q1, r1, _) = to.to_cube();
+    // nudge the line off the hex edges, so ties always round to the same side
+    let (q0, r0) = (q0 as f32 + 1e-4, r0 as f32 + 1e-4);
+    let (q1, r1) = (q1 as f32 + 1e-4, r1 as f32 + 1e-4);
+
+    (0..=steps)
+        .map(|step| {
+            let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+            let (q, r) = cube_round(q0 + (q1 - q0) * t, r0 + (r1 - r0) * t);
+            HexCoord::from_cube(q, r)
+        })
+        .collect()
+}
+
+/// Whether a unit on `from` with the given sight range sees `to` on a `width` x `height` odd-r grid.
+/// Looking across a tile between them uses up its sight cost, seeing into `to` itself costs 1.
+pub fn has_line_of_sight(
+    width: usize,
+    height: usize,
+    from: HexCoord,
+    to: HexCoord,
+    range: u32,
+    sight_cost: impl Fn(usize) -> u32,
+) -> bool {
+    if grid_index(from, width, height).is_none() || grid_index(to, width, height).is_none() {
+        return false;
+    }
+    if from == to {
+        return true;
+    }
+
+    let line = hex_line(from, to);
+    let mut used = 1u32;
+    for hex in &line[1..line.len() - 1] {
+        let Some(idx) = grid_index(*hex, width, height) else {
+            return false;
+        };
+        used = used.saturating_add(sight_cost(idx));
+    }
+    used <= range
+}
+
+fn grid_index(hex: HexCoord, width: usize, height: usize) -> Option<usize> {
+    let (x, y) = (usize::try_from(hex.x()).ok()?, usize::try_from(hex.y()).ok()?);
+    (x < width && y < height).then_some(y * width + x)
+}
+
+/// Round fractional cube coordinates (q, r) to the nearest hex
+fn cube_round(q: f32, r: f32) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq as i32, rr as i32)
+}
@@ -0,0 +1,178 @@
+use std::{fmt, str::FromStr};
+
+use crate::pipeline::{helpers::config_hash, map_sizes::MapSizes, map_types::MapTypes};
+
+/// Start of every recipe, the version changes when the meaning of a key changes
+pub const RECIPE_PREFIX: &str = "civorum:v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Everything needed to generate a map again, as one shareable string, e.g.
+/// `civorum:v1;seed=12;size=standard;type=terra;preset=archipelago;cfg=0x9af3c01d5e2b7480`.
+/// Unlike `SeedString` it also carries the generation options and the config hash.
+pub struct Recipe {
+    pub seed: u64,
+    pub size: MapSizes,
+    pub map_type: MapTypes,
+    /// Options on top of the seed, size and type in recipe order, e.g. `("preset", "archipelago")`.
+    /// Keys this build does not know are kept, so they are written again by `to_string`
+    pub options: Vec<(String, String)>,
+    /// `config_hash` of the config the map was generated with
+    pub config_hash: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Errors of `Recipe::parse`
+pub enum ParseRecipeError {
+    /// The string does not start with `civorum:`
+    MissingPrefix,
+    /// A recipe of another version, holds the version
+    UnsupportedVersion(String),
+    /// An entry is not `key=value`
+    InvalidEntry(String),
+    MissingKey(&'static str),
+    InvalidValue { key: String, value: String },
+    DuplicateKey(String),
+}
+
+impl fmt::Display for ParseRecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => write!(f, "a recipe starts with '{RECIPE_PREFIX};'"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "recipe version '{version}' is not supported, expected '{RECIPE_PREFIX}'")
+            }
+            Self::InvalidEntry(entry) => write!(f, "recipe entry '{entry}' is not key=value"),
+            Self::MissingKey(key) => write!(f, "recipe has no '{key}'"),
+            Self::InvalidValue { key, value } => write!(f, "invalid recipe value '{value}' for '{key}'"),
+            Self::DuplicateKey(key) => write!(f, "recipe has '{key}' more than once"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRecipeError {}
+
+impl Recipe {
+    /// Recipe of a seed, size and map type without options or config hash
+    pub fn new(seed: u64, size: MapSizes, map_type: MapTypes) -> Self {
+        Recipe {
+            seed,
+            size,
+            map_type,
+            options: Vec::new(),
+            config_hash: None,
+        }
+    }
+
+    /// Add an option, replacing an earlier value of the key
+    pub fn with_option(mut self, key: &str, value: &str) -> Self {
+        match self.options.iter_mut().find(|(name, _)| name == key) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.options.push((key.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    pub fn with_config_hash(mut self, hash: u64) -> Self {
+        self.config_hash = Some(hash);
+        self
+    }
+
+    /// Value of an option, None if the recipe does not have it
+    pub fn option(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the recipe was made with the current config, None for a recipe without config hash.
+    /// A mismatch means the same recipe may give another map.
+    pub fn config_matches(&self) -> Option<bool> {
+        self.config_hash.map(|hash| hash == config_hash())
+    }
+
+    /// Parse a recipe written by `to_string`. Entries are `key=value` separated by `;`,
+    /// `seed`, `size` and `type` are required and any other key is kept as an option.
+    pub fn parse(s: &str) -> Result<Self, ParseRecipeError> {
+        let mut entries = s.trim().split(';').map(str::trim).filter(|entry| !entry.is_empty());
+        let header = entries.next().unwrap_or_default();
+        let version = header.strip_prefix("civorum:").ok_or(ParseRecipeError::MissingPrefix)?;
+        if header != RECIPE_PREFIX {
+            return Err(ParseRecipeError::UnsupportedVersion(version.to_string()));
+        }
+
+        let mut pairs: Vec<(&str, &str)> = Vec::new();
+        for entry in entries {
+            let (key, value) = entry
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .filter(|(key, _)| !key.is_empty())
+                .ok_or_else(|| ParseRecipeError::InvalidEntry(entry.to_string()))?;
+            if pairs.iter().any(|(other, _)| *other == key) {
+                return Err(ParseRecipeError::DuplicateKey(key.to_string()));
+            }
+            pairs.push((key, value));
+        }
+
+        let required = |key: &'static str| {
+            pairs
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| *value)
+                .ok_or(ParseRecipeError::MissingKey(key))
+        };
+        let invalid = |key: &str, value: &str| ParseRecipeError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        let seed = required("seed")?;
+        let seed = seed.parse::<u64>().map_err(|_| invalid("seed", seed))?;
+        let size = required("size")?;
+        let size = size.parse::<MapSizes>().map_err(|_| invalid("size", size))?;
+        let map_type = required("type")?;
+        let map_type = map_type.parse::<MapTypes>().map_err(|_| invalid("type", map_type))?;
+
+        let mut recipe = Recipe::new(seed, size, map_type);
+        for (key, value) in pairs {
+            match key {
+                "seed" | "size" | "type" => {}
+                "cfg" => {
+                    let digits = value.strip_prefix("0x").unwrap_or(value);
+                    let hash = u64::from_str_radix(digits, 16).map_err(|_| invalid("cfg", value))?;
+                    recipe.config_hash = Some(hash);
+                }
+                _ => recipe.options.push((key.to_string(), value.to_string())),
+            }
+        }
+        Ok(recipe)
+    }
+}
+
+impl fmt::Display for Recipe {
+    /// `civorum:v1;seed=..;size=..;type=..`, then the options in order and the config hash last
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{RECIPE_PREFIX};seed={};size={};type={}",
+            self.seed,
+            self.size.short_label(),
+            self.map_type
+        )?;
+        for (key, value) in &self.options {
+            write!(f, ";{key}={value}")?;
+        }
+        if let Some(hash) = self.config_hash {
+            write!(f, ";cfg=0x{hash:016x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = ParseRecipeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Recipe::parse(s)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    map_components::{hex_coords::HexCoord, resources::StrategicResource, tile::Tile},
+    pipeline::{
+        analysis::LandmassInfo,
+        helpers::{StrategicConfig, tile_hash},
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+/// Salt of the order vein centers are tried in, so it does not follow the tile hashes of the other placements
+const VEIN_ORDER_SALT: u64 = 0x7665_696e_7321;
+/// Salt of the resource, radius and size picked for a vein
+const VEIN_SHAPE_SALT: u64 = 0x7665_696e_7322;
+/// Salt of the roll that keeps or skips a vein center by its geological age
+const VEIN_AGE_SALT: u64 = 0x7665_696e_7323;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A cluster of deposits of one strategic resource on a single landmass
+pub struct StrategicVein {
+    pub resource: StrategicResource,
+    pub landmass_id: usize,
+    /// Row-major tile indices of the deposits, the first one is the center of the vein
+    pub deposits: Vec<usize>,
+    /// Every deposit lies within this many tiles of the center
+    pub radius: u32,
+}
+
+/// Place the strategic resources in veins of a few deposits close together.
+/// First every landmass from `guaranteed_min_landmass` tiles on gets one vein (largest landmass first),
+/// so no continent is strategically barren, then the rest of `vein_budget` is spread over all land.
+/// Vein centers lie further apart than the radius of the new vein, deposits never share a tile.
+/// A resource with an `age_weight` skips part of the young vein centers, see `VeinConfig::age_weight`.
+/// The terrain, landmass and geological age of every tile are read from `tiles`.
+pub fn place_strategic_veins(
+    seed: u64,
+    tiles: &[Tile],
+    landmasses: &[LandmassInfo],
+    size: &MapSizes,
+    map_type: MapTypes,
+    config: &StrategicConfig,
+) -> Vec<StrategicVein> {
+    let (width, height) = size.dimensions();
+    let budget = config.vein_budget(size, map_type);
+    let mut placer = VeinPlacer {
+        seed,
+        tiles,
+        width,
+        height,
+        config,
+        occupied: vec![false; tiles.len()],
+        veins: Vec::new(),
+    };
+
+    let mut centers: Vec<usize> = (0..tiles.len()).filter(|&idx| tiles[idx].landmass_id().is_some()).collect();
+    centers.sort_by_key(|&idx| tile_hash(seed ^ VEIN_ORDER_SALT, idx));
+
+    let min_landmass = config.guaranteed_min_landmass(size);
+    for info in landmasses.iter().filter(|info| info.size >= min_landmass) {
+        if placer.veins.len() >= budget {
+            break;
+        }
+        let mut on_landmass = centers.iter().filter(|&&idx| tiles[idx].landmass_id() == Some(info.id));
+        // any resource that fits will do for the guaranteed vein
+        let _ = on_landmass.any(|&center| placer.try_place(center, true));
+    }
+    for &center in &centers {
+        if placer.veins.len() >= budget {
+            break;
+        }
+        placer.try_place(center, false);
+    }
+    placer.veins
+}
+
+struct VeinPlacer<'a> {
+    seed: u64,
+    tiles: &'a [Tile],
+    width: usize,
+    height: usize,
+    config: &'a StrategicConfig,
+    occupied: Vec<bool>,
+    veins: Vec<StrategicVein>,
+}
+
+impl VeinPlacer<'_> {
+    /// Place a vein around `center` with the resource picked for it, or with any resource that fits
+    fn try_place(&mut self, center: usize, any_resource: bool) -> bool {
+        let hash = tile_hash(self.seed ^ VEIN_SHAPE_SALT, center);
+        let picked = self.pick_resource(hash);
+        if !any_resource && !self.old_enough(center, picked) {
+            return false;
+        }
+        let mut resources = vec![picked];
+        if any_resource {
+            resources.extend(StrategicResource::ALL.into_iter().filter(|resource| *resource != picked));
+        }
+
+        let Some(vein) = resources.into_iter().find_map(|resource| self.build(center, resource, hash)) else {
+            return false;
+        };
+        for &idx in &vein.deposits {
+            self.occupied[idx] = true;
+        }
+        self.veins.push(vein);
+        true
+    }
+
+    /// Resource of a vein, by the configured weights
+    fn pick_resource(&self, hash: u64) -> StrategicResource {
+        let total: u64 = StrategicResource::ALL
+            .iter()
+            .map(|resource| u64::from(self.config.get(*resource).weight))
+            .sum();
+        let mut roll = hash % total.max(1);
+        for resource in StrategicResource::ALL {
+            let weight = u64::from(self.config.get(resource).weight);
+            if roll < weight {
+                return resource;
+            }
+            roll -= weight;
+        }
+        StrategicResource::ALL[0]
+    }
+
+    /// Whether the age roll keeps `center` as a vein center of `resource`.
+    /// The chance is `1 - age_weight * (1 - age / 255)`, so old tiles are always kept.
+    fn old_enough(&self, center: usize, resource: StrategicResource) -> bool {
+        let weight = self.config.get(resource).age_weight.clamp(0.0, 1.0);
+        if weight == 0.0 {
+            return true;
+        }
+        let youth = 1.0 - self.tiles[center].geological_age() as f32 / 255.0;
+        let chance = 1.0 - weight * youth;
+        let roll = (tile_hash(self.seed ^ VEIN_AGE_SALT, center) % 1000) as f32;
+        roll < chance * 1000.0
+    }
+
+    fn build(&self, center: usize, resource: StrategicResource, hash: u64) -> Option<StrategicVein> {
+        let vein = self.config.get(resource);
+        let landmass_id = self.tiles[center].landmass_id();
+        let fits = |idx: usize| {
+            let tile = &self.tiles[idx];
+            !self.occupied[idx] && tile.landmass_id() == landmass_id && vein.terrains.contains(&tile.terrain())
+        };
+        if !fits(center) {
+            return None;
+        }
+
+        let radius = pick_in_range(vein.min_radius as usize, vein.max_radius as usize, hash >> 8) as u32;
+        let center_hex = self.hex(center);
+        if self
+            .veins
+            .iter()
+            .any(|other| self.hex(other.deposits[0]).distance(&center_hex) <= radius)
+        {
+            return None;
+        }
+
+        let mut nearby: Vec<usize> = tiles_within(center_hex, radius, self.width, self.height)
+            .filter(|&idx| idx != center && fits(idx))
+            .collect();
+        if nearby.len() + 1 < vein.min_deposits {
+            return None;
+        }
+        nearby.sort_by_key(|&idx| tile_hash(hash, idx));
+
+        let count = pick_in_range(vein.min_deposits, vein.max_deposits, hash >> 24).max(1);
+        let deposits = std::iter::once(center).chain(nearby.into_iter().take(count - 1)).collect();
+        Some(StrategicVein {
+            resource,
+            landmass_id: landmass_id?,
+            deposits,
+            radius,
+        })
+    }
+
+    fn hex(&self, idx: usize) -> HexCoord {
+        HexCoord::new((idx % self.width) as i32, (idx / self.width) as i32)
+    }
+}
+
+/// Value in `min..=max` picked by the hash (`min` if the range is empty)
+fn pick_in_range(min: usize, max: usize, hash: u64) -> usize {
+    min + (hash % (max.saturating_sub(min) as u64 + 1)) as usize
+}
+
+/// Row-major indices of the tiles within `radius` steps of `center` that lie on the map
+fn tiles_within(center: HexCoord, radius: u32, width: usize, height: usize) -> impl Iterator<Item = usize> {
+    let (q, r, _) = center.to_cube();
+    let radius = radius as i32;
+    (-radius..=radius)
+        .flat_map(move |dq| ((-radius).max(-dq - radius)..=radius.min(radius - dq)).map(move |dr| (dq, dr)))
+        .map(move |(dq, dr)| HexCoord::from_cube(q + dq, r + dr))
+        .filter(move |hex| hex.x() >= 0 && hex.y() >= 0 && (hex.x() as usize) < width && (hex.y() as usize) < height)
+        .map(move |hex| hex.y() as usize * width + hex.x() as usize)
+}
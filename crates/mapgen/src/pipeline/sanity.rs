@@ -0,0 +1,269 @@
+use serde::Serialize;
+
+use crate::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{dead_lakes, lake_sizes, ocean_connected_water, terrain_adjacency_matrix},
+        helpers::{biomes_config, landmasses_config, neighbors_odd_r},
+        map::Map,
+        map_types::MapTypes,
+    },
+};
+
+/// Expected share of the land as (terrain, min, max) per map type, see `terrain_share_ranges`.
+/// Mountains are left out, they have their own limit (`MAX_MOUNTAIN_SHARE`).
+type TerrainShareRanges = [(Terrain, f32, f32); 5];
+
+/// Ranges of continents, the other map types differ in the terrains their shape favors
+const CONTINENTS_SHARES: TerrainShareRanges = [
+    (Terrain::Plains, 0.0, 0.6),
+    (Terrain::Grassland, 0.1, 0.8),
+    (Terrain::Desert, 0.0, 0.3),
+    (Terrain::Tundra, 0.0, 0.25),
+    (Terrain::Snow, 0.0, 0.3),
+];
+/// Small landmasses reach further towards the poles, more of their land is snow
+const SMALL_CONTINENTS_SHARES: TerrainShareRanges = [
+    (Terrain::Plains, 0.0, 0.6),
+    (Terrain::Grassland, 0.1, 0.8),
+    (Terrain::Desert, 0.0, 0.3),
+    (Terrain::Tundra, 0.0, 0.25),
+    (Terrain::Snow, 0.0, 0.35),
+];
+const ISLANDS_CONTINENTS_SHARES: TerrainShareRanges = [
+    (Terrain::Plains, 0.0, 0.6),
+    (Terrain::Grassland, 0.1, 0.8),
+    (Terrain::Desert, 0.0, 0.3),
+    (Terrain::Tundra, 0.0, 0.25),
+    (Terrain::Snow, 0.0, 0.4),
+];
+/// Mirrored halves double every outlier, so a single terrain may cover more of the land
+const MIRROR_SHARES: TerrainShareRanges = [
+    (Terrain::Plains, 0.0, 0.6),
+    (Terrain::Grassland, 0.1, 0.85),
+    (Terrain::Desert, 0.0, 0.3),
+    (Terrain::Tundra, 0.0, 0.25),
+    (Terrain::Snow, 0.0, 0.35),
+];
+/// Terra spreads its land over every latitude
+const TERRA_SHARES: TerrainShareRanges = [
+    (Terrain::Plains, 0.0, 0.6),
+    (Terrain::Grassland, 0.1, 0.75),
+    (Terrain::Desert, 0.0, 0.3),
+    (Terrain::Tundra, 0.0, 0.25),
+    (Terrain::Snow, 0.0, 0.3),
+];
+/// Share of the land that may be mountains.
+const MAX_MOUNTAIN_SHARE: f32 = 0.12;
+/// Factor by which the landmass count may miss the constraints of the map type.
+const LANDMASS_COUNT_SLACK: f32 = 2.0;
+/// Anomalous terrain pairs named in the adjacency warning.
+const SHOWN_ADJACENCY_ANOMALIES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Kinds of suspicious generation outcomes.
+pub enum SanityCode {
+    /// Terrains cover a share of the land outside the range of the map type, see `terrain_share_ranges`
+    TerrainShare,
+    /// The land is hot on average, but there is no desert at all
+    MissingDesert,
+    /// Too much of the land is mountains
+    TooManyMountains,
+    /// There are lakes, but all of them are below the minimum lake size
+    SmallLakesOnly,
+    /// The number of landmasses is far outside the constraints of the map type
+    LandmassCount,
+    /// Some lakes are fully ringed by mountains, see `dead_lakes`
+    DeadLakes,
+    /// The biome constraints still failed after the last threshold adjustment, see `BiomeConstraints`
+    BiomeConstraintsUnmet,
+    /// Terrains that should not touch are neighbors, e.g. snow next to desert (see `ANOMALOUS_TERRAIN_PAIRS`)
+    TerrainAdjacencyAnomaly,
+    /// The map has rivers, but none of them ends at the ocean
+    NoRiverReachesOcean,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A single warning produced by `check_map`.
+pub struct SanityWarning {
+    pub code: SanityCode,
+    pub message: String,
+    /// The measured value that triggered the warning
+    pub value: f32,
+}
+
+/// Expected share of the land per terrain as (terrain, min, max) for a map type
+pub fn terrain_share_ranges(map_type: MapTypes) -> &'static [(Terrain, f32, f32)] {
+    match map_type {
+        MapTypes::Continents | MapTypes::Pangea => &CONTINENTS_SHARES,
+        MapTypes::SmallContinents => &SMALL_CONTINENTS_SHARES,
+        MapTypes::IslandsContinents => &ISLANDS_CONTINENTS_SHARES,
+        MapTypes::Mirror => &MIRROR_SHARES,
+        MapTypes::Terra => &TERRA_SHARES,
+    }
+}
+
+/// Run heuristic checks on a generated map and return a warning for every suspicious outcome.
+/// The checks are cheap and meant to be run right after generation.
+pub fn check_map(map: &Map) -> Vec<SanityWarning> {
+    let mut warnings = Vec::new();
+    let (width, height) = map.size().dimensions();
+    let tiles = map.tiles();
+
+    let land: Vec<_> = tiles.iter().filter(|tile| !tile.terrain().is_water()).collect();
+    if land.is_empty() {
+        return warnings;
+    }
+    let land_tiles = land.len() as f32;
+
+    // Terrain shares, one warning for all terrains out of range with the share furthest outside as value
+    let counts = map.terrain_counts();
+    let mut out_of_range = Vec::new();
+    let mut furthest = (0.0, 0.0);
+    for &(terrain, min, max) in terrain_share_ranges(map.map_type()) {
+        let share = counts.get(&terrain).copied().unwrap_or(0) as f32 / land_tiles;
+        let miss = (min - share).max(share - max);
+        if miss > 0.0 {
+            out_of_range.push(format!(
+                "{terrain:?} at {:.0}% (expected {:.0}-{:.0}%)",
+                share * 100.0,
+                min * 100.0,
+                max * 100.0
+            ));
+            if miss > furthest.0 {
+                furthest = (miss, share);
+            }
+        }
+    }
+    if !out_of_range.is_empty() {
+        warnings.push(SanityWarning {
+            code: SanityCode::TerrainShare,
+            message: format!("unusual terrain shares for {}: {}", map.map_type(), out_of_range.join(", ")),
+            value: furthest.1,
+        });
+    }
+
+    let mountain_share = counts.get(&Terrain::Mountain).copied().unwrap_or(0) as f32 / land_tiles;
+    if mountain_share > MAX_MOUNTAIN_SHARE {
+        warnings.push(SanityWarning {
+            code: SanityCode::TooManyMountains,
+            message: format!("mountains cover {:.0}% of the land", mountain_share * 100.0),
+            value: mountain_share,
+        });
+    }
+
+    let avg_temperature = land.iter().map(|tile| tile.temperature() as f32).sum::<f32>() / land_tiles;
+    let desert_temp = biomes_config().terrain.desert_temp_threshold as f32;
+    if avg_temperature > desert_temp && !counts.contains_key(&Terrain::Desert) {
+        warnings.push(SanityWarning {
+            code: SanityCode::MissingDesert,
+            message: format!("average land temperature is {avg_temperature:.0} but there is no desert"),
+            value: avg_temperature,
+        });
+    }
+
+    // Lakes
+    let terrain: Vec<Terrain> = tiles.iter().map(|tile| tile.terrain()).collect();
+    let lakes = lake_sizes(&terrain, width, height);
+    let min_lake_size = landmasses_config().global.min_lake_size;
+    if let Some(&largest_lake) = lakes.iter().max()
+        && largest_lake < min_lake_size
+    {
+        warnings.push(SanityWarning {
+            code: SanityCode::SmallLakesOnly,
+            message: format!(
+                "all {} lakes are below the minimum lake size of {min_lake_size} (largest has {largest_lake} tiles)",
+                lakes.len()
+            ),
+            value: largest_lake as f32,
+        });
+    }
+
+    let dead = dead_lakes(&terrain, width, height);
+    if !dead.is_empty() {
+        let first_tiles: Vec<String> = dead
+            .iter()
+            .map(|lake| format!("({}, {})", lake[0] % width, lake[0] / width))
+            .collect();
+        warnings.push(SanityWarning {
+            code: SanityCode::DeadLakes,
+            message: format!(
+                "{} lakes are fully ringed by mountains, at {}",
+                dead.len(),
+                first_tiles.join(", ")
+            ),
+            value: dead.len() as f32,
+        });
+    }
+
+    let anomalies = terrain_adjacency_matrix(&terrain, width, height).anomalies();
+    if !anomalies.is_empty() {
+        let top: Vec<String> = anomalies
+            .iter()
+            .take(SHOWN_ADJACENCY_ANOMALIES)
+            .map(|(a, b, count)| format!("{a:?}-{b:?} {count}"))
+            .collect();
+        warnings.push(SanityWarning {
+            code: SanityCode::TerrainAdjacencyAnomaly,
+            message: format!("unusual terrain neighbors: {}", top.join(", ")),
+            value: anomalies.iter().map(|(_, _, count)| *count as f32).sum(),
+        });
+    }
+
+    // Rivers, a map without rivers has nothing to check
+    let ocean = ocean_connected_water(&terrain, width, height);
+    let reaches_ocean = |mouth: usize| {
+        neighbors_odd_r(mouth % width, mouth / width, width, height)
+            .into_iter()
+            .any(|(nx, ny)| ocean[ny * width + nx])
+    };
+    let rivers = map.rivers();
+    if !rivers.is_empty() && !rivers.iter().filter_map(|river| river.last()).any(|&mouth| reaches_ocean(mouth)) {
+        warnings.push(SanityWarning {
+            code: SanityCode::NoRiverReachesOcean,
+            message: format!("none of the {} rivers reaches the ocean", rivers.len()),
+            value: rivers.len() as f32,
+        });
+    }
+
+    // Landmass count
+    let config = landmasses_config();
+    let constraints = config.constraints_for(map.map_type());
+    let n_landmasses = map.landmass_stats().len();
+    let too_few = (n_landmasses as f32) < constraints.min_components as f32 / LANDMASS_COUNT_SLACK;
+    let too_many = (n_landmasses as f32) > constraints.max_components as f32 * LANDMASS_COUNT_SLACK;
+    if too_few || too_many {
+        warnings.push(SanityWarning {
+            code: SanityCode::LandmassCount,
+            message: format!(
+                "{n_landmasses} landmasses, expected {}-{} for {:?}",
+                constraints.min_components,
+                constraints.max_components,
+                map.map_type()
+            ),
+            value: n_landmasses as f32,
+        });
+    }
+
+    if let Some(report) = map.biome_constraints()
+        && !report.is_met()
+    {
+        let missed: Vec<String> = report
+            .unmet
+            .iter()
+            .map(|(constraint, share)| format!("{:?} at {:.0}%", constraint.terrain, share * 100.0))
+            .collect();
+        warnings.push(SanityWarning {
+            code: SanityCode::BiomeConstraintsUnmet,
+            message: format!(
+                "biome constraints unmet after {} adjustments: {}",
+                report.adjustments,
+                missed.join(", ")
+            ),
+            value: report.unmet.len() as f32,
+        });
+    }
+
+    warnings
+}
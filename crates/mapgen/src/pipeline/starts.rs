@@ -0,0 +1,277 @@
+use std::fmt;
+
+use crate::{
+    map_components::{
+        hex_coords::HexCoord,
+        terrain::{Feature, Terrain},
+        yields::BaseYields,
+    },
+    pipeline::{
+        analysis::ocean_connected_water,
+        helpers::{neighbors_odd_r, tile_hash},
+        text_map::GeneratedMapData,
+    },
+};
+
+/// Salt of the tie break between equally good start tiles
+const START_ORDER_SALT: u64 = 0x7374_6172_7473;
+/// Tiles around a start that count towards its quality
+const QUALITY_RADIUS: u32 = 2;
+/// Smallest distance between two starts picked by `default_start_distance`
+const MIN_START_DISTANCE: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Per-call settings of `place_starts`
+pub struct StartPlacementOptions {
+    /// Share [0, 1] of the starts that must be on coastal land: at least ceil(coastal_bias * players)
+    pub coastal_bias: f32,
+    /// Every start must be on or next to a river, a lake or an oasis. Starts that can not get fresh water
+    /// are placed without it and listed in `StartPlacementReport::relaxed_fresh_water`
+    pub require_fresh_water: bool,
+    /// Smallest distance in tiles between two starts, None uses `default_start_distance`
+    pub min_distance_override: Option<u32>,
+    /// Terrains no start may be on, in addition to water and mountains
+    pub forbidden_terrains: Vec<Terrain>,
+}
+
+impl Default for StartPlacementOptions {
+    fn default() -> Self {
+        StartPlacementOptions {
+            coastal_bias: 0.0,
+            require_fresh_water: false,
+            min_distance_override: None,
+            forbidden_terrains: Vec::new(),
+        }
+    }
+}
+
+impl StartPlacementOptions {
+    /// Number of starts that must be coastal for `players` players
+    pub fn coastal_quota(&self, players: usize) -> usize {
+        (self.coastal_bias.clamp(0.0, 1.0) * players as f32).ceil() as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// One placed start, see `place_starts`
+pub struct StartPosition {
+    /// Row-major tile index
+    pub tile: usize,
+    pub hex: HexCoord,
+    /// Food and production around the start plus its fresh water and coast bonus, higher is better
+    pub quality: i32,
+    /// Next to ocean-connected water
+    pub coastal: bool,
+    /// On or next to a river, a lake or an oasis
+    pub fresh_water: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Outcome of `place_starts`
+pub struct StartPlacementReport {
+    /// One start per player in the order they were placed
+    pub starts: Vec<StartPosition>,
+    /// Positions in `starts` that got no fresh water although it was required, as few as the placer found
+    pub relaxed_fresh_water: Vec<usize>,
+    /// Distance every two starts keep at least
+    pub min_distance: u32,
+    /// Starts that had to be coastal, all met
+    pub coastal_quota: usize,
+}
+
+impl StartPlacementReport {
+    /// Lowest start quality, e.g. to re-roll seeds with a bad start. None without players
+    pub fn worst_quality(&self) -> Option<i32> {
+        self.starts.iter().map(|start| start.quality).min()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Errors of `place_starts`
+pub enum StartPlacementError {
+    /// Not enough allowed land tiles lie `min_distance` apart
+    NotEnoughRoom { players: usize, placed: usize, min_distance: u32 },
+    /// Not enough allowed coastal tiles lie `min_distance` apart for the coastal quota
+    CoastalQuota { required: usize, placed: usize },
+}
+
+impl fmt::Display for StartPlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughRoom {
+                players,
+                placed,
+                min_distance,
+            } => write!(
+                f,
+                "only {placed} of {players} starts fit at least {min_distance} tiles apart"
+            ),
+            Self::CoastalQuota { required, placed } => {
+                write!(f, "only {placed} of the {required} required coastal starts fit")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StartPlacementError {}
+
+/// Default distance between starts: two thirds of the side of the land share of one player, at least 3
+pub fn default_start_distance(land_tiles: usize, players: usize) -> u32 {
+    let per_player = land_tiles / players.max(1);
+    ((per_player.isqrt() * 2 / 3) as u32).max(MIN_START_DISTANCE)
+}
+
+/// Place one start per player on allowed land (not water, mountains or `forbidden_terrains`), best quality first.
+/// Ties are broken by a tile hash of `seed`, so the same seed and options give the same starts.
+///
+/// Starts keep the minimum distance to each other. The coastal quota is filled first, then the other starts,
+/// always with fresh water if it is required. Only if that leaves players without a start, the remaining
+/// starts are placed without fresh water and reported in `relaxed_fresh_water`.
+pub fn place_starts(
+    data: &GeneratedMapData,
+    rivers: &[Vec<usize>],
+    players: usize,
+    seed: u64,
+    options: &StartPlacementOptions,
+) -> Result<StartPlacementReport, StartPlacementError> {
+    let (width, height) = (data.width, data.height);
+    let ocean = ocean_connected_water(&data.terrain, width, height);
+    let mut on_river = vec![false; data.terrain.len()];
+    for &idx in rivers.iter().flatten() {
+        on_river[idx] = true;
+    }
+
+    let neighbors = |idx: usize| {
+        neighbors_odd_r(idx % width, idx / width, width, height)
+            .into_iter()
+            .map(|(x, y)| y * width + x)
+    };
+    let gives_fresh_water = |idx: usize| {
+        on_river[idx]
+            || data.features[idx] == Some(Feature::Oasis)
+            || (data.terrain[idx].is_water() && !ocean[idx])
+    };
+    let allowed = |idx: usize| {
+        let terrain = data.terrain[idx];
+        !terrain.is_water() && terrain != Terrain::Mountain && !options.forbidden_terrains.contains(&terrain)
+    };
+
+    let mut candidates: Vec<StartPosition> = (0..data.terrain.len())
+        .filter(|&idx| allowed(idx))
+        .map(|idx| {
+            let coastal = neighbors(idx).any(|n| ocean[n]);
+            let fresh_water = gives_fresh_water(idx) || neighbors(idx).any(gives_fresh_water);
+            let hex = HexCoord::new((idx % width) as i32, (idx / width) as i32);
+            let quality = surroundings_quality(data, hex) + 4 * i32::from(fresh_water) + 2 * i32::from(coastal);
+            StartPosition {
+                tile: idx,
+                hex,
+                quality,
+                coastal,
+                fresh_water,
+            }
+        })
+        .collect();
+    candidates.sort_by_key(|start| (std::cmp::Reverse(start.quality), tile_hash(seed ^ START_ORDER_SALT, start.tile)));
+
+    let land_tiles = data.terrain.iter().filter(|terrain| !terrain.is_water()).count();
+    let min_distance = options
+        .min_distance_override
+        .unwrap_or_else(|| default_start_distance(land_tiles, players));
+    let quota = options.coastal_quota(players).min(players);
+
+    let mut placer = StartPlacer {
+        players,
+        quota,
+        min_distance,
+        starts: Vec::with_capacity(players),
+    };
+    let strict = |start: &StartPosition| !options.require_fresh_water || start.fresh_water;
+    placer.fill(candidates.iter().filter(|start| strict(start)), true);
+    placer.fill(candidates.iter().filter(|start| strict(start)), false);
+    placer.fill(candidates.iter(), true);
+    placer.fill(candidates.iter(), false);
+
+    let coastal = placer.coastal_count();
+    if coastal < quota {
+        return Err(StartPlacementError::CoastalQuota {
+            required: quota,
+            placed: coastal,
+        });
+    }
+    if placer.starts.len() < players {
+        return Err(StartPlacementError::NotEnoughRoom {
+            players,
+            placed: placer.starts.len(),
+            min_distance,
+        });
+    }
+    let relaxed_fresh_water = if options.require_fresh_water {
+        (0..players).filter(|&i| !placer.starts[i].fresh_water).collect()
+    } else {
+        Vec::new()
+    };
+    Ok(StartPlacementReport {
+        starts: placer.starts,
+        relaxed_fresh_water,
+        min_distance,
+        coastal_quota: quota,
+    })
+}
+
+/// Doubled food plus production of the land and water around a hex, hills add one production
+fn surroundings_quality(data: &GeneratedMapData, center: HexCoord) -> i32 {
+    let mut quality = 0;
+    for dy in -(QUALITY_RADIUS as i32)..=QUALITY_RADIUS as i32 {
+        for dx in -(QUALITY_RADIUS as i32) - 1..=QUALITY_RADIUS as i32 + 1 {
+            let hex = HexCoord::new(center.x() + dx, center.y() + dy);
+            let (Ok(x), Ok(y)) = (usize::try_from(hex.x()), usize::try_from(hex.y())) else {
+                continue;
+            };
+            if x >= data.width || y >= data.height || center.distance(&hex) > QUALITY_RADIUS {
+                continue;
+            }
+            let idx = data.index(x, y);
+            let yields = data.terrain[idx].base_yields();
+            quality += 2 * yields.get_yield(BaseYields::Food) + yields.get_yield(BaseYields::Production);
+            quality += i32::from(data.hills[idx]);
+        }
+    }
+    quality
+}
+
+struct StartPlacer {
+    players: usize,
+    quota: usize,
+    min_distance: u32,
+    starts: Vec<StartPosition>,
+}
+
+impl StartPlacer {
+    fn coastal_count(&self) -> usize {
+        self.starts.iter().filter(|start| start.coastal).count()
+    }
+
+    /// Take candidates in order while players lack a start. With `coastal_only` only until the quota is met,
+    /// otherwise non-coastal starts only while enough players are left for the rest of the quota.
+    fn fill<'a>(&mut self, candidates: impl Iterator<Item = &'a StartPosition>, coastal_only: bool) {
+        for candidate in candidates {
+            let coastal = self.coastal_count();
+            let open = self.players - self.starts.len();
+            if open == 0 || (coastal_only && coastal >= self.quota) {
+                return;
+            }
+            let quota_left = self.quota.saturating_sub(coastal);
+            if !candidate.coastal && (coastal_only || open <= quota_left) {
+                continue;
+            }
+            let free = self
+                .starts
+                .iter()
+                .all(|start| start.tile != candidate.tile && start.hex.distance(&candidate.hex) >= self.min_distance);
+            if free {
+                self.starts.push(*candidate);
+            }
+        }
+    }
+}
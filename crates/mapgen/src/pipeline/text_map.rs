@@ -0,0 +1,329 @@
+use std::fmt;
+
+use crate::map_components::{
+    terrain::{Feature, Terrain},
+    tile_modifiers::{TileModifierRules, TileModifiers},
+};
+
+/// Terrain characters used when a text map has no legend, and by `write_text_map`.
+const DEFAULT_LEGEND: [(char, Terrain); 8] = [
+    ('.', Terrain::Ocean),
+    ('~', Terrain::CoastLake),
+    ('p', Terrain::Plains),
+    ('g', Terrain::Grassland),
+    ('d', Terrain::Desert),
+    ('t', Terrain::Tundra),
+    ('s', Terrain::Snow),
+    ('^', Terrain::Mountain),
+];
+
+/// Feature characters of the `#features` section, `.` means no feature.
+const FEATURE_CHARS: [(char, Feature); 9] = [
+    ('w', Feature::Woods),
+    ('r', Feature::Rainforest),
+    ('m', Feature::Marsh),
+    ('f', Feature::Floodplains),
+    ('o', Feature::Oasis),
+    ('x', Feature::Fissure),
+    ('v', Feature::VolanicSoil),
+    ('e', Feature::Reef),
+    ('i', Feature::Ice),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// Per-tile layers of a map in row-major order (odd-r offset rows), as read from or written to a text map.
+pub struct GeneratedMapData {
+    pub width: usize,
+    pub height: usize,
+    pub terrain: Vec<Terrain>,
+    pub hills: Vec<bool>,
+    pub features: Vec<Option<Feature>>,
+}
+
+impl GeneratedMapData {
+    /// A map of the given size filled with a single terrain, without hills or features
+    pub fn filled(width: usize, height: usize, terrain: Terrain) -> Self {
+        GeneratedMapData {
+            width,
+            height,
+            terrain: vec![terrain; width * height],
+            hills: vec![false; width * height],
+            features: vec![None; width * height],
+        }
+    }
+
+    /// Row-major index of the tile at offset coordinates (x, y)
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Movement, defense and sight modifiers of every tile under the given rules
+    pub fn tile_modifiers(&self, rules: &TileModifierRules) -> Vec<TileModifiers> {
+        (0..self.terrain.len())
+            .map(|idx| rules.modifiers(self.terrain[idx], self.hills[idx], self.features[idx]))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Errors when parsing a text map. Lines and columns are 1-based.
+pub enum TextMapError {
+    /// A legend line is not of the form `<char> = <terrain>`
+    InvalidLegend { line: usize },
+    /// A legend line names a terrain that does not exist
+    UnknownTerrain { line: usize, name: String },
+    /// A `#` line names a section other than `terrain`, `hills` or `features`
+    UnknownSection { line: usize, name: String },
+    /// The same section appears twice
+    DuplicateSection { line: usize, name: String },
+    /// A grid character is not in the legend (or not a hill/feature character)
+    UnknownCharacter { line: usize, column: usize, character: char },
+    /// A row is shorter or longer than the first terrain row, the column is where the rows stop matching
+    RaggedRow { line: usize, column: usize, expected: usize, found: usize },
+    /// A hills or features section does not have as many rows as the terrain
+    RowCount { line: usize, section: String, expected: usize, found: usize },
+    /// There is no `#terrain` section or it has no rows
+    MissingTerrain,
+}
+
+impl fmt::Display for TextMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLegend { line } => {
+                write!(f, "line {line}: invalid legend entry, expected `<char> = <terrain>`")
+            }
+            Self::UnknownTerrain { line, name } => write!(f, "line {line}: unknown terrain '{name}'"),
+            Self::UnknownSection { line, name } => {
+                write!(f, "line {line}: unknown section '#{name}', expected #terrain, #hills or #features")
+            }
+            Self::DuplicateSection { line, name } => write!(f, "line {line}: section '#{name}' appears twice"),
+            Self::UnknownCharacter { line, column, character } => {
+                write!(f, "line {line}, column {column}: unknown character '{character}'")
+            }
+            Self::RaggedRow { line, column, expected, found } => write!(
+                f,
+                "line {line}, column {column}: row has {found} tiles, expected {expected}"
+            ),
+            Self::RowCount { line, section, expected, found } => write!(
+                f,
+                "line {line}: section '#{section}' has {found} rows, expected {expected}"
+            ),
+            Self::MissingTerrain => f.write_str("missing #terrain section"),
+        }
+    }
+}
+
+impl std::error::Error for TextMapError {}
+
+/// A grid row: its 1-based line number, the column of its first tile and its tiles
+struct Row<'a> {
+    line: usize,
+    first_column: usize,
+    tiles: &'a str,
+}
+
+/// A `#` section: the line of its header and its rows
+struct Section<'a> {
+    line: usize,
+    rows: Vec<Row<'a>>,
+}
+
+/// Parse a hand-authored text map.
+///
+/// The file starts with an optional legend of `<char> = <terrain>` lines (without one the default
+/// legend of `write_text_map` is used), followed by a `#terrain` section with one character per tile
+/// and one line per row, top to bottom. Optional `#hills` (`h` or `.`) and `#features` sections
+/// (`w`oods, `r`ainforest, `m`arsh, `f`loodplains, `o`asis, fissure `x`, `v`olcanic soil, r`e`ef,
+/// `i`ce or `.`) have the same shape. Leading whitespace (the odd-row indent) and blank lines are ignored.
+pub fn parse_text_map(input: &str) -> Result<GeneratedMapData, TextMapError> {
+    let mut legend: Vec<(char, Terrain)> = Vec::new();
+    let mut terrain_section: Option<Section> = None;
+    let mut hills_section: Option<Section> = None;
+    let mut features_section: Option<Section> = None;
+    let mut current: Option<&mut Section> = None;
+
+    for (i, raw) in input.lines().enumerate() {
+        let line = i + 1;
+        let trimmed = raw.trim_end();
+        let tiles = trimmed.trim_start();
+        if tiles.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = tiles.strip_prefix('#') {
+            let name = name.trim();
+            let slot = match name {
+                "terrain" => &mut terrain_section,
+                "hills" => &mut hills_section,
+                "features" => &mut features_section,
+                _ => return Err(TextMapError::UnknownSection { line, name: name.to_string() }),
+            };
+            if slot.is_some() {
+                return Err(TextMapError::DuplicateSection { line, name: name.to_string() });
+            }
+            current = Some(slot.insert(Section { line, rows: Vec::new() }));
+            continue;
+        }
+
+        match current.as_deref_mut() {
+            Some(section) => section.rows.push(Row {
+                line,
+                first_column: trimmed.chars().count() - tiles.chars().count() + 1,
+                tiles,
+            }),
+            None => legend.push(parse_legend_line(tiles, line)?),
+        }
+    }
+    if legend.is_empty() {
+        legend = DEFAULT_LEGEND.to_vec();
+    }
+
+    let terrain_section = terrain_section
+        .filter(|section| !section.rows.is_empty())
+        .ok_or(TextMapError::MissingTerrain)?;
+    let width = terrain_section.rows[0].tiles.chars().count();
+    let height = terrain_section.rows.len();
+
+    let terrain = parse_grid(&terrain_section, "terrain", width, height, |c| {
+        legend.iter().find(|(key, _)| *key == c).map(|(_, terrain)| *terrain)
+    })?;
+    let hills = match &hills_section {
+        Some(section) => parse_grid(section, "hills", width, height, |c| match c {
+            'h' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?,
+        None => vec![false; width * height],
+    };
+    let features = match &features_section {
+        Some(section) => parse_grid(section, "features", width, height, |c| match c {
+            '.' => Some(None),
+            _ => feature_from_char(c).map(Some),
+        })?,
+        None => vec![None; width * height],
+    };
+
+    Ok(GeneratedMapData { width, height, terrain, hills, features })
+}
+
+/// Write a map in the text format read by `parse_text_map`, with the default legend.
+/// Odd rows are indented by one space to show the hex offset, empty hill and feature sections are left out.
+pub fn write_text_map(data: &GeneratedMapData) -> String {
+    let mut out = String::new();
+    for (c, terrain) in DEFAULT_LEGEND {
+        out.push_str(&format!("{c} = {}\n", terrain_name(terrain)));
+    }
+
+    write_section(&mut out, "terrain", data, |idx| {
+        DEFAULT_LEGEND
+            .iter()
+            .find(|(_, terrain)| *terrain == data.terrain[idx])
+            .map(|(c, _)| *c)
+            .expect("every terrain has a default character")
+    });
+    if data.hills.iter().any(|hill| *hill) {
+        write_section(&mut out, "hills", data, |idx| if data.hills[idx] { 'h' } else { '.' });
+    }
+    if data.features.iter().any(Option::is_some) {
+        write_section(&mut out, "features", data, |idx| {
+            data.features[idx].map_or('.', |feature| {
+                FEATURE_CHARS
+                    .iter()
+                    .find(|(_, f)| *f == feature)
+                    .map(|(c, _)| *c)
+                    .expect("every feature has a character")
+            })
+        });
+    }
+    out
+}
+
+fn write_section(out: &mut String, name: &str, data: &GeneratedMapData, tile_char: impl Fn(usize) -> char) {
+    out.push_str(&format!("#{name}\n"));
+    for y in 0..data.height {
+        if y % 2 == 1 {
+            out.push(' ');
+        }
+        out.extend((0..data.width).map(|x| tile_char(data.index(x, y))));
+        out.push('\n');
+    }
+}
+
+/// Parse a `<char> = <terrain>` legend line
+fn parse_legend_line(text: &str, line: usize) -> Result<(char, Terrain), TextMapError> {
+    let (key, name) = text.split_once('=').ok_or(TextMapError::InvalidLegend { line })?;
+    let mut key_chars = key.trim().chars();
+    let (Some(c), None) = (key_chars.next(), key_chars.next()) else {
+        return Err(TextMapError::InvalidLegend { line });
+    };
+    let name = name.trim();
+    let terrain = terrain_from_name(name).ok_or_else(|| TextMapError::UnknownTerrain {
+        line,
+        name: name.to_string(),
+    })?;
+    Ok((c, terrain))
+}
+
+/// Parse the rows of a section into one value per tile
+fn parse_grid<T>(
+    section: &Section,
+    name: &str,
+    width: usize,
+    height: usize,
+    value: impl Fn(char) -> Option<T>,
+) -> Result<Vec<T>, TextMapError> {
+    if section.rows.len() != height {
+        return Err(TextMapError::RowCount {
+            line: section.line,
+            section: name.to_string(),
+            expected: height,
+            found: section.rows.len(),
+        });
+    }
+
+    let mut values = Vec::with_capacity(width * height);
+    for row in &section.rows {
+        let found = row.tiles.chars().count();
+        if found != width {
+            return Err(TextMapError::RaggedRow {
+                line: row.line,
+                column: row.first_column + found.min(width),
+                expected: width,
+                found,
+            });
+        }
+        for (x, c) in row.tiles.chars().enumerate() {
+            let parsed = value(c).ok_or(TextMapError::UnknownCharacter {
+                line: row.line,
+                column: row.first_column + x,
+                character: c,
+            })?;
+            values.push(parsed);
+        }
+    }
+    Ok(values)
+}
+
+fn terrain_name(terrain: Terrain) -> &'static str {
+    match terrain {
+        Terrain::Plains => "plains",
+        Terrain::Grassland => "grassland",
+        Terrain::Desert => "desert",
+        Terrain::Tundra => "tundra",
+        Terrain::Snow => "snow",
+        Terrain::CoastLake => "coast",
+        Terrain::Ocean => "ocean",
+        Terrain::Mountain => "mountain",
+    }
+}
+
+fn terrain_from_name(name: &str) -> Option<Terrain> {
+    DEFAULT_LEGEND
+        .iter()
+        .map(|(_, terrain)| *terrain)
+        .find(|terrain| terrain_name(*terrain).eq_ignore_ascii_case(name))
+}
+
+fn feature_from_char(c: char) -> Option<Feature> {
+    FEATURE_CHARS.iter().find(|(key, _)| *key == c).map(|(_, feature)| *feature)
+}
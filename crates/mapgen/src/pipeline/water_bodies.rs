@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use crate::{
+    map_components::terrain::Terrain,
+    pipeline::{analysis::ocean_connected_water, helpers::neighbors_odd_r, names::unique_place_names},
+};
+
+/// Smallest share of the map tiles a connected deep water region needs to be an ocean
+pub const MIN_OCEAN_SHARE: f32 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Classification of a water body, see `water_bodies`
+pub enum WaterBodyKind {
+    /// Deep water (`Terrain::Ocean`) connected to the map border through deep water,
+    /// with at least `MIN_OCEAN_SHARE` of the tiles
+    Ocean,
+    /// The rest of the water connected to the ocean: the shallows along the coasts, enclosed deep basins and
+    /// small deep bays
+    Sea,
+    /// Water cut off from the ocean with at least `min_lake_size` tiles, counted by the lake constraints
+    Lake,
+    /// Water cut off from the ocean that is too small to count as a lake
+    Pond,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// Statistics and name of a single water body
+pub struct WaterBodyInfo {
+    /// Id of the water body, water bodies are ordered by size (0 is the largest)
+    pub id: usize,
+    pub kind: WaterBodyKind,
+    /// Deterministic name, e.g. "Sea of Kalth". The largest ocean is the "World Ocean"
+    pub name: String,
+    /// Number of water tiles
+    pub size: usize,
+    /// Smallest and largest offset coordinates (x, y) of its tiles
+    pub bounds: ((usize, usize), (usize, usize)),
+    /// The largest ocean a sea borders, None for the other kinds and for seas without deep water next to them
+    pub connects_to: Option<usize>,
+}
+
+impl WaterBodyInfo {
+    /// Hover text, e.g. "Sea of Kalth — 412 tiles — connects to World Ocean".
+    /// `bodies` are all water bodies of the map, to name the ocean a sea connects to
+    pub fn hover_text(&self, bodies: &[WaterBodyInfo]) -> String {
+        let tiles = if self.size == 1 { "tile" } else { "tiles" };
+        let mut text = format!("{} — {} {tiles}", self.name, self.size);
+        if let Some(ocean) = self.connects_to.and_then(|id| bodies.get(id)) {
+            text += &format!(" — connects to {}", ocean.name);
+        }
+        text
+    }
+}
+
+/// Label the water bodies of a map: water connected to the ocean (see `ocean_connected_water`) is split into
+/// oceans and seas, see `WaterBodyKind`, water cut off from the ocean into lakes and ponds by `min_lake_size`. Returns the water body id per tile (None for land) and every water body,
+/// ordered by size like `label_landmasses`. Names are unique and only depend on `seed` and the terrain.
+pub fn water_bodies(
+    terrain: &[Terrain],
+    width: usize,
+    height: usize,
+    seed: u64,
+    min_lake_size: usize,
+) -> (Vec<Option<usize>>, Vec<WaterBodyInfo>) {
+    debug_assert_eq!(terrain.len(), width * height);
+    let ocean = ocean_connected_water(terrain, width, height);
+    let open_ocean = open_ocean(terrain, width, height);
+    let kind_of = |idx: usize| match (ocean[idx], open_ocean[idx]) {
+        (true, true) => WaterBodyKind::Ocean,
+        (true, false) => WaterBodyKind::Sea,
+        // lakes and ponds are told apart by size once the component is known
+        (false, _) => WaterBodyKind::Lake,
+    };
+
+    // flood fill the water, only joining neighbors of the same kind
+    let mut raw_ids = vec![usize::MAX; terrain.len()];
+    let mut components: Vec<(WaterBodyKind, Vec<usize>)> = Vec::new();
+    let mut q = VecDeque::new();
+    for start in 0..terrain.len() {
+        if !terrain[start].is_water() || raw_ids[start] != usize::MAX {
+            continue;
+        }
+        let kind = kind_of(start);
+        let comp_id = components.len();
+        let mut tiles = Vec::new();
+        raw_ids[start] = comp_id;
+        q.push_back(start);
+        while let Some(idx) = q.pop_front() {
+            tiles.push(idx);
+            for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                let nidx = ny * width + nx;
+                if terrain[nidx].is_water() && raw_ids[nidx] == usize::MAX && kind_of(nidx) == kind {
+                    raw_ids[nidx] = comp_id;
+                    q.push_back(nidx);
+                }
+            }
+        }
+        components.push((kind, tiles));
+    }
+
+    // largest first, ties keep scan order
+    let mut order: Vec<usize> = (0..components.len()).collect();
+    order.sort_by_key(|&comp| std::cmp::Reverse(components[comp].1.len()));
+    let mut id_of = vec![0; components.len()];
+    for (id, &comp) in order.iter().enumerate() {
+        id_of[comp] = id;
+    }
+    let ids: Vec<Option<usize>> = raw_ids
+        .iter()
+        .map(|&raw| (raw != usize::MAX).then(|| id_of[raw]))
+        .collect();
+
+    let first_tiles: Vec<usize> = order.iter().map(|&comp| components[comp].1[0]).collect();
+    let stems = unique_place_names(seed, &first_tiles, terrain.len());
+    let world_ocean = order.iter().position(|&comp| components[comp].0 == WaterBodyKind::Ocean);
+    let bodies = order
+        .iter()
+        .zip(stems)
+        .enumerate()
+        .map(|(id, (&comp, stem))| {
+            let (kind, tiles) = &components[comp];
+            let kind = match kind {
+                WaterBodyKind::Lake if tiles.len() < min_lake_size => WaterBodyKind::Pond,
+                kind => *kind,
+            };
+            let xs = tiles.iter().map(|idx| idx % width);
+            let ys = tiles.iter().map(|idx| idx / width);
+            let bounds = (
+                (xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+                (xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+            );
+            // oceans have smaller ids the larger they are, so the smallest neighboring id is the largest ocean
+            let connects_to = (kind == WaterBodyKind::Sea)
+                .then(|| {
+                    tiles
+                        .iter()
+                        .flat_map(|&idx| neighbors_odd_r(idx % width, idx / width, width, height))
+                        .filter_map(|(nx, ny)| ids[ny * width + nx])
+                        .filter(|&nid| components[order[nid]].0 == WaterBodyKind::Ocean)
+                        .min()
+                })
+                .flatten();
+            let name = match kind {
+                WaterBodyKind::Ocean if Some(id) == world_ocean => "World Ocean".to_string(),
+                WaterBodyKind::Ocean => format!("{stem} Ocean"),
+                WaterBodyKind::Sea => format!("Sea of {stem}"),
+                WaterBodyKind::Lake => format!("Lake {stem}"),
+                WaterBodyKind::Pond => format!("{stem} Pond"),
+            };
+            WaterBodyInfo {
+                id,
+                kind,
+                name,
+                size: tiles.len(),
+                bounds,
+                connects_to,
+            }
+        })
+        .collect();
+    (ids, bodies)
+}
+
+/// Deep water tiles reachable from a deep border tile without leaving deep water, in connected regions of at
+/// least `MIN_OCEAN_SHARE` of the map. Smaller regions are bays or straits of a sea
+fn open_ocean(terrain: &[Terrain], width: usize, height: usize) -> Vec<bool> {
+    let min_size = (terrain.len() as f32 * MIN_OCEAN_SHARE).ceil() as usize;
+    let mut open = vec![false; terrain.len()];
+    let mut seen = vec![false; terrain.len()];
+    let on_border = |idx: usize| {
+        let (x, y) = (idx % width, idx / width);
+        x == 0 || y == 0 || x + 1 == width || y + 1 == height
+    };
+    for start in 0..terrain.len() {
+        if seen[start] || terrain[start] != Terrain::Ocean || !on_border(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut region = vec![start];
+        let mut q = VecDeque::from([start]);
+        while let Some(idx) = q.pop_front() {
+            for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                let nidx = ny * width + nx;
+                if terrain[nidx] == Terrain::Ocean && !seen[nidx] {
+                    seen[nidx] = true;
+                    region.push(nidx);
+                    q.push_back(nidx);
+                }
+            }
+        }
+        if region.len() >= min_size {
+            for idx in region {
+                open[idx] = true;
+            }
+        }
+    }
+    open
+}
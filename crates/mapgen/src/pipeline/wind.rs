@@ -0,0 +1,90 @@
+use crate::{
+    map_components::hex_coords::{CompassDirection, HexCoord},
+    pipeline::helpers::WindConfig,
+};
+
+/// Latitude of the center of row `y` in degrees, positive in the north (top rows) and negative in the south
+pub fn row_latitude(y: usize, height: usize) -> f32 {
+    90.0 - 180.0 * (y as f32 + 0.5) / height as f32
+}
+
+/// Wind band of a latitude: the number of band latitudes at or below its distance to the equator
+pub fn wind_band(latitude: f32, band_latitudes: &[f32]) -> usize {
+    band_latitudes.iter().filter(|&&band| band <= latitude.abs()).count()
+}
+
+/// Prevailing wind of a band. Even bands blow west and toward the equator (trade winds, polar easterlies),
+/// odd bands east and toward the pole (westerlies).
+pub fn band_direction(band: usize, northern: bool) -> CompassDirection {
+    match (band.is_multiple_of(2), northern) {
+        (true, true) => CompassDirection::SW,
+        (true, false) => CompassDirection::NW,
+        (false, true) => CompassDirection::NE,
+        (false, false) => CompassDirection::SE,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Prevailing wind direction per row of the map, see `WindConfig`
+pub struct WindLayer {
+    width: usize,
+    rows: Vec<CompassDirection>,
+}
+
+impl WindLayer {
+    /// Wind of a `width` x `height` map with the bands of `config`
+    pub fn new(width: usize, height: usize, config: &WindConfig) -> Self {
+        let rows = (0..height)
+            .map(|y| {
+                let latitude = row_latitude(y, height);
+                band_direction(wind_band(latitude, &config.band_latitudes), latitude >= 0.0)
+            })
+            .collect();
+        WindLayer { width, rows }
+    }
+
+    /// Wind with one given direction per row, e.g. for hand-authored maps
+    pub fn from_rows(width: usize, rows: Vec<CompassDirection>) -> Self {
+        WindLayer { width, rows }
+    }
+
+    /// Return the wind direction per row, top to bottom
+    pub fn rows(&self) -> &[CompassDirection] {
+        &self.rows
+    }
+
+    /// Return the wind angle per row in degrees, see `CompassDirection::angle_degrees`
+    pub fn row_angles(&self) -> Vec<f32> {
+        self.rows.iter().map(CompassDirection::angle_degrees).collect()
+    }
+
+    /// Return the wind direction at a row-major tile index
+    pub fn wind_at(&self, tile_idx: usize) -> CompassDirection {
+        self.rows[tile_idx / self.width]
+    }
+
+    /// Direction of the step between two neighboring tiles, None if they are not neighbors
+    fn step_direction(&self, from: usize, to: usize) -> Option<CompassDirection> {
+        let hex = |idx: usize| HexCoord::new((idx % self.width) as i32, (idx / self.width) as i32);
+        let (from, to) = (hex(from), hex(to));
+        CompassDirection::ALL.into_iter().find(|dir| from.neighbor(dir) == to)
+    }
+}
+
+/// Step cost for `find_path_directed` where moving with the wind is cheaper, e.g. for sailing.
+/// Entering a tile costs `base`, minus `with_wind_discount` (but not below 1) if the step goes in the wind
+/// direction of the tile it leaves.
+pub fn wind_assisted_cost<'a>(
+    base: impl Fn(usize) -> Option<u32> + 'a,
+    wind_layer: &'a WindLayer,
+    with_wind_discount: u32,
+) -> impl Fn(usize, usize) -> Option<u32> + 'a {
+    move |from, to| {
+        let cost = base(to)?;
+        if wind_layer.step_direction(from, to) == Some(wind_layer.wind_at(from)) {
+            Some(cost.saturating_sub(with_wind_discount).max(cost.min(1)))
+        } else {
+            Some(cost)
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use civorum_mapgen::{
+    map_components::{terrain::Terrain, yields::BaseYields},
+    pipeline::{
+        appeal::compute_appeal, helpers::AppealConfig, map_builder::MapBuilder, map_sizes::MapSizes,
+        text_map::parse_text_map,
+    },
+};
+
+const SIZE: usize = 5;
+const CENTER: usize = 2 * SIZE + 2;
+
+fn config() -> AppealConfig {
+    AppealConfig {
+        mountain: 1,
+        coast: 1,
+        woods: 1,
+        marsh: -1,
+        floodplains: -1,
+        desert: -1,
+        min: -4,
+        max: 4,
+    }
+}
+
+/// Appeal of every tile of a 5x5 text map
+fn appeal(text: &str) -> Vec<i8> {
+    let data = parse_text_map(text).unwrap();
+    assert_eq!((data.width, data.height), (SIZE, SIZE));
+    compute_appeal(&data.terrain, &data.features, SIZE, SIZE, &config())
+}
+
+fn center_appeal(text: &str) -> i8 {
+    appeal(text)[CENTER]
+}
+
+#[test]
+fn plain_neighbors_are_neutral() {
+    let appeal = appeal(
+        "
+        #terrain
+        ppppp
+         ppppp
+        ppppp
+         ppppp
+        ppppp
+        ",
+    );
+    assert!(appeal.iter().all(|a| *a == 0));
+}
+
+#[test]
+fn adjacent_terrain_rules() {
+    for (neighbor, expected) in [('^', 1), ('~', 1), ('d', -1)] {
+        let text = format!("#terrain\nppppp\n pp{neighbor}pp\nppppp\n ppppp\nppppp\n");
+        assert_eq!(center_appeal(&text), expected, "{neighbor}");
+    }
+}
+
+#[test]
+fn adjacent_feature_rules() {
+    for (feature, expected) in [('w', 1), ('m', -1), ('f', -1)] {
+        let text = format!(
+            "#terrain\nppppp\n ppppp\nppppp\n ppppp\nppppp\n#features\n.....\n ..{feature}..\n.....\n .....\n.....\n"
+        );
+        assert_eq!(center_appeal(&text), expected, "{feature}");
+    }
+}
+
+#[test]
+fn rules_add_up() {
+    let appeal = center_appeal(
+        "
+        #terrain
+        ppppp
+         pp^pp
+        ppp~p
+         pdppp
+        ppppp
+        #features
+        .....
+         .....
+        .....
+         ..w..
+        .....
+        ",
+    );
+    assert_eq!(appeal, 2);
+}
+
+#[test]
+fn appeal_is_clamped() {
+    let surrounded_by_mountains = "
+        #terrain
+        ppppp
+         p^^pp
+        p^p^p
+         p^^pp
+        ppppp
+        ";
+    assert_eq!(center_appeal(surrounded_by_mountains), config().max);
+
+    let surrounded_by_marshy_desert = "
+        #terrain
+        ppppp
+         pddpp
+        pdpdp
+         pddpp
+        ppppp
+        #features
+        .....
+         mm...
+        .m.m.
+         mm...
+        .....
+        ";
+    assert_eq!(center_appeal(surrounded_by_marshy_desert), config().min);
+}
+
+#[test]
+fn water_tiles_are_neutral() {
+    for center in ['~', '.'] {
+        let text = format!("#terrain\nppppp\n p^^pp\np^{center}^p\n p^^pp\nppppp\n");
+        assert_eq!(center_appeal(&text), 0, "{center}");
+    }
+}
+
+#[test]
+fn tiles_keep_their_own_appeal() {
+    let lone_mountain = "#terrain\nppppp\n ppppp\npp^pp\n ppppp\nppppp\n";
+    assert_eq!(center_appeal(lone_mountain), Terrain::Mountain.default_appeal() as i8);
+
+    // grassland (+1) with woods (+1) next to a marsh (-1)
+    let text = "#terrain\nppppp\n ppppp\nppgpp\n ppppp\nppppp\n#features\n.....\n .....\n..wm.\n .....\n.....\n";
+    assert_eq!(center_appeal(text), 1);
+}
+
+#[test]
+fn a_lone_mountain_keeps_its_appeal_on_the_map() {
+    let (width, height) = MapSizes::Duel.dimensions();
+    let center = height / 2 * width + width / 2;
+    let mut terrain = vec![Terrain::Plains; width * height];
+    terrain[center] = Terrain::Mountain;
+    let map = MapBuilder::new(MapSizes::Duel).terrain(terrain).build().unwrap();
+
+    assert_eq!(map.tiles()[center].yields().get_yield(BaseYields::Appeal), 2);
+    assert_eq!(map.tiles()[center + 1].yields().get_yield(BaseYields::Appeal), AppealConfig::default().mountain as i32);
+}
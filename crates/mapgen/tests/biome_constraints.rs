@@ -0,0 +1,106 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        biomes::{MapLayers, generate_map_with_type, try_generate_map_with_type},
+        error::GenError,
+        helpers::{BiomeConstraints, BiomesConfig, TerrainShareConstraint, biomes_config, with_biomes_config},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        sanity::{SanityCode, check_map},
+    },
+};
+
+fn cap(terrain: Terrain, max: f32) -> BiomeConstraints {
+    BiomeConstraints {
+        terrains: vec![TerrainShareConstraint {
+            terrain,
+            min: None,
+            max: Some(max),
+        }],
+        ..BiomeConstraints::default()
+    }
+}
+
+fn with_constraints(constraints: BiomeConstraints) -> BiomesConfig {
+    let mut config = biomes_config().as_ref().clone();
+    config.biome_constraints = constraints;
+    config
+}
+
+/// A config that turns most of the land into desert
+fn arid(constraints: BiomeConstraints) -> BiomesConfig {
+    let mut config = with_constraints(constraints);
+    config.terrain.desert_temp_threshold = 90;
+    config.terrain.desert_rain_threshold = 200;
+    config
+}
+
+fn land_share(layers: &MapLayers, terrain: Terrain) -> f32 {
+    let land = layers.terrain.iter().filter(|t| !t.is_water()).count();
+    layers.terrain.iter().filter(|t| **t == terrain).count() as f32 / land as f32
+}
+
+#[test]
+fn unconstrained_maps_are_unchanged() {
+    let size = MapSizes::Small;
+    for seed in [3u64, 8] {
+        let default = generate_map_with_type(&seed, &size, MapTypes::Continents);
+        assert!(default.biome_constraints.is_none());
+
+        // a bound every map meets assigns the terrain once with the configured thresholds
+        let config = with_constraints(cap(Terrain::Desert, 1.0));
+        let loose = with_biomes_config(config, || generate_map_with_type(&seed, &size, MapTypes::Continents));
+        let report = loose.biome_constraints.expect("constrained maps report");
+        assert!(report.is_met());
+        assert_eq!(report.adjustments, 0);
+        assert_eq!(loose.terrain, default.terrain);
+        assert_eq!(loose.hills, default.hills);
+    }
+}
+
+#[test]
+fn desert_cap_converges_or_reports_failure() {
+    let size = MapSizes::Small;
+    for seed in [1u64, 4, 9] {
+        let unconstrained =
+            with_biomes_config(arid(BiomeConstraints::default()), || generate_map_with_type(&seed, &size, MapTypes::Continents));
+        assert!(land_share(&unconstrained, Terrain::Desert) > 0.05, "seed {seed}: the arid config is not arid");
+
+        let layers = with_biomes_config(arid(cap(Terrain::Desert, 0.05)), || {
+            generate_map_with_type(&seed, &size, MapTypes::Continents)
+        });
+        let report = layers.biome_constraints.clone().expect("constrained maps report");
+        let share = land_share(&layers, Terrain::Desert);
+        assert!(report.adjustments > 0);
+        if report.is_met() {
+            assert!(share <= 0.05, "seed {seed}: reported met at {share}");
+        } else {
+            assert_eq!(report.adjustments, BiomeConstraints::default().max_adjustments);
+            let (constraint, reported) = report.unmet[0];
+            assert_eq!(constraint.terrain, Terrain::Desert);
+            assert!((reported - share).abs() < 1e-6 && share > 0.05, "seed {seed}: reported {reported}, measured {share}");
+        }
+    }
+}
+
+#[test]
+fn unmet_constraints_are_a_sanity_warning() {
+    // no threshold moves a mountain share below zero
+    let mut config = arid(cap(Terrain::Mountain, 0.0));
+    config.biome_constraints.max_adjustments = 2;
+    let map = with_biomes_config(config, || Map::new(Some(2), MapSizes::Duel));
+
+    let report = map.biome_constraints().expect("constrained maps report");
+    assert!(!report.is_met());
+    assert_eq!(report.adjustments, 2);
+    let warnings = check_map(&map);
+    assert_eq!(warnings.iter().filter(|warning| warning.code == SanityCode::BiomeConstraintsUnmet).count(), 1);
+}
+
+#[test]
+fn water_constraints_are_rejected() {
+    let config = with_constraints(cap(Terrain::Ocean, 0.5));
+    let result = with_biomes_config(config, || try_generate_map_with_type(&1, &MapSizes::Duel, MapTypes::Continents));
+    assert!(matches!(result, Err(GenError::InvalidBiomeConstraint { terrain: Terrain::Ocean, .. })));
+}
@@ -0,0 +1,96 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        canals::{MAX_CANAL_LENGTH, find_canal_candidates},
+        map::Map,
+        map_sizes::MapSizes,
+        text_map::GeneratedMapData,
+    },
+};
+
+/// Ocean split by a 3 row land band (rows 9-11), with a 1 tile isthmus at x=15 where only row 10 is land
+fn isthmus_map(width: usize, height: usize) -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(width, height, Terrain::Ocean);
+    for x in 0..width {
+        for y in 9..=11 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = Terrain::Grassland;
+        }
+    }
+    for y in [9, 11] {
+        let idx = data.index(15, y);
+        data.terrain[idx] = Terrain::Ocean;
+    }
+    data
+}
+
+#[test]
+fn isthmus_between_two_oceans_is_the_top_candidate() {
+    let data = isthmus_map(30, 21);
+    let candidates = find_canal_candidates(&data, 3, 5);
+    let top = &candidates[0];
+    assert_eq!(top.tiles, vec![data.index(15, 10)]);
+    assert_eq!(top.distance_saved, u32::MAX);
+    assert!(top.joins_separate_oceans());
+
+    assert_eq!(candidates.len(), 5);
+    let mut tiles: Vec<usize> = candidates.iter().flat_map(|candidate| candidate.tiles.clone()).collect();
+    let count = tiles.len();
+    tiles.sort_unstable();
+    tiles.dedup();
+    assert_eq!(tiles.len(), count, "candidates share tiles");
+    assert!(candidates.iter().all(|candidate| candidate.tiles.len() <= 3));
+
+    // usize::MAX asks for every candidate
+    let all = find_canal_candidates(&data, 3, usize::MAX);
+    assert_eq!(all[0].tiles, top.tiles);
+    assert!(all.len() >= candidates.len());
+}
+
+#[test]
+fn isthmus_within_one_ocean_saves_the_detour() {
+    // open the band at both edges so the sea route goes around it, the isthmus is the longest detour
+    let mut data = isthmus_map(31, 21);
+    for x in [0, 30] {
+        for y in 9..=11 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = Terrain::Ocean;
+        }
+    }
+    let candidates = find_canal_candidates(&data, 3, 3);
+    let top = &candidates[0];
+    assert_eq!(top.tiles, vec![data.index(15, 10)]);
+    assert!(!top.joins_separate_oceans());
+    assert!(top.distance_saved > 20, "saved {}", top.distance_saved);
+    assert!(candidates.windows(2).all(|pair| pair[0].distance_saved >= pair[1].distance_saved));
+}
+
+#[test]
+fn max_length_bounds_the_canal() {
+    // without the isthmus the band is 3 tiles thick
+    let mut data = GeneratedMapData::filled(20, 21, Terrain::Ocean);
+    for x in 0..20 {
+        for y in 9..=11 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = Terrain::Grassland;
+        }
+    }
+    assert!(find_canal_candidates(&data, 2, 4).is_empty());
+    let candidates = find_canal_candidates(&data, MAX_CANAL_LENGTH + 4, 4);
+    assert_eq!(candidates.len(), 4);
+    assert!(candidates.iter().all(|candidate| candidate.tiles.len() == 3 && candidate.joins_separate_oceans()));
+    assert!(find_canal_candidates(&data, 3, 0).is_empty());
+}
+
+#[test]
+fn generated_maps_give_stable_candidates() {
+    let data = Map::new(Some(4), MapSizes::Duel).to_map_data();
+    let candidates = find_canal_candidates(&data, 2, 5);
+    assert!(candidates.len() <= 5);
+    assert_eq!(candidates, find_canal_candidates(&data, 2, 5));
+    assert!(candidates.iter().all(|candidate| candidate.distance_saved > 0
+        && candidate
+            .tiles
+            .iter()
+            .all(|&idx| !data.terrain[idx].is_water())));
+}
@@ -0,0 +1,57 @@
+use civorum_mapgen::pipeline::{
+    biomes::generate_map_with_type, chunked::ChunkedMap, map_sizes::MapSizes, map_types::MapTypes,
+};
+
+#[test]
+fn materialized_chunks_match_the_eager_pipeline() {
+    for (size, map_type, seed, chunk_size) in [
+        (MapSizes::Duel, MapTypes::Continents, 3u64, 16),
+        (MapSizes::Small, MapTypes::Pangea, 8, 64),
+        (MapSizes::Duel, MapTypes::Mirror, 5, 13),
+        (MapSizes::Small, MapTypes::Mirror, 1, 20),
+    ] {
+        let eager = generate_map_with_type(&seed, &size, map_type);
+        let chunked = ChunkedMap::new(seed, size, map_type, chunk_size);
+        let (width, height) = size.dimensions();
+        assert_eq!(chunked.landmask(), eager.landmask.as_slice());
+        assert_eq!(chunked.temperature_range(), eager.temperature_range.as_slice());
+        assert_eq!(chunked.geological_age(), eager.geological_age.as_slice());
+
+        // access the chunks back to front, the order must not matter
+        let (across, down) = chunked.chunks_across();
+        for cy in (0..down).rev() {
+            for cx in (0..across).rev() {
+                let chunk = chunked.chunk(cx, cy);
+                for y in chunk.y0..chunk.y0 + chunk.height {
+                    for x in chunk.x0..chunk.x0 + chunk.width {
+                        let (local, idx) = (chunk.local_index(x, y).unwrap(), y * width + x);
+                        let context = format!("{map_type:?} {size:?} seed {seed} at ({x}, {y})");
+                        assert_eq!(chunk.terrain[local], eager.terrain[idx], "terrain, {context}");
+                        assert_eq!(chunk.hills[local], eager.hills[idx], "hills, {context}");
+                        assert_eq!(chunk.desert_variants[local], eager.desert_variants[idx], "variant, {context}");
+                    }
+                }
+            }
+        }
+        let tiles: usize = (0..down)
+            .flat_map(|cy| (0..across).map(move |cx| (cx, cy)))
+            .map(|(cx, cy)| chunked.chunk(cx, cy).terrain.len())
+            .sum();
+        assert_eq!(tiles, width * height);
+    }
+}
+
+#[test]
+fn chunks_are_only_assigned_on_access() {
+    let chunked = ChunkedMap::new(2, MapSizes::Small, MapTypes::Continents, 16);
+    assert_eq!(chunked.chunks_across(), (5, 3));
+    assert_eq!(chunked.materialized_chunks(), 0);
+
+    let chunk = chunked.chunk_of(70, 40);
+    assert_eq!((chunk.x0, chunk.y0, chunk.width, chunk.height), (64, 32, 10, 14));
+    assert_eq!(chunk.local_index(63, 40), None);
+    assert_eq!(chunked.materialized_chunks(), 1);
+
+    chunked.chunk(4, 2);
+    assert_eq!(chunked.materialized_chunks(), 1);
+}
@@ -0,0 +1,52 @@
+use civorum_mapgen::pipeline::{
+    helpers::{landmasses_config, try_load_landmasses_config, with_landmasses_config},
+    land::{GenerationStepRecorder, generate_landmasses},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+const BAND: usize = 12;
+
+/// Land tiles of the initial draft within `BAND` tiles of the border, summed over a few seeds.
+/// Also checks that the outermost ring of the finished landmask is water.
+fn draft_land_near_border(band_width: usize, penalty_per_tile: f32) -> usize {
+    let mut config = landmasses_config().as_ref().clone();
+    config.continents.draft.coast_band_width = band_width;
+    config.continents.draft.coast_band_penalty_per_tile = penalty_per_tile;
+    let (width, height) = MapSizes::Large.dimensions();
+    let border_distance = |idx: usize| {
+        let (x, y) = (idx % width, idx / width);
+        x.min(width - 1 - x).min(y).min(height - 1 - y)
+    };
+
+    with_landmasses_config(config, || {
+        (1..=2)
+            .map(|seed| {
+                let ((grid, _), steps) = GenerationStepRecorder::record(|| {
+                    generate_landmasses(seed, &MapSizes::Large, MapTypes::Continents)
+                });
+                assert!((0..grid.len()).all(|idx| border_distance(idx) > 0 || grid[idx] == 0));
+
+                let draft = &steps[0].landmask;
+                (0..draft.len()).filter(|&idx| border_distance(idx) < BAND && draft[idx] == 1).count()
+            })
+            .sum()
+    })
+}
+
+#[test]
+fn configs_default_to_the_hard_border() {
+    let config = try_load_landmasses_config().expect("landmasses.yml parses");
+    assert_eq!(config.continents.draft.coast_band_width, 0);
+    assert_eq!(config.mirror.base.draft.coast_band_width, 0);
+}
+
+#[test]
+fn soft_band_allows_some_land_near_the_border() {
+    let hard = draft_land_near_border(0, 1.0);
+    let gentle = draft_land_near_border(BAND, 1.0);
+    let steep = draft_land_near_border(BAND, 10.0);
+
+    assert!(gentle > hard, "{gentle} draft land tiles near the border with the band, {hard} without");
+    assert!(steep <= gentle, "{steep} draft land tiles with a steep band, {gentle} with a gentle one");
+}
@@ -0,0 +1,103 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{coast_ruggedness, label_landmasses},
+        helpers::{load_landmasses_config, with_landmasses_config},
+        land::{generate_landmasses, vary_coast_ruggedness},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+fn landmass_count(grid: &[u8], width: usize, height: usize) -> usize {
+    let terrain: Vec<Terrain> = grid
+        .iter()
+        .map(|v| if *v == 1 { Terrain::Plains } else { Terrain::Ocean })
+        .collect();
+    label_landmasses(&terrain, width, height).1.len()
+}
+
+#[test]
+fn zero_strength_leaves_the_grid_untouched() {
+    let size = MapSizes::Small;
+    let (width, height) = size.dimensions();
+    let (grid, _) = generate_landmasses(3, &size, MapTypes::Continents);
+
+    let mut edited = grid.clone();
+    let applied = vary_coast_ruggedness(&mut edited, width, height, 3, 0.0);
+    assert_eq!(edited, grid);
+    assert!(applied.iter().all(Option::is_none));
+}
+
+#[test]
+fn ruggedness_keeps_land_total_and_landmass_count() {
+    for (size, map_type) in [
+        (MapSizes::Small, MapTypes::Continents),
+        (MapSizes::Small, MapTypes::IslandsContinents),
+        (MapSizes::Tiny, MapTypes::Pangea),
+    ] {
+        let (width, height) = size.dimensions();
+        for seed in [1u64, 9, 23] {
+            let (grid, _) = generate_landmasses(seed, &size, map_type);
+            let mut edited = grid.clone();
+            vary_coast_ruggedness(&mut edited, width, height, seed, 0.5);
+
+            assert_ne!(edited, grid, "{map_type:?} seed {seed} was not edited");
+            let land_before = grid.iter().filter(|v| **v == 1).count() as f32;
+            let land_after = edited.iter().filter(|v| **v == 1).count() as f32;
+            assert!(
+                (land_after - land_before).abs() <= 0.005 * grid.len() as f32,
+                "{map_type:?} seed {seed}: land changed from {land_before} to {land_after}"
+            );
+            assert_eq!(
+                landmass_count(&edited, width, height),
+                landmass_count(&grid, width, height),
+                "{map_type:?} seed {seed}"
+            );
+        }
+    }
+}
+
+#[test]
+fn applied_ruggedness_is_reported_per_landmass() {
+    let size = MapSizes::Small;
+    let (width, height) = size.dimensions();
+    let (grid, _) = generate_landmasses(9, &size, MapTypes::Continents);
+    let mut edited = grid.clone();
+    let applied = vary_coast_ruggedness(&mut edited, width, height, 9, 0.5);
+
+    let terrain: Vec<Terrain> = edited
+        .iter()
+        .map(|v| if *v == 1 { Terrain::Plains } else { Terrain::Ocean })
+        .collect();
+    let (ids, sizes) = label_landmasses(&terrain, width, height);
+    for id in 0..sizes.len() {
+        let factors: Vec<Option<f32>> =
+            (0..ids.len()).filter(|&idx| ids[idx] == Some(id)).map(|idx| applied[idx]).collect();
+        let factor = factors[0].unwrap();
+        assert!(factors.iter().all(|f| *f == Some(factor)), "landmass {id}");
+        assert!((0..sizes.len()).any(|pass_id| coast_ruggedness(9, pass_id) == factor));
+    }
+    assert!(edited.iter().zip(&applied).all(|(land, factor)| (*land == 1) == factor.is_some()));
+}
+
+#[test]
+fn maps_report_ruggedness_only_where_the_pass_ran() {
+    let mut config = load_landmasses_config();
+    config.global.coast_ruggedness_strength = 0.5;
+    let (rugged, mirror) = with_landmasses_config(config, || {
+        (
+            Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::Continents),
+            Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::Mirror),
+        )
+    });
+    assert!(rugged.landmass_stats().iter().all(|info| info.ruggedness.is_some()));
+    assert!(mirror.landmass_stats().iter().all(|info| info.ruggedness.is_none()));
+
+    let mut config = load_landmasses_config();
+    config.global.coast_ruggedness_strength = 0.0;
+    let smooth = with_landmasses_config(config, || Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::Continents));
+    assert!(!smooth.landmass_stats().is_empty());
+    assert!(smooth.landmass_stats().iter().all(|info| info.ruggedness.is_none()));
+}
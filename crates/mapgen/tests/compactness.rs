@@ -0,0 +1,70 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain},
+    pipeline::{
+        analysis::hex_compactness,
+        map::Map,
+        map_builder::MapBuilder,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+const WIDTH: usize = 44;
+const HEIGHT: usize = 26;
+
+/// Duel map with land on the tiles for which `is_land` holds
+fn map_with_land(is_land: impl Fn(HexCoord) -> bool) -> Map {
+    let terrain = (0..WIDTH * HEIGHT)
+        .map(|idx| {
+            let coord = HexCoord::new((idx % WIDTH) as i32, (idx / WIDTH) as i32);
+            if is_land(coord) { Terrain::Grassland } else { Terrain::Ocean }
+        })
+        .collect();
+    MapBuilder::new(MapSizes::Duel).terrain(terrain).build().unwrap()
+}
+
+#[test]
+fn hex_disks_are_near_the_maximum() {
+    for radius in 2..=6 {
+        let center = HexCoord::new(20, 12);
+        let map = map_with_land(|coord| coord.distance(&center) <= radius);
+        let stats = map.landmass_stats();
+
+        assert_eq!(stats.len(), 1);
+        let area = 3 * radius * radius + 3 * radius + 1;
+        assert_eq!(stats[0].size, area as usize);
+        assert_eq!(stats[0].coastline_length, 12 * radius as usize + 6);
+        assert!(stats[0].compactness >= 0.95, "radius {radius}: {}", stats[0].compactness);
+    }
+}
+
+#[test]
+fn snakes_score_low() {
+    // a one tile wide line along a row, and one through the rows (a column zigzags on the hex grid)
+    let line = map_with_land(|coord| coord.y() == 12 && (5..35).contains(&coord.x()));
+    let zigzag = map_with_land(|coord| (3..23).contains(&coord.y()) && coord.x() == 10);
+    for snake in [line, zigzag] {
+        let stats = snake.landmass_stats();
+        assert_eq!(stats.len(), 1);
+        assert!(stats[0].compactness < 0.25, "{}", stats[0].compactness);
+    }
+
+    let disk = map_with_land(|coord| coord.distance(&HexCoord::new(20, 12)) <= 3);
+    let blob = map_with_land(|coord| (8..14).contains(&coord.y()) && (10..20).contains(&coord.x()));
+    let blob = blob.landmass_stats()[0].compactness;
+    assert!(blob > 0.5 && blob <= disk.landmass_stats()[0].compactness, "{blob}");
+}
+
+#[test]
+fn compactness_is_deterministic() {
+    assert_eq!(hex_compactness(0, 0), 0.0);
+    assert_eq!(hex_compactness(1, 0), 1.0);
+    // a single tile scores above 1 before clamping
+    assert_eq!(hex_compactness(1, 6), 1.0);
+
+    let compactness = |map: &Map| map.landmass_stats().iter().map(|info| info.compactness).collect::<Vec<_>>();
+    let first = Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::SmallContinents);
+    let second = Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::SmallContinents);
+    assert_eq!(compactness(&first), compactness(&second));
+    assert!(compactness(&first).iter().all(|&value| value > 0.0 && value <= 1.0));
+}
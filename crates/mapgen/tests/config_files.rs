@@ -0,0 +1,50 @@
+use civorum_mapgen::pipeline::helpers::{
+    AppealConfig, BiomesConfig, ContinentalityConfig, LandmassesConfig, TemperatureModel, try_load_biomes_config,
+    try_load_landmasses_config,
+};
+
+/// `biomes.yaml` as it was before the options added since then
+const BASELINE_BIOMES: &str = include_str!("fixtures/biomes_baseline.yaml");
+/// `landmasses.yml` as it was before the options added since then
+const BASELINE_LANDMASSES: &str = include_str!("fixtures/landmasses_baseline.yml");
+
+#[test]
+fn the_shipped_configs_parse() {
+    try_load_biomes_config().unwrap();
+    try_load_landmasses_config().unwrap();
+}
+
+#[test]
+fn a_baseline_biomes_config_parses_with_defaults() {
+    let config: BiomesConfig = serde_yaml::from_str(BASELINE_BIOMES).unwrap();
+
+    assert_eq!(config.temperature_model, TemperatureModel::Simple);
+    assert_eq!(config.temperature.axial_tilt_degrees, 23.5);
+    // options that change the terrain are off
+    assert_eq!(config.terrain.threshold_dither_window, 0);
+    assert_eq!(config.terrain.dither_elevation_bias, 0.0);
+    assert!(!config.smooth_elevation_before_biome_assign);
+    // the new sections get their built-in settings
+    assert_eq!(config.rainfall.coastal_boost, 30);
+    assert_eq!(config.rainfall.coastal_max_dist, 8);
+    assert_eq!(config.continentality.continental_range, ContinentalityConfig::default().continental_range);
+    assert_eq!(config.appeal.mountain, AppealConfig::default().mountain);
+    assert_eq!(config.geological_age.distance_scale, 8.0);
+    assert_eq!(config.desert.oasis_min_region_size, 12);
+}
+
+#[test]
+fn a_baseline_landmasses_config_parses_with_defaults() {
+    let config: LandmassesConfig = serde_yaml::from_str(BASELINE_LANDMASSES).unwrap();
+
+    assert!(config.global.scale_to_map_size);
+    assert_eq!(config.global.coast_ruggedness_strength, 0.0);
+    assert!(config.mirror.mirror_climate);
+    for repair in [&config.continents.repair, &config.pangea.repair, &config.terra.merged_repair] {
+        // the thin string and peninsula passes are off
+        assert_eq!(repair.min_connection_width, 0);
+        assert_eq!(repair.thin_string_min_length, 0);
+        assert!(!repair.thicken_thin_strings);
+        assert_eq!(repair.peninsula_min_width, 0);
+    }
+}
@@ -0,0 +1,116 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        helpers::{EffectiveConfig, config_hash, effective_config, landmasses_config, with_landmasses_config},
+        map::Map,
+        map_builder::MapBuilder,
+        map_sizes::MapSizes,
+    },
+};
+use serde_yaml::Value;
+
+/// Every number and bool of the config as a path of mapping keys and sequence indices
+fn leaf_paths(value: &Value, path: &mut Vec<Value>, paths: &mut Vec<Vec<Value>>) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, child) in mapping {
+                path.push(key.clone());
+                leaf_paths(child, path, paths);
+                path.pop();
+            }
+        }
+        Value::Sequence(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                path.push(Value::from(idx));
+                leaf_paths(child, path, paths);
+                path.pop();
+            }
+        }
+        Value::Number(_) | Value::Bool(_) => paths.push(path.clone()),
+        _ => {}
+    }
+}
+
+fn leaf_mut<'v>(value: &'v mut Value, path: &[Value]) -> &'v mut Value {
+    path.iter().fold(value, |value, key| match (value, key) {
+        (Value::Sequence(items), Value::Number(idx)) => &mut items[idx.as_u64().unwrap() as usize],
+        (value, key) => value.get_mut(key).unwrap(),
+    })
+}
+
+/// The value one step away from `value`, still valid for its field
+fn nudged(value: &Value) -> Value {
+    match value {
+        Value::Bool(flag) => Value::Bool(!flag),
+        Value::Number(number) if number.is_u64() => Value::from(number.as_u64().unwrap() ^ 1),
+        Value::Number(number) if number.is_i64() => Value::from(number.as_i64().unwrap() ^ 1),
+        Value::Number(number) => Value::from(number.as_f64().unwrap() + 0.25),
+        _ => unreachable!("only numbers and bools are nudged"),
+    }
+}
+
+#[test]
+fn changing_any_setting_changes_the_hash() {
+    let config = effective_config();
+    let hash = config.hash();
+    assert_eq!(hash, config_hash());
+
+    let tree = serde_yaml::to_value(&config).unwrap();
+    let mut paths = Vec::new();
+    leaf_paths(&tree, &mut Vec::new(), &mut paths);
+    assert!(paths.len() > 100, "only {} settings found", paths.len());
+
+    for path in paths {
+        let mut changed = tree.clone();
+        let leaf = leaf_mut(&mut changed, &path);
+        *leaf = nudged(leaf);
+        let changed: EffectiveConfig = serde_yaml::from_value(changed).unwrap();
+        assert_ne!(changed.hash(), hash, "changing {path:?} keeps the config hash");
+    }
+}
+
+#[test]
+fn a_dumped_config_reloads_with_the_same_hash() {
+    let config = effective_config();
+    let path = std::env::temp_dir().join(format!("civorum_config_hash_{}.yaml", std::process::id()));
+    std::fs::write(&path, config.to_yaml()).unwrap();
+    let raw = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let reloaded = EffectiveConfig::from_yaml(&raw).unwrap();
+    assert_eq!(reloaded.hash(), config.hash());
+    assert_eq!(reloaded.to_yaml(), raw);
+}
+
+#[test]
+fn maps_keep_the_hash_of_the_config_they_were_made_with() {
+    let build = || {
+        let (width, height) = MapSizes::Duel.dimensions();
+        let terrain = (0..width * height)
+            .map(|idx| if (idx % width) % 3 == 0 { Terrain::Plains } else { Terrain::Ocean })
+            .collect();
+        MapBuilder::new(MapSizes::Duel).terrain(terrain).build().unwrap()
+    };
+    let header = |map: &Map| map.to_json_value()["config_hash"].as_str().unwrap().to_string();
+
+    let default_hash = config_hash();
+    let default_map = build();
+    assert_eq!(default_map.config_hash(), default_hash);
+
+    let mut changed = landmasses_config().as_ref().clone();
+    changed.global.min_lake_size += 1;
+    let (changed_map, changed_hash) = with_landmasses_config(changed, || {
+        // a config installed after the map was made does not change what it reports
+        assert_ne!(config_hash(), default_hash);
+        assert_eq!(header(&default_map), format!("{default_hash:016x}"));
+        assert_eq!(default_map.recipe().config_hash, Some(default_hash));
+        (build(), config_hash())
+    });
+    assert_eq!(changed_map.config_hash(), changed_hash);
+    assert_eq!(header(&changed_map), format!("{changed_hash:016x}"));
+    assert_eq!(changed_map.recipe().config_hash, Some(changed_hash));
+
+    // same tiles, different config
+    assert_eq!(changed_map.to_map_data().terrain, default_map.to_map_data().terrain);
+    assert_ne!(changed_map.fingerprint(), default_map.fingerprint());
+}
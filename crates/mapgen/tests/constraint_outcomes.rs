@@ -0,0 +1,117 @@
+use civorum_mapgen::pipeline::{
+    constraints::{ConstraintBound, ConstraintKind, ConstraintOutcome, SUGGESTION_RULES, suggestion, suggestion_rule},
+    helpers::ConstraintsConfig,
+    map::Map,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+#[test]
+fn every_configurable_bound_has_a_rule() {
+    for kind in ConstraintKind::ALL {
+        for bound in [ConstraintBound::Min, ConstraintBound::Max] {
+            let rule = suggestion_rule(kind, bound);
+            // there is no max_islands
+            if (kind, bound) == (ConstraintKind::Islands, ConstraintBound::Max) {
+                assert!(rule.is_none());
+            } else {
+                assert!(rule.is_some_and(|rule| !rule.knobs.is_empty()), "{kind:?} {bound:?}");
+            }
+        }
+    }
+    for (i, rule) in SUGGESTION_RULES.iter().enumerate() {
+        assert!(
+            SUGGESTION_RULES[i + 1..]
+                .iter()
+                .all(|other| (other.kind, other.bound) != (rule.kind, rule.bound)),
+            "duplicate rule for {:?} {:?}",
+            rule.kind,
+            rule.bound
+        );
+    }
+}
+
+#[test]
+fn suggestions_name_the_knobs_and_the_bound() {
+    assert_eq!(
+        suggestion(ConstraintKind::Components, ConstraintBound::Min, 6.0, 3.0),
+        "min_components=6 not reached (got 3); consider increasing channel_carve_count or lowering min_components"
+    );
+    assert_eq!(
+        suggestion(ConstraintKind::Lakes, ConstraintBound::Min, 2.0, 0.0),
+        "min_lakes=2 not reached (got 0); consider increasing lake_blob_max, decreasing min_lake_size or lowering min_lakes"
+    );
+    assert_eq!(
+        suggestion(ConstraintKind::LargestRatio, ConstraintBound::Max, 0.5, 0.875),
+        "max_largest_ratio=0.50 exceeded (got 0.88); consider increasing largest_carve_base_count, \
+         decreasing largest_carve_trigger_ratio or raising max_largest_ratio"
+    );
+    // a bound without a rule still suggests loosening it
+    assert_eq!(
+        suggestion(ConstraintKind::Islands, ConstraintBound::Max, 4.0, 9.0),
+        "max_islands=4 exceeded (got 9); consider raising max_islands"
+    );
+}
+
+#[test]
+fn outcomes_check_both_ends_of_the_range() {
+    let inside = ConstraintOutcome::evaluate(ConstraintKind::LandRatio, Some(0.3), Some(0.45), 0.4);
+    assert!(inside.satisfied && inside.suggestion.is_none());
+    assert_eq!(inside.to_string(), "land_ratio       0.30-0.45     0.40  ok");
+
+    let below = ConstraintOutcome::evaluate(ConstraintKind::Components, Some(6.0), Some(10.0), 3.0);
+    assert!(!below.satisfied);
+    assert!(below.suggestion.as_deref().is_some_and(|text| text.starts_with("min_components=6 not reached")));
+
+    let above = ConstraintOutcome::evaluate(ConstraintKind::Lakes, Some(1.0), Some(3.0), 5.0);
+    assert!(above.suggestion.as_deref().is_some_and(|text| text.starts_with("max_lakes=3 exceeded (got 5)")));
+
+    let open = ConstraintOutcome::evaluate(ConstraintKind::Islands, Some(2.0), None, 40.0);
+    assert!(open.satisfied);
+    assert_eq!(open.target(), ">= 2");
+}
+
+#[test]
+fn unreachable_constraints_report_a_suggestion() {
+    let hard = ConstraintsConfig {
+        min_components: 40,
+        max_components: 60,
+        ..ConstraintsConfig::default()
+    };
+    let map = Map::generate_with_constraints(MapSizes::Duel, Some(1), MapTypes::Continents, &hard);
+    let outcomes = map.constraint_outcomes();
+    let components = outcomes
+        .iter()
+        .find(|outcome| outcome.kind == ConstraintKind::Components)
+        .unwrap();
+    assert_eq!((components.min, components.max), (Some(40.0), Some(60.0)));
+    assert!(!components.satisfied);
+    assert!(components.suggestion.as_deref().is_some_and(|text| text.contains("channel_carve_count")));
+    assert!(map.satisfied_constraints() < outcomes.len());
+    assert!(
+        outcomes
+            .iter()
+            .all(|outcome| outcome.satisfied == outcome.suggestion.is_none())
+    );
+
+    let summary = map.to_json_value();
+    let constraints = summary["constraints"].as_array().unwrap();
+    assert_eq!(constraints.len(), outcomes.len());
+    assert!(constraints.iter().any(|outcome| outcome["kind"] == "components" && outcome["satisfied"] == false));
+}
+
+#[test]
+fn stress_test_rows_count_satisfied_constraints() {
+    let report = Map::stress_test(0..3, MapSizes::Duel, MapTypes::Pangea);
+    let csv = report.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("seed,land_ratio,landmasses,coastline_length,compactness,repair_passes,satisfied_constraints,constraints")
+    );
+    for (row, line) in report.seeds.iter().zip(lines) {
+        assert!(row.satisfied_constraints <= row.constraints);
+        assert!(line.ends_with(&format!(",{},{}", row.satisfied_constraints, row.constraints)), "{line}");
+    }
+    assert_eq!(report.seeds.len(), 3);
+}
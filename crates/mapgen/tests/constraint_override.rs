@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use civorum_mapgen::pipeline::{
+    helpers::{ConstraintsConfig, config_hash, landmasses_config, with_landmasses_config},
+    map::Map,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+#[test]
+fn merge_override_ignores_zero_and_nan_fields() {
+    let base = landmasses_config().constraints_for(MapTypes::Continents).clone();
+    let merged = base.merge_override(&ConstraintsConfig {
+        min_land_ratio: 0.4,
+        max_land_ratio: f32::NAN,
+        min_islands: 7,
+        max_compactness: Some(0.9),
+        ..Default::default()
+    });
+
+    assert_eq!(merged.min_land_ratio, 0.4);
+    assert_eq!(merged.max_land_ratio, base.max_land_ratio);
+    assert_eq!(merged.min_largest_ratio, base.min_largest_ratio);
+    assert_eq!(merged.min_islands, 7);
+    assert_eq!(merged.max_components, base.max_components);
+    assert_eq!(merged.min_compactness, base.min_compactness);
+    assert_eq!(merged.max_compactness, Some(0.9));
+
+    let unchanged = base.merge_override(&ConstraintsConfig::default());
+    assert_eq!(format!("{unchanged:?}"), format!("{base:?}"));
+}
+
+#[test]
+fn generate_with_constraints_only_affects_that_map() {
+    let configured = landmasses_config().constraints_for(MapTypes::Continents).clone();
+    let extra = ConstraintsConfig {
+        min_land_ratio: 0.65,
+        max_land_ratio: 0.8,
+        ..Default::default()
+    };
+
+    // the maps record the hash of the config with the merged constraints
+    let merged = landmasses_config()
+        .as_ref()
+        .clone()
+        .with_constraint_override(MapTypes::Continents, configured.merge_override(&extra));
+    let merged_hash = with_landmasses_config(merged, config_hash);
+    assert_ne!(merged_hash, config_hash());
+
+    for seed in [1, 5] {
+        let default_map = Map::new_with_type(Some(seed), MapSizes::Small, MapTypes::Continents);
+        let overridden = Map::generate_with_constraints(MapSizes::Small, Some(seed), MapTypes::Continents, &extra);
+        assert_eq!(default_map.config_hash(), config_hash());
+        assert_eq!(overridden.config_hash(), merged_hash);
+        assert_eq!(overridden.recipe().config_hash, Some(merged_hash));
+        let default_ratio = default_map.statistics().land_ratio;
+        let overridden_ratio = overridden.statistics().land_ratio;
+        assert!(
+            overridden_ratio > default_ratio,
+            "seed {seed}: land ratio {overridden_ratio} with the override, {default_ratio} without"
+        );
+    }
+
+    let after = landmasses_config().constraints_for(MapTypes::Continents).clone();
+    assert_eq!(format!("{after:?}"), format!("{configured:?}"));
+}
+
+#[test]
+fn scoped_configs_are_freed_afterwards() {
+    let scoped = with_landmasses_config(landmasses_config().as_ref().clone(), || Arc::downgrade(&landmasses_config()));
+    assert!(scoped.upgrade().is_none(), "the scoped config is still alive");
+    // outside of a scope every call shares the installed config
+    assert!(Arc::ptr_eq(&landmasses_config(), &landmasses_config()));
+}
@@ -0,0 +1,66 @@
+use std::fs;
+
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{map::Map, map_builder::MapBuilder, map_sizes::MapSizes, map_types::MapTypes},
+};
+
+#[test]
+fn dot_graph_has_a_node_per_continent_and_an_edge_per_sea_route() {
+    let map = Map::new_with_type(Some(4), MapSizes::Tiny, MapTypes::IslandsContinents);
+    let path = std::env::temp_dir().join("civorum_continent_graph_test.dot");
+    map.export_to_dot_graph(&path).unwrap();
+    let dot = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(dot.starts_with("graph continents {\n"));
+    assert!(dot.ends_with("}\n"));
+
+    let nodes = dot.lines().filter(|line| line.contains("[label=\"Continent ")).count();
+    assert_eq!(nodes, map.landmass_stats().len());
+    let largest = &map.landmass_stats()[0];
+    assert!(dot.contains(&format!("  0 [label=\"Continent 0\\n{} tiles\"];", largest.size)));
+
+    let matrix = map.continent_connectivity_matrix();
+    let routes = (0..matrix.len()).flat_map(|a| (a + 1..matrix.len()).map(move |b| (a, b)));
+    let mut expected_edges = 0;
+    for (a, b) in routes.filter(|&(a, b)| matrix[a][b]) {
+        assert!(dot.contains(&format!("  {a} -- {b} [label=\"sea\"];")));
+        expected_edges += 1;
+    }
+    assert!(expected_edges > 0);
+    assert_eq!(dot.matches(" -- ").count(), expected_edges);
+}
+
+#[test]
+fn continents_are_connected_only_by_shallow_water() {
+    const WIDTH: usize = 44;
+    const HEIGHT: usize = 26;
+    let mut terrain = vec![Terrain::Ocean; WIDTH * HEIGHT];
+    // two islands on either side of a shallow strait, and one alone in the deep ocean
+    let strips = [
+        (4..10, Terrain::Plains),
+        (10..12, Terrain::CoastLake),
+        (12..16, Terrain::Plains),
+        (30..38, Terrain::Plains),
+    ];
+    for (xs, land) in strips {
+        for y in 5..12 {
+            for x in xs.clone() {
+                terrain[y * WIDTH + x] = land;
+            }
+        }
+    }
+    let map = MapBuilder::new(MapSizes::Duel).terrain(terrain).build().unwrap();
+    let sizes: Vec<usize> = map.landmass_stats().iter().map(|info| info.size).collect();
+    assert_eq!(sizes, [56, 42, 28]);
+
+    let matrix = map.continent_connectivity_matrix();
+    assert_eq!(matrix, [[true, false, false], [false, true, true], [false, true, true]]);
+    for (a, row) in matrix.iter().enumerate() {
+        for (b, &connected) in row.iter().enumerate() {
+            assert_eq!(map.continents_are_connected(a, b), connected, "{a} and {b}");
+        }
+    }
+    assert!(!map.continents_are_connected(0, 3));
+}
@@ -0,0 +1,78 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{dead_lakes, lake_rim, lakes},
+        biomes::{generate_map_with_type, open_dead_lakes},
+        helpers::{biomes_config, neighbors_odd_r, set_biomes_config},
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+/// Duel sized plains with a lake ringed by mountains at (10, 5) and an open lake at (20, 8)
+fn synthetic_terrain() -> (Vec<Terrain>, usize, usize) {
+    let (width, height) = MapSizes::Duel.dimensions();
+    let mut terrain = vec![Terrain::Plains; width * height];
+    terrain[5 * width + 10] = Terrain::CoastLake;
+    for (nx, ny) in neighbors_odd_r(10, 5, width, height) {
+        terrain[ny * width + nx] = Terrain::Mountain;
+    }
+    terrain[8 * width + 20] = Terrain::CoastLake;
+    (terrain, width, height)
+}
+
+#[test]
+fn mountain_ringed_lakes_are_detected() {
+    let (terrain, width, height) = synthetic_terrain();
+    assert_eq!(lakes(&terrain, width, height).len(), 2);
+    assert_eq!(dead_lakes(&terrain, width, height), vec![vec![5 * width + 10]]);
+}
+
+#[test]
+fn repair_opens_exactly_one_rim_tile() {
+    let (mut terrain, width, height) = synthetic_terrain();
+    let mut hills = vec![false; terrain.len()];
+    let mut heightmap = vec![200u8; terrain.len()];
+    let lake = 5 * width + 10;
+    let rim = lake_rim(&[lake], &terrain, width, height);
+    // two rim tiles share the lowest height, the lower index wins
+    heightmap[rim[4]] = 120;
+    heightmap[rim[2]] = 120;
+    let before = terrain.clone();
+
+    let opened = open_dead_lakes(&mut terrain, &mut hills, &heightmap, &MapSizes::Duel, |_| Terrain::Grassland);
+    assert_eq!(opened, vec![rim[2]]);
+    assert_eq!(terrain[rim[2]], Terrain::Grassland);
+    assert!(hills[rim[2]]);
+    let changed = (0..terrain.len()).filter(|&idx| terrain[idx] != before[idx]).count();
+    assert_eq!(changed, 1);
+    assert!(dead_lakes(&terrain, width, height).is_empty());
+}
+
+#[test]
+fn maps_without_dead_lakes_are_untouched() {
+    let (width, height) = MapSizes::Duel.dimensions();
+    // seed 8 has no dead lakes, seed 9 has one
+    let generate = |seed: u64| generate_map_with_type(&seed, &MapSizes::Duel, MapTypes::Continents);
+    let closed: Vec<_> = [8, 9].map(generate).into_iter().collect();
+
+    let original = biomes_config().as_ref().clone();
+    let mut config = original.clone();
+    config.terrain.open_dead_lakes = true;
+    set_biomes_config(config);
+    let opened: Vec<_> = [8, 9].map(generate).into_iter().collect();
+    set_biomes_config(original);
+
+    assert!(dead_lakes(&closed[0].terrain, width, height).is_empty());
+    assert_eq!(opened[0].terrain, closed[0].terrain);
+    assert_eq!(opened[0].hills, closed[0].hills);
+
+    assert_eq!(dead_lakes(&closed[1].terrain, width, height).len(), 1);
+    assert!(dead_lakes(&opened[1].terrain, width, height).is_empty());
+    let changed: Vec<usize> = (0..width * height)
+        .filter(|&idx| opened[1].terrain[idx] != closed[1].terrain[idx])
+        .collect();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(closed[1].terrain[changed[0]], Terrain::Mountain);
+    assert!(opened[1].hills[changed[0]]);
+}
@@ -0,0 +1,91 @@
+use civorum_mapgen::{
+    map_components::{
+        decorations::{DecorKind, DecorRules, decoration_points},
+        hex_coords::HexCoord,
+        terrain::{Feature, Terrain},
+        tile::Tile,
+    },
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+
+fn tile(hex: HexCoord, terrain: Terrain, feature: Option<Feature>) -> Tile {
+    let mut tile = Tile::new(hex, terrain, false);
+    tile.set_feature(feature);
+    tile
+}
+
+/// Whether a point lies strictly inside a convex polygon with counterclockwise corners
+fn inside_polygon(corners: &[(f32, f32); 6], (x, z): (f32, f32)) -> bool {
+    (0..6).all(|i| {
+        let (ax, az) = corners[i];
+        let (bx, bz) = corners[(i + 1) % 6];
+        (bx - ax) * (z - az) - (bz - az) * (x - ax) > 0.0
+    })
+}
+
+#[test]
+fn points_are_deterministic_per_seed_and_hex() {
+    let rules = DecorRules::default();
+    let hex = HexCoord::new(7, 3);
+    let woods = tile(hex, Terrain::Plains, Some(Feature::Woods));
+
+    let first = decoration_points(hex, &woods, 42, &rules);
+    assert!(!first.is_empty());
+    assert_eq!(decoration_points(hex, &woods, 42, &rules), first);
+    assert_ne!(decoration_points(hex, &woods, 43, &rules), first);
+
+    let other = HexCoord::new(8, 3);
+    assert_ne!(decoration_points(other, &tile(other, Terrain::Plains, Some(Feature::Woods)), 42, &rules), first);
+}
+
+#[test]
+fn points_lie_inside_the_tile_polygon() {
+    let rules = DecorRules::default();
+    let map = Map::new(Some(5), MapSizes::Duel);
+    let mut decorated = 0;
+    for tile in map.tiles() {
+        let hex = tile.hex_coords();
+        let (cx, cz) = hex.world_pos();
+        let corners = map.tile_polygon(hex);
+        for point in decoration_points(hex, tile, 5, &rules) {
+            let world = (cx + point.offset.0, cz + point.offset.1);
+            assert!(inside_polygon(&corners, world), "{hex:?}: {point:?} outside the hex");
+            assert!((0.0..std::f32::consts::TAU).contains(&point.rotation));
+            decorated += 1;
+        }
+    }
+    assert!(decorated > 0);
+}
+
+#[test]
+fn counts_follow_the_rules_per_terrain() {
+    let rules = DecorRules::default();
+    let cases = [
+        (Terrain::Plains, Some(Feature::Woods), DecorKind::Tree, 5..=12),
+        (Terrain::Grassland, Some(Feature::Rainforest), DecorKind::Tree, 8..=12),
+        (Terrain::Tundra, None, DecorKind::Rock, 1..=3),
+        (Terrain::Grassland, Some(Feature::Marsh), DecorKind::Reed, 3..=6),
+        (Terrain::Desert, None, DecorKind::Rock, 0..=2),
+    ];
+    for (terrain, feature, kind, range) in cases {
+        for x in 0..20 {
+            let hex = HexCoord::new(x, 2);
+            let points = decoration_points(hex, &tile(hex, terrain, feature), 9, &rules);
+            let count = points.iter().filter(|point| point.kind == kind).count();
+            assert!(range.contains(&count), "{terrain:?} {feature:?}: {count} {kind:?}");
+        }
+    }
+}
+
+#[test]
+fn water_and_mountains_are_bare() {
+    let rules = DecorRules::default();
+    for terrain in [Terrain::Ocean, Terrain::CoastLake, Terrain::Mountain] {
+        for x in 0..10 {
+            let hex = HexCoord::new(x, 4);
+            assert!(decoration_points(hex, &tile(hex, terrain, None), 1, &rules).is_empty(), "{terrain:?}");
+        }
+    }
+    let hex = HexCoord::new(0, 0);
+    assert!(decoration_points(hex, &tile(hex, Terrain::Ocean, Some(Feature::Reef)), 1, &rules).is_empty());
+}
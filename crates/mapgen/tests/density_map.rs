@@ -0,0 +1,46 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain, tile::Tile},
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+
+fn is_mountain(tile: &Tile) -> bool {
+    tile.terrain() == Terrain::Mountain
+}
+
+#[test]
+fn density_matches_a_search_around_every_tile() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let (width, _) = map.size().dimensions();
+    for radius in [0, 1, 2, 5] {
+        let density = map.density_map(is_mountain, radius);
+        assert_eq!(density.len(), map.tiles().len());
+        for (tile, value) in map.tiles().iter().zip(&density) {
+            let in_range: Vec<_> = map.spiral_iterator(tile.hex_coords(), radius as u32).collect();
+            let matching = in_range
+                .iter()
+                .filter(|hex| is_mountain(&map.tiles()[hex.y() as usize * width + hex.x() as usize]))
+                .count();
+            let expected = matching as f32 / in_range.len() as f32;
+            assert!((value - expected).abs() < 1e-6, "{:?} radius {radius}", tile.hex_coords());
+        }
+    }
+
+    let water = map.density_map(|tile| tile.terrain().is_water(), 0);
+    for (tile, value) in map.tiles().iter().zip(water) {
+        assert_eq!(value, if tile.terrain().is_water() { 1.0 } else { 0.0 });
+    }
+}
+
+#[test]
+fn is_dense_agrees_with_the_density_map() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let radius = 2;
+    let density = map.density_map(|tile| !tile.terrain().is_water(), radius);
+    for threshold in [0.0, 0.3, 0.5, 1.0] {
+        for (tile, value) in map.tiles().iter().zip(&density) {
+            let dense = map.is_dense(tile.hex_coords(), |tile| !tile.terrain().is_water(), radius, threshold);
+            assert_eq!(dense, *value >= threshold - 1e-6, "{:?} threshold {threshold}", tile.hex_coords());
+        }
+    }
+    assert!(!map.is_dense(HexCoord::new(-1, 0), |_| true, radius, 0.0));
+}
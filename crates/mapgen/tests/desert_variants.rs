@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use civorum_mapgen::{
+    map_components::{
+        hex_coords::HexCoord,
+        terrain::{DesertVariant, Feature, Terrain},
+    },
+    pipeline::{
+        biomes::generate_map_with_type,
+        features::place_oases,
+        helpers::{biomes_config, neighbors_odd_r},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+/// Connected groups of tiles matching `pred`
+fn groups(width: usize, height: usize, pred: impl Fn(usize) -> bool) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; width * height];
+    let mut groups = Vec::new();
+    for start in 0..width * height {
+        if seen[start] || !pred(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut group = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(idx) = queue.pop_front() {
+            group.push(idx);
+            for (nx, ny) in neighbors_odd_r(idx % width, idx / width, width, height) {
+                let nidx = ny * width + nx;
+                if !seen[nidx] && pred(nidx) {
+                    seen[nidx] = true;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+#[test]
+fn only_deserts_have_a_variant() {
+    let size = MapSizes::Small;
+    for seed in [1u64, 4, 9] {
+        let layers = generate_map_with_type(&seed, &size, MapTypes::Continents);
+        for (terrain, variant) in layers.terrain.iter().zip(&layers.desert_variants) {
+            assert_eq!(*terrain == Terrain::Desert, variant.is_some(), "{terrain:?} has variant {variant:?}");
+        }
+    }
+}
+
+#[test]
+fn oases_respect_variant_region_and_spacing() {
+    let config = &biomes_config().desert;
+    let size = MapSizes::Small;
+    let (width, height) = size.dimensions();
+    let coords = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+
+    let mut total = 0;
+    for seed in [1u64, 3, 7, 12, 21] {
+        let layers = generate_map_with_type(&seed, &size, MapTypes::Continents);
+        let features = place_oases(seed, &layers.terrain, &layers.hills, &layers.desert_variants, &size, config);
+
+        let deserts = groups(width, height, |idx| layers.terrain[idx] == Terrain::Desert);
+        let region_size = |idx: usize| deserts.iter().find(|group| group.contains(&idx)).map_or(0, Vec::len);
+        for (idx, feature) in features.iter().enumerate() {
+            if feature.is_some() {
+                assert_eq!(*feature, Some(Feature::Oasis));
+                assert_eq!(layers.desert_variants[idx], Some(DesertVariant::SandDunes));
+                assert!(!layers.hills[idx]);
+                assert!(region_size(idx) >= config.oasis_min_region_size);
+            }
+        }
+
+        let clusters = groups(width, height, |idx| features[idx].is_some());
+        total += clusters.len();
+        for (i, cluster) in clusters.iter().enumerate() {
+            assert!((1..=3).contains(&cluster.len()), "cluster of {} oases", cluster.len());
+            for other in &clusters[i + 1..] {
+                let distance = cluster
+                    .iter()
+                    .flat_map(|a| other.iter().map(move |b| coords(*a).distance(&coords(*b))))
+                    .min()
+                    .unwrap();
+                assert!(distance >= config.oasis_min_distance, "oasis clusters {distance} apart");
+            }
+        }
+    }
+    assert!(total > 0, "no oases placed on any seed");
+}
+
+#[test]
+fn variants_and_oases_are_deterministic_per_seed() {
+    let tiles = |seed| {
+        let map = Map::new(Some(seed), MapSizes::Small);
+        let features: Vec<_> = map.tiles().iter().map(|tile| tile.feature()).collect();
+        (map.desert_variants(), features)
+    };
+    assert_eq!(tiles(6), tiles(6));
+}
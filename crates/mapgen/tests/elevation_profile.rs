@@ -0,0 +1,30 @@
+use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes};
+
+#[test]
+fn profile_covers_one_row_west_to_east() {
+    let map = Map::new(Some(11), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+
+    for row in [0, height as u32 / 2, height as u32 - 1] {
+        let profile = map.generate_elevation_profile(row);
+        assert_eq!(profile.len(), width);
+        for (x, (hex, elevation)) in profile.iter().enumerate() {
+            assert_eq!((hex.x(), hex.y()), (x as i32, row as i32));
+            let tile = &map.tiles()[row as usize * width + x];
+            assert_eq!(*elevation, f32::from(tile.elevation()));
+        }
+        assert_eq!(map.generate_elevation_profile_axial(row as i32), profile);
+    }
+
+    assert!(map.generate_elevation_profile(height as u32).is_empty());
+    assert!(map.generate_elevation_profile_axial(-1).is_empty());
+}
+
+#[test]
+fn axial_profile_is_sorted_by_q() {
+    let map = Map::new(Some(11), MapSizes::Duel);
+    let profile = map.generate_elevation_profile_axial(3);
+    let qs: Vec<i32> = profile.iter().map(|(hex, _)| hex.to_cube().0).collect();
+    assert!(qs.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    assert!(profile.iter().all(|(hex, _)| hex.to_cube().1 == 3));
+}
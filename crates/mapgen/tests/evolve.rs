@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+
+use civorum_mapgen::pipeline::{
+    evolve::{EvolveParam, Perturbation, apply_perturbations, perturbation_schedule},
+    helpers::{default_biomes_config, default_landmasses_config},
+    map_types::MapTypes,
+};
+
+#[test]
+fn schedule_starts_with_the_base_map() {
+    assert!(perturbation_schedule(0).is_empty());
+
+    // the first cells step every parameter once
+    let first: HashSet<EvolveParam> = (1..=EvolveParam::ALL.len())
+        .map(|cell| perturbation_schedule(cell)[0].param)
+        .collect();
+    assert_eq!(first.len(), EvolveParam::ALL.len());
+
+    for cell in 1..200 {
+        let schedule = perturbation_schedule(cell);
+        assert_eq!(schedule, perturbation_schedule(cell));
+        assert!((1..=2).contains(&schedule.len()), "cell {cell}: {schedule:?}");
+        assert_eq!(schedule.iter().map(|p| p.param).collect::<HashSet<_>>().len(), schedule.len());
+        assert!(schedule.iter().all(|p| p.delta.abs() == p.param.step()));
+    }
+}
+
+#[test]
+fn cells_differ_in_exactly_the_labeled_parameters() {
+    for map_type in MapTypes::ALL {
+        for cell in 0..64 {
+            let (mut landmasses, mut biomes) = (default_landmasses_config(), default_biomes_config());
+            let applied = apply_perturbations(&perturbation_schedule(cell), map_type, &mut landmasses, &mut biomes);
+            assert_eq!(applied.len(), perturbation_schedule(cell).len());
+
+            let (base_landmasses, base_biomes) = (default_landmasses_config(), default_biomes_config());
+            for param in EvolveParam::ALL {
+                let before = param.values(map_type, &base_landmasses, &base_biomes);
+                let after = param.values(map_type, &landmasses, &biomes);
+                match applied.iter().find(|p| p.param == param) {
+                    Some(perturbation) => {
+                        for (old, new) in before.iter().zip(&after) {
+                            assert!((new - old - perturbation.delta).abs() < 1e-4, "{map_type} cell {cell} {perturbation}");
+                        }
+                    }
+                    None => assert_eq!(before, after, "{map_type} cell {cell} {}", param.name()),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn steps_out_of_range_go_the_other_way() {
+    let (mut landmasses, mut biomes) = (default_landmasses_config(), default_biomes_config());
+    landmasses.continents.draft.center_bias = 0.0;
+    let down = Perturbation {
+        param: EvolveParam::CenterBias,
+        delta: -0.1,
+    };
+    let applied = apply_perturbations(&[down], MapTypes::Continents, &mut landmasses, &mut biomes);
+    assert_eq!(applied[0].delta, 0.1);
+    assert_eq!(landmasses.continents.draft.center_bias, 0.1);
+    assert_eq!(applied[0].to_string(), "center_bias +0.1");
+}
@@ -0,0 +1,100 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{coast_distance, label_landmasses, lake_sizes, ocean_connected_water},
+        biomes::try_generate_map_with_type,
+        error::GenError,
+        helpers::{ConstraintsConfig, landmasses_config, with_landmasses_config},
+        land::{enforce_horizontal_mirror, enforce_vertical_mirror, try_generate_landmasses, vary_coast_ruggedness},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+#[test]
+fn tiny_grids_do_not_panic() {
+    for (width, height) in [(4, 4), (1, 1), (1, 4), (4, 1)] {
+        for land in [0u8, 1] {
+            let mut grid = vec![land; width * height];
+            vary_coast_ruggedness(&mut grid, width, height, 7, 1.0);
+            enforce_vertical_mirror(&mut grid, width, height);
+            enforce_horizontal_mirror(&mut grid, width, height);
+
+            let terrain: Vec<Terrain> = grid
+                .iter()
+                .map(|&cell| if cell == 1 { Terrain::Grassland } else { Terrain::Ocean })
+                .collect();
+            let (ids, sizes) = label_landmasses(&terrain, width, height);
+            assert_eq!(ids.len(), width * height);
+            assert_eq!(sizes.iter().sum::<usize>(), grid.iter().filter(|&&cell| cell == 1).count());
+            lake_sizes(&terrain, width, height);
+            coast_distance(&terrain, width, height);
+        }
+    }
+}
+
+#[test]
+fn all_land_has_no_water_to_analyze() {
+    let (width, height) = (4, 4);
+    let terrain = vec![Terrain::Plains; width * height];
+
+    assert!(ocean_connected_water(&terrain, width, height).iter().all(|ocean| !ocean));
+    assert!(lake_sizes(&terrain, width, height).is_empty());
+    assert!(coast_distance(&terrain, width, height).iter().all(|&d| d == u16::MAX));
+    assert_eq!(label_landmasses(&terrain, width, height).1, vec![width * height]);
+}
+
+#[test]
+fn min_above_max_constraints_are_errors() {
+    let extra = ConstraintsConfig {
+        min_land_ratio: 0.7,
+        max_land_ratio: 0.6,
+        ..Default::default()
+    };
+    let result = Map::try_generate_with_constraints(MapSizes::Duel, Some(1), MapTypes::Pangea, &extra);
+    let Err(err) = result else {
+        panic!("constraints with min_land_ratio above max_land_ratio were accepted");
+    };
+    assert!(matches!(
+        err,
+        GenError::InvalidConstraints { map_type: MapTypes::Pangea, field: "min_land_ratio", .. }
+    ));
+
+    let mut constraints = landmasses_config().constraints_for(MapTypes::Continents).clone();
+    constraints.min_components = constraints.max_components + 1;
+    let config = landmasses_config().as_ref().clone().with_constraint_override(MapTypes::Continents, constraints);
+    with_landmasses_config(config, || {
+        let result = try_generate_landmasses(1, &MapSizes::Duel, MapTypes::Continents);
+        assert!(matches!(result, Err(GenError::InvalidConstraints { field: "min_components", .. })));
+        // the other map types are still valid
+        assert!(try_generate_landmasses(1, &MapSizes::Duel, MapTypes::Pangea).is_ok());
+    });
+}
+
+#[test]
+fn empty_terra_barrier_range_is_an_error() {
+    let mut config = landmasses_config().as_ref().clone();
+    config.terra.barrier_min = config.terra.barrier_max + 1;
+    let (min, max) = (config.terra.barrier_min, config.terra.barrier_max);
+    with_landmasses_config(config, || {
+        let result = try_generate_map_with_type(&1, &MapSizes::Duel, MapTypes::Terra);
+        assert_eq!(result.err(), Some(GenError::InvalidTerraBarrier { min, max }));
+    });
+}
+
+#[test]
+fn preset_sizes_generate_without_errors() {
+    for size in [MapSizes::Duel, MapSizes::Tiny] {
+        for map_type in [
+            MapTypes::Continents,
+            MapTypes::SmallContinents,
+            MapTypes::IslandsContinents,
+            MapTypes::Pangea,
+            MapTypes::Mirror,
+            MapTypes::Terra,
+        ] {
+            assert!(try_generate_landmasses(3, &size, map_type).is_ok(), "{size:?} {map_type:?}");
+        }
+    }
+}
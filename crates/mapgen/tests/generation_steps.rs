@@ -0,0 +1,32 @@
+use civorum_mapgen::pipeline::{
+    land::{GenerationStepRecorder, generate_landmasses},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+#[test]
+fn recorder_captures_every_repair_pass() {
+    let ((grid, iterations), steps) =
+        GenerationStepRecorder::record(|| generate_landmasses(3, &MapSizes::Duel, MapTypes::Continents));
+
+    let labels: Vec<&str> = steps.iter().map(|step| step.label.as_str()).collect();
+    assert_eq!(labels.len(), iterations.get() as usize + 2);
+    assert_eq!(labels[0], "initial");
+    assert_eq!(labels[labels.len() - 1], "final");
+    for (pass, label) in labels[1..labels.len() - 1].iter().enumerate() {
+        assert_eq!(*label, format!("repair{pass}"));
+    }
+    assert_eq!(steps[steps.len() - 1].landmask, grid);
+    assert!(steps.iter().all(|step| step.landmask.len() == MapSizes::Duel.grid_size()));
+}
+
+#[test]
+fn nothing_is_recorded_outside_of_the_recorder() {
+    let (recorded, _) =
+        GenerationStepRecorder::record(|| generate_landmasses(3, &MapSizes::Duel, MapTypes::Pangea));
+    let unrecorded = generate_landmasses(3, &MapSizes::Duel, MapTypes::Pangea);
+    assert_eq!(recorded, unrecorded);
+
+    let (_, steps) = GenerationStepRecorder::record(|| ());
+    assert!(steps.is_empty());
+}
@@ -0,0 +1,59 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        biomes::generate_map_with_type, helpers::neighbors_odd_r, map_sizes::MapSizes, map_types::MapTypes,
+    },
+};
+
+#[test]
+fn ridges_are_younger_than_the_interior() {
+    let size = MapSizes::Small;
+    let (width, height) = size.dimensions();
+
+    for seed in [2u64, 5, 11] {
+        let layers = generate_map_with_type(&seed, &size, MapTypes::Continents);
+        let terrain = &layers.terrain;
+        let age = &layers.geological_age;
+        assert_eq!(age.len(), size.grid_size());
+
+        let mut near_ridge = Vec::new();
+        let mut interior = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                if terrain[idx].is_water() {
+                    assert_eq!(age[idx], 0, "water at ({x}, {y}) has an age");
+                    continue;
+                }
+                if terrain[idx] == Terrain::Mountain {
+                    continue;
+                }
+
+                // flat land whose 2-ring has neither mountains nor water
+                let ring: Vec<(usize, usize)> = neighbors_odd_r(x, y, width, height)
+                    .into_iter()
+                    .flat_map(|(nx, ny)| neighbors_odd_r(nx, ny, width, height))
+                    .collect();
+                let touches = |target: fn(&Terrain) -> bool| ring.iter().any(|(nx, ny)| target(&terrain[ny * width + nx]));
+                let next_to_ridge = neighbors_odd_r(x, y, width, height)
+                    .into_iter()
+                    .any(|(nx, ny)| terrain[ny * width + nx] == Terrain::Mountain);
+
+                if next_to_ridge {
+                    near_ridge.push(age[idx] as f32);
+                } else if !touches(|t| *t == Terrain::Mountain) && !touches(Terrain::is_water) && !layers.hills[idx] {
+                    interior.push(age[idx] as f32);
+                }
+            }
+        }
+
+        let mean = |values: &[f32]| values.iter().sum::<f32>() / values.len() as f32;
+        assert!(!near_ridge.is_empty() && !interior.is_empty());
+        assert!(
+            mean(&near_ridge) < mean(&interior),
+            "seed {seed}: ridge mean {} >= interior mean {}",
+            mean(&near_ridge),
+            mean(&interior)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use civorum_mapgen::{
+    map_components::hex_coords::HexCoord,
+    pipeline::{
+        helpers::{adjacency_pairs, neighbors_odd_r},
+        map_sizes::MapSizes,
+    },
+};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+const SIZES: [MapSizes; 6] = [
+    MapSizes::Duel,
+    MapSizes::Tiny,
+    MapSizes::Small,
+    MapSizes::Standard,
+    MapSizes::Large,
+    MapSizes::Huge,
+];
+
+fn is_border(x: usize, y: usize, width: usize, height: usize) -> bool {
+    x == 0 || y == 0 || x + 1 == width || y + 1 == height
+}
+
+/// Check all index and neighbor invariants on one grid
+fn check_grid(width: usize, height: usize) {
+    for y in 0..height {
+        for x in 0..width {
+            let coord = HexCoord::new(x as i32, y as i32);
+            let neighbors = neighbors_odd_r(x, y, width, height);
+
+            // index and cube round trips
+            let idx = y * width + x;
+            assert_eq!((idx % width, idx / width), (x, y));
+            let (q, r, s) = coord.to_cube();
+            assert_eq!(q + r + s, 0);
+            assert_eq!(HexCoord::from_cube(q, r), coord);
+
+            // 6 neighbors inside, 2-5 on the border (corners without a diagonal only have 2)
+            if is_border(x, y, width, height) {
+                assert!(
+                    (2..=5).contains(&neighbors.len()),
+                    "({x}, {y}) has {} neighbors",
+                    neighbors.len()
+                );
+            } else {
+                assert_eq!(neighbors.len(), 6, "({x}, {y})");
+            }
+
+            for &(nx, ny) in &neighbors {
+                assert!(
+                    neighbors_odd_r(nx, ny, width, height).contains(&(x, y)),
+                    "({nx}, {ny}) does not list ({x}, {y}) as neighbor"
+                );
+                assert_eq!(coord.distance(&HexCoord::new(nx as i32, ny as i32)), 1);
+            }
+
+            // HexCoord::neighbors agrees with neighbors_odd_r inside the grid, in the same order
+            let in_bounds: Vec<(usize, usize)> = coord
+                .neighbors()
+                .into_iter()
+                .filter(|n| n.x() >= 0 && n.y() >= 0 && (n.x() as usize) < width && (n.y() as usize) < height)
+                .map(|n| (n.x() as usize, n.y() as usize))
+                .collect();
+            assert_eq!(in_bounds, neighbors);
+        }
+    }
+
+    // every adjacency is listed once: (w - 1) edges per row, 2w - 1 between consecutive rows
+    let pairs: Vec<(usize, usize)> = adjacency_pairs(width, height).collect();
+    assert_eq!(pairs.len(), height * (width - 1) + (height - 1) * (2 * width - 1));
+    let unique: HashSet<(usize, usize)> = pairs.iter().copied().collect();
+    assert_eq!(unique.len(), pairs.len());
+    for &(a, b) in &pairs {
+        assert!(a < b);
+        let (ax, ay, bx, by) = (a % width, a / width, b % width, b / width);
+        assert!(neighbors_odd_r(ax, ay, width, height).contains(&(bx, by)));
+    }
+}
+
+#[test]
+fn neighbor_invariants_hold_for_all_map_sizes() {
+    for size in SIZES {
+        let (width, height) = size.dimensions();
+        assert_eq!(width * height, size.grid_size());
+        check_grid(width, height);
+    }
+}
+
+proptest! {
+    #[test]
+    fn neighbor_invariants_hold_for_custom_sizes(width in 2usize..40, height in 2usize..40) {
+        check_grid(width, height);
+    }
+
+    #[test]
+    fn cube_round_trip(x in -500i32..500, y in -500i32..500) {
+        let coord = HexCoord::new(x, y);
+        let (q, r, _) = coord.to_cube();
+        prop_assert_eq!(HexCoord::from_cube(q, r), coord);
+    }
+
+    #[test]
+    fn world_pos_round_trip(
+        x in -500i32..500,
+        y in -500i32..500,
+        angle in 0f32..std::f32::consts::TAU,
+        radius in 0f32..0.45,
+    ) {
+        // anything closer to the center than the inner radius (0.5) belongs to the hex
+        let coord = HexCoord::new(x, y);
+        let (wx, wz) = coord.world_pos();
+        prop_assert_eq!(HexCoord::from_world_pos(wx, wz), coord);
+        prop_assert_eq!(HexCoord::from_world_pos(wx + radius * angle.cos(), wz + radius * angle.sin()), coord);
+    }
+
+    #[test]
+    fn distance_is_a_metric(
+        a in (-50i32..50, -50i32..50),
+        b in (-50i32..50, -50i32..50),
+        c in (-50i32..50, -50i32..50),
+    ) {
+        let (a, b, c) = (HexCoord::new(a.0, a.1), HexCoord::new(b.0, b.1), HexCoord::new(c.0, c.1));
+        prop_assert_eq!(a.distance(&b), b.distance(&a));
+        prop_assert_eq!(a.distance(&b) == 0, a == b);
+        prop_assert!(a.distance(&c) <= a.distance(&b) + b.distance(&c));
+    }
+}
@@ -0,0 +1,45 @@
+use civorum_mapgen::map_components::hex_coords::{HEX_CIRCUMRADIUS, HexCoord, hex_ring_mesh};
+
+#[test]
+fn ring_has_twelve_vertices_and_twelve_triangles() {
+    let mesh = hex_ring_mesh(HEX_CIRCUMRADIUS * 0.9, HEX_CIRCUMRADIUS * 1.05);
+    assert_eq!(mesh.positions.len(), 12);
+    assert_eq!(mesh.indices.len(), 36);
+    assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.positions.len()));
+    assert!(mesh.positions.iter().all(|p| p[1] == 0.0));
+}
+
+#[test]
+fn vertices_lie_on_the_given_radii() {
+    let (inner, outer) = (0.4, 0.7);
+    let mesh = hex_ring_mesh(inner, outer);
+    for (i, p) in mesh.positions.iter().enumerate() {
+        let radius = (p[0] * p[0] + p[2] * p[2]).sqrt();
+        let expected = if i < 6 { inner } else { outer };
+        assert!((radius - expected).abs() < 1e-5, "vertex {i} at radius {radius}");
+    }
+}
+
+#[test]
+fn triangles_face_up() {
+    let mesh = hex_ring_mesh(0.4, 0.7);
+    for tri in mesh.indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+        let (ab, ac) = ([b[0] - a[0], b[2] - a[2]], [c[0] - a[0], c[2] - a[2]]);
+        // y component of (b - a) x (c - a)
+        let normal_y = ab[1] * ac[0] - ab[0] * ac[1];
+        assert!(normal_y > 0.0, "triangle {tri:?} faces down");
+    }
+}
+
+#[test]
+fn outer_corners_touch_the_neighbor_tiles() {
+    // corners of the tile outline are shared with its neighbors
+    let mesh = hex_ring_mesh(0.0, HEX_CIRCUMRADIUS);
+    let (cx, cz) = HexCoord::new(4, 4).world_pos();
+    let (nx, nz) = HexCoord::new(5, 4).world_pos();
+    let corner = mesh.positions[6];
+    let to_center = ((corner[0]).powi(2) + (corner[2]).powi(2)).sqrt();
+    let to_neighbor = ((corner[0] + cx - nx).powi(2) + (corner[2] + cz - nz).powi(2)).sqrt();
+    assert!((to_center - to_neighbor).abs() < 1e-5);
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use civorum_mapgen::{
+    map_components::hex_coords::HexCoord,
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+
+/// Hexes on the map at exactly `radius` (or within it) from `center`, by brute force
+fn hexes_at(map: &Map, center: HexCoord, radius: u32, within: bool) -> HashSet<HexCoord> {
+    let (width, height) = map.size().dimensions();
+    (0..height as i32)
+        .flat_map(|y| (0..width as i32).map(move |x| HexCoord::new(x, y)))
+        .filter(|hex| {
+            let d = center.distance(hex);
+            if within { d <= radius } else { d == radius }
+        })
+        .collect()
+}
+
+#[test]
+fn rings_match_brute_force_and_clip_to_the_map() {
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+    let centers = [
+        HexCoord::new(0, 0),
+        HexCoord::new(width as i32 / 2, height as i32 / 2),
+        HexCoord::new(width as i32 / 2 + 1, height as i32 / 2 + 1),
+        HexCoord::new(width as i32 - 1, height as i32 - 1),
+    ];
+    for center in centers {
+        for radius in 0..6 {
+            let ring: Vec<HexCoord> = map.ring_iterator(center, radius).collect();
+            let unique: HashSet<HexCoord> = ring.iter().copied().collect();
+            assert_eq!(unique.len(), ring.len(), "ring {radius} around {center:?} repeats a hex");
+            assert_eq!(unique, hexes_at(&map, center, radius, false), "ring {radius} around {center:?}");
+        }
+    }
+}
+
+#[test]
+fn full_rings_start_east_and_run_counterclockwise() {
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let center = HexCoord::new(10, 6);
+    let ring: Vec<HexCoord> = map.ring_iterator(center, 2).collect();
+    assert_eq!(ring.len(), 12);
+    assert_eq!(ring[0], HexCoord::new(12, 6));
+    // consecutive hexes are neighbors, the last one closes the ring
+    for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+        assert_eq!(a.distance(b), 1);
+    }
+    // the side after the east corner goes up (north-west)
+    assert!(ring[1].y() < center.y());
+}
+
+#[test]
+fn spiral_walks_rings_outward() {
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let center = HexCoord::new(1, 2);
+    let spiral: Vec<HexCoord> = map.spiral_iterator(center, 4).collect();
+    assert_eq!(spiral[0], center);
+    assert!(spiral.windows(2).all(|pair| center.distance(&pair[0]) <= center.distance(&pair[1])));
+    assert_eq!(spiral.iter().copied().collect::<HashSet<_>>(), hexes_at(&map, center, 4, true));
+    assert_eq!(spiral.len(), hexes_at(&map, center, 4, true).len());
+}
@@ -0,0 +1,68 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{interest_point, label_landmasses},
+        map::Map,
+        map_sizes::MapSizes,
+        text_map::GeneratedMapData,
+    },
+};
+
+const WIDTH: usize = 40;
+const HEIGHT: usize = 24;
+
+/// Ocean with a grassland continent in the north-east corner (x 26-37, y 2-9) and a small island in the south-west
+fn corner_continent(mixed_west: bool) -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(WIDTH, HEIGHT, Terrain::Ocean);
+    for y in 2..10 {
+        for x in 26..38 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = if mixed_west && x < 30 {
+                [Terrain::Desert, Terrain::Tundra, Terrain::Plains, Terrain::Snow][(x + y) % 4]
+            } else {
+                Terrain::Grassland
+            };
+        }
+    }
+    let idx = data.index(4, 20);
+    data.terrain[idx] = Terrain::Plains;
+    data
+}
+
+#[test]
+fn interest_point_is_on_the_continent_not_the_map_center() {
+    let data = corner_continent(false);
+    let (ids, _) = label_landmasses(&data.terrain, WIDTH, HEIGHT);
+    let point = interest_point(&data.terrain, &ids, WIDTH, HEIGHT).unwrap();
+    let (x, y) = (point.tile % WIDTH, point.tile / WIDTH);
+    assert_eq!(ids[point.tile], Some(0));
+    assert!((30..34).contains(&x) && (4..8).contains(&y), "({x}, {y})");
+    // frames the 12x8 continent, not the 40x24 map
+    assert!(point.radius >= 6.0 && point.radius < 10.0, "{}", point.radius);
+}
+
+#[test]
+fn interest_point_leans_towards_diverse_terrain() {
+    let plain = corner_continent(false);
+    let mixed = corner_continent(true);
+    let (ids, _) = label_landmasses(&mixed.terrain, WIDTH, HEIGHT);
+    let plain_point = interest_point(&plain.terrain, &ids, WIDTH, HEIGHT).unwrap();
+    let mixed_point = interest_point(&mixed.terrain, &ids, WIDTH, HEIGHT).unwrap();
+    assert!(mixed_point.tile % WIDTH < plain_point.tile % WIDTH);
+    assert_eq!(ids[mixed_point.tile], Some(0));
+}
+
+#[test]
+fn maps_without_land_have_no_interest_point() {
+    let data = GeneratedMapData::filled(WIDTH, HEIGHT, Terrain::Ocean);
+    let (ids, _) = label_landmasses(&data.terrain, WIDTH, HEIGHT);
+    assert_eq!(interest_point(&data.terrain, &ids, WIDTH, HEIGHT), None);
+}
+
+#[test]
+fn generated_maps_look_at_their_largest_landmass() {
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let point = map.interest_point().unwrap();
+    assert_eq!(map.tiles()[point.tile].landmass_id(), Some(0));
+    assert_eq!(map.interest_point(), Some(point));
+}
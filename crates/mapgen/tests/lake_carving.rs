@@ -0,0 +1,98 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::lake_sizes,
+        helpers::{ConstraintsConfig, RepairConfig, default_landmasses_config},
+        land::carve_missing_lakes,
+    },
+};
+
+const WIDTH: usize = 40;
+const HEIGHT: usize = 24;
+
+/// One landmass with two water tiles of ocean on every side
+fn block() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|idx| {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+            u8::from((2..WIDTH - 2).contains(&x) && (2..HEIGHT - 2).contains(&y))
+        })
+        .collect()
+}
+
+/// Sizes of the enclosed water bodies of a landmask
+fn lakes(grid: &[u8]) -> Vec<usize> {
+    let terrain: Vec<Terrain> = grid.iter().map(|&v| if v == 1 { Terrain::Plains } else { Terrain::Ocean }).collect();
+    lake_sizes(&terrain, WIDTH, HEIGHT)
+}
+
+fn water(grid: &[u8]) -> usize {
+    grid.iter().filter(|&&v| v == 0).count()
+}
+
+fn configs(min_lakes: usize) -> (ConstraintsConfig, RepairConfig) {
+    let config = default_landmasses_config();
+    let constraints = ConstraintsConfig {
+        min_lakes,
+        ..config.continents.constraints
+    };
+    let repair = RepairConfig {
+        lake_blob_min: config.global.min_lake_size,
+        lake_blob_max: 7,
+        ..config.continents.repair
+    };
+    (constraints, repair)
+}
+
+fn carve(grid: &mut [u8], seed: u64, min_lakes: usize) {
+    let (constraints, repair) = configs(min_lakes);
+    carve_missing_lakes(grid, WIDTH, HEIGHT, seed, &default_landmasses_config().global, &constraints, &repair);
+}
+
+#[test]
+fn carved_water_stays_within_the_lake_budget() {
+    for seed in 0..8 {
+        for min_lakes in 1..=3 {
+            let mut grid = block();
+            carve(&mut grid, seed, min_lakes);
+
+            // blobs next to each other merge into one lake
+            let lakes = lakes(&grid);
+            assert!((1..=min_lakes).contains(&lakes.len()), "seed {seed}: {lakes:?}");
+            let carved = water(&grid) - water(&block());
+            assert_eq!(carved, lakes.iter().sum::<usize>(), "seed {seed}: all carved water is lake water");
+            assert!(carved <= min_lakes * 7, "seed {seed}: {carved} tiles for {min_lakes} lakes");
+        }
+    }
+}
+
+#[test]
+fn only_the_missing_lakes_are_carved() {
+    // a lake of the minimum size in the north-west of the landmass
+    let mut with_lake = block();
+    for idx in [6 * WIDTH + 6, 6 * WIDTH + 7, 7 * WIDTH + 6, 7 * WIDTH + 7] {
+        with_lake[idx] = 0;
+    }
+    assert_eq!(lakes(&with_lake), [4]);
+
+    // enough lakes already, nothing to carve
+    let mut grid = with_lake.clone();
+    carve(&mut grid, 3, 1);
+    assert_eq!(grid, with_lake);
+
+    // one more lake, at most one blob of water
+    carve(&mut grid, 3, 2);
+    assert_eq!(lakes(&grid).len(), 2);
+    assert!(water(&grid) - water(&with_lake) <= 7);
+
+    // a lake opened to the ocean no longer counts, the lake that replaces it is the only new water
+    let mut opened = with_lake.clone();
+    for x in 0..6 {
+        opened[6 * WIDTH + x] = 0;
+    }
+    assert!(lakes(&opened).is_empty());
+    let before = water(&opened);
+    carve(&mut opened, 3, 1);
+    assert_eq!(lakes(&opened).len(), 1);
+    assert!(water(&opened) - before <= 7);
+}
@@ -0,0 +1,96 @@
+use std::time::Instant;
+
+use civorum_mapgen::pipeline::{
+    helpers::{config_hash, landmasses_config, with_landmasses_config},
+    land::land_draft,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+/// FNV-1a hashes of Huge drafts, the seed 7 drafts were recorded before the draft upscale was reworked into row runs.
+/// Seed 0 leaves some Huge drafts without land, so the other seed is 3.
+const HUGE_DRAFTS: [(MapTypes, u64, u64); 12] = [
+    (MapTypes::Continents, 3, 0x19efa83b9550dba5),
+    (MapTypes::Continents, 7, 0x0bf938e469a517a7),
+    (MapTypes::SmallContinents, 3, 0xe8a1f9e558684482),
+    (MapTypes::SmallContinents, 7, 0xccd807b82f82f9f4),
+    (MapTypes::IslandsContinents, 3, 0x2aaf2fbd83416e84),
+    (MapTypes::IslandsContinents, 7, 0xb45223383befb68e),
+    (MapTypes::Pangea, 3, 0xef2bd9c838fc3cf8),
+    (MapTypes::Pangea, 7, 0x4010c91ad46f4256),
+    (MapTypes::Mirror, 3, 0x75806046a488072b),
+    (MapTypes::Mirror, 7, 0x2fbb560ae2486952),
+    (MapTypes::Terra, 3, 0x96dc7cc6e541bf64),
+    (MapTypes::Terra, 7, 0x8f61941ec0b1dd75),
+];
+
+/// `config_hash` of the shipped configs the Huge drafts were recorded with, a default change fails the goldens
+/// as loudly as a change of the algorithm
+const HUGE_DRAFTS_CONFIG: u64 = 0x62fa63d6c43a5871;
+
+/// Same as `HUGE_DRAFTS` for scale-invariant Standard drafts, whose upscale steps stretch the grid
+const SCALE_INVARIANT_STANDARD_DRAFTS: [(MapTypes, u64, u64); 12] = [
+    (MapTypes::Continents, 0, 0xe6d85972bdc8f6c9),
+    (MapTypes::Continents, 7, 0x6e1469378d3ebd3a),
+    (MapTypes::SmallContinents, 0, 0xa9859dd920043a50),
+    (MapTypes::SmallContinents, 7, 0xa41eae95f96f6690),
+    (MapTypes::IslandsContinents, 0, 0xe042333f49dc4abe),
+    (MapTypes::IslandsContinents, 7, 0x560f5363200a9acb),
+    (MapTypes::Pangea, 0, 0x3766f62f55a8575c),
+    (MapTypes::Pangea, 7, 0x9512e2e9019f1499),
+    (MapTypes::Mirror, 0, 0xa46893ec0673aa57),
+    (MapTypes::Mirror, 7, 0xda8fa3dcd6735d0f),
+    (MapTypes::Terra, 0, 0x03480331e5f09c75),
+    (MapTypes::Terra, 7, 0x8bc9358b04f95c35),
+];
+
+/// `config_hash` of the shipped configs with `scale_invariant` enabled
+const SCALE_INVARIANT_STANDARD_DRAFTS_CONFIG: u64 = 0x324c1508d8ed07a6;
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn assert_drafts(size: MapSizes, expected_config: u64, goldens: &[(MapTypes, u64, u64)]) {
+    assert_eq!(config_hash(), expected_config, "{size:?}: config changed, {:#018x}", config_hash());
+    for &(map_type, seed, expected) in goldens {
+        let draft = land_draft(seed, &size, map_type);
+        // a draft without land hashes the same for every map type and would not catch changes
+        assert!(draft.contains(&1), "{size:?} {map_type:?} seed {seed}: draft has no land");
+        let hash = fnv1a(&draft);
+        assert_eq!(hash, expected, "{size:?} {map_type:?} seed {seed}: draft changed");
+    }
+}
+
+#[test]
+fn huge_drafts_are_byte_identical() {
+    assert_drafts(MapSizes::Huge, HUGE_DRAFTS_CONFIG, &HUGE_DRAFTS);
+}
+
+#[test]
+fn scale_invariant_drafts_are_byte_identical() {
+    let config = landmasses_config().as_ref().clone().with_scale_invariant(true);
+    with_landmasses_config(config, || {
+        assert_drafts(MapSizes::Standard, SCALE_INVARIANT_STANDARD_DRAFTS_CONFIG, &SCALE_INVARIANT_STANDARD_DRAFTS)
+    });
+}
+
+/// Timing of the draft stage on Huge maps.
+/// Run with `cargo test --release -p civorum-mapgen --test land_draft -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark, run explicitly"]
+fn draft_stage_on_huge() {
+    const RUNS: u32 = 20;
+    for map_type in [MapTypes::Continents, MapTypes::Pangea, MapTypes::IslandsContinents] {
+        // warm up so lazily loaded configs are not counted
+        let _ = land_draft(0, &MapSizes::Huge, map_type);
+
+        let start = Instant::now();
+        for seed in 0..RUNS as u64 {
+            let _ = land_draft(seed, &MapSizes::Huge, map_type);
+        }
+        println!("{map_type:?}: {:?} per draft", start.elapsed() / RUNS);
+    }
+}
@@ -0,0 +1,78 @@
+use civorum_mapgen::pipeline::{
+    helpers::{EARTHLIKE_LAND_PROFILE, LandPreset, LandmassesConfig, with_landmasses_config},
+    map::Map,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+fn land_ratio(preset: LandPreset, seed: u64, map_type: MapTypes) -> f32 {
+    with_landmasses_config(LandmassesConfig::from_presets(preset), || {
+        Map::new_with_type(Some(seed), MapSizes::Small, map_type)
+            .statistics()
+            .land_ratio
+    })
+}
+
+#[test]
+fn preset_names_round_trip() {
+    for preset in LandPreset::ALL {
+        assert_eq!(LandPreset::from_name(preset.name()), Some(preset));
+        assert_eq!(
+            LandPreset::from_name(&preset.name().replace('-', "_").to_uppercase()),
+            Some(preset)
+        );
+    }
+    assert_eq!(
+        LandPreset::from_name("earthlike"),
+        Some(LandPreset::EarthLike)
+    );
+    assert_eq!(LandPreset::from_name("mars"), None);
+}
+
+#[test]
+fn presets_replace_the_land_ratio_of_every_map_type() {
+    for preset in LandPreset::ALL {
+        let config = LandmassesConfig::from_presets(preset);
+        let ratios: Vec<_> = MapTypes::ALL
+            .iter()
+            .map(|map_type| {
+                let constraints = config.constraints_for(*map_type);
+                (constraints.min_land_ratio, constraints.max_land_ratio)
+            })
+            .collect();
+        assert!(
+            ratios.windows(2).all(|pair| pair[0] == pair[1]),
+            "{preset:?}"
+        );
+        assert!(ratios[0].0 < ratios[0].1);
+        assert_eq!(
+            config.global.latitude_land_profile.is_empty(),
+            preset != LandPreset::EarthLike
+        );
+    }
+    assert_eq!(
+        LandmassesConfig::from_presets(LandPreset::EarthLike)
+            .global
+            .latitude_land_profile,
+        EARTHLIKE_LAND_PROFILE.to_vec()
+    );
+}
+
+#[test]
+fn presets_order_the_land_share() {
+    for map_type in [MapTypes::Continents, MapTypes::Pangea, MapTypes::Terra] {
+        for seed in 1..2 {
+            let ocean = land_ratio(LandPreset::OceanWorld, seed, map_type);
+            let earth = land_ratio(LandPreset::EarthLike, seed, map_type);
+            let land = land_ratio(LandPreset::LandWorld, seed, map_type);
+            assert!(
+                ocean < earth && earth < land,
+                "{map_type:?} seed {seed}: {ocean} {earth} {land}"
+            );
+            assert!(
+                ocean <= 0.25 && land >= 0.55,
+                "{map_type:?} seed {seed}: {ocean} {land}"
+            );
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain},
+    pipeline::{
+        analysis::{label_landmasses, landmass_stats},
+        map::{Map, SUMMARY_LANDMASSES},
+        map_builder::MapBuilder,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+/// Three landmasses, the largest around a one-tile lake. `.` is ocean, `~` the lake
+const SYNTHETIC: [&str; 6] = [
+    "........",
+    ".PPG....",
+    ".P~GP.S.",
+    ".PPPG.S.",
+    "........",
+    "..GG....",
+];
+
+fn synthetic() -> (Vec<Terrain>, usize, usize) {
+    let terrain = SYNTHETIC
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|c| match c {
+            '.' => Terrain::Ocean,
+            '~' => Terrain::CoastLake,
+            'P' => Terrain::Plains,
+            'G' => Terrain::Grassland,
+            'S' => Terrain::Snow,
+            _ => unreachable!(),
+        })
+        .collect();
+    (terrain, SYNTHETIC[0].len(), SYNTHETIC.len())
+}
+
+fn land_histogram(terrain: &[Terrain]) -> BTreeMap<Terrain, usize> {
+    let mut histogram = BTreeMap::new();
+    for &terrain in terrain.iter().filter(|terrain| !terrain.is_water()) {
+        *histogram.entry(terrain).or_insert(0) += 1;
+    }
+    histogram
+}
+
+fn summed_histograms<'a>(histograms: impl Iterator<Item = &'a BTreeMap<Terrain, usize>>) -> BTreeMap<Terrain, usize> {
+    let mut sum = BTreeMap::new();
+    for histogram in histograms {
+        for (&terrain, &count) in histogram {
+            *sum.entry(terrain).or_insert(0) += count;
+        }
+    }
+    sum
+}
+
+#[test]
+fn landmass_histograms_sum_to_the_map_histogram() {
+    for (seed, size, map_type) in [
+        (1, MapSizes::Duel, MapTypes::Continents),
+        (4, MapSizes::Small, MapTypes::IslandsContinents),
+        (7, MapSizes::Duel, MapTypes::Terra),
+    ] {
+        let map = Map::new_with_type(Some(seed), size, map_type);
+        let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+        let stats = map.landmass_stats();
+
+        assert!(stats.len() > 1, "{map_type:?} seed {seed}");
+        assert_eq!(summed_histograms(stats.iter().map(|info| &info.terrain_histogram)), land_histogram(&terrain));
+        let n_land = terrain.iter().filter(|terrain| !terrain.is_water()).count();
+        assert_eq!(stats.iter().map(|info| info.size).sum::<usize>(), n_land);
+    }
+}
+
+#[test]
+fn coastal_counts_match_a_recount() {
+    let (terrain, width, height) = synthetic();
+    let n_tiles = width * height;
+    let (ids, sizes) = label_landmasses(&terrain, width, height);
+    assert_eq!(sizes, [10, 2, 2]);
+    let (hills, climate) = (vec![false; n_tiles], vec![0; n_tiles]);
+    let stats = landmass_stats(&terrain, &hills, &climate, &climate, &ids, width, height);
+    assert_eq!(stats.len(), 3);
+    assert_eq!(summed_histograms(stats.iter().map(|info| &info.terrain_histogram)), land_histogram(&terrain));
+
+    // recount with the hex distance instead of the neighbor tables of the map
+    let coord = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+    let neighbors = |idx: usize| (0..n_tiles).filter(move |&other| coord(idx).distance(&coord(other)) == 1);
+    let mut ocean: Vec<bool> = (0..n_tiles)
+        .map(|idx| {
+            let (x, y) = (idx % width, idx / width);
+            terrain[idx].is_water() && (x == 0 || y == 0 || x + 1 == width || y + 1 == height)
+        })
+        .collect();
+    while let Some(idx) = (0..n_tiles).find(|&idx| {
+        !ocean[idx] && terrain[idx].is_water() && neighbors(idx).any(|other| ocean[other])
+    }) {
+        ocean[idx] = true;
+    }
+    assert_eq!(ocean.iter().filter(|&&ocean| !ocean).count(), 15, "14 land tiles and the lake");
+
+    for info in &stats {
+        let tiles: Vec<usize> = (0..n_tiles).filter(|&idx| ids[idx] == Some(info.id)).collect();
+        let water_neighbors = |idx: usize| neighbors(idx).filter(|&other| terrain[other].is_water());
+        let coastal = tiles.iter().filter(|&&idx| water_neighbors(idx).any(|other| ocean[other])).count();
+        let lake_adjacent = tiles.iter().filter(|&&idx| water_neighbors(idx).any(|other| !ocean[other])).count();
+        let coastline: usize = tiles.iter().map(|&idx| water_neighbors(idx).count()).sum();
+
+        assert_eq!(info.size, tiles.len());
+        assert_eq!(info.coastal_tiles, coastal, "landmass {}", info.id);
+        assert_eq!(info.lake_adjacent_tiles, lake_adjacent, "landmass {}", info.id);
+        assert_eq!(info.coastline_length, coastline, "landmass {}", info.id);
+    }
+    // six tiles ring the lake, every tile of the landmass but the one east of the lake touches the ocean
+    assert_eq!((stats[0].coastal_tiles, stats[0].lake_adjacent_tiles), (9, 6));
+}
+
+#[test]
+fn the_summary_json_has_the_largest_landmasses() {
+    // seven islands of 1 to 7 columns of three plains tiles, two columns of ocean apart
+    let (width, height) = MapSizes::Duel.dimensions();
+    let mut terrain = vec![Terrain::Ocean; width * height];
+    let mut x = 1;
+    for columns in 1..=7 {
+        for column in x..x + columns {
+            for y in 2..5 {
+                terrain[y * width + column] = Terrain::Plains;
+            }
+        }
+        x += columns + 2;
+    }
+    let map = MapBuilder::new(MapSizes::Duel).terrain(terrain).build().unwrap();
+    assert_eq!(map.landmass_stats().len(), 7);
+
+    let json = map.to_json_value();
+    let landmasses = json["landmasses"].as_array().unwrap();
+    assert_eq!(landmasses.len(), SUMMARY_LANDMASSES);
+    for (entry, info) in landmasses.iter().zip(map.landmass_stats()) {
+        assert_eq!(*entry, info.to_json_value());
+    }
+    let largest = &landmasses[0];
+    assert_eq!((largest["id"].as_u64(), largest["size"].as_u64()), (Some(0), Some(21)));
+    assert_eq!(largest["terrain_histogram"]["Plains"].as_u64(), Some(21));
+    assert_eq!(landmasses[4]["size"].as_u64(), Some(9));
+    assert!(largest["coastline_length"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn coastline_and_compactness_reach_the_csv_and_the_summary_json() {
+    let map = Map::new_with_type(Some(4), MapSizes::Duel, MapTypes::Pangea);
+    let stats = map.statistics();
+    let land: usize = map.landmass_stats().iter().map(|info| info.size).sum();
+    let weighted: f32 = map.landmass_stats().iter().map(|info| info.compactness * info.size as f32).sum();
+    assert!((stats.compactness - weighted / land as f32).abs() < 1e-5);
+    assert!(stats.compactness > 0.0 && stats.compactness <= 1.0);
+
+    let json = map.to_json_value();
+    assert_eq!(json["coastline_length"].as_u64(), Some(stats.coastline_length as u64));
+    assert!((json["compactness"].as_f64().unwrap() as f32 - stats.compactness).abs() < 1e-6);
+
+    let report = Map::stress_test(4..5, MapSizes::Duel, MapTypes::Pangea);
+    let row = &report.seeds[0];
+    assert_eq!((row.coastline_length, row.compactness), (stats.coastline_length, stats.compactness));
+    let csv = report.to_csv();
+    let fields: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+    assert_eq!(fields[3], stats.coastline_length.to_string());
+    assert_eq!(fields[4], format!("{:.4}", stats.compactness));
+}
@@ -0,0 +1,56 @@
+use civorum_mapgen::pipeline::{
+    error::GenError,
+    helpers::{EARTHLIKE_LAND_PROFILE, land_profile_preset, landmasses_config, with_landmasses_config},
+    land::{generate_landmasses, try_generate_landmasses},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+/// Share of the land that lies in the northern half of the map
+fn northern_share(grid: &[u8], size: &MapSizes) -> f32 {
+    let (width, height) = size.dimensions();
+    let north = grid[..height / 2 * width].iter().filter(|&&v| v == 1).count();
+    let south = grid[height.div_ceil(2) * width..].iter().filter(|&&v| v == 1).count();
+    north as f32 / (north + south).max(1) as f32
+}
+
+#[test]
+fn northern_bias_moves_land_north() {
+    // the northern half has to hold at least 20% more of the land than the southern half
+    let margin = 0.2;
+    let config = landmasses_config().as_ref().clone().with_latitude_land_profile(vec![4.0, 0.25]);
+    let size = MapSizes::Small;
+    for map_type in [MapTypes::Continents, MapTypes::Pangea] {
+        for seed in 1..5 {
+            let (grid, _) = with_landmasses_config(config.clone(), || generate_landmasses(seed, &size, map_type));
+            let north = northern_share(&grid, &size);
+            assert!(
+                north - (1.0 - north) >= margin,
+                "{map_type:?} seed {seed}: only {north} of the land is in the north"
+            );
+        }
+    }
+}
+
+#[test]
+fn flat_profiles_do_not_change_the_map() {
+    for map_type in [MapTypes::Continents, MapTypes::Terra, MapTypes::Mirror] {
+        let unbiased = generate_landmasses(4, &MapSizes::Duel, map_type);
+        for profile in [vec![1.0, 1.0, 1.0], vec![2.5; 4]] {
+            let config = landmasses_config().as_ref().clone().with_latitude_land_profile(profile);
+            let flat = with_landmasses_config(config, || generate_landmasses(4, &MapSizes::Duel, map_type));
+            assert_eq!(flat, unbiased, "{map_type:?}");
+        }
+    }
+}
+
+#[test]
+fn profile_presets_and_validation() {
+    assert_eq!(land_profile_preset("earthlike"), Some(EARTHLIKE_LAND_PROFILE.to_vec()));
+    assert_eq!(land_profile_preset("Flat"), Some(Vec::new()));
+    assert_eq!(land_profile_preset("mars"), None);
+
+    let config = landmasses_config().as_ref().clone().with_latitude_land_profile(vec![1.0, -0.5]);
+    let result = with_landmasses_config(config, || try_generate_landmasses(1, &MapSizes::Duel, MapTypes::Continents));
+    assert_eq!(result.err(), Some(GenError::InvalidLandProfile { weight: -0.5 }));
+}
@@ -0,0 +1,105 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{lowland_mask, ocean_connected_water},
+        helpers::{LowlandConfig, biomes_config},
+        map::Map,
+        map_sizes::MapSizes,
+    },
+};
+
+#[test]
+fn lowland_is_low_land_near_the_ocean_or_a_river() {
+    let radius = biomes_config().lowland.water_radius;
+    let map = Map::new(Some(6), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+    let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    let ocean = ocean_connected_water(&terrain, width, height);
+    let river_tiles: Vec<usize> = map.rivers().iter().flatten().copied().collect();
+
+    let lowland = map.lowland();
+    assert!(lowland.iter().any(|low| *low));
+    let highest_lowland = map
+        .tiles()
+        .iter()
+        .filter(|tile| tile.is_lowland())
+        .map(|tile| tile.elevation())
+        .max()
+        .unwrap();
+    for (idx, tile) in map.tiles().iter().enumerate() {
+        if !lowland[idx] {
+            continue;
+        }
+        assert!(!tile.terrain().is_water(), "{idx}");
+        let near_water = map.tiles().iter().enumerate().any(|(other, other_tile)| {
+            (ocean[other] || river_tiles.contains(&other))
+                && other_tile.hex_coords().distance(&tile.hex_coords()) <= radius
+        });
+        assert!(near_water, "{idx}");
+    }
+
+    // every lowland tile is lower than the land around the water that is not lowland
+    let land = map.tiles().iter().filter(|tile| !tile.terrain().is_water()).count();
+    let below = map
+        .tiles()
+        .iter()
+        .filter(|tile| !tile.terrain().is_water() && tile.elevation() < highest_lowland)
+        .count();
+    assert!(below <= land / 5, "{below} of {land}");
+}
+
+#[test]
+fn lowland_mask_follows_the_config() {
+    // one row: ocean, then land rising away from it
+    let terrain = [Terrain::Ocean, Terrain::Grassland, Terrain::Grassland, Terrain::Grassland, Terrain::Grassland];
+    let elevation = [0, 1, 2, 3, 4];
+    let mask = |elevation_quantile: f32, water_radius: u32, rivers: &[Vec<usize>]| {
+        let config = LowlandConfig {
+            elevation_quantile,
+            water_radius,
+        };
+        lowland_mask(&terrain, &elevation, rivers, 5, 1, &config)
+    };
+
+    assert_eq!(mask(0.5, 2, &[]), [false, true, true, false, false]);
+    assert_eq!(mask(1.0, 2, &[]), [false, true, true, false, false]);
+    assert_eq!(mask(1.0, 2, &[vec![4]]), [false, true, true, true, true]);
+    assert_eq!(mask(0.0, 4, &[]), [false; 5]);
+}
+
+#[test]
+fn traced_rivers_add_lowland_inland() {
+    let map = Map::new(Some(6), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+    let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    let elevation: Vec<u8> = map.tiles().iter().map(|tile| tile.elevation()).collect();
+    let config = &biomes_config().lowland;
+    assert!(!map.rivers().is_empty());
+
+    let without_rivers = lowland_mask(&terrain, &elevation, &[], width, height, config);
+    assert_eq!(lowland_mask(&terrain, &elevation, map.rivers(), width, height, config), map.lowland());
+    assert!(without_rivers.iter().zip(map.lowland()).all(|(without, with)| !without || with));
+    assert!(without_rivers.iter().zip(map.lowland()).any(|(without, with)| !without && with));
+}
+
+#[test]
+fn sea_level_rise_is_monotone() {
+    let map = Map::new(Some(6), MapSizes::Duel);
+    let steps = map.simulate_sea_level_rise(5);
+    assert_eq!(steps.len(), 5);
+    for pair in steps.windows(2) {
+        assert!(pair[0].len() <= pair[1].len());
+        assert!(pair[0].iter().all(|idx| pair[1].binary_search(idx).is_ok()));
+    }
+
+    let land = map.tiles().iter().filter(|tile| !tile.terrain().is_water()).count();
+    assert_eq!(steps[4].len(), land);
+    assert!(steps[0].len() < land);
+
+    // same tiles as actually applying the first raise
+    let flooded = map.apply_sea_level_change(0.2);
+    for &idx in &steps[0] {
+        assert!(flooded.tiles()[idx].terrain().is_water(), "{idx}");
+    }
+    assert!(map.simulate_sea_level_rise(0).is_empty());
+}
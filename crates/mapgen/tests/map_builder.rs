@@ -0,0 +1,133 @@
+use civorum_mapgen::{
+    map_components::{
+        hex_coords::HexCoord,
+        rivers::RiverClass,
+        terrain::{Feature, Terrain, WaterDepth},
+    },
+    pipeline::{
+        document::MapDocument,
+        map::Map,
+        map_builder::{LayerError, MapBuilder},
+        map_sizes::MapSizes,
+        text_map::{GeneratedMapData, parse_text_map, write_text_map},
+    },
+};
+
+const DUEL_TILES: usize = 44 * 26;
+
+#[test]
+fn layers_need_one_entry_per_tile() {
+    let short = MapBuilder::new(MapSizes::Duel)
+        .hills(vec![false; DUEL_TILES - 1])
+        .build()
+        .map(|_| ());
+    assert_eq!(
+        short,
+        Err(LayerError::LengthMismatch {
+            layer: "hills",
+            expected: DUEL_TILES,
+            found: DUEL_TILES - 1
+        })
+    );
+
+    let map = Map::new(Some(2), MapSizes::Duel);
+    let tiles = map.tiles()[..DUEL_TILES - 3].to_vec();
+    assert!(matches!(
+        Map::from_layers(MapSizes::Duel, tiles),
+        Err(LayerError::LengthMismatch { layer: "tiles", found, .. }) if found == DUEL_TILES - 3
+    ));
+
+    let data = GeneratedMapData::filled(3, 2, Terrain::Plains);
+    let err = MapBuilder::from_map_data(&data).map(|_| ()).unwrap_err();
+    assert_eq!(err, LayerError::UnsupportedDimensions { width: 3, height: 2 });
+}
+
+#[test]
+fn tiles_must_sit_at_the_hex_of_their_index() {
+    let map = Map::new(Some(2), MapSizes::Duel);
+    let mut tiles = map.tiles().to_vec();
+    tiles.swap(45, 46);
+    let err = Map::from_layers(MapSizes::Duel, tiles).map(|_| ()).unwrap_err();
+    assert_eq!(
+        err,
+        LayerError::HexMismatch {
+            index: 45,
+            expected: HexCoord::new(1, 1),
+            found: HexCoord::new(2, 1)
+        }
+    );
+    assert_eq!(err.to_string(), "tile 45 is at (2, 1), expected (1, 1)");
+}
+
+#[test]
+fn unset_layers_get_defaults() {
+    let empty = MapBuilder::new(MapSizes::Duel).build().unwrap();
+    assert_eq!(empty.tiles().len(), DUEL_TILES);
+    assert!(empty.tiles().iter().all(|tile| tile.terrain() == Terrain::Ocean
+        && !tile.is_hill()
+        && tile.feature().is_none()
+        && tile.elevation() == 0
+        && tile.landmass_id().is_none()));
+    assert!(empty.landmass_stats().is_empty());
+    assert_eq!(empty.seed(), None);
+
+    // a 3 tile island in the middle of the ocean
+    let mut terrain = vec![Terrain::Ocean; DUEL_TILES];
+    let island = [10 * 44 + 20, 10 * 44 + 21, 11 * 44 + 21];
+    for idx in island {
+        terrain[idx] = Terrain::Grassland;
+    }
+    let mut features = vec![None; DUEL_TILES];
+    features[island[0]] = Some(Feature::Woods);
+    let map = MapBuilder::new(MapSizes::Duel)
+        .seed(9)
+        .terrain(terrain)
+        .features(features)
+        .elevation(vec![40; DUEL_TILES])
+        .build()
+        .unwrap();
+    assert_eq!(map.seed(), Some(9));
+    assert_eq!(map.landmass_stats().len(), 1);
+    assert_eq!(map.landmass_stats()[0].size, 3);
+    assert!(island.iter().all(|&idx| map.tiles()[idx].landmass_id() == Some(0)));
+    assert_eq!(map.tiles()[island[0]].feature(), Some(Feature::Woods));
+    assert_eq!(map.tiles()[5 * 44 + 5].water_depth(), Some(WaterDepth::Deep));
+    assert_eq!(map.tiles()[10 * 44 + 19].water_depth(), Some(WaterDepth::Shallow));
+    assert!(map.tiles().iter().all(|tile| tile.elevation() == 40));
+}
+
+#[test]
+fn generated_tiles_round_trip() {
+    let map = Map::new(Some(5), MapSizes::Duel);
+    let rebuilt = Map::from_layers(MapSizes::Duel, map.tiles().to_vec()).unwrap();
+    assert_eq!(rebuilt.tiles(), map.tiles());
+    assert_eq!(rebuilt.appeal(), map.appeal());
+    assert_eq!(rebuilt.fingerprint(), map.fingerprint());
+    assert_eq!(rebuilt.landmass_stats().len(), map.landmass_stats().len());
+    assert_eq!(rebuilt.constraint_outcomes(), map.constraint_outcomes());
+}
+
+#[test]
+fn imported_layers_build_a_map() {
+    let map = Map::new(Some(8), MapSizes::Duel);
+    let data = parse_text_map(&write_text_map(&map.to_map_data())).unwrap();
+    let imported = MapBuilder::from_map_data(&data).unwrap().build().unwrap();
+    assert_eq!(imported.to_map_data(), map.to_map_data());
+    assert_eq!(imported.fingerprint(), map.fingerprint());
+
+    let document = MapDocument::new(map.to_map_data());
+    assert_eq!(document.to_map().unwrap().fingerprint(), map.fingerprint());
+}
+
+#[test]
+fn rivers_mark_their_tiles() {
+    let rivers = vec![vec![100, 101, 102, 103, 104], vec![200, 201]];
+    let map = MapBuilder::new(MapSizes::Duel).rivers(rivers.clone()).build().unwrap();
+    assert_eq!(map.rivers(), rivers.as_slice());
+    assert_eq!(map.tiles()[104].river_class(), Some(RiverClass::Moderate));
+    assert_eq!((map.tiles()[201].river_class(), map.tiles()[201].river_width()), (Some(RiverClass::Minor), 1));
+    assert_eq!(map.tiles()[105].river_class(), None);
+
+    let err = MapBuilder::new(MapSizes::Duel).rivers(vec![vec![0, DUEL_TILES]]).build().unwrap_err();
+    assert_eq!(err, LayerError::RiverOutOfBounds { index: DUEL_TILES, tiles: DUEL_TILES });
+}
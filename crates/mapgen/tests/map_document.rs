@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::{
+        document::{DocumentError, MAP_DOCUMENT_VERSION, MapDocument},
+        map::Map,
+        map_sizes::MapSizes,
+    },
+};
+
+fn fixture(version: u32) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/map_document_v{version}.json"))
+}
+
+const TERRAIN: [Terrain; 6] = [
+    Terrain::Ocean,
+    Terrain::Grassland,
+    Terrain::Plains,
+    Terrain::Ocean,
+    Terrain::Desert,
+    Terrain::Mountain,
+];
+
+#[test]
+fn every_historical_version_loads() {
+    for version in 1..=MAP_DOCUMENT_VERSION {
+        let document = MapDocument::load(&fixture(version)).unwrap_or_else(|err| panic!("v{version}: {err}"));
+        assert_eq!(document.version, version);
+        assert_eq!((document.data.width, document.data.height), (3, 2));
+        assert_eq!(document.data.terrain, TERRAIN);
+
+        let applied: Vec<u32> = document.migrations_applied.iter().map(|migration| migration.from).collect();
+        assert_eq!(applied, (version..MAP_DOCUMENT_VERSION).collect::<Vec<_>>(), "v{version}");
+    }
+}
+
+#[test]
+fn migrations_fill_defaults_for_new_layers() {
+    let v1 = MapDocument::load(&fixture(1)).unwrap();
+    assert!(v1.data.hills.iter().all(|hill| !hill));
+    assert!(v1.data.features.iter().all(Option::is_none));
+    assert_eq!(v1.migrations_applied[0].to_string(), "v1 -> v2: add the hill layer, no hills");
+
+    let v2 = MapDocument::load(&fixture(2)).unwrap();
+    assert_eq!(v2.data.hills, [false, true, false, false, true, false]);
+    assert!(v2.data.features.iter().all(Option::is_none));
+
+    let v3 = MapDocument::load(&fixture(3)).unwrap();
+    assert_eq!(v3.migrations_applied[0].to_string(), "v3 -> v4: add the scenario tags, no tags");
+    assert_eq!(v3.data.hills, v2.data.hills);
+    assert_eq!(v3.data.features, [Some(Feature::Reef), Some(Feature::Woods), None, None, Some(Feature::Oasis), None]);
+    assert_eq!(v3.tags.n_tagged_tiles(), 0);
+
+    let v4 = MapDocument::load(&fixture(4)).unwrap();
+    assert!(v4.migrations_applied.is_empty());
+    assert_eq!(v4.data, v3.data);
+    assert_eq!(v4.tags.tiles_with_tag("spawn:barbarian").collect::<Vec<_>>(), [1, 4]);
+    assert_eq!(v4.tags.tiles_with_tag("goody_hut").collect::<Vec<_>>(), [2]);
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let err = MapDocument::load(&fixture(5)).unwrap_err();
+    assert!(matches!(err, DocumentError::TooNew { found: 5, supported: MAP_DOCUMENT_VERSION }));
+    assert_eq!(err.to_string(), "map document version 5 is newer than the supported version 4");
+}
+
+#[test]
+fn saved_maps_load_without_migrations() {
+    let map = Map::new(Some(6), MapSizes::Duel);
+    let path = std::env::temp_dir().join(format!("civorum_map_document_{}.json", std::process::id()));
+    map.save(&path).unwrap();
+
+    let document = MapDocument::load(&path).unwrap();
+    assert_eq!(document.version, MAP_DOCUMENT_VERSION);
+    assert!(document.migrations_applied.is_empty());
+    assert_eq!(document.data, map.to_map_data());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn broken_layers_are_reported() {
+    let short = serde_json::json!({ "version": 1, "width": 3, "height": 2, "terrain": ["Ocean"] });
+    let err = MapDocument::from_json_value(short).unwrap_err();
+    assert!(matches!(err, DocumentError::InvalidField { field: "terrain", .. }), "{err}");
+
+    let unversioned = serde_json::json!({ "width": 1, "height": 1, "terrain": ["Ocean"] });
+    let err = MapDocument::from_json_value(unversioned).unwrap_err();
+    assert!(matches!(err, DocumentError::InvalidField { field: "version", .. }), "{err}");
+
+    let outside = serde_json::json!({
+        "version": 4, "width": 1, "height": 1, "terrain": ["Ocean"], "hills": [false], "features": [null],
+        "tags": { "spawn": [1] },
+    });
+    let err = MapDocument::from_json_value(outside).unwrap_err();
+    assert!(matches!(err, DocumentError::InvalidField { field: "tags", .. }), "{err}");
+}
@@ -0,0 +1,84 @@
+use civorum_mapgen::pipeline::{
+    map::Map,
+    map_sizes::{MapScaling, MapSizes},
+    map_types::MapTypes,
+};
+
+const PRESETS: [MapSizes; 6] = [
+    MapSizes::Duel,
+    MapSizes::Tiny,
+    MapSizes::Small,
+    MapSizes::Standard,
+    MapSizes::Large,
+    MapSizes::Huge,
+];
+
+#[test]
+fn preset_values_are_pinned() {
+    // (rivers, island_max, mid_max, wonders, min_start_distance)
+    let expected = [
+        (2, 20, 120, 2, 7),
+        (3, 20, 120, 3, 8),
+        (3, 20, 121, 4, 8),
+        (4, 20, 162, 5, 9),
+        (5, 26, 205, 6, 9),
+        (6, 31, 249, 7, 10),
+    ];
+    for (size, expected) in PRESETS.iter().zip(expected) {
+        let scaling = MapScaling::for_size(size);
+        let actual = (
+            scaling.rivers,
+            scaling.island_max,
+            scaling.mid_max,
+            scaling.wonders,
+            scaling.min_start_distance,
+        );
+        assert_eq!(actual, expected, "{size:?}");
+        assert_eq!(scaling.area_ratio, size.grid_size() as f32 / (84.0 * 54.0), "{size:?}");
+    }
+    assert_eq!(MapScaling::for_size(&MapSizes::Standard).area_ratio, 1.0);
+}
+
+#[test]
+fn preset_dimensions_match_the_preset() {
+    for size in PRESETS {
+        let (width, height) = size.dimensions();
+        assert_eq!(MapScaling::for_dimensions(width, height), MapScaling::for_size(&size));
+    }
+}
+
+#[test]
+fn custom_sizes_are_monotonic_between_presets() {
+    let mut previous = MapScaling::for_dimensions(28, 28 * 5 / 8);
+    for width in (30..=130).step_by(2) {
+        let scaling = MapScaling::for_dimensions(width, width * 5 / 8);
+        assert!(scaling.area_ratio > previous.area_ratio, "{width}");
+        assert!(scaling.rivers >= previous.rivers, "{width}");
+        assert!(scaling.island_max >= previous.island_max, "{width}");
+        assert!(scaling.mid_max >= previous.mid_max, "{width}");
+        assert!(scaling.wonders >= previous.wonders, "{width}");
+        assert!(scaling.min_start_distance >= previous.min_start_distance, "{width}");
+        previous = scaling;
+    }
+}
+
+#[test]
+fn custom_sizes_interpolate_between_the_nearest_presets() {
+    // halfway between Standard (4536 tiles) and Large (5760 tiles)
+    let scaling = MapScaling::for_dimensions(5148, 1);
+    assert_eq!((scaling.rivers, scaling.wonders), (5, 6));
+
+    // outside the preset range the nearest preset is used
+    let tiny_map = MapScaling::for_dimensions(10, 10);
+    assert_eq!((tiny_map.rivers, tiny_map.wonders, tiny_map.min_start_distance), (2, 2, 7));
+    let giant_map = MapScaling::for_dimensions(200, 120);
+    assert_eq!((giant_map.rivers, giant_map.wonders, giant_map.min_start_distance), (6, 7, 10));
+}
+
+#[test]
+fn maps_trace_the_river_count_of_their_size() {
+    for (seed, size) in [(2, MapSizes::Duel), (1, MapSizes::Standard), (0, MapSizes::Huge)] {
+        let map = Map::new_with_type(Some(seed), size, MapTypes::Pangea);
+        assert_eq!(map.rivers().len(), MapScaling::for_size(&size).rivers, "{size:?}");
+    }
+}
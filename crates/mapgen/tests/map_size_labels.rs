@@ -0,0 +1,31 @@
+use civorum_mapgen::pipeline::map_sizes::{MapSizes, ParseMapSizesError};
+
+#[test]
+fn labels_match_the_dimensions() {
+    for size in MapSizes::ALL {
+        let (width, height) = size.dimensions();
+        let label = size.label();
+        assert!(label.ends_with(&format!("({width}×{height}, {} tiles)", size.grid_size())), "{label}");
+        assert!(label.to_lowercase().starts_with(size.short_label()), "{label}");
+        assert!(size.short_label().chars().all(|c| c.is_ascii_lowercase()));
+    }
+    assert_eq!(MapSizes::Standard.label(), "Standard (84×54, 4536 tiles)");
+    assert_eq!(MapSizes::Standard.short_label(), "standard");
+}
+
+#[test]
+fn all_sizes_are_ordered_by_tile_count() {
+    assert!(MapSizes::ALL.windows(2).all(|pair| pair[0].grid_size() < pair[1].grid_size()));
+}
+
+#[test]
+fn short_labels_parse_back() {
+    for size in MapSizes::ALL {
+        assert_eq!(size.short_label().parse::<MapSizes>(), Ok(size));
+        assert_eq!(format!(" {} ", size.short_label().to_uppercase()).parse::<MapSizes>(), Ok(size));
+    }
+    let err = "gigantic".parse::<MapSizes>().unwrap_err();
+    assert_eq!(err, ParseMapSizesError("gigantic".to_string()));
+    assert_eq!(err.to_string(), "invalid size 'gigantic'. Use one of: duel, tiny, small, standard, large, huge");
+    assert!("".parse::<MapSizes>().is_err());
+}
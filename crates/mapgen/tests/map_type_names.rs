@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+
+use civorum_mapgen::pipeline::map_types::{MapTypes, ParseMapTypesError};
+
+#[test]
+fn names_round_trip() {
+    for map_type in MapTypes::ALL {
+        let name = map_type.to_string();
+        assert!(name.chars().all(|c| c.is_ascii_lowercase() || c == '-'), "{name}");
+        assert_eq!(name.parse::<MapTypes>(), Ok(map_type));
+        assert_eq!(name.to_uppercase().parse::<MapTypes>(), Ok(map_type));
+    }
+    assert_eq!(MapTypes::IslandsContinents.to_string(), "islands-continents");
+    assert_eq!(MapTypes::SmallContinents.to_string(), "small-continents");
+    assert_eq!(MapTypes::ALL.into_iter().collect::<HashSet<_>>().len(), MapTypes::ALL.len());
+}
+
+#[test]
+fn separators_are_optional() {
+    for input in ["smallcontinents", "small-continents", "Small_Continents", " SmallContinents "] {
+        assert_eq!(input.parse::<MapTypes>(), Ok(MapTypes::SmallContinents), "{input}");
+    }
+    assert_eq!("islandscontinents".parse::<MapTypes>(), Ok(MapTypes::IslandsContinents));
+}
+
+#[test]
+fn unknown_names_are_errors() {
+    let err = "archipelago".parse::<MapTypes>().unwrap_err();
+    assert_eq!(err, ParseMapTypesError("archipelago".to_string()));
+    assert!(err.to_string().contains("small-continents"));
+    assert!("".parse::<MapTypes>().is_err());
+}
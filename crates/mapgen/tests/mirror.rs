@@ -0,0 +1,26 @@
+use civorum_mapgen::pipeline::{biomes::generate_map_with_type, map_sizes::MapSizes, map_types::MapTypes};
+
+fn assert_mirrored<T: PartialEq + std::fmt::Debug>(values: &[T], width: usize, height: usize, layer: &str) {
+    for y in 0..height {
+        for x in 0..width {
+            assert_eq!(
+                values[y * width + x],
+                values[y * width + (width - 1 - x)],
+                "{layer} differs at ({x}, {y})"
+            );
+        }
+    }
+}
+
+#[test]
+fn mirror_maps_have_symmetric_terrain_and_hills() {
+    for size in [MapSizes::Duel, MapSizes::Small] {
+        let (width, height) = size.dimensions();
+        for seed in [1u64, 7, 42] {
+            let layers = generate_map_with_type(&seed, &size, MapTypes::Mirror);
+            assert_mirrored(&layers.landmask, width, height, "landmask");
+            assert_mirrored(&layers.terrain, width, height, "terrain");
+            assert_mirrored(&layers.hills, width, height, "hills");
+        }
+    }
+}
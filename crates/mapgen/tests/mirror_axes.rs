@@ -0,0 +1,71 @@
+use civorum_mapgen::pipeline::{
+    biomes::generate_map_with_type,
+    helpers::{MirrorAxis, landmasses_config, set_landmasses_config},
+    land::{enforce_horizontal_mirror, enforce_vertical_mirror},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+fn assert_rows_mirrored<T: PartialEq + std::fmt::Debug>(values: &[T], width: usize, height: usize, layer: &str) {
+    for y in 0..height {
+        for x in 0..width {
+            assert_eq!(
+                values[y * width + x],
+                values[(height - 1 - y) * width + x],
+                "{layer} differs at ({x}, {y})"
+            );
+        }
+    }
+}
+
+fn assert_columns_mirrored<T: PartialEq + std::fmt::Debug>(values: &[T], width: usize, height: usize, layer: &str) {
+    for y in 0..height {
+        for x in 0..width {
+            assert_eq!(
+                values[y * width + x],
+                values[y * width + (width - 1 - x)],
+                "{layer} differs at ({x}, {y})"
+            );
+        }
+    }
+}
+
+#[test]
+fn enforce_horizontal_mirror_copies_the_top_half_down() {
+    for (width, height) in [(4, 5), (3, 6)] {
+        let mut grid: Vec<u8> = (0..width * height).map(|i| i as u8).collect();
+        let original = grid.clone();
+        enforce_horizontal_mirror(&mut grid, width, height);
+        assert_rows_mirrored(&grid, width, height, "grid");
+        assert_eq!(grid[..height / 2 * width], original[..height / 2 * width]);
+
+        enforce_vertical_mirror(&mut grid, width, height);
+        assert_rows_mirrored(&grid, width, height, "grid");
+        assert_columns_mirrored(&grid, width, height, "grid");
+    }
+}
+
+// Both axes in one test, the landmass config is global
+#[test]
+fn horizontal_and_four_way_mirror_maps_are_symmetric() {
+    let size = MapSizes::Small;
+    let (width, height) = size.dimensions();
+
+    for axis in [MirrorAxis::Horizontal, MirrorAxis::FourWay] {
+        let mut config = landmasses_config().as_ref().clone();
+        config.mirror.axis = axis;
+        set_landmasses_config(config);
+
+        for seed in [1u64, 7] {
+            let layers = generate_map_with_type(&seed, &size, MapTypes::Mirror);
+            assert!(layers.landmask.contains(&1), "{axis:?} map {seed} has no land");
+            assert_rows_mirrored(&layers.landmask, width, height, "landmask");
+            assert_rows_mirrored(&layers.terrain, width, height, "terrain");
+            assert_rows_mirrored(&layers.hills, width, height, "hills");
+            if axis == MirrorAxis::FourWay {
+                assert_columns_mirrored(&layers.landmask, width, height, "landmask");
+                assert_columns_mirrored(&layers.terrain, width, height, "terrain");
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::{
+        map::Map,
+        map_sizes::MapSizes,
+        ndjson::{NdjsonError, export_ndjson, import_ndjson},
+        text_map::GeneratedMapData,
+    },
+};
+use serde_json::Value;
+
+fn export(data: &GeneratedMapData) -> String {
+    let mut out = Vec::new();
+    export_ndjson(data, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn map_export_matches_the_json_export() {
+    let map = Map::new(Some(8), MapSizes::Duel);
+    let mut out = Vec::new();
+    map.export_ndjson(&mut out).unwrap();
+
+    let mut lines = out.split(|b| *b == b'\n').filter(|line| !line.is_empty());
+    let mut document: Value = serde_json::from_slice(lines.next().unwrap()).unwrap();
+    let tiles: Vec<Value> = lines
+        .enumerate()
+        .map(|(idx, line)| {
+            let mut tile: Value = serde_json::from_slice(line).unwrap();
+            assert_eq!(tile["index"], idx);
+            tile.as_object_mut().unwrap().remove("index");
+            tile
+        })
+        .collect();
+    document["tiles"] = Value::Array(tiles);
+    assert_eq!(document, map.to_json_value());
+
+    // the importer reads the map export as well
+    assert_eq!(import_ndjson(out.as_slice()).unwrap(), map.to_map_data());
+}
+
+#[test]
+fn map_data_round_trips() {
+    let mut data = GeneratedMapData::filled(5, 3, Terrain::Grassland);
+    data.terrain[0] = Terrain::CoastLake;
+    data.terrain[7] = Terrain::Mountain;
+    data.hills[3] = true;
+    data.features[4] = Some(Feature::Woods);
+    data.features[14] = Some(Feature::VolanicSoil);
+
+    let text = export(&data);
+    assert_eq!(text.lines().count(), 1 + 15);
+    assert_eq!(import_ndjson(text.as_bytes()).unwrap(), data);
+
+    // a large map streams through without trouble
+    let large = GeneratedMapData::filled(512, 512, Terrain::Ocean);
+    let mut out = Vec::new();
+    export_ndjson(&large, &mut out).unwrap();
+    assert_eq!(import_ndjson(out.as_slice()).unwrap(), large);
+}
+
+#[test]
+fn errors_name_the_line() {
+    let text = export(&GeneratedMapData::filled(2, 2, Terrain::Plains));
+    let with_line = |line: usize, replacement: &str| {
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines[line - 1] = replacement;
+        lines.join("\n")
+    };
+    let line_of = |input: &str| match import_ndjson(input.as_bytes()) {
+        Err(NdjsonError::InvalidJson { line, .. } | NdjsonError::InvalidField { line, .. }) => line,
+        other => panic!("{other:?}"),
+    };
+
+    assert_eq!(line_of(&with_line(3, "{\"index\": 1, ")), 3);
+    assert_eq!(line_of(&with_line(4, r#"{"index":2,"x":0,"y":1,"terrain":"Lava"}"#)), 4);
+    assert_eq!(line_of(&with_line(4, r#"{"index":3,"x":1,"y":1,"terrain":"Plains"}"#)), 4);
+    assert_eq!(line_of(&with_line(2, r#"{"index":0,"x":0,"y":0}"#)), 2);
+    assert_eq!(line_of(&with_line(1, r#"{"width":2}"#)), 1);
+
+    let err = import_ndjson(with_line(5, r#"{"index":3,"x":1,"y":1,"terrain":"Snow","hill":"yes"}"#).as_bytes())
+        .unwrap_err();
+    assert!(err.to_string().starts_with("line 5: field 'hill'"), "{err}");
+
+    let extra = format!("{text}{}\n", r#"{"index":4,"x":0,"y":2,"terrain":"Plains"}"#);
+    assert!(matches!(import_ndjson(extra.as_bytes()), Err(NdjsonError::ExtraTile { line: 6 })));
+    let short: String = text.lines().take(3).map(|line| format!("{line}\n")).collect();
+    assert!(matches!(
+        import_ndjson(short.as_bytes()),
+        Err(NdjsonError::TileCount { expected: 4, found: 2 })
+    ));
+    assert!(matches!(import_ndjson("\n\n".as_bytes()), Err(NdjsonError::MissingHeader)));
+}
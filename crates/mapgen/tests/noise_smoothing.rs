@@ -0,0 +1,73 @@
+use civorum_mapgen::pipeline::{
+    biomes::smooth_noise_layer,
+    helpers::{SmoothKernel, try_load_biomes_config},
+    map_sizes::MapSizes,
+};
+
+const SIZE: MapSizes = MapSizes::Duel;
+
+/// Alternating 0/255 columns, the worst case of small-scale noise
+fn striped() -> Vec<u8> {
+    let (width, height) = SIZE.dimensions();
+    (0..width * height).map(|idx| if idx % 2 == 0 { 0 } else { 255 }).collect()
+}
+
+fn spread(values: &[u8]) -> u8 {
+    values.iter().max().unwrap() - values.iter().min().unwrap()
+}
+
+#[test]
+fn zero_passes_leave_the_layer_untouched() {
+    for kernel in [SmoothKernel::MajorityVote, SmoothKernel::Average, SmoothKernel::Gaussian] {
+        let mut values = striped();
+        smooth_noise_layer(&mut values, &SIZE, 0, kernel);
+        assert_eq!(values, striped(), "{kernel:?}");
+    }
+}
+
+#[test]
+fn constant_layers_stay_constant() {
+    for kernel in [SmoothKernel::MajorityVote, SmoothKernel::Average, SmoothKernel::Gaussian] {
+        let mut values = vec![90; SIZE.grid_size()];
+        smooth_noise_layer(&mut values, &SIZE, 3, kernel);
+        assert!(values.iter().all(|v| *v == 90), "{kernel:?}");
+    }
+}
+
+#[test]
+fn averaging_kernels_flatten_stripes() {
+    let (width, _) = SIZE.dimensions();
+    // an interior tile of an even row has 2 neighbors of its own column parity and 4 of the other
+    let interior = 2 * width + 2;
+
+    let mut average = striped();
+    smooth_noise_layer(&mut average, &SIZE, 1, SmoothKernel::Average);
+    assert_eq!(average[interior], (4.0_f32 * 255.0 / 7.0).round() as u8);
+
+    let mut gaussian = striped();
+    smooth_noise_layer(&mut gaussian, &SIZE, 1, SmoothKernel::Gaussian);
+    assert_eq!(gaussian[interior], (4.0_f32 * 255.0 / 8.0).round() as u8);
+
+    smooth_noise_layer(&mut average, &SIZE, 4, SmoothKernel::Average);
+    assert!(spread(&average) < spread(&striped()) / 2);
+}
+
+#[test]
+fn majority_vote_takes_the_median() {
+    let (width, _) = SIZE.dimensions();
+    let interior = 2 * width + 2;
+
+    let mut values = striped();
+    smooth_noise_layer(&mut values, &SIZE, 1, SmoothKernel::MajorityVote);
+    // 4 of the 7 values around the tile are 255
+    assert_eq!(values[interior], 255);
+    assert!(values.iter().all(|v| *v == 0 || *v == 255));
+}
+
+#[test]
+fn shipped_config_does_not_smooth() {
+    let config = try_load_biomes_config().expect("biomes.yaml parses");
+    assert_eq!(config.rainfall.noise.post_smooth_passes, 0);
+    assert_eq!(config.heightmap.post_smooth_passes, 0);
+    assert_eq!(config.heightmap.post_smooth_kernel, SmoothKernel::Average);
+}
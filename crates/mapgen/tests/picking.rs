@@ -0,0 +1,35 @@
+use civorum_mapgen::{
+    map_components::hex_coords::HexCoord,
+    pipeline::{
+        map::{Map, cursor_ray_to_tile},
+        map_sizes::MapSizes,
+    },
+};
+
+#[test]
+fn cursor_rays_resolve_to_tiles() {
+    let map = Map::new(Some(7), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+
+    // straight down and slanted rays onto every tile center
+    for (idx, tile) in map.tiles().iter().enumerate() {
+        let (x, z) = tile.hex_coords().world_pos();
+        let down = cursor_ray_to_tile([x, 10.0, z], [0.0, -1.0, 0.0], &map).unwrap();
+        assert_eq!(down.index, idx);
+        assert_eq!(down.hex, tile.hex_coords());
+
+        let slanted = cursor_ray_to_tile([x - 5.0, 10.0, z - 5.0], [0.5, -1.0, 0.5], &map).unwrap();
+        assert_eq!(slanted.index, idx);
+    }
+
+    // off the map
+    let (x, z) = HexCoord::new(width as i32 + 2, height as i32 + 2).world_pos();
+    assert!(cursor_ray_to_tile([x, 10.0, z], [0.0, -1.0, 0.0], &map).is_none());
+    assert!(cursor_ray_to_tile([-3.0, 10.0, 0.0], [0.0, -1.0, 0.0], &map).is_none());
+
+    // degenerate rays
+    assert!(cursor_ray_to_tile([1.0, 10.0, 1.0], [1.0, 0.0, 0.0], &map).is_none());
+    assert!(cursor_ray_to_tile([1.0, 10.0, 1.0], [0.0, 1.0, 0.0], &map).is_none());
+    assert!(cursor_ray_to_tile([1.0, 10.0, 1.0], [0.0, f32::NAN, 0.0], &map).is_none());
+    assert!(cursor_ray_to_tile([0.0; 3], [0.0; 3], &map).is_none());
+}
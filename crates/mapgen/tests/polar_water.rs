@@ -0,0 +1,21 @@
+use civorum_mapgen::pipeline::helpers::{default_landmasses_config, try_load_landmasses_config};
+
+#[test]
+fn configs_without_polar_rows_keep_the_single_border_row() {
+    // landmasses.yml predates the option, so every draft falls back to the default
+    let config = try_load_landmasses_config().expect("landmasses.yml parses");
+    let drafts = [
+        &config.continents.draft,
+        &config.small_continents.draft,
+        &config.island_continents.draft,
+        &config.pangea.draft,
+        &config.terra.old_world.draft,
+        &config.terra.new_world.draft,
+        &config.mirror.base.draft,
+    ];
+    for draft in drafts {
+        assert_eq!(draft.polar_water_rows, 1);
+    }
+
+    assert_eq!(default_landmasses_config().continents.draft.polar_water_rows, 1);
+}
@@ -0,0 +1,93 @@
+use civorum_mapgen::pipeline::{
+    document::MapDocument,
+    helpers::config_hash,
+    map::Map,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+    recipe::{ParseRecipeError, Recipe},
+};
+
+#[test]
+fn recipes_round_trip() {
+    let recipe = Recipe::new(12, MapSizes::Standard, MapTypes::Terra)
+        .with_option("preset", "archipelago")
+        .with_option("age", "old")
+        .with_config_hash(0x9af3);
+    let text = recipe.to_string();
+    assert_eq!(
+        text,
+        "civorum:v1;seed=12;size=standard;type=terra;preset=archipelago;age=old;cfg=0x0000000000009af3"
+    );
+    assert_eq!(Recipe::parse(&text), Ok(recipe.clone()));
+    assert_eq!(text.parse::<Recipe>(), Ok(recipe));
+
+    // entries may come in any order, with spaces and a trailing separator
+    let reordered = Recipe::parse(" civorum:v1; type=small-continents ;cfg=9af3;size=Duel;seed=4; ").unwrap();
+    assert_eq!(reordered.size, MapSizes::Duel);
+    assert_eq!(reordered.map_type, MapTypes::SmallContinents);
+    assert_eq!(reordered.config_hash, Some(0x9af3));
+    assert!(reordered.options.is_empty());
+}
+
+#[test]
+fn unknown_keys_are_kept() {
+    let recipe = Recipe::parse("civorum:v1;seed=12;size=small;type=pangea;sea=high;climate=hot").unwrap();
+    assert_eq!(recipe.option("sea"), Some("high"));
+    assert_eq!(recipe.option("climate"), Some("hot"));
+    assert_eq!(recipe.option("age"), None);
+    assert_eq!(recipe.to_string(), "civorum:v1;seed=12;size=small;type=pangea;sea=high;climate=hot");
+}
+
+#[test]
+fn broken_recipes_are_rejected() {
+    let err = |text: &str| Recipe::parse(text).unwrap_err();
+    assert_eq!(err("seed=12;size=small;type=pangea"), ParseRecipeError::MissingPrefix);
+    assert_eq!(
+        err("civorum:v2;seed=12;size=small;type=pangea"),
+        ParseRecipeError::UnsupportedVersion("v2".to_string())
+    );
+    assert_eq!(err("civorum:v1;size=small;type=pangea"), ParseRecipeError::MissingKey("seed"));
+    assert_eq!(
+        err("civorum:v1;seed=12;size=small;type=pangea;seed=13"),
+        ParseRecipeError::DuplicateKey("seed".to_string())
+    );
+    assert_eq!(
+        err("civorum:v1;seed=12;size=medium;type=pangea"),
+        ParseRecipeError::InvalidValue {
+            key: "size".to_string(),
+            value: "medium".to_string()
+        }
+    );
+    assert_eq!(
+        err("civorum:v1;seed=12;size=small;type=pangea;hot"),
+        ParseRecipeError::InvalidEntry("hot".to_string())
+    );
+}
+
+#[test]
+fn recipes_tell_a_changed_config() {
+    let recipe = Recipe::new(5, MapSizes::Duel, MapTypes::Continents);
+    assert_eq!(recipe.config_matches(), None);
+    assert_eq!(recipe.clone().with_config_hash(config_hash()).config_matches(), Some(true));
+    assert_eq!(recipe.with_config_hash(config_hash() ^ 1).config_matches(), Some(false));
+}
+
+#[test]
+fn maps_embed_their_recipe() {
+    let map = Map::new(Some(5), MapSizes::Duel);
+    let recipe = map.recipe();
+    assert_eq!(recipe.seed, 5);
+    assert_eq!(recipe.size, MapSizes::Duel);
+    assert_eq!(recipe.config_matches(), Some(true));
+    assert_eq!(map.to_json_value()["recipe"], recipe.to_string());
+    assert_eq!(map.to_json_value()["config_hash"], format!("{:016x}", config_hash()));
+
+    let path = std::env::temp_dir().join(format!("civorum_recipe_{}.json", std::process::id()));
+    map.save(&path).unwrap();
+    let document = MapDocument::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(document.recipe, Some(recipe.clone()));
+
+    let regenerated = Map::new_with_type(Some(recipe.seed), recipe.size, recipe.map_type);
+    assert_eq!(regenerated.fingerprint(), map.fingerprint());
+}
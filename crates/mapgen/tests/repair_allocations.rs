@@ -0,0 +1,142 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+
+use civorum_mapgen::pipeline::{
+    helpers::{config_hash, landmasses_config, with_landmasses_config},
+    land::generate_landmasses,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+/// FNV-1a hashes of `generate_landmasses` grids, recorded before the repair loop reused its vectors
+const LANDMASKS: [(MapSizes, MapTypes, u64, u64); 16] = [
+    (MapSizes::Duel, MapTypes::Continents, 1, 0x0b44eee6f851cb30),
+    (MapSizes::Duel, MapTypes::Terra, 7, 0x5237a0d8bbbf943d),
+    (MapSizes::Small, MapTypes::SmallContinents, 1, 0x97ba01c4c1e51b45),
+    (MapSizes::Small, MapTypes::IslandsContinents, 7, 0x0a11acab7a31211e),
+    (MapSizes::Small, MapTypes::Mirror, 1, 0xa22f9d484a90f321),
+    (MapSizes::Standard, MapTypes::Continents, 1, 0x74486700e95e83b5),
+    (MapSizes::Standard, MapTypes::Continents, 7, 0xab61d025da3896b9),
+    (MapSizes::Standard, MapTypes::SmallContinents, 7, 0xd89b88eca56cd35a),
+    (MapSizes::Standard, MapTypes::IslandsContinents, 1, 0xeda927766c94d401),
+    (MapSizes::Standard, MapTypes::Pangea, 1, 0x38f9710fb1b40b6b),
+    (MapSizes::Standard, MapTypes::Mirror, 7, 0xb71e4d4c4bef580f),
+    (MapSizes::Standard, MapTypes::Terra, 1, 0x32c2e38fa60fade3),
+    (MapSizes::Huge, MapTypes::Continents, 1, 0x53671727ef4113a6),
+    (MapSizes::Huge, MapTypes::IslandsContinents, 7, 0x4df0a11733f1c0d1),
+    (MapSizes::Huge, MapTypes::Pangea, 7, 0x997a5deaaa856510),
+    (MapSizes::Huge, MapTypes::Terra, 1, 0x755d5051741c1f01),
+];
+
+/// `config_hash` of the shipped configs the landmasks were recorded with
+const LANDMASKS_CONFIG: u64 = 0x62fa63d6c43a5871;
+
+/// Most allocations of at least one byte per tile `generate_landmasses` may make: the draft, the analysis
+/// and a few masks per repair pass. Candidate and flood fill vectors are reused instead of reallocated.
+const MAX_GRID_SIZED_ALLOCATIONS: usize = 200;
+
+/// Forwards to the system allocator and counts the allocations of threads inside `count_allocations`
+/// that are at least `MIN_SIZE` bytes.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static MIN_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.try_with(Cell::get).unwrap_or(false) && layout.size() >= MIN_SIZE.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Run `f` and return its result with the number of allocations of at least `min_size` bytes and the bytes
+/// they allocated. Other tests run on other threads and are not counted, two counting calls must not overlap.
+fn count_allocations<T>(min_size: usize, f: impl FnOnce() -> T) -> (T, usize, usize) {
+    static LOCK: Mutex<()> = Mutex::new(());
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    MIN_SIZE.store(min_size, Ordering::Relaxed);
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    ALLOCATED_BYTES.store(0, Ordering::Relaxed);
+    COUNTING.with(|counting| counting.set(true));
+    let result = f();
+    COUNTING.with(|counting| counting.set(false));
+    (result, ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[test]
+fn landmasks_are_byte_identical() {
+    assert_eq!(config_hash(), LANDMASKS_CONFIG, "config changed, {:#018x}", config_hash());
+    for (size, map_type, seed, expected) in LANDMASKS {
+        let (grid, _) = generate_landmasses(seed, &size, map_type);
+        assert_eq!(fnv1a(&grid), expected, "{size:?} {map_type:?} seed {seed}: landmask changed");
+    }
+}
+
+#[test]
+fn repairs_reuse_their_grid_sized_vectors() {
+    for (size, map_type) in [
+        (MapSizes::Small, MapTypes::Continents),
+        (MapSizes::Standard, MapTypes::Pangea),
+        (MapSizes::Standard, MapTypes::Terra),
+        (MapSizes::Huge, MapTypes::SmallContinents),
+    ] {
+        // warm up so lazily loaded configs are not counted
+        let _ = generate_landmasses(1, &size, map_type);
+
+        let (_, allocations, _) = count_allocations(size.grid_size(), || generate_landmasses(1, &size, map_type));
+        assert!(
+            allocations <= MAX_GRID_SIZED_ALLOCATIONS,
+            "{size:?} {map_type:?}: {allocations} allocations of a grid or larger"
+        );
+    }
+}
+
+/// Allocation and timing report for the land repair loop on Huge maps.
+/// Run with `cargo test --release -p civorum-mapgen --test repair_allocations -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark, run explicitly"]
+fn repair_loop_allocations_on_huge() {
+    for map_type in [MapTypes::Continents, MapTypes::Pangea, MapTypes::Terra] {
+        // warm up so lazily loaded configs are not counted
+        let _ = generate_landmasses(1, &MapSizes::Huge, map_type);
+
+        let start = Instant::now();
+        let ((_, iterations), allocations, bytes) =
+            count_allocations(0, || generate_landmasses(1, &MapSizes::Huge, map_type));
+        let elapsed = start.elapsed();
+
+        println!(
+            "{map_type:?}: {} repair passes, {allocations} allocations, {bytes} bytes, {elapsed:?}",
+            iterations.get(),
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::{
+        features::{place_river_deltas, river_width_tiers},
+        helpers::neighbors_odd_r,
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        text_map::parse_text_map,
+    },
+};
+
+const DUEL: MapSizes = MapSizes::Duel;
+
+#[test]
+fn width_never_decreases_downstream() {
+    let a = vec![1, 2, 3, 4, 5, 50, 51, 52];
+    let b = vec![10, 11, 12, 13, 50, 51, 52];
+    let short = vec![20, 21, 22];
+    let rivers = vec![a, b, short];
+    let tiers = river_width_tiers(&rivers, 100);
+
+    for river in &rivers {
+        assert!(river.windows(2).all(|pair| tiers[pair[0]] <= tiers[pair[1]]), "{river:?}");
+    }
+    // the tributaries merge at 50: 5 + 4 + 1 tiles of flow, 12 at the mouth
+    assert_eq!((tiers[1], tiers[50], tiers[52]), (1, 2, 3));
+    assert_eq!(tiers[22], 1);
+    assert_eq!(tiers[0], 0);
+}
+
+/// 44x26 ocean with a block of grassland around a lake at (12, 10)
+fn island() -> (Vec<Terrain>, usize) {
+    let (width, height) = DUEL.dimensions();
+    let mut text = String::from("#terrain\n");
+    for y in 0..height {
+        let row: String = (0..width)
+            .map(|x| match (x, y) {
+                (12, 10) => '~',
+                (8..=20, 6..=14) => 'g',
+                _ => '.',
+            })
+            .collect();
+        text.push_str(&row);
+        text.push('\n');
+    }
+    (parse_text_map(&text).unwrap().terrain, width)
+}
+
+/// A river of `length` tiles along row 9 and then 8, ending at `mouth`
+fn river_to(mouth: (usize, usize), length: usize, width: usize) -> Vec<usize> {
+    let mut tiles: Vec<usize> = (8..=20).map(|x| 8 * width + x).chain((8..=20).map(|x| 9 * width + x)).collect();
+    tiles.retain(|&idx| idx != mouth.1 * width + mouth.0);
+    let mut river: Vec<usize> = tiles.into_iter().take(length - 1).collect();
+    river.push(mouth.1 * width + mouth.0);
+    river
+}
+
+#[test]
+fn deltas_form_only_at_wide_ocean_mouths() {
+    let (terrain, width) = island();
+    let (height, grid) = (DUEL.dimensions().1, DUEL.grid_size());
+    let ocean_mouth = (20, 11);
+    let lake_mouth = (12, 11);
+
+    for seed in 0..8u64 {
+        // tier 3 river into the ocean
+        let rivers = vec![river_to(ocean_mouth, 14, width)];
+        let tiers = river_width_tiers(&rivers, grid);
+        assert_eq!(tiers[ocean_mouth.1 * width + ocean_mouth.0], 3);
+        let mut features = vec![None; grid];
+        place_river_deltas(seed, &rivers, &tiers, &terrain, &DUEL, &mut features);
+        let delta: Vec<usize> = (0..grid).filter(|&idx| features[idx].is_some()).collect();
+        assert!((1..=2).contains(&delta.len()), "seed {seed}: {} delta tiles", delta.len());
+        let next_to_mouth = neighbors_odd_r(ocean_mouth.0, ocean_mouth.1, width, height);
+        for idx in delta {
+            assert!(matches!(features[idx], Some(Feature::Marsh | Feature::Floodplains)));
+            assert!(next_to_mouth.contains(&(idx % width, idx / width)));
+            assert!(!terrain[idx].is_water());
+        }
+
+        // narrow river into the ocean
+        let rivers = vec![river_to(ocean_mouth, 4, width)];
+        let tiers = river_width_tiers(&rivers, grid);
+        let mut features = vec![None; grid];
+        place_river_deltas(seed, &rivers, &tiers, &terrain, &DUEL, &mut features);
+        assert!(features.iter().all(Option::is_none), "seed {seed}: narrow river formed a delta");
+
+        // tier 3 river into the lake
+        let rivers = vec![river_to(lake_mouth, 14, width)];
+        let tiers = river_width_tiers(&rivers, grid);
+        assert_eq!(tiers[lake_mouth.1 * width + lake_mouth.0], 3);
+        let mut features = vec![None; grid];
+        place_river_deltas(seed, &rivers, &tiers, &terrain, &DUEL, &mut features);
+        assert!(features.iter().all(Option::is_none), "seed {seed}: lake river formed a delta");
+    }
+}
+
+#[test]
+fn generated_rivers_widen_downstream_and_form_deltas() {
+    let map = Map::new_with_type(Some(2), MapSizes::Large, MapTypes::Pangea);
+    let (width, height) = MapSizes::Large.dimensions();
+    let tiles = map.tiles();
+
+    for river in map.rivers() {
+        let widths: Vec<u8> = river.iter().map(|&idx| tiles[idx].river_width()).collect();
+        assert!(widths.windows(2).all(|pair| (1..=pair[1]).contains(&pair[0])), "{widths:?}");
+    }
+    let wide_mouths: Vec<usize> = map
+        .rivers()
+        .iter()
+        .filter_map(|river| river.last().copied())
+        .filter(|&mouth| tiles[mouth].river_width() == 3)
+        .collect();
+    assert!(!wide_mouths.is_empty());
+
+    // every delta tile lies next to the mouth of a tier 3 river
+    let delta: Vec<usize> = (0..tiles.len())
+        .filter(|&idx| matches!(tiles[idx].feature(), Some(Feature::Marsh | Feature::Floodplains)))
+        .collect();
+    assert!((1..=2 * wide_mouths.len()).contains(&delta.len()), "{delta:?}");
+    for idx in delta {
+        let next_to_mouth = wide_mouths.iter().any(|&mouth| {
+            neighbors_odd_r(mouth % width, mouth / width, width, height).contains(&(idx % width, idx / width))
+        });
+        assert!(next_to_mouth, "{idx}");
+    }
+    assert!(map.to_json_value()["tiles"].as_array().unwrap().iter().any(|tile| tile["river_width"] == 3));
+}
@@ -0,0 +1,26 @@
+use civorum_mapgen::{map_components::terrain::Terrain, pipeline::features::compute_river_score};
+
+#[test]
+fn score_blends_elevation_and_rainfall() {
+    assert_eq!(compute_river_score(Terrain::Plains, 0, 0, true), 0.0);
+    assert!((compute_river_score(Terrain::Plains, 255, 255, true) - 1.0).abs() < 1e-6);
+    assert!((compute_river_score(Terrain::Grassland, 255, 0, false) - 0.65).abs() < 1e-6);
+    assert!((compute_river_score(Terrain::Grassland, 0, 255, false) - 0.35).abs() < 1e-6);
+}
+
+#[test]
+fn only_mountains_bordering_land_count_as_fully_elevated() {
+    let bordering = compute_river_score(Terrain::Mountain, 100, 51, true);
+    assert!((bordering - (0.65 + 0.35 * 0.2)).abs() < 1e-6);
+
+    // a mountain walled in by mountains or coast scores by its elevation like any other tile
+    let walled_in = compute_river_score(Terrain::Mountain, 100, 51, false);
+    assert_eq!(walled_in, compute_river_score(Terrain::Plains, 100, 51, false));
+    assert!(walled_in < bordering);
+
+    // the bonus is for mountains only
+    assert_eq!(
+        compute_river_score(Terrain::Plains, 100, 51, true),
+        compute_river_score(Terrain::Plains, 100, 51, false)
+    );
+}
@@ -0,0 +1,80 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, rivers::RiverClass, terrain::Terrain},
+    pipeline::{
+        features::place_features,
+        helpers::neighbors_odd_r,
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        text_map::parse_text_map,
+    },
+};
+
+#[test]
+fn rivers_run_over_land_from_the_source_to_the_water() {
+    for (seed, size, map_type) in [
+        (2, MapSizes::Duel, MapTypes::Pangea),
+        (5, MapSizes::Small, MapTypes::Continents),
+        (1, MapSizes::Standard, MapTypes::Terra),
+    ] {
+        let map = Map::new_with_type(Some(seed), size, map_type);
+        let (width, height) = size.dimensions();
+        let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+        let neighbors = |idx: usize| -> Vec<usize> {
+            neighbors_odd_r(idx % width, idx / width, width, height)
+                .into_iter()
+                .map(|(x, y)| y * width + x)
+                .collect()
+        };
+
+        assert!(!map.rivers().is_empty(), "{size:?} seed {seed}");
+        for river in map.rivers() {
+            assert!(river.len() >= 5, "{river:?}");
+            assert!(river.iter().all(|&idx| !terrain[idx].is_water() && terrain[idx] != Terrain::Mountain));
+            assert!(river.windows(2).all(|pair| neighbors(pair[0]).contains(&pair[1])), "{river:?}");
+            let mouth = *river.last().unwrap();
+            assert!(neighbors(mouth).iter().any(|&n| terrain[n] == Terrain::CoastLake), "{river:?}");
+            assert!(river[..river.len() - 1].iter().all(|&idx| neighbors(idx).iter().all(|&n| !terrain[n].is_water())));
+
+            let class = RiverClass::from_length(river.len());
+            assert!(river.iter().all(|&idx| map.tiles()[idx].river_class() >= Some(class)));
+        }
+
+        // the same seed traces the same rivers
+        assert_eq!(Map::new_with_type(Some(seed), size, map_type).rivers(), map.rivers());
+    }
+}
+
+#[test]
+fn rivers_take_the_shortest_way_to_the_water() {
+    // a plain of grassland with coast along the top and the right edge, sloping down towards the right
+    let size = MapSizes::Duel;
+    let (width, height) = size.dimensions();
+    let mut text = String::from("#terrain\n");
+    for y in 0..height {
+        let row: String = (0..width).map(|x| if x == width - 1 || y == 0 { '~' } else { 'g' }).collect();
+        text.push_str(&row);
+        text.push('\n');
+    }
+    let terrain = parse_text_map(&text).unwrap().terrain;
+    let heights: Vec<u8> = (0..width * height).map(|idx| (255 - idx % width * 5) as u8).collect();
+    let rain = vec![128u8; width * height];
+
+    let rivers = place_features(&terrain, &rain, &heights, &size);
+    assert!(!rivers.is_empty());
+    for river in &rivers {
+        // every step gets one tile closer to the water, so no river is longer than it has to be
+        let (x, y) = (river[0] % width, river[0] / width);
+        assert_eq!(river.len(), (width - 2 - x).min(y - 1) + 1, "{river:?}");
+    }
+
+    // the rivers leave the highest scored tiles first, at least five tiles apart
+    let sources: Vec<usize> = rivers.iter().map(|river| river[0]).collect();
+    assert_eq!(sources[0] % width, 0);
+    let coords = |idx: usize| HexCoord::new((idx % width) as i32, (idx / width) as i32);
+    for (i, &a) in sources.iter().enumerate() {
+        for &b in &sources[i + 1..] {
+            assert!(coords(a).distance(&coords(b)) >= 5, "{a} {b}");
+        }
+    }
+}
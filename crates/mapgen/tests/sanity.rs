@@ -0,0 +1,158 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain},
+    pipeline::{
+        map::Map,
+        map_builder::MapBuilder,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        sanity::{SanityCode, check_map, terrain_share_ranges},
+    },
+};
+
+const WIDTH: usize = 44;
+const HEIGHT: usize = 26;
+
+fn idx(x: usize, y: usize) -> usize {
+    y * WIDTH + x
+}
+
+/// Three islands of alternating grassland and plains columns, a Continents map without warnings
+fn islands() -> Vec<Terrain> {
+    let mut terrain = vec![Terrain::Ocean; WIDTH * HEIGHT];
+    for (xs, ys) in [(4..14, 3..9), (18..28, 3..9), (30..40, 14..20)] {
+        for y in ys {
+            for x in xs.clone() {
+                terrain[idx(x, y)] = if x % 2 == 0 { Terrain::Grassland } else { Terrain::Plains };
+            }
+        }
+    }
+    terrain
+}
+
+fn build(terrain: Vec<Terrain>, temperature: u8) -> Map {
+    MapBuilder::new(MapSizes::Duel)
+        .terrain(terrain)
+        .temperature(vec![temperature; WIDTH * HEIGHT])
+        .build()
+        .unwrap()
+}
+
+/// `islands` with a lake of the minimum lake size on the first island
+fn island_with_lake() -> Vec<Terrain> {
+    let mut terrain = islands();
+    for tile in [idx(7, 5), idx(8, 5), idx(7, 6), idx(8, 6)] {
+        terrain[tile] = Terrain::CoastLake;
+    }
+    terrain
+}
+
+fn codes(map: &Map) -> Vec<SanityCode> {
+    check_map(map).iter().map(|warning| warning.code).collect()
+}
+
+#[test]
+fn a_plain_map_has_no_warnings() {
+    assert_eq!(codes(&build(islands(), 0)), []);
+    // a river into the ocean next to one into a lake
+    let rivers = vec![vec![idx(10, 5), idx(11, 5), idx(12, 5), idx(13, 5)], vec![idx(10, 6), idx(9, 6)]];
+    let map = MapBuilder::new(MapSizes::Duel).terrain(island_with_lake()).rivers(rivers).build().unwrap();
+    assert_eq!(codes(&map), []);
+    assert_eq!(map.to_json_value()["sanity"], serde_json::json!([]));
+    // no land, nothing to check
+    assert_eq!(codes(&build(vec![Terrain::Ocean; WIDTH * HEIGHT], 0)), []);
+}
+
+#[test]
+fn every_check_warns_exactly_once() {
+    let mut dominant = islands();
+    for terrain in dominant.iter_mut().filter(|terrain| **terrain == Terrain::Plains) {
+        *terrain = Terrain::Grassland;
+    }
+
+    let mut mountains = islands();
+    for (tile, terrain) in mountains.iter_mut().enumerate() {
+        if (tile % WIDTH).is_multiple_of(5) && !terrain.is_water() {
+            *terrain = Terrain::Mountain;
+        }
+    }
+
+    let mut small_lake = islands();
+    small_lake[idx(8, 5)] = Terrain::CoastLake;
+
+    // a lake of the minimum lake size, ringed by mountains
+    let mut dead_lake = islands();
+    let lake = [idx(7, 5), idx(8, 5), idx(7, 6), idx(8, 6)];
+    for &tile in &lake {
+        let coord = HexCoord::new((tile % WIDTH) as i32, (tile / WIDTH) as i32);
+        for neighbor in coord.neighbors() {
+            dead_lake[idx(neighbor.x() as usize, neighbor.y() as usize)] = Terrain::Mountain;
+        }
+    }
+    for tile in lake {
+        dead_lake[tile] = Terrain::CoastLake;
+    }
+
+    let mut anomaly = islands();
+    anomaly[idx(20, 5)] = Terrain::Snow;
+    anomaly[idx(21, 5)] = Terrain::Desert;
+
+    // Continents need at least 3 landmasses, one is below half of that
+    let mut one_landmass = islands();
+    for (tile, terrain) in one_landmass.iter_mut().enumerate() {
+        if tile % WIDTH > 16 {
+            *terrain = Terrain::Ocean;
+        }
+    }
+
+    for (code, terrain, temperature) in [
+        (SanityCode::TerrainShare, dominant, 0),
+        (SanityCode::TooManyMountains, mountains, 0),
+        (SanityCode::MissingDesert, islands(), 255),
+        (SanityCode::SmallLakesOnly, small_lake, 0),
+        (SanityCode::DeadLakes, dead_lake, 0),
+        (SanityCode::TerrainAdjacencyAnomaly, anomaly, 0),
+        (SanityCode::LandmassCount, one_landmass, 0),
+    ] {
+        assert_eq!(codes(&build(terrain, temperature)), [code], "{code:?}");
+    }
+
+    // the only river ends at the lake
+    let map = MapBuilder::new(MapSizes::Duel)
+        .terrain(island_with_lake())
+        .rivers(vec![vec![idx(12, 5), idx(11, 5), idx(10, 5), idx(9, 5)]])
+        .build()
+        .unwrap();
+    assert_eq!(codes(&map), [SanityCode::NoRiverReachesOcean]);
+}
+
+#[test]
+fn terrain_share_ranges_follow_the_map_type() {
+    // four of five land columns are grassland
+    let mut terrain = islands();
+    for (tile, terrain) in terrain.iter_mut().enumerate() {
+        if !terrain.is_water() {
+            *terrain = if (tile % WIDTH).is_multiple_of(5) { Terrain::Plains } else { Terrain::Grassland };
+        }
+    }
+    let grassland_max = |map_type| {
+        let ranges = terrain_share_ranges(map_type);
+        ranges.iter().find(|(terrain, _, _)| *terrain == Terrain::Grassland).unwrap().2
+    };
+    assert!(grassland_max(MapTypes::Terra) < 0.8 && grassland_max(MapTypes::Mirror) > 0.8);
+
+    for (map_type, warns) in [(MapTypes::Continents, false), (MapTypes::Terra, true), (MapTypes::Mirror, false)] {
+        let map = MapBuilder::new(MapSizes::Duel).map_type(map_type).terrain(terrain.clone()).build().unwrap();
+        let warnings = check_map(&map);
+        let share = warnings.iter().find(|warning| warning.code == SanityCode::TerrainShare);
+        assert_eq!(share.is_some(), warns, "{map_type:?}");
+        if let Some(warning) = share {
+            assert!((warning.value - 0.8).abs() < 1e-6);
+            assert!(warning.message.contains("Grassland at 80%"), "{}", warning.message);
+        }
+    }
+
+    let grassland = islands().into_iter().map(|terrain| if terrain.is_water() { terrain } else { Terrain::Grassland });
+    let json = build(grassland.collect(), 0).to_json_value();
+    assert_eq!(json["sanity"][0]["code"], "terrain_share");
+    assert_eq!(json["sanity"][0]["value"], 1.0);
+}
@@ -0,0 +1,51 @@
+use civorum_mapgen::pipeline::{
+    helpers::{landmasses_config, with_landmasses_config},
+    land::try_generate_landmasses,
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+/// Land fraction of an 8 x 8 grid of blocks over the map
+fn land_blocks(grid: &[u8], size: MapSizes) -> Vec<f64> {
+    let (width, height) = size.dimensions();
+    let mut land = vec![0.0; 64];
+    let mut tiles = vec![0.0; 64];
+    for y in 0..height {
+        for x in 0..width {
+            let block = (y * 8 / height) * 8 + x * 8 / width;
+            land[block] += f64::from(grid[y * width + x]);
+            tiles[block] += 1.0;
+        }
+    }
+    land.iter().zip(&tiles).map(|(land, tiles)| land / tiles).collect()
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let covariance: f64 = a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum();
+    let variance = |values: &[f64], mean: f64| values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    covariance / (variance(a, mean_a) * variance(b, mean_b)).sqrt()
+}
+
+/// Correlation of the Small and Huge landmasks of the same seed
+fn size_correlation(seed: u64, scale_invariant: bool) -> f64 {
+    let config = landmasses_config().as_ref().clone().with_scale_invariant(scale_invariant);
+    with_landmasses_config(config, || {
+        let (small, _) = try_generate_landmasses(seed, &MapSizes::Small, MapTypes::Continents).unwrap();
+        let (huge, _) = try_generate_landmasses(seed, &MapSizes::Huge, MapTypes::Continents).unwrap();
+        correlation(&land_blocks(&small, MapSizes::Small), &land_blocks(&huge, MapSizes::Huge))
+    })
+}
+
+#[test]
+fn scale_invariant_worlds_look_alike_across_sizes() {
+    let correlation = size_correlation(2, true);
+    assert!(correlation > 0.7, "{correlation}");
+}
+
+#[test]
+fn default_worlds_differ_across_sizes() {
+    let correlation = size_correlation(2, false);
+    assert!(correlation < 0.5, "{correlation}");
+}
@@ -0,0 +1,150 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::Terrain},
+    pipeline::{
+        map::Map,
+        map_sizes::MapSizes,
+        pathfinding::{CoastalGroups, Navigation, sea_distance, sea_reachable},
+        text_map::GeneratedMapData,
+    },
+};
+
+fn fill(data: &mut GeneratedMapData, xs: std::ops::RangeInclusive<usize>, ys: std::ops::RangeInclusive<usize>, terrain: Terrain) {
+    for y in ys {
+        for x in xs.clone() {
+            let idx = data.index(x, y);
+            data.terrain[idx] = terrain;
+        }
+    }
+}
+
+/// Deep ocean between a west coast (x 0-2) and an east coast (x 13-15)
+fn strait() -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(16, 7, Terrain::Ocean);
+    fill(&mut data, 0..=2, 0..=6, Terrain::Grassland);
+    fill(&mut data, 13..=15, 0..=6, Terrain::Grassland);
+    data
+}
+
+/// Land with two enclosed lakes, x 2-4 and x 7-9
+fn two_lakes() -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(12, 8, Terrain::Plains);
+    fill(&mut data, 2..=4, 2..=4, Terrain::CoastLake);
+    fill(&mut data, 7..=9, 2..=4, Terrain::CoastLake);
+    data
+}
+
+fn distance(data: &GeneratedMapData, from: (i32, i32), to: (i32, i32), navigation: Navigation) -> Option<u32> {
+    sea_distance(
+        &data.terrain,
+        data.width,
+        data.height,
+        HexCoord::new(from.0, from.1),
+        HexCoord::new(to.0, to.1),
+        navigation,
+    )
+}
+
+#[test]
+fn ports_on_the_same_ocean_have_a_distance() {
+    let data = strait();
+    // straight along row 3: ten ocean tiles (x 3-12), then landing on (13, 3)
+    assert_eq!(distance(&data, (2, 3), (13, 3), Navigation::Open), Some(11));
+    assert_eq!(distance(&data, (13, 3), (2, 3), Navigation::Open), Some(11));
+    assert_eq!(distance(&data, (2, 3), (2, 3), Navigation::Open), Some(0));
+    // inland tiles are not ports
+    assert_eq!(distance(&data, (1, 3), (13, 3), Navigation::Open), None);
+    assert_eq!(distance(&data, (2, 3), (40, 3), Navigation::Open), None);
+
+    let groups = CoastalGroups::new(&data.terrain, data.width, data.height, Navigation::Open);
+    assert_eq!(groups.count(), 1);
+    assert!(groups.share_water(HexCoord::new(2, 3), HexCoord::new(13, 3)));
+    assert!(!groups.share_water(HexCoord::new(1, 3), HexCoord::new(13, 3)));
+}
+
+#[test]
+fn ports_on_separate_seas_have_no_distance() {
+    let data = two_lakes();
+    assert_eq!(distance(&data, (1, 3), (10, 3), Navigation::Open), None);
+    // (5, 3) is on the east shore of the west lake: across it along row 3
+    assert_eq!(distance(&data, (1, 3), (5, 3), Navigation::Open), Some(4));
+    assert_eq!(distance(&data, (6, 3), (10, 3), Navigation::CoastOnly), Some(4));
+
+    let groups = CoastalGroups::new(&data.terrain, data.width, data.height, Navigation::Open);
+    assert_eq!(groups.count(), 2);
+    assert!(!groups.share_water(HexCoord::new(1, 3), HexCoord::new(10, 3)));
+    assert!(groups.share_water(HexCoord::new(1, 3), HexCoord::new(5, 3)));
+    assert!(groups.share_water(HexCoord::new(6, 3), HexCoord::new(10, 3)));
+    assert_eq!(groups.groups(HexCoord::new(0, 0)).count(), 0);
+}
+
+#[test]
+fn coast_only_ships_avoid_the_open_ocean() {
+    let mut data = strait();
+    assert_eq!(distance(&data, (2, 3), (13, 3), Navigation::CoastOnly), None);
+    let groups = CoastalGroups::new(&data.terrain, data.width, data.height, Navigation::CoastOnly);
+    assert_eq!(groups.count(), 0);
+    assert!(!groups.share_water(HexCoord::new(2, 3), HexCoord::new(13, 3)));
+
+    // shallow water along both shores and along the north edge
+    fill(&mut data, 3..=3, 0..=6, Terrain::CoastLake);
+    fill(&mut data, 12..=12, 0..=6, Terrain::CoastLake);
+    fill(&mut data, 3..=12, 0..=0, Terrain::CoastLake);
+    assert_eq!(distance(&data, (2, 3), (13, 3), Navigation::Open), Some(11));
+    // embark on (3, 2), up to (3, 1) and (4, 0), east to (12, 0), down to (12, 3), then land on (13, 3)
+    assert_eq!(distance(&data, (2, 3), (13, 3), Navigation::CoastOnly), Some(15));
+    let groups = CoastalGroups::new(&data.terrain, data.width, data.height, Navigation::CoastOnly);
+    assert!(groups.share_water(HexCoord::new(2, 3), HexCoord::new(13, 3)));
+    // deep water is not a place to start from
+    assert_eq!(distance(&data, (7, 3), (13, 3), Navigation::CoastOnly), None);
+    assert_eq!(groups.groups(HexCoord::new(7, 3)).count(), 0);
+}
+
+#[test]
+fn reachable_ports_stay_within_the_budget() {
+    let data = two_lakes();
+    let reachable = |budget| {
+        sea_reachable(&data.terrain, data.width, data.height, HexCoord::new(1, 3), budget, Navigation::Open)
+    };
+
+    assert!(reachable(1).is_empty());
+    let near = reachable(2);
+    assert!(!near.is_empty());
+    assert!(near.iter().all(|&(_, steps)| steps == 2));
+    assert!(!near.iter().any(|&(hex, _)| hex == HexCoord::new(1, 3)));
+
+    let all = reachable(u32::MAX);
+    assert!(all.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    assert!(all.contains(&(HexCoord::new(5, 3), 4)));
+    assert!(!all.iter().any(|&(hex, _)| hex.x() >= 6));
+    for (hex, steps) in all {
+        assert!(!data.terrain[data.index(hex.x() as usize, hex.y() as usize)].is_water());
+        assert_eq!(distance(&data, (1, 3), (hex.x(), hex.y()), Navigation::Open), Some(steps));
+    }
+}
+
+#[test]
+fn coastal_groups_agree_with_sea_distances() {
+    let map = Map::new(Some(8), MapSizes::Duel);
+    let (width, _) = MapSizes::Duel.dimensions();
+    let is_water = |hex: HexCoord| map.tiles()[hex.y() as usize * width + hex.x() as usize].terrain().is_water();
+    let ports: Vec<HexCoord> = map
+        .tiles()
+        .iter()
+        .map(|tile| tile.hex_coords())
+        .filter(|&hex| !is_water(hex) && map.neighbors(hex).into_iter().any(is_water))
+        .step_by(17)
+        .collect();
+    assert!(ports.len() > 4);
+
+    for navigation in [Navigation::Open, Navigation::CoastOnly] {
+        let groups = map.coastal_groups(navigation);
+        for pair in ports.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            assert_eq!(
+                groups.share_water(from, to),
+                map.sea_distance(from, to, navigation).is_some(),
+                "{navigation:?} {from:?} -> {to:?}"
+            );
+        }
+    }
+}
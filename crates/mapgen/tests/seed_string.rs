@@ -0,0 +1,57 @@
+use civorum_mapgen::pipeline::{
+    map::{Map, ParseSeedStringError, SeedString, generate_map_with_seed_string},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+fn parse(s: &str) -> Result<SeedString, ParseSeedStringError> {
+    s.parse()
+}
+
+#[test]
+fn malformed_seed_strings_are_invalid_format() {
+    for s in ["", "MC-2A", "MC-2A-5-0", "M-2A-5", "MCX-2A-5", "MC--5", "MC-2G-5", "MC-2A-", "MC-2A-10", "MC-2A-G"] {
+        assert_eq!(parse(s), Err(ParseSeedStringError::InvalidFormat), "{s:?}");
+    }
+}
+
+#[test]
+fn a_wrong_checksum_is_rejected() {
+    let valid = SeedString { seed: 0x2a, size: MapSizes::Standard, map_type: MapTypes::Continents };
+    let wrong = format!("MC-2A-{:X}", (valid.checksum() + 1) & 0xF);
+    assert_eq!(parse(&wrong), Err(ParseSeedStringError::InvalidChecksum));
+    // swapped seed digits change the checksum
+    let swapped = format!("MC-A2-{:X}", valid.checksum());
+    assert_eq!(parse(&swapped), Err(ParseSeedStringError::InvalidChecksum));
+}
+
+#[test]
+fn unknown_codes_are_named() {
+    assert_eq!(parse("XC-2A-5"), Err(ParseSeedStringError::UnknownMapSize));
+    assert_eq!(parse("MX-2A-5"), Err(ParseSeedStringError::UnknownMapType));
+    // the size is checked first
+    assert_eq!(parse("XX-2A-5"), Err(ParseSeedStringError::UnknownMapSize));
+}
+
+#[test]
+fn seed_strings_round_trip_through_generation() {
+    for (seed, size, map_type) in [
+        (3, MapSizes::Duel, MapTypes::Continents),
+        (0x2a, MapSizes::Duel, MapTypes::Terra),
+        (0xdead_beef_cafe, MapSizes::Tiny, MapTypes::Pangea),
+    ] {
+        let map = Map::new_with_type(Some(seed), size, map_type);
+        let seed_string = map.seed_string();
+        let text = seed_string.to_string();
+
+        let parsed = parse(&text).unwrap();
+        assert_eq!(parsed, seed_string);
+        assert_eq!(parsed.checksum(), seed_string.checksum());
+        // lowercase codes and surrounding whitespace are accepted
+        assert_eq!(parse(&format!(" {} ", text.to_lowercase())), Ok(seed_string));
+
+        let regenerated = generate_map_with_seed_string(&text).unwrap();
+        assert_eq!(regenerated.seed_string(), seed_string);
+        assert_eq!(regenerated.fingerprint(), map.fingerprint(), "{text}");
+    }
+}
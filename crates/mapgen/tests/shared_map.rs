@@ -0,0 +1,58 @@
+use std::thread;
+
+use civorum_mapgen::pipeline::{map::Map, map_sizes::MapSizes};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn maps_can_be_shared_between_threads() {
+    assert_send_sync::<Map>();
+
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let map = map.clone();
+            thread::spawn(move || (map.fingerprint(), map.statistics().n_landmasses, map.tiles().len()))
+        })
+        .collect();
+    for handle in handles {
+        let (fingerprint, landmasses, tiles) = handle.join().unwrap();
+        assert_eq!(fingerprint, map.fingerprint());
+        assert_eq!(landmasses, map.statistics().n_landmasses);
+        assert_eq!(tiles, MapSizes::Duel.grid_size());
+    }
+}
+
+#[test]
+fn clones_share_the_tiles() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let copy = map.clone();
+    assert!(copy.shares_data_with(&map));
+    assert_eq!(copy.tiles().as_ptr(), map.tiles().as_ptr());
+    assert!(!Map::new(Some(4), MapSizes::Duel).shares_data_with(&map));
+}
+
+#[test]
+fn edits_copy_the_shared_tiles() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let mut edited = map.clone();
+    let idx = map.tiles().iter().position(|tile| !tile.terrain().is_water()).unwrap();
+    let before = map.tiles()[idx].elevation();
+
+    edited.tiles_mut()[idx].set_elevation(before.wrapping_add(1));
+    assert!(!edited.shares_data_with(&map));
+    assert_eq!(map.tiles()[idx].elevation(), before);
+    assert_eq!(edited.tiles()[idx].elevation(), before.wrapping_add(1));
+
+    // a map that is not shared is edited in place
+    let ptr = edited.tiles().as_ptr();
+    edited.smooth_elevation(1);
+    assert_eq!(edited.tiles().as_ptr(), ptr);
+
+    let mut picked = map.clone();
+    let (x, z) = picked.tiles()[idx].hex_coords().world_pos();
+    picked.tile_mut_at_world_pos(x, z).unwrap().set_elevation(0);
+    assert!(!picked.shares_data_with(&map));
+    assert_eq!(map.tiles()[idx].elevation(), before);
+    assert_eq!(picked.tiles()[idx].elevation(), 0);
+}
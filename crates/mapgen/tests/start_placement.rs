@@ -0,0 +1,145 @@
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::{
+        map::Map,
+        map_sizes::MapSizes,
+        starts::{StartPlacementError, StartPlacementOptions, place_starts},
+        text_map::GeneratedMapData,
+    },
+};
+
+fn fresh_water_options(min_distance: u32) -> StartPlacementOptions {
+    StartPlacementOptions {
+        require_fresh_water: true,
+        min_distance_override: Some(min_distance),
+        ..StartPlacementOptions::default()
+    }
+}
+
+/// Plains without water, with an oasis at each of the given tiles
+fn dry_land(oases: &[(usize, usize)]) -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(24, 12, Terrain::Plains);
+    for &(x, y) in oases {
+        let idx = data.index(x, y);
+        data.features[idx] = Some(Feature::Oasis);
+    }
+    data
+}
+
+/// Grassland with an ocean column on the left edge behind a strip of coastal desert
+fn desert_coast() -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(24, 12, Terrain::Grassland);
+    for y in 0..data.height {
+        let (ocean, coast) = (data.index(0, y), data.index(1, y));
+        data.terrain[ocean] = Terrain::Ocean;
+        data.terrain[coast] = Terrain::Desert;
+    }
+    data
+}
+
+#[test]
+fn fresh_water_is_relaxed_for_the_fewest_starts() {
+    let two_oases = dry_land(&[(3, 5), (20, 5)]);
+    let report = place_starts(&two_oases, &[], 3, 1, &fresh_water_options(8)).unwrap();
+    assert_eq!(report.relaxed_fresh_water, [2]);
+    assert!(report.starts[..2].iter().all(|start| start.fresh_water));
+
+    let one_oasis = dry_land(&[(3, 5)]);
+    let report = place_starts(&one_oasis, &[], 3, 1, &fresh_water_options(8)).unwrap();
+    assert_eq!(report.relaxed_fresh_water, [1, 2]);
+
+    // a river and a lake count as fresh water as well
+    let mut wet = dry_land(&[]);
+    let lake = wet.index(4, 5);
+    wet.terrain[lake] = Terrain::CoastLake;
+    let river: Vec<usize> = (6..12).map(|y| wet.index(20, y)).collect();
+    let report = place_starts(&wet, &[river], 2, 1, &fresh_water_options(8)).unwrap();
+    assert!(report.relaxed_fresh_water.is_empty());
+    assert!(report.starts.iter().all(|start| start.fresh_water));
+
+    // without the requirement nothing is relaxed
+    let options = StartPlacementOptions {
+        min_distance_override: Some(8),
+        ..StartPlacementOptions::default()
+    };
+    assert!(place_starts(&one_oasis, &[], 3, 1, &options).unwrap().relaxed_fresh_water.is_empty());
+}
+
+#[test]
+fn coastal_bias_sets_a_quota_of_coastal_starts() {
+    let data = desert_coast();
+    let options = |coastal_bias: f32| StartPlacementOptions {
+        coastal_bias,
+        min_distance_override: Some(4),
+        ..StartPlacementOptions::default()
+    };
+    let coastal = |bias: f32| {
+        let report = place_starts(&data, &[], 4, 3, &options(bias)).unwrap();
+        assert_eq!(report.coastal_quota, options(bias).coastal_quota(4));
+        report.starts.iter().filter(|start| start.coastal).count()
+    };
+
+    // the grassland is better, so only the quota ends up on the desert coast
+    assert_eq!(coastal(0.0), 0);
+    assert_eq!(coastal(0.3), 2);
+    assert_eq!(coastal(0.5), 2);
+    assert_eq!(coastal(0.75), 3);
+
+    // 12 coastal rows hold 3 starts 4 tiles apart
+    assert_eq!(
+        place_starts(&data, &[], 4, 3, &options(1.0)),
+        Err(StartPlacementError::CoastalQuota { required: 4, placed: 3 })
+    );
+}
+
+#[test]
+fn starts_keep_their_distance_and_avoid_forbidden_terrain() {
+    let map = Map::new(Some(4), MapSizes::Small);
+    let options = StartPlacementOptions {
+        forbidden_terrains: vec![Terrain::Desert, Terrain::Tundra, Terrain::Snow],
+        ..StartPlacementOptions::default()
+    };
+    let report = map.place_starts(6, &options).unwrap();
+    assert_eq!(report.starts.len(), 6);
+    for (i, start) in report.starts.iter().enumerate() {
+        let terrain = map.tiles()[start.tile].terrain();
+        assert!(!terrain.is_water() && terrain != Terrain::Mountain, "{start:?}");
+        assert!(!options.forbidden_terrains.contains(&terrain), "{start:?}");
+        for other in &report.starts[i + 1..] {
+            assert!(start.hex.distance(&other.hex) >= report.min_distance);
+        }
+    }
+    assert!(report.worst_quality().is_some_and(|worst| report.starts.iter().all(|start| start.quality >= worst)));
+
+    let crowded = StartPlacementOptions {
+        min_distance_override: Some(40),
+        ..StartPlacementOptions::default()
+    };
+    assert!(matches!(
+        map.place_starts(6, &crowded),
+        Err(StartPlacementError::NotEnoughRoom { players: 6, min_distance: 40, .. })
+    ));
+}
+
+#[test]
+fn placement_is_deterministic() {
+    let options = StartPlacementOptions {
+        coastal_bias: 0.5,
+        require_fresh_water: true,
+        ..StartPlacementOptions::default()
+    };
+    let map = Map::new(Some(11), MapSizes::Small);
+    let first = map.place_starts(6, &options).unwrap();
+    assert_eq!(map.place_starts(6, &options).unwrap(), first);
+    assert_eq!(Map::new(Some(11), MapSizes::Small).place_starts(6, &options).unwrap(), first);
+
+    // equally good tiles are picked by the seed
+    let data = dry_land(&[]);
+    let open = StartPlacementOptions {
+        min_distance_override: Some(3),
+        ..StartPlacementOptions::default()
+    };
+    let starts = |seed: u64| place_starts(&data, &[], 4, seed, &open).unwrap().starts;
+    assert_eq!(starts(5), starts(5));
+    assert_ne!(starts(5), starts(6));
+}
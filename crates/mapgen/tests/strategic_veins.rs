@@ -0,0 +1,119 @@
+use std::collections::BTreeSet;
+
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, resources::ResourceType, terrain::Terrain, tile::Tile},
+    pipeline::{
+        helpers::{StrategicConfig, default_resources_config},
+        map::Map,
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+        resources::place_strategic_veins,
+    },
+};
+
+fn hex(map: &Map, idx: usize) -> HexCoord {
+    map.tiles()[idx].hex_coords()
+}
+
+#[test]
+fn veins_are_compact_clusters_on_allowed_terrain() {
+    let config = default_resources_config().strategic;
+    let map = Map::new_with_type(Some(3), MapSizes::Small, MapTypes::Continents);
+    let veins = map.strategic_veins();
+    assert!(!veins.is_empty());
+
+    let mut taken = BTreeSet::new();
+    for vein in veins {
+        let resource = config.get(vein.resource);
+        assert!((resource.min_radius..=resource.max_radius).contains(&vein.radius), "{vein:?}");
+        assert!(
+            (resource.min_deposits..=resource.max_deposits).contains(&vein.deposits.len()),
+            "{vein:?}"
+        );
+
+        let center = hex(&map, vein.deposits[0]);
+        for &idx in &vein.deposits {
+            let tile = &map.tiles()[idx];
+            assert!(hex(&map, idx).distance(&center) <= vein.radius, "{vein:?}");
+            assert!(resource.terrains.contains(&tile.terrain()), "{vein:?}");
+            assert_eq!(tile.landmass_id(), Some(vein.landmass_id));
+            assert_eq!(tile.strategic_resource(), Some(vein.resource));
+            assert_eq!(tile.resource(), Some(ResourceType::Strategic));
+            assert!(taken.insert(idx), "deposit {idx} is shared");
+        }
+    }
+
+    let deposits = map.tiles().iter().filter(|tile| tile.strategic_resource().is_some()).count();
+    assert_eq!(deposits, taken.len());
+}
+
+#[test]
+fn every_large_landmass_gets_a_vein() {
+    let config = default_resources_config().strategic;
+    let size = MapSizes::Small;
+    let min_landmass = config.guaranteed_min_landmass(&size);
+    for seed in 1..=4 {
+        let map = Map::new_with_type(Some(seed), size, MapTypes::Continents);
+        let with_veins: BTreeSet<usize> = map.strategic_veins().iter().map(|vein| vein.landmass_id).collect();
+        for info in map.landmass_stats().iter().filter(|info| info.size >= min_landmass) {
+            assert!(with_veins.contains(&info.id), "seed {seed}: landmass {} ({} tiles)", info.id, info.size);
+        }
+    }
+}
+
+#[test]
+fn vein_count_stays_within_the_budget() {
+    let config = default_resources_config().strategic;
+    let small = config.vein_budget(&MapSizes::Small, MapTypes::Continents);
+    assert!(config.vein_budget(&MapSizes::Duel, MapTypes::Continents) < small);
+    assert!(config.vein_budget(&MapSizes::Huge, MapTypes::Continents) > small);
+    assert!(config.vein_budget(&MapSizes::Small, MapTypes::IslandsContinents) < small);
+
+    for map_type in [MapTypes::Continents, MapTypes::IslandsContinents] {
+        let map = Map::new_with_type(Some(2), MapSizes::Small, map_type);
+        let budget = config.vein_budget(map.size(), map_type);
+        let deposits: usize = map.strategic_veins().iter().map(|vein| vein.deposits.len()).sum();
+        assert!(map.strategic_veins().len() <= budget, "{map_type}");
+        assert!(deposits <= budget * config.iron.max_deposits.max(config.horses.max_deposits).max(config.niter.max_deposits));
+    }
+}
+
+/// Plains of a single landmass, the west half is young (age 0) and the east half old (age 255)
+fn half_old_plains(size: &MapSizes) -> Vec<Tile> {
+    let (width, height) = size.dimensions();
+    (0..width * height)
+        .map(|idx| {
+            let (x, y) = (idx % width, idx / width);
+            let mut tile = Tile::new(HexCoord::new(x as i32, y as i32), Terrain::Plains, false);
+            tile.set_landmass_id(Some(0));
+            tile.set_geological_age(if x < width / 2 { 0 } else { 255 });
+            tile
+        })
+        .collect()
+}
+
+fn vein_centers(tiles: &[Tile], size: &MapSizes, config: &StrategicConfig) -> Vec<usize> {
+    place_strategic_veins(5, tiles, &[], size, MapTypes::Continents, config)
+        .iter()
+        .map(|vein| vein.deposits[0] % size.dimensions().0)
+        .collect()
+}
+
+#[test]
+fn age_weight_moves_veins_to_old_terrain() {
+    let size = MapSizes::Small;
+    let (width, _) = size.dimensions();
+    let tiles = half_old_plains(&size);
+    let mut config = default_resources_config().strategic;
+
+    let unweighted = vein_centers(&tiles, &size, &config);
+    assert!(unweighted.iter().any(|&x| x < width / 2), "{unweighted:?}");
+
+    for resource in [&mut config.iron, &mut config.horses, &mut config.niter] {
+        resource.age_weight = 1.0;
+    }
+    let weighted = vein_centers(&tiles, &size, &config);
+    assert!(!weighted.is_empty());
+    assert!(weighted.iter().all(|&x| x >= width / 2), "{weighted:?}");
+    assert_eq!(weighted, vein_centers(&tiles, &size, &config));
+}
@@ -0,0 +1,63 @@
+use civorum_mapgen::pipeline::{
+    biomes::{generate_map_with_type, generate_temperature_range},
+    helpers::{ContinentalityConfig, neighbors_odd_r},
+    map_sizes::MapSizes,
+    map_types::MapTypes,
+};
+
+const DUEL: MapSizes = MapSizes::Duel;
+
+/// 44x26 landmask with a large block (x 4-27, y 3-22) and two 2x2 islands
+fn landmask() -> Vec<u8> {
+    let (width, height) = DUEL.dimensions();
+    (0..width * height)
+        .map(|idx| {
+            let (x, y) = (idx % width, idx / width);
+            let block = (4..=27).contains(&x) && (3..=22).contains(&y);
+            let islands = (34..=35).contains(&x) && ((4..=5).contains(&y) || (18..=19).contains(&y));
+            u8::from(block || islands)
+        })
+        .collect()
+}
+
+#[test]
+fn small_islands_stay_maritime() {
+    let (width, _) = DUEL.dimensions();
+    let config = ContinentalityConfig::default();
+    let range = generate_temperature_range(&landmask(), &DUEL, &config);
+
+    for y in [4, 5, 18, 19] {
+        for x in [34, 35] {
+            assert_eq!(range[y * width + x], config.maritime_range, "({x}, {y})");
+        }
+    }
+    // water is maritime as well
+    assert_eq!(range[0], config.maritime_range);
+}
+
+#[test]
+fn the_widest_swing_lies_inside_the_largest_landmass() {
+    let (width, height) = DUEL.dimensions();
+    let mask = landmask();
+    let range = generate_temperature_range(&mask, &DUEL, &ContinentalityConfig::default());
+
+    let peak = (0..range.len()).max_by_key(|&idx| range[idx]).unwrap();
+    let (x, y) = (peak % width, peak / width);
+    assert!((4..=27).contains(&x) && (3..=22).contains(&y), "({x}, {y})");
+    // interior: the peak and all its neighbours are land
+    assert!(neighbors_odd_r(x, y, width, height).into_iter().all(|(nx, ny)| mask[ny * width + nx] == 1));
+    assert!(range[peak] > range[3 * width + 4]);
+}
+
+#[test]
+fn temperature_range_is_deterministic_per_seed() {
+    for (size, map_type, seed) in [
+        (MapSizes::Duel, MapTypes::Continents, 3u64),
+        (MapSizes::Small, MapTypes::Pangea, 8),
+    ] {
+        let first = generate_map_with_type(&seed, &size, map_type);
+        let second = generate_map_with_type(&seed, &size, map_type);
+        assert_eq!(first.temperature_range, second.temperature_range);
+        assert_eq!(first.temperature_range.len(), size.grid_size());
+    }
+}
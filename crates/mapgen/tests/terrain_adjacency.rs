@@ -0,0 +1,103 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::{AdjacencyMatrix, AdjacencyNormalization, terrain_adjacency_matrix},
+        map::Map,
+        map_sizes::MapSizes,
+        sanity::{SanityCode, check_map},
+    },
+};
+
+/// Neighbor pairs of a `width` x `height` odd-r grid: `width - 1` in every row plus `2 * width - 1` between two rows
+fn grid_pairs(width: usize, height: usize) -> u64 {
+    (height * (width - 1) + (height - 1) * (2 * width - 1)) as u64
+}
+
+fn terrains(map: &Map) -> Vec<Terrain> {
+    map.tiles().iter().map(|tile| tile.terrain()).collect()
+}
+
+#[test]
+fn hand_built_map_counts() {
+    use Terrain::*;
+
+    // row 1 is shifted right:
+    //  D G G
+    //   G S S
+    //  O O O
+    let terrain = [Desert, Grassland, Grassland, Grassland, Snow, Snow, Ocean, Ocean, Ocean];
+    let matrix = terrain_adjacency_matrix(&terrain, 3, 3);
+
+    assert_eq!(matrix.pair_count(), grid_pairs(3, 3));
+    assert_eq!(matrix.unordered(Desert, Grassland), 2);
+    assert_eq!(matrix.unordered(Grassland, Grassland), 2);
+    assert_eq!(matrix.unordered(Grassland, Snow), 4);
+    assert_eq!(matrix.unordered(Snow, Snow), 1);
+    assert_eq!(matrix.unordered(Grassland, Ocean), 2);
+    assert_eq!(matrix.unordered(Snow, Ocean), 3);
+    assert_eq!(matrix.unordered(Ocean, Ocean), 2);
+    assert_eq!(matrix.unordered(Desert, Snow), 0);
+    assert_eq!(matrix.get(Grassland, Grassland), 4);
+    assert_eq!(matrix.anomalies(), [(Snow, Grassland, 4)]);
+
+    let csv = matrix.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("terrain,Plains,Grassland,Desert,Tundra,Snow,CoastLake,Ocean,Mountain"));
+    assert_eq!(lines.nth(1), Some("Grassland,0,4,2,0,4,0,2,0"));
+    assert_eq!(matrix.to_string().lines().count(), Terrain::ALL.len() + 1);
+}
+
+#[test]
+fn matrices_are_symmetric_and_count_every_pair() {
+    for size in [MapSizes::Duel, MapSizes::Small] {
+        let map = Map::new(Some(2), size);
+        let (width, height) = size.dimensions();
+        let matrix = terrain_adjacency_matrix(&terrains(&map), width, height);
+
+        for a in Terrain::ALL {
+            for b in Terrain::ALL {
+                assert_eq!(matrix.get(a, b), matrix.get(b, a), "{a:?} {b:?}");
+            }
+        }
+        assert_eq!(matrix.pair_count(), grid_pairs(width, height), "{size:?}");
+
+        let rows = matrix.normalized(AdjacencyNormalization::Rows);
+        for (terrain, row) in Terrain::ALL.iter().zip(rows) {
+            let sum: f64 = row.iter().sum();
+            assert!(sum == 0.0 || (sum - 1.0).abs() < 1e-9, "{terrain:?}: {sum}");
+        }
+        let total: f64 = matrix.normalized(AdjacencyNormalization::Total).iter().flatten().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn matrices_add_up_across_maps() {
+    let (width, height) = MapSizes::Duel.dimensions();
+    let matrices: Vec<AdjacencyMatrix> = (0..3)
+        .map(|seed| terrain_adjacency_matrix(&terrains(&Map::new(Some(seed), MapSizes::Duel)), width, height))
+        .collect();
+    let total: AdjacencyMatrix = matrices.iter().copied().sum();
+    assert_eq!(total.pair_count(), 3 * grid_pairs(width, height));
+    assert_eq!(total, matrices[0] + matrices[1] + matrices[2]);
+    assert_eq!(
+        total.get(Terrain::Ocean, Terrain::Ocean),
+        matrices.iter().map(|matrix| matrix.get(Terrain::Ocean, Terrain::Ocean)).sum::<u64>()
+    );
+}
+
+#[test]
+fn anomalies_are_reported() {
+    use Terrain::*;
+
+    let terrain = [Snow, Desert, Desert, Tundra, Snow, Grassland];
+    let matrix = terrain_adjacency_matrix(&terrain, 3, 2);
+    assert_eq!(
+        matrix.anomalies(),
+        [(Snow, Desert, 3), (Snow, Grassland, 1), (Tundra, Desert, 1)]
+    );
+
+    // generated maps keep snow and desert apart
+    let map = Map::new(Some(5), MapSizes::Small);
+    assert!(!check_map(&map).iter().any(|warning| warning.code == SanityCode::TerrainAdjacencyAnomaly));
+}
@@ -0,0 +1,91 @@
+use civorum_mapgen::{
+    map_components::terrain::{Feature, Terrain},
+    pipeline::text_map::{GeneratedMapData, TextMapError, parse_text_map, write_text_map},
+};
+
+const ISLAND: &str = "
+#terrain
+......
+ .~pp~.
+.~g^d.
+ ......
+#hills
+......
+ ...h..
+..h...
+ ......
+#features
+......
+ ..w...
+...m..
+ ......
+";
+
+#[test]
+fn parses_terrain_hills_and_features() {
+    let data = parse_text_map(ISLAND).unwrap();
+    assert_eq!((data.width, data.height), (6, 4));
+    assert_eq!(data.terrain[data.index(1, 1)], Terrain::CoastLake);
+    assert_eq!(data.terrain[data.index(3, 2)], Terrain::Mountain);
+    assert!(data.hills[data.index(3, 1)]);
+    assert!(data.hills[data.index(2, 2)]);
+    assert_eq!(data.hills.iter().filter(|hill| **hill).count(), 2);
+    assert_eq!(data.features[data.index(2, 1)], Some(Feature::Woods));
+    assert_eq!(data.features[data.index(3, 2)], Some(Feature::Marsh));
+}
+
+#[test]
+fn custom_legend_replaces_the_default() {
+    let data = parse_text_map("L = plains\nW = ocean\n#terrain\nWLW\n").unwrap();
+    assert_eq!(data.terrain, vec![Terrain::Ocean, Terrain::Plains, Terrain::Ocean]);
+    assert!(parse_text_map("L = plains\n#terrain\n.L.\n").is_err());
+}
+
+#[test]
+fn write_then_parse_round_trips() {
+    let data = parse_text_map(ISLAND).unwrap();
+    assert_eq!(parse_text_map(&write_text_map(&data)).unwrap(), data);
+
+    let plain = GeneratedMapData::filled(3, 3, Terrain::Grassland);
+    let written = write_text_map(&plain);
+    assert!(!written.contains("#hills") && !written.contains("#features"));
+    assert_eq!(parse_text_map(&written).unwrap(), plain);
+}
+
+#[test]
+fn unknown_characters_report_their_position() {
+    let err = parse_text_map("#terrain\n...\n .?.\n").unwrap_err();
+    assert_eq!(err, TextMapError::UnknownCharacter { line: 3, column: 3, character: '?' });
+    assert_eq!(err.to_string(), "line 3, column 3: unknown character '?'");
+
+    let err = parse_text_map("#terrain\n...\n#hills\n.x.\n").unwrap_err();
+    assert_eq!(err, TextMapError::UnknownCharacter { line: 4, column: 2, character: 'x' });
+}
+
+#[test]
+fn ragged_rows_report_where_they_stop_matching() {
+    let err = parse_text_map("#terrain\n....\n ..\n").unwrap_err();
+    assert_eq!(err, TextMapError::RaggedRow { line: 3, column: 4, expected: 4, found: 2 });
+    assert_eq!(err.to_string(), "line 3, column 4: row has 2 tiles, expected 4");
+
+    let err = parse_text_map("#terrain\n..\n...\n").unwrap_err();
+    assert_eq!(err, TextMapError::RaggedRow { line: 3, column: 3, expected: 2, found: 3 });
+}
+
+#[test]
+fn malformed_headers_and_sections_are_rejected() {
+    assert_eq!(parse_text_map("...\n").unwrap_err(), TextMapError::InvalidLegend { line: 1 });
+    assert_eq!(
+        parse_text_map("x = lava\n#terrain\nx\n").unwrap_err().to_string(),
+        "line 1: unknown terrain 'lava'"
+    );
+    assert_eq!(
+        parse_text_map("#terrain\n.\n#rivers\n.\n").unwrap_err(),
+        TextMapError::UnknownSection { line: 3, name: "rivers".to_string() }
+    );
+    assert_eq!(
+        parse_text_map("#terrain\n..\n..\n#hills\n..\n").unwrap_err().to_string(),
+        "line 4: section '#hills' has 1 rows, expected 2"
+    );
+    assert_eq!(parse_text_map("p = plains\n").unwrap_err(), TextMapError::MissingTerrain);
+}
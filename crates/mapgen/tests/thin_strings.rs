@@ -0,0 +1,106 @@
+use civorum_mapgen::pipeline::{
+    helpers::{RepairConfig, default_landmasses_config},
+    land::{peninsula_necks, resolve_thin_strings},
+};
+
+const WIDTH: usize = 30;
+const HEIGHT: usize = 12;
+/// String tiles of the dumbbell, the end tiles at x 19 touch three blob tiles and are not part of it
+const STRING: std::ops::RangeInclusive<usize> = 10..=18;
+
+/// Two 8x8 blobs (x 2-9 and 20-27) joined by a one-tile-wide string along row 5
+fn dumbbell() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|idx| {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+            let blob = (2..=9).contains(&x) || (20..=27).contains(&x);
+            u8::from(((2..=9).contains(&y) && blob) || (y == 5 && (10..=19).contains(&x)))
+        })
+        .collect()
+}
+
+fn repair(thicken: bool) -> RepairConfig {
+    RepairConfig {
+        min_connection_width: 2,
+        thin_string_min_length: 3,
+        thicken_thin_strings: thicken,
+        ..default_landmasses_config().continents.repair
+    }
+}
+
+#[test]
+fn thin_strings_are_carved_away() {
+    let mut grid = dumbbell();
+    resolve_thin_strings(&mut grid, WIDTH, HEIGHT, &repair(false), 10);
+
+    for x in STRING {
+        assert_eq!(grid[5 * WIDTH + x], 0, "x {x}");
+    }
+    // the blobs are untouched
+    let blobs = dumbbell().iter().filter(|&&land| land == 1).count() - STRING.count();
+    assert_eq!(grid.iter().filter(|&&land| land == 1).count(), blobs);
+}
+
+#[test]
+fn thin_strings_are_thickened_into_an_isthmus() {
+    let mut grid = dumbbell();
+    resolve_thin_strings(&mut grid, WIDTH, HEIGHT, &repair(true), 10);
+
+    for x in STRING {
+        // a ring of land grows on both sides, the string is 3 tiles wide for a width of 2
+        let column = (3..=7).filter(|y| grid[y * WIDTH + x] == 1).count();
+        assert_eq!(column, 3, "x {x}");
+        assert!((4..=6).all(|y| grid[y * WIDTH + x] == 1), "x {x}");
+    }
+}
+
+#[test]
+fn short_strings_and_disabled_checks_are_left_alone() {
+    let mut grid = dumbbell();
+    let mut long_only = repair(false);
+    long_only.thin_string_min_length = STRING.count() + 1;
+    resolve_thin_strings(&mut grid, WIDTH, HEIGHT, &long_only, 10);
+    assert_eq!(grid, dumbbell());
+
+    // min_connection_width 0 is the default of configs without the setting
+    let off = RepairConfig {
+        min_connection_width: 0,
+        ..repair(false)
+    };
+    resolve_thin_strings(&mut grid, WIDTH, HEIGHT, &off, 10);
+    assert_eq!(grid, dumbbell());
+
+    // both sides must be larger than an island
+    resolve_thin_strings(&mut grid, WIDTH, HEIGHT, &repair(false), 64);
+    assert_eq!(grid, dumbbell());
+}
+
+/// Blob (x 2-9) with a peninsula (x 11-14) attached by the single neck tile (10, 5)
+fn peninsula() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|idx| {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+            let blob = (2..=9).contains(&x) && (2..=9).contains(&y);
+            let peninsula = (11..=14).contains(&x) && (4..=6).contains(&y);
+            u8::from(blob || peninsula || (x, y) == (10, 5))
+        })
+        .collect()
+}
+
+#[test]
+fn one_tile_necks_are_protected() {
+    let grid = peninsula();
+    // the neck and the blob tile it hangs on
+    let neck = [5 * WIDTH + 9, 5 * WIDTH + 10];
+    for min_width in [0, 1] {
+        let necks = peninsula_necks(&grid, WIDTH, HEIGHT, min_width);
+        let protected: Vec<usize> = (0..necks.len()).filter(|&idx| necks[idx]).collect();
+        assert_eq!(protected, neck, "min width {min_width}");
+    }
+
+    // a wider guard also protects the land next to the neck
+    let necks = peninsula_necks(&grid, WIDTH, HEIGHT, 2);
+    assert!(neck.iter().all(|&idx| necks[idx]));
+    assert!(necks[5 * WIDTH + 8] && necks[5 * WIDTH + 11]);
+    assert!(!necks[5 * WIDTH + 2]);
+}
@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        biomes::{MapLayers, generate_map_with_type},
+        helpers::{BiomesConfig, biomes_config, with_biomes_config},
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+const SEEDS: [u64; 3] = [1, 2, 3];
+const MAP_TYPES: [MapTypes; 3] = [MapTypes::Continents, MapTypes::Pangea, MapTypes::Terra];
+const SIZE: MapSizes = MapSizes::Small;
+
+type Maps = Vec<(u64, MapTypes, MapLayers)>;
+
+/// Layers of every map of the test with the given config, with the seed and map type
+fn generate_maps(config: &BiomesConfig) -> Maps {
+    SEEDS
+        .iter()
+        .flat_map(|&seed| {
+            MAP_TYPES.iter().map(move |&map_type| {
+                let layers = with_biomes_config(config.clone(), || generate_map_with_type(&seed, &SIZE, map_type));
+                (seed, map_type, layers)
+            })
+        })
+        .collect()
+}
+
+/// The maps with and without dithering, generated once for all tests
+fn maps() -> &'static (Maps, Maps) {
+    static MAPS: OnceLock<(Maps, Maps)> = OnceLock::new();
+    MAPS.get_or_init(|| (generate_maps(&dithered()), generate_maps(&undithered())))
+}
+
+fn dithered() -> BiomesConfig {
+    let config = biomes_config().as_ref().clone();
+    assert!(config.terrain.threshold_dither_window > 0, "the shipped config dithers");
+    config
+}
+
+fn undithered() -> BiomesConfig {
+    let mut config = biomes_config().as_ref().clone();
+    config.terrain.threshold_dither_window = 0;
+    config
+}
+
+/// For every row and snow/tundra threshold with at least 10 land tiles in the middle half of the dither window
+/// of the shipped config: how many of those tiles are on the cold side of the threshold, and how many there are
+fn boundary_rows(layers: &MapLayers) -> Vec<(usize, u8, usize, usize)> {
+    let thresholds = &dithered().terrain;
+    let half_window = (thresholds.threshold_dither_window / 2) as i32;
+    let (width, height) = SIZE.dimensions();
+    let mut rows = Vec::new();
+    for y in 0..height {
+        let row = y * width..(y + 1) * width;
+        for (threshold, cold) in [
+            (thresholds.snow_temp_threshold, &[Terrain::Snow][..]),
+            (thresholds.tundra_temp_threshold, &[Terrain::Snow, Terrain::Tundra][..]),
+        ] {
+            let near: Vec<Terrain> = row
+                .clone()
+                .filter(|&idx| !layers.terrain[idx].is_water() && layers.terrain[idx] != Terrain::Mountain)
+                .filter(|&idx| (layers.temperature[idx] as i32 - threshold as i32).abs() <= half_window)
+                .map(|idx| layers.terrain[idx])
+                .collect();
+            if near.len() >= 10 {
+                let n_cold = near.iter().filter(|terrain| cold.contains(terrain)).count();
+                rows.push((y, threshold, n_cold, near.len()));
+            }
+        }
+    }
+    rows
+}
+
+#[test]
+fn dithered_boundary_rows_are_never_one_biome() {
+    // without dithering the thresholds draw straight lines through some rows
+    let (dithered_maps, undithered_maps) = maps();
+    let hard_lines = undithered_maps
+        .iter()
+        .flat_map(|(_, _, layers)| boundary_rows(layers))
+        .filter(|&(_, _, n_cold, n)| n_cold == 0 || n_cold == n)
+        .count();
+    assert!(hard_lines > 0);
+
+    let mut n_rows = 0;
+    for (seed, map_type, layers) in dithered_maps {
+        for (y, threshold, n_cold, n) in boundary_rows(layers) {
+            assert!(
+                0 < n_cold && n_cold < n,
+                "{map_type:?} seed {seed}: row {y} is one biome at threshold {threshold} ({n_cold} of {n} tiles cold)"
+            );
+            n_rows += 1;
+        }
+    }
+    assert!(n_rows >= SEEDS.len() * MAP_TYPES.len(), "only {n_rows} boundary rows");
+}
+
+#[test]
+fn dithering_keeps_the_biome_totals() {
+    let totals = |maps: &Maps| {
+        let mut totals = BTreeMap::new();
+        for (_, _, layers) in maps {
+            for &terrain in &layers.terrain {
+                *totals.entry(terrain).or_insert(0usize) += 1;
+            }
+        }
+        totals
+    };
+    let (dithered_maps, undithered_maps) = maps();
+    let (dithered, undithered) = (totals(dithered_maps), totals(undithered_maps));
+
+    for (terrain, &expected) in &undithered {
+        let actual = dithered.get(terrain).copied().unwrap_or(0);
+        let change = (actual as f32 - expected as f32).abs() / expected as f32;
+        assert!(change <= 0.05, "{terrain:?}: {actual} tiles with dithering, {expected} without");
+    }
+}
@@ -0,0 +1,132 @@
+use civorum_mapgen::{
+    map_components::{
+        hex_coords::HexCoord,
+        terrain::{Feature, Terrain},
+        tile_modifiers::{TileModifierRules, TileModifiers},
+    },
+    pipeline::{
+        map::Map,
+        map_sizes::MapSizes,
+        pathfinding::{find_path, has_line_of_sight, movement_cost},
+        text_map::GeneratedMapData,
+    },
+};
+
+const TERRAINS: [Terrain; 8] = [
+    Terrain::Plains,
+    Terrain::Grassland,
+    Terrain::Desert,
+    Terrain::Tundra,
+    Terrain::Snow,
+    Terrain::CoastLake,
+    Terrain::Ocean,
+    Terrain::Mountain,
+];
+
+const FEATURES: [Option<Feature>; 10] = [
+    None,
+    Some(Feature::Woods),
+    Some(Feature::Rainforest),
+    Some(Feature::Marsh),
+    Some(Feature::Floodplains),
+    Some(Feature::Oasis),
+    Some(Feature::Fissure),
+    Some(Feature::VolanicSoil),
+    Some(Feature::Reef),
+    Some(Feature::Ice),
+];
+
+#[test]
+fn every_tile_combination_has_modifiers() {
+    let rules = TileModifierRules::default();
+    for terrain in TERRAINS {
+        for hill in [false, true] {
+            for feature in FEATURES {
+                let modifiers = rules.modifiers(terrain, hill, feature);
+                let blocked = terrain.is_water() || terrain == Terrain::Mountain || feature == Some(Feature::Ice);
+                assert_eq!(modifiers.is_passable(), !blocked, "{terrain:?} hill {hill} {feature:?}");
+                if modifiers.is_passable() {
+                    assert!(modifiers.movement_cost >= 1, "{terrain:?} hill {hill} {feature:?}");
+                }
+
+                let feature_bonus = feature.map_or(0, |feature| rules.features.get(feature).defense_bonus);
+                let hill_bonus = if hill { rules.hills.defense_bonus } else { 0 };
+                assert_eq!(modifiers.defense_bonus, hill_bonus + feature_bonus);
+            }
+        }
+    }
+}
+
+#[test]
+fn paths_detour_around_marsh() {
+    let mut data = GeneratedMapData::filled(7, 3, Terrain::Plains);
+    for x in 2..=4 {
+        let idx = data.index(x, 1);
+        data.features[idx] = Some(Feature::Marsh);
+    }
+    let modifiers = data.tile_modifiers(&TileModifierRules::default());
+    let cost = |idx: usize| movement_cost(&modifiers[idx]);
+
+    let path = find_path(7, 3, HexCoord::new(0, 1), HexCoord::new(6, 1), cost).expect("plains are passable");
+    assert_eq!(path.tiles.first(), Some(&HexCoord::new(0, 1)));
+    assert_eq!(path.tiles.last(), Some(&HexCoord::new(6, 1)));
+    assert_eq!(path.cost, 7);
+    assert!(path.tiles.iter().all(|hex| hex.y() != 1 || !(2..=4).contains(&hex.x())));
+
+    // without the detour rows the marsh has to be crossed
+    let mut strip = GeneratedMapData::filled(7, 1, Terrain::Plains);
+    strip.features[3] = Some(Feature::Marsh);
+    let modifiers = strip.tile_modifiers(&TileModifierRules::default());
+    let path = find_path(7, 1, HexCoord::new(0, 0), HexCoord::new(6, 0), |idx| movement_cost(&modifiers[idx]));
+    assert_eq!(path.map(|path| path.cost), Some(8));
+}
+
+#[test]
+fn water_is_impassable() {
+    let mut data = GeneratedMapData::filled(5, 1, Terrain::Plains);
+    data.terrain[2] = Terrain::Ocean;
+    let modifiers = data.tile_modifiers(&TileModifierRules::default());
+    let path = find_path(5, 1, HexCoord::new(0, 0), HexCoord::new(4, 0), |idx| movement_cost(&modifiers[idx]));
+    assert!(path.is_none());
+
+    let map = Map::new(Some(3), MapSizes::Duel);
+    let land = map.tiles().iter().find(|tile| tile.modifiers().is_passable()).expect("map has land");
+    let water = map.tiles().iter().find(|tile| tile.terrain().is_water()).expect("map has water");
+    assert!(!water.modifiers().is_passable());
+    assert!(map.find_path(land.hex_coords(), water.hex_coords()).is_none());
+    assert_eq!(
+        map.find_path(land.hex_coords(), land.hex_coords()).map(|path| path.cost),
+        Some(0)
+    );
+}
+
+#[test]
+fn generated_tiles_use_the_configured_rules() {
+    let rules = TileModifierRules::default();
+    let map = Map::new(Some(8), MapSizes::Duel);
+    for (tile, modifiers) in map.tiles().iter().zip(map.tile_modifiers()) {
+        assert_eq!(modifiers, rules.modifiers(tile.terrain(), tile.is_hill(), tile.feature()));
+    }
+}
+
+#[test]
+fn sight_is_blocked_by_costly_tiles() {
+    let sight = |modifiers: &[TileModifiers]| {
+        let modifiers = modifiers.to_vec();
+        move |idx: usize| u32::from(modifiers[idx].sight_cost)
+    };
+
+    let mut data = GeneratedMapData::filled(7, 1, Terrain::Plains);
+    let open = data.tile_modifiers(&TileModifierRules::default());
+    let (from, to) = (HexCoord::new(0, 0), HexCoord::new(3, 0));
+    assert!(has_line_of_sight(7, 1, from, to, 3, sight(&open)));
+    assert!(!has_line_of_sight(7, 1, from, to, 2, sight(&open)));
+    assert!(has_line_of_sight(7, 1, from, from, 0, sight(&open)));
+
+    data.terrain[1] = Terrain::Mountain;
+    let blocked = data.tile_modifiers(&TileModifierRules::default());
+    assert!(!has_line_of_sight(7, 1, from, to, 3, sight(&blocked)));
+    // the mountain itself is still visible
+    assert!(has_line_of_sight(7, 1, from, HexCoord::new(1, 0), 1, sight(&blocked)));
+    assert!(!has_line_of_sight(7, 1, from, HexCoord::new(9, 0), 20, sight(&blocked)));
+}
@@ -0,0 +1,71 @@
+use civorum_mapgen::{
+    map_components::tile_tags::TileTags,
+    pipeline::{document::MapDocument, map::Map, map_sizes::MapSizes},
+};
+use serde_json::json;
+
+fn tagged() -> TileTags {
+    let mut tags = TileTags::new();
+    for idx in [40, 3, 17] {
+        tags.add_tag(idx, "spawn:barbarian");
+    }
+    tags.add_tag(17, "goody_hut");
+    tags.add_tag(5, "goody_hut");
+    tags
+}
+
+#[test]
+fn tag_strings_are_interned_once() {
+    let mut tags = tagged();
+    assert_eq!(tags.n_distinct_tags(), 2);
+    assert_eq!(tags.n_tagged_tiles(), 4);
+
+    // adding a tag again changes nothing
+    tags.add_tag(3, "spawn:barbarian");
+    assert_eq!(tags.n_distinct_tags(), 2);
+    assert_eq!(tags.tags_of(3).collect::<Vec<_>>(), ["spawn:barbarian"]);
+    assert_eq!(tags.tags_of(17).collect::<Vec<_>>(), ["spawn:barbarian", "goody_hut"]);
+}
+
+#[test]
+fn tiles_with_tag_lists_exactly_the_tagged_tiles() {
+    let mut tags = tagged();
+    assert_eq!(tags.tiles_with_tag("spawn:barbarian").collect::<Vec<_>>(), [3, 17, 40]);
+    assert_eq!(tags.tiles_with_tag("goody_hut").collect::<Vec<_>>(), [5, 17]);
+    assert_eq!(tags.tiles_with_tag("spawn").count(), 0);
+
+    assert!(tags.remove_tag(17, "spawn:barbarian"));
+    assert!(!tags.remove_tag(17, "spawn:barbarian"));
+    assert_eq!(tags.tiles_with_tag("spawn:barbarian").collect::<Vec<_>>(), [3, 40]);
+    assert!(tags.has_tag(17, "goody_hut"));
+
+    assert!(tags.remove_tag(5, "goody_hut") && tags.remove_tag(17, "goody_hut"));
+    assert_eq!(tags.n_tagged_tiles(), 2);
+    assert_eq!(tags.to_json_value(), json!({ "spawn:barbarian": [3, 40] }));
+}
+
+#[test]
+fn tags_round_trip_through_json() {
+    let mut map = Map::new(Some(6), MapSizes::Duel);
+    *map.tags_mut() = tagged();
+
+    let expected = json!({ "spawn:barbarian": [3, 17, 40], "goody_hut": [5, 17] });
+    assert_eq!(map.to_json_value()["tags"], expected);
+    let mut ndjson = Vec::new();
+    map.export_ndjson(&mut ndjson).unwrap();
+    let header: serde_json::Value = serde_json::from_slice(ndjson.split(|&b| b == b'\n').next().unwrap()).unwrap();
+    assert_eq!(header["tags"], expected);
+
+    let path = std::env::temp_dir().join(format!("civorum_tile_tags_{}.json", std::process::id()));
+    map.save(&path).unwrap();
+    let document = MapDocument::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    for tag in ["spawn:barbarian", "goody_hut"] {
+        assert_eq!(
+            document.tags.tiles_with_tag(tag).collect::<Vec<_>>(),
+            map.tags().tiles_with_tag(tag).collect::<Vec<_>>()
+        );
+    }
+    let loaded = document.to_map().unwrap();
+    assert_eq!(loaded.tags().to_json_value(), expected);
+}
@@ -0,0 +1,49 @@
+use civorum_mapgen::map_components::{
+    hex_coords::HexCoord,
+    rivers::RiverClass,
+    terrain::{Terrain, WaterDepth},
+    tile::Tile,
+    tile_modifiers::TileModifierRules,
+    yields::BaseYields,
+};
+
+#[test]
+fn a_larger_river_replaces_the_bonus_and_keeps_the_appeal() {
+    let mut tile = Tile::new(HexCoord::new(3, 4), Terrain::Grassland, false);
+    let base = Terrain::Grassland.base_yields();
+    let modifiers = TileModifierRules::default().modifiers(Terrain::Grassland, false, None);
+    tile.set_modifiers(modifiers);
+    tile.set_appeal(3);
+
+    tile.set_river_class(RiverClass::Minor);
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), base.get_yield(BaseYields::Food) + 1);
+
+    tile.set_river_class(RiverClass::Great);
+    assert_eq!(tile.river_class(), Some(RiverClass::Great));
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), base.get_yield(BaseYields::Food) + 2);
+    assert_eq!(tile.yields().get_yield(BaseYields::Gold), base.get_yield(BaseYields::Gold) + 1);
+    assert_eq!(tile.yields().get_yield(BaseYields::Appeal), 3);
+    assert_eq!(tile.modifiers(), modifiers);
+
+    // a smaller river changes nothing
+    tile.set_river_class(RiverClass::Moderate);
+    assert_eq!(tile.river_class(), Some(RiverClass::Great));
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), base.get_yield(BaseYields::Food) + 2);
+}
+
+#[test]
+fn a_new_water_depth_replaces_the_bonus_and_keeps_the_appeal() {
+    let mut tile = Tile::new(HexCoord::new(0, 0), Terrain::CoastLake, false);
+    let food = Terrain::CoastLake.base_yields().get_yield(BaseYields::Food);
+    tile.set_appeal(-2);
+
+    tile.set_water_depth(Some(WaterDepth::Shallow));
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), food + 1);
+    tile.set_water_depth(Some(WaterDepth::Shallow));
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), food + 1);
+    tile.set_water_depth(Some(WaterDepth::Deep));
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), food);
+    tile.set_water_depth(None);
+    assert_eq!(tile.yields().get_yield(BaseYields::Food), food);
+    assert_eq!(tile.yields().get_yield(BaseYields::Appeal), -2);
+}
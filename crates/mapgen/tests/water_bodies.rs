@@ -0,0 +1,126 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        analysis::lake_sizes,
+        helpers::landmasses_config_for,
+        map::Map,
+        map_sizes::MapSizes,
+        text_map::GeneratedMapData,
+        water_bodies::{WaterBodyKind, water_bodies},
+    },
+};
+
+/// Deep ocean with an island (x 8-21, y 5-14) ringed by shallow water, holding a 6 tile lake and a 1 tile pond
+fn island_map() -> GeneratedMapData {
+    let mut data = GeneratedMapData::filled(30, 20, Terrain::Ocean);
+    for y in 4..16 {
+        for x in 7..23 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = Terrain::CoastLake;
+        }
+    }
+    for y in 5..15 {
+        for x in 8..22 {
+            let idx = data.index(x, y);
+            data.terrain[idx] = Terrain::Grassland;
+        }
+    }
+    for (x, y) in [(11, 8), (12, 8), (13, 8), (11, 9), (12, 9), (13, 9), (18, 11)] {
+        let idx = data.index(x, y);
+        data.terrain[idx] = Terrain::CoastLake;
+    }
+    data
+}
+
+#[test]
+fn every_kind_has_hover_text() {
+    let data = island_map();
+    let (ids, bodies) = water_bodies(&data.terrain, data.width, data.height, 7, 4);
+    let kinds: Vec<WaterBodyKind> = bodies.iter().map(|body| body.kind).collect();
+    assert_eq!(
+        kinds,
+        [WaterBodyKind::Ocean, WaterBodyKind::Sea, WaterBodyKind::Lake, WaterBodyKind::Pond]
+    );
+
+    let (ocean, sea, lake, pond) = (&bodies[0], &bodies[1], &bodies[2], &bodies[3]);
+    assert_eq!(ocean.hover_text(&bodies), format!("World Ocean — {} tiles", 30 * 20 - 16 * 12));
+    assert_eq!(sea.connects_to, Some(0));
+    assert!(sea.name.starts_with("Sea of "));
+    assert_eq!(sea.hover_text(&bodies), format!("{} — 52 tiles — connects to World Ocean", sea.name));
+    assert!(lake.name.starts_with("Lake "));
+    assert_eq!(lake.hover_text(&bodies), format!("{} — 6 tiles", lake.name));
+    assert_eq!(lake.bounds, ((11, 8), (13, 9)));
+    assert!(pond.name.ends_with(" Pond"));
+    assert_eq!(pond.hover_text(&bodies), format!("{} — 1 tile", pond.name));
+    assert_eq!(ids[data.index(18, 11)], Some(3));
+    assert_eq!(ids[data.index(10, 10)], None);
+
+    // names only depend on the seed and the terrain
+    let (_, again) = water_bodies(&data.terrain, data.width, data.height, 7, 4);
+    assert_eq!(again, bodies);
+}
+
+#[test]
+fn every_water_tile_is_in_one_water_body() {
+    for seed in [3, 5] {
+        let map = Map::new(Some(seed), MapSizes::Small);
+        let mut counted = vec![0; map.water_bodies().len()];
+        for (idx, tile) in map.tiles().iter().enumerate() {
+            match map.water_body_at(idx) {
+                Some(body) => {
+                    assert!(tile.terrain().is_water());
+                    counted[body.id] += 1;
+                }
+                None => assert!(!tile.terrain().is_water()),
+            }
+        }
+        for (body, count) in map.water_bodies().iter().zip(counted) {
+            assert_eq!(body.size, count, "{}", body.name);
+        }
+        assert!(map.water_bodies().windows(2).all(|pair| pair[0].size >= pair[1].size));
+    }
+}
+
+#[test]
+fn lakes_follow_the_minimum_lake_size() {
+    let map = Map::new(Some(5), MapSizes::Small);
+    let (width, height) = MapSizes::Small.dimensions();
+    let terrain: Vec<Terrain> = map.tiles().iter().map(|tile| tile.terrain()).collect();
+    let min_lake_size = landmasses_config_for(&MapSizes::Small).global.min_lake_size;
+
+    let mut enclosed: Vec<usize> = lake_sizes(&terrain, width, height);
+    enclosed.sort_unstable();
+    let mut lakes_and_ponds: Vec<usize> = map
+        .water_bodies()
+        .iter()
+        .filter(|body| matches!(body.kind, WaterBodyKind::Lake | WaterBodyKind::Pond))
+        .map(|body| body.size)
+        .collect();
+    lakes_and_ponds.sort_unstable();
+    assert_eq!(lakes_and_ponds, enclosed);
+
+    for body in map.water_bodies() {
+        match body.kind {
+            WaterBodyKind::Lake => assert!(body.size >= min_lake_size),
+            WaterBodyKind::Pond => assert!(body.size < min_lake_size),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn names_are_unique_and_exported() {
+    let map = Map::new(Some(3), MapSizes::Standard);
+    let mut names: Vec<&str> = map.water_bodies().iter().map(|body| body.name.as_str()).collect();
+    let count = names.len();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), count);
+    assert_eq!(map.water_bodies().iter().filter(|body| body.name == "World Ocean").count(), 1);
+
+    let summary = map.to_json_value();
+    let exported = summary["water_bodies"].as_array().unwrap();
+    assert_eq!(exported.len(), count);
+    assert_eq!(exported[0]["name"], "World Ocean");
+    assert_eq!(exported[0]["kind"], "ocean");
+}
@@ -0,0 +1,56 @@
+use civorum_mapgen::{
+    map_components::{hex_coords::HexCoord, terrain::WaterDepth, yields::BaseYields},
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+
+#[test]
+fn water_depth_follows_the_neighbors() {
+    let map = Map::new(Some(5), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+    let mut seen = (false, false);
+    for tile in map.tiles() {
+        let hex = tile.hex_coords();
+        let depth = map.water_depth_at(hex);
+        assert_eq!(depth, tile.water_depth(), "{hex:?}");
+        if !tile.terrain().is_water() {
+            assert_eq!(depth, None);
+            continue;
+        }
+
+        let neighbors = map.neighbors(hex);
+        let deep = neighbors.len() == 6
+            && neighbors.iter().all(|n| {
+                map.tiles()[n.y() as usize * width + n.x() as usize]
+                    .terrain()
+                    .is_water()
+            });
+        let expected = if deep {
+            WaterDepth::Deep
+        } else {
+            WaterDepth::Shallow
+        };
+        assert_eq!(depth, Some(expected), "{hex:?}");
+        match expected {
+            WaterDepth::Deep => seen.0 = true,
+            WaterDepth::Shallow => seen.1 = true,
+        }
+
+        // shallow water yields one more food than deep water of the same terrain
+        let base_food = tile.terrain().base_yields().get_yield(BaseYields::Food);
+        let bonus = i32::from(expected == WaterDepth::Shallow);
+        assert_eq!(
+            tile.yields().get_yield(BaseYields::Food),
+            base_food + bonus,
+            "{hex:?}"
+        );
+    }
+    assert_eq!(seen, (true, true));
+
+    // water on the map edge is always shallow, hexes off the map have no depth
+    for x in 0..width {
+        let edge = HexCoord::new(x as i32, 0);
+        assert_ne!(map.water_depth_at(edge), Some(WaterDepth::Deep));
+    }
+    assert_eq!(map.water_depth_at(HexCoord::new(-1, 0)), None);
+    assert_eq!(map.water_depth_at(HexCoord::new(0, height as i32)), None);
+}
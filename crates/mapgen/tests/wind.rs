@@ -0,0 +1,83 @@
+use civorum_mapgen::{
+    map_components::hex_coords::{CompassDirection, HexCoord},
+    pipeline::{
+        helpers::{WindConfig, biomes_config, with_biomes_config},
+        map::Map,
+        map_sizes::MapSizes,
+        pathfinding::find_path_directed,
+        wind::{WindLayer, row_latitude, wind_assisted_cost, wind_band},
+    },
+};
+
+#[test]
+fn bands_turn_at_the_configured_latitudes() {
+    let config = WindConfig {
+        band_latitudes: vec![20.0, 50.0],
+    };
+    for size in MapSizes::ALL {
+        let (width, height) = size.dimensions();
+        let layer = WindLayer::new(width, height, &config);
+        assert_eq!(layer.rows().len(), height);
+        for (y, direction) in layer.rows().iter().enumerate() {
+            let latitude = row_latitude(y, height);
+            let expected = match (latitude.abs(), latitude >= 0.0) {
+                (lat, true) if lat < 20.0 => CompassDirection::SW,
+                (lat, true) if lat < 50.0 => CompassDirection::NE,
+                (_, true) => CompassDirection::SW,
+                (lat, false) if lat < 20.0 => CompassDirection::NW,
+                (lat, false) if lat < 50.0 => CompassDirection::SE,
+                (_, false) => CompassDirection::NW,
+            };
+            assert_eq!(*direction, expected, "{size:?} row {y} at {latitude}°");
+        }
+        // the wind turns between two rows exactly where a band latitude lies between their latitudes
+        for y in 1..height {
+            let (above, below) = (row_latitude(y - 1, height), row_latitude(y, height));
+            let crosses = [-50.0, -20.0, 0.0, 20.0, 50.0].iter().any(|&band| below < band && band <= above);
+            assert_eq!(layer.rows()[y - 1] != layer.rows()[y], crosses, "{size:?} rows {} and {y}", y - 1);
+        }
+    }
+    assert_eq!(wind_band(-35.0, &config.band_latitudes), 1);
+}
+
+#[test]
+fn maps_expose_the_configured_wind() {
+    let mut config = biomes_config().as_ref().clone();
+    config.wind.band_latitudes = vec![45.0];
+    let map = with_biomes_config(config.clone(), || Map::new(Some(3), MapSizes::Duel));
+    let (width, height) = MapSizes::Duel.dimensions();
+
+    assert_eq!(map.wind(), &WindLayer::new(width, height, &config.wind));
+    assert_eq!(map.wind_at(0), CompassDirection::NE);
+    assert_eq!(map.wind_at(width * height - 1), CompassDirection::SE);
+    assert_eq!(map.wind_at(width * (height / 2 - 1)), CompassDirection::SW);
+
+    let json = map.to_json_value();
+    let angles: Vec<f64> = json["wind"].as_array().unwrap().iter().map(|angle| angle.as_f64().unwrap()).collect();
+    assert_eq!(angles.len(), height);
+    assert_eq!(angles[0], 60.0);
+}
+
+#[test]
+fn sailing_prefers_the_downwind_route() {
+    // open ocean, the wind blows east in the two top rows and west everywhere else
+    let (width, height) = (16, 7);
+    let rows = (0..height)
+        .map(|y| if y < 2 { CompassDirection::E } else { CompassDirection::W })
+        .collect();
+    let wind = WindLayer::from_rows(width, rows);
+    let ocean = |_| Some(3);
+    let (from, to) = (HexCoord::new(1, 4), HexCoord::new(14, 4));
+
+    let calm = find_path_directed(width, height, from, to, |_, next| ocean(next)).unwrap();
+    assert!(calm.tiles.iter().all(|hex| hex.y() >= 2), "{calm:?}");
+
+    let sailing = find_path_directed(width, height, from, to, wind_assisted_cost(ocean, &wind, 2)).unwrap();
+    assert!(sailing.tiles.iter().any(|hex| hex.y() < 2), "{sailing:?}");
+    assert!(sailing.cost < calm.cost);
+
+    // sailing back west runs with the wind along the straight line
+    let back = find_path_directed(width, height, to, from, wind_assisted_cost(ocean, &wind, 2)).unwrap();
+    assert!(back.tiles.iter().all(|hex| hex.y() == 4), "{back:?}");
+    assert_eq!(back.cost, 13);
+}
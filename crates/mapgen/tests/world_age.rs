@@ -0,0 +1,61 @@
+use civorum_mapgen::{
+    map_components::terrain::Terrain,
+    pipeline::{
+        biomes::{MapLayers, generate_map_with_type},
+        helpers::{WorldAge, biomes_config, with_biomes_config},
+        map_sizes::MapSizes,
+        map_types::MapTypes,
+    },
+};
+
+fn generate(seed: u64, world_age: WorldAge) -> MapLayers {
+    let config = biomes_config().as_ref().clone().with_world_age(world_age);
+    with_biomes_config(config, || generate_map_with_type(&seed, &MapSizes::Small, MapTypes::Continents))
+}
+
+/// Mountain and hill counts of a map
+fn relief(layers: &MapLayers) -> (usize, usize) {
+    let mountains = layers.terrain.iter().filter(|terrain| **terrain == Terrain::Mountain).count();
+    let hills = layers.hills.iter().filter(|hill| **hill).count();
+    (mountains, hills)
+}
+
+#[test]
+fn younger_worlds_have_more_mountains_and_hills() {
+    for seed in [1u64, 6, 13] {
+        let [young, standard, old] = WorldAge::ALL.map(|age| relief(&generate(seed, age)));
+        assert!(
+            young.0 > standard.0 && standard.0 > old.0,
+            "seed {seed}: mountains young {} standard {} old {}",
+            young.0,
+            standard.0,
+            old.0
+        );
+        assert!(
+            young.1 > standard.1 && standard.1 > old.1,
+            "seed {seed}: hills young {} standard {} old {}",
+            young.1,
+            standard.1,
+            old.1
+        );
+    }
+}
+
+#[test]
+fn standard_age_keeps_the_map() {
+    let seed = 4;
+    let configured = generate_map_with_type(&seed, &MapSizes::Small, MapTypes::Continents);
+    let standard = generate(seed, WorldAge::Standard);
+    assert_eq!(standard.terrain, configured.terrain);
+    assert_eq!(standard.hills, configured.hills);
+    assert_eq!(standard.height, configured.height);
+}
+
+#[test]
+fn world_age_names_round_trip() {
+    for age in WorldAge::ALL {
+        assert_eq!(WorldAge::from_name(age.name()), Some(age));
+    }
+    assert_eq!(WorldAge::from_name("OLD"), Some(WorldAge::Old));
+    assert_eq!(WorldAge::from_name("ancient"), None);
+}
@@ -0,0 +1,56 @@
+use civorum_mapgen::{
+    map_components::hex_coords::{CompassDirection, HexCoord, hex_ring_mesh},
+    pipeline::{map::Map, map_sizes::MapSizes},
+};
+
+fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+    (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5
+}
+
+#[test]
+fn neighbors_share_an_edge() {
+    for hex in [HexCoord::new(3, 4), HexCoord::new(3, 5)] {
+        let corners = hex.world_corners();
+        for direction in CompassDirection::ALL {
+            let neighbor = hex.neighbor(&direction).world_corners();
+            let shared = corners.iter().filter(|a| neighbor.iter().any(|b| close(**a, *b))).count();
+            assert_eq!(shared, 2, "{hex:?} {direction:?}");
+        }
+    }
+
+    // same corner order as the hover ring
+    let ring = hex_ring_mesh(0.0, 0.577_350_26);
+    let origin = HexCoord::new(0, 0).world_corners();
+    for (i, corner) in origin.iter().enumerate() {
+        let vertex = ring.positions[6 + i];
+        assert!(close(*corner, (vertex[0], vertex[2])), "corner {i}");
+    }
+}
+
+#[test]
+fn tile_centers_round_trip() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    for tile in map.tiles() {
+        let hex = tile.hex_coords();
+        let (x, z) = hex.world_pos();
+        assert_eq!(map.hex_at_world_pos(x, z), Some(hex));
+        assert_eq!(map.tile_polygon(hex), hex.world_corners());
+    }
+}
+
+#[test]
+fn bounds_cover_the_tile_polygons() {
+    let map = Map::new(Some(4), MapSizes::Duel);
+    let (width, height) = map.size().dimensions();
+    let ((min_x, min_z), (max_x, max_z)) = map.world_bounds();
+
+    // the polygons stick out half a hex past the centers on the sides and a corner at the ends
+    let centers = map.tiles().iter().map(|tile| tile.hex_coords().world_pos());
+    let (center_min_x, center_max_x) = centers.fold((f32::MAX, f32::MIN), |(lo, hi), (x, _)| (lo.min(x), hi.max(x)));
+    assert!(close((min_x, max_x), (center_min_x - 0.5, center_max_x + 0.5)));
+    assert!(close((min_z, max_z), (-0.577_350_26, (height - 1) as f32 * 3_f32.sqrt() / 2.0 + 0.577_350_26)));
+    assert!(close((min_x, max_x), (-0.5, width as f32)));
+
+    let (center_x, center_z) = map.world_center();
+    assert!(close((center_x, center_z), ((min_x + max_x) / 2.0, (min_z + max_z) / 2.0)));
+}
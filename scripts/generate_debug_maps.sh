@@ -97,9 +97,9 @@ MAP_TYPES=(
 )
 
 echo "Building debug renderer binary..."
-cargo build -p civorum-core --bin render_debug_map >/dev/null
+cargo build -p civorum-cli --bin civorum >/dev/null
 
-BIN="./target/debug/render_debug_map"
+BIN="./target/debug/civorum"
 mkdir -p "$OUT_DIR"
 
 for seed in "${SEEDS[@]}"; do
@@ -109,7 +109,7 @@ for seed in "${SEEDS[@]}"; do
   echo "Generating maps for seed '$seed' (size=$SIZE, cell_px=$CELL_PX)..."
   for map_type in "${MAP_TYPES[@]}"; do
     out_file="$seed_dir/${map_type}.png"
-    "$BIN" "$SIZE" "$seed" "$map_type" "$CELL_PX" "$out_file"
+    "$BIN" render --size "$SIZE" --seed "$seed" --type "$map_type" --cell-px "$CELL_PX" --out "$out_file"
   done
 done
 